@@ -0,0 +1,85 @@
+use anode::adaptive_lock::AdaptiveLock;
+use anode::bench::{self, Report, Workload, CONTENDERS};
+use anode::parking_spin_mutex::ParkingSpinMutex;
+use anode::spin_mutex::SpinMutex;
+use anode::ticket_lock::TicketLock;
+use anode::zlock::{SpinModerator, Stochastic, UpgradeBiased, ZLock};
+use anode_bench::lock_spec::LockSpec;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::time::Duration;
+
+/// Operations per thread when sampling latencies, outside of criterion's timing loop.
+const LATENCY_SAMPLE_OPS: u64 = 10_000;
+
+const READ_RATIOS: [(&str, f64); 3] = [("read_heavy", 0.9), ("balanced", 0.5), ("write_heavy", 0.1)];
+
+const THREAD_COUNTS: [usize; 3] = [2, 4, 16];
+
+fn criterion_benchmark(c: &mut Criterion) {
+    for (ratio_name, read_ratio) in READ_RATIOS {
+        for threads in THREAD_COUNTS {
+            let mut group = c.benchmark_group(format!("{ratio_name}/{threads}"));
+            group.measurement_time(Duration::from_secs(2));
+            group.sample_size(10);
+            let workload = Workload {
+                threads,
+                read_ratio,
+                ops_per_thread: LATENCY_SAMPLE_OPS,
+                record_latencies: true,
+            };
+            for contender in CONTENDERS {
+                bench(&mut group, contender.name, workload, |workload| {
+                    bench::run(&*(contender.make_lock)(0), workload)
+                });
+            }
+            bench(&mut group, "stochastic", workload, run_spec::<ZLock<u64, Stochastic>>);
+            bench(&mut group, "spin", workload, run_spec::<ZLock<u64, SpinModerator>>);
+            bench(&mut group, "upgrade_biased", workload, run_spec::<ZLock<u64, UpgradeBiased>>);
+            bench(&mut group, "spin_mutex", workload, run_spec::<SpinMutex<u64>>);
+            bench(&mut group, "parking_spin_mutex", workload, run_spec::<ParkingSpinMutex<u64, 100>>);
+            bench(&mut group, "ticket_lock", workload, run_spec::<TicketLock<u64>>);
+            bench(&mut group, "adaptive_lock", workload, run_spec::<AdaptiveLock<u64>>);
+            group.finish();
+        }
+    }
+
+    fn bench(
+        group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+        name: &str,
+        workload: Workload,
+        run: impl Fn(&Workload) -> Report,
+    ) {
+        // each criterion iteration is one operation, spread evenly across the threads
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter_custom(|iters| {
+                run(&Workload {
+                    ops_per_thread: iters.div_ceil(workload.threads as u64),
+                    record_latencies: false,
+                    ..workload
+                })
+                .elapsed
+            });
+        });
+
+        let report = run(&workload);
+        println!("{name}: {report}");
+    }
+
+    /// Runs `workload` against a lock that is not `Locklike`. Locks that do not support reading
+    /// perform writes in place of reads.
+    fn run_spec<L: for<'a> LockSpec<'a, T = u64>>(workload: &Workload) -> Report {
+        let lock = L::new(0);
+        let read_ratio = if L::supports_read() { workload.read_ratio } else { 0.0 };
+        bench::run_ops(
+            &Workload { read_ratio, ..*workload },
+            || {
+                black_box(*lock.try_read(Duration::MAX).unwrap());
+            },
+            || *lock.try_write(Duration::MAX).unwrap() += 1,
+        )
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
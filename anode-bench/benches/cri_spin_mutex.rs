@@ -1,11 +1,110 @@
+use std::hint;
+use std::sync::{Arc, Barrier};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 use criterion::{criterion_group, criterion_main, Criterion};
+use anode::adaptive_lock::AdaptiveLock;
+use anode::backoff::{Backoff, ExpBackoff};
+use anode::parking_spin_mutex::ParkingSpinMutex;
 use anode::spin_mutex::SpinMutex;
+use anode::ticket_lock::TicketLock;
+
+const CONTENDING_THREADS: u64 = 4;
+
+/// A lock that retries its compare-and-swap in a tight loop, as a baseline for the
+/// test-and-test-and-set acquisition of [`SpinMutex`].
+struct CasSpinLock(AtomicBool);
+
+impl CasSpinLock {
+    fn lock(&self) {
+        while self.0.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            hint::spin_loop();
+        }
+    }
+
+    fn unlock(&self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Runs `iters` invocations of `f`, split evenly across contending threads, returning the
+/// elapsed time.
+fn contended(iters: u64, f: impl Fn() + Send + Sync + 'static) -> Duration {
+    let f = Arc::new(f);
+    let barrier = Arc::new(Barrier::new(CONTENDING_THREADS as usize + 1));
+    let threads = (0..CONTENDING_THREADS)
+        .map(|_| {
+            let f = f.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..iters / CONTENDING_THREADS {
+                    f();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    barrier.wait();
+    let start = Instant::now();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    start.elapsed()
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
     let mutex = SpinMutex::new(());
     c.bench_function("lock", |b| {
         b.iter(|| mutex.lock());
     });
+
+    let mutex = ParkingSpinMutex::<_, 100>::new(());
+    c.bench_function("parking/lock", |b| {
+        b.iter(|| mutex.lock());
+    });
+
+    let mutex = AdaptiveLock::new(());
+    c.bench_function("adaptive/lock", |b| {
+        b.iter(|| mutex.lock());
+    });
+
+    let mutex = TicketLock::new(());
+    c.bench_function("ticket/lock", |b| {
+        b.iter(|| mutex.lock());
+    });
+
+    // contended benchmarks, contrasting a tight CAS loop with TTAS, with and without backoff
+    let mut group = c.benchmark_group("contended");
+    group.bench_function("cas/lock", |b| {
+        let lock = Arc::new(CasSpinLock(AtomicBool::new(false)));
+        b.iter_custom(|iters| {
+            let lock = lock.clone();
+            contended(iters, move || {
+                lock.lock();
+                lock.unlock();
+            })
+        });
+    });
+    group.bench_function("ttas_spinny/lock", |b| {
+        let mutex = Arc::new(SpinMutex::new(()));
+        b.iter_custom(|iters| {
+            let mutex = mutex.clone();
+            contended(iters, move || {
+                drop(mutex.lock_with(Backoff::with_config(ExpBackoff::spinny())));
+            })
+        });
+    });
+    group.bench_function("ttas_backoff/lock", |b| {
+        let mutex = Arc::new(SpinMutex::new(()));
+        b.iter_custom(|iters| {
+            let mutex = mutex.clone();
+            contended(iters, move || {
+                drop(mutex.lock());
+            })
+        });
+    });
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
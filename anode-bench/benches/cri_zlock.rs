@@ -1,4 +1,6 @@
-use anode::zlock::{Moderator, ReadBiased, Stochastic, WriteBiased, ZLock};
+use anode::zlock::any_lock::AnyLock;
+use anode::zlock::locklike::{LockBoxSized, ModeratorKind};
+use anode::zlock::{Moderator, ReadBiased, SpinModerator, Stochastic, UpgradeBiased, WriteBiased, ZLock};
 use criterion::{criterion_group, criterion_main, Criterion};
 use std::sync::RwLock;
 
@@ -6,6 +8,8 @@ fn criterion_benchmark(c: &mut Criterion) {
     cycle(c, "read_biased", ZLock::<_, ReadBiased>::new(()));
     cycle(c, "write_biased", ZLock::<_, WriteBiased>::new(()));
     cycle(c, "stochastic", ZLock::<_, Stochastic>::new(()));
+    cycle(c, "spin", ZLock::<_, SpinModerator>::new(()));
+    cycle(c, "upgrade_biased", ZLock::<_, UpgradeBiased>::new(()));
 
     fn cycle<M: Moderator>(c: &mut Criterion, moderator: &str, lock: ZLock<(), M>) {
         c.bench_function(&format!("{moderator}/read"), |b| {
@@ -28,6 +32,28 @@ fn criterion_benchmark(c: &mut Criterion) {
         });
     }
 
+    // runtime-selected moderators: static dispatch through AnyLock vs. dynamic dispatch through LockBox
+    any_cycle(c, "any/read_biased", AnyLock::new(ModeratorKind::ReadBiased, ()));
+    boxed_cycle(c, "boxed/read_biased", ModeratorKind::ReadBiased.make_lock(()));
+
+    fn any_cycle(c: &mut Criterion, name: &str, lock: AnyLock<()>) {
+        c.bench_function(&format!("{name}/read"), |b| {
+            b.iter(|| lock.read());
+        });
+        c.bench_function(&format!("{name}/write"), |b| {
+            b.iter(|| lock.write());
+        });
+    }
+
+    fn boxed_cycle(c: &mut Criterion, name: &str, lock: LockBoxSized<()>) {
+        c.bench_function(&format!("{name}/read"), |b| {
+            b.iter(|| lock.read());
+        });
+        c.bench_function(&format!("{name}/write"), |b| {
+            b.iter(|| lock.write());
+        });
+    }
+
     let std_lock = RwLock::new(());
     c.bench_function("std/read", |b| {
         b.iter(|| std_lock.read());
@@ -1,10 +1,30 @@
 use std::sync::{MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::hint;
 use std::time::Duration;
+use anode::deadline::Deadline;
 use anode::remedy::Remedy;
+use anode::adaptive_lock::{AdaptiveGuard, AdaptiveLock};
+use anode::parking_spin_mutex::{ParkingSpinGuard, ParkingSpinMutex};
 use anode::spin_mutex::{SpinGuard, SpinMutex};
+use anode::ticket_lock::{TicketGuard, TicketLock};
 use anode::zlock::{LockReadGuard, LockWriteGuard, Moderator, UpgradeOutcome, ZLock};
 use crate::lock_spec::{LockSpec, NoReadGuard, ReadGuardSpec, WriteGuardSpec};
 
+/// Retries `try_lock` until it succeeds or `duration` elapses, for locks that offer no timed
+/// acquisition of their own.
+fn retry_until<G>(duration: Duration, mut try_lock: impl FnMut() -> Option<G>) -> Option<G> {
+    let mut deadline = Deadline::lazy_after(duration);
+    loop {
+        if let Some(guard) = try_lock() {
+            return Some(guard);
+        }
+        if deadline.expired() {
+            return None;
+        }
+        hint::spin_loop();
+    }
+}
+
 impl<'a, T, M: Moderator> ReadGuardSpec<'a, T> for LockReadGuard<'a, T, M> {}
 
 impl<'a, T, M: Moderator> WriteGuardSpec<'a, T> for LockWriteGuard<'a, T, M> {}
@@ -132,7 +152,139 @@ impl<'a, T: Sync + Send + 'a> LockSpec<'a> for SpinMutex<T> {
         if duration == Duration::MAX {
             Some(self.lock())
         } else {
-            self.try_lock()
+            self.try_lock_for(duration)
+        }
+    }
+
+    fn downgrade(_guard: Self::W) -> Self::R {
+        unimplemented!()
+    }
+
+    fn try_upgrade(_guard: Self::R, _duration: Duration) -> UpgradeOutcome<Self::W, Self::R> {
+        unimplemented!()
+    }
+}
+
+impl<'a, T, const SPINS: usize> WriteGuardSpec<'a, T> for ParkingSpinGuard<'a, T, SPINS> {}
+
+impl<'a, T: Sync + Send + 'a, const SPINS: usize> LockSpec<'a> for ParkingSpinMutex<T, SPINS> {
+    type T = T;
+    type R = NoReadGuard<T>;
+    type W = ParkingSpinGuard<'a, T, SPINS>;
+
+    fn new(t: Self::T) -> Self {
+        Self::new(t)
+    }
+
+    fn supports_read() -> bool {
+        false
+    }
+
+    fn supports_downgrade() -> bool {
+        false
+    }
+
+    fn supports_upgrade() -> bool {
+        false
+    }
+
+    fn try_read(&'a self, _duration: Duration) -> Option<Self::R> {
+        unimplemented!()
+    }
+
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        if duration == Duration::MAX {
+            Some(self.lock())
+        } else {
+            retry_until(duration, || self.try_lock())
+        }
+    }
+
+    fn downgrade(_guard: Self::W) -> Self::R {
+        unimplemented!()
+    }
+
+    fn try_upgrade(_guard: Self::R, _duration: Duration) -> UpgradeOutcome<Self::W, Self::R> {
+        unimplemented!()
+    }
+}
+
+impl<'a, T> WriteGuardSpec<'a, T> for TicketGuard<'a, T> {}
+
+impl<'a, T: Sync + Send + 'a> LockSpec<'a> for TicketLock<T> {
+    type T = T;
+    type R = NoReadGuard<T>;
+    type W = TicketGuard<'a, T>;
+
+    fn new(t: Self::T) -> Self {
+        Self::new(t)
+    }
+
+    fn supports_read() -> bool {
+        false
+    }
+
+    fn supports_downgrade() -> bool {
+        false
+    }
+
+    fn supports_upgrade() -> bool {
+        false
+    }
+
+    fn try_read(&'a self, _duration: Duration) -> Option<Self::R> {
+        unimplemented!()
+    }
+
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        if duration == Duration::MAX {
+            Some(self.lock())
+        } else {
+            retry_until(duration, || self.try_lock())
+        }
+    }
+
+    fn downgrade(_guard: Self::W) -> Self::R {
+        unimplemented!()
+    }
+
+    fn try_upgrade(_guard: Self::R, _duration: Duration) -> UpgradeOutcome<Self::W, Self::R> {
+        unimplemented!()
+    }
+}
+
+impl<'a, T> WriteGuardSpec<'a, T> for AdaptiveGuard<'a, T> {}
+
+impl<'a, T: Sync + Send + 'a> LockSpec<'a> for AdaptiveLock<T> {
+    type T = T;
+    type R = NoReadGuard<T>;
+    type W = AdaptiveGuard<'a, T>;
+
+    fn new(t: Self::T) -> Self {
+        Self::new(t)
+    }
+
+    fn supports_read() -> bool {
+        false
+    }
+
+    fn supports_downgrade() -> bool {
+        false
+    }
+
+    fn supports_upgrade() -> bool {
+        false
+    }
+
+    fn try_read(&'a self, _duration: Duration) -> Option<Self::R> {
+        unimplemented!()
+    }
+
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        if duration == Duration::MAX {
+            Some(self.lock())
+        } else {
+            retry_until(duration, || self.try_lock())
         }
     }
 
@@ -187,4 +339,7 @@ impl<'a, T: Sync + Send + 'a> LockSpec<'a> for std::sync::Mutex<T> {
     fn try_upgrade(_guard: Self::R, _duration: Duration) -> UpgradeOutcome<Self::W, Self::R> {
         unimplemented!()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests;
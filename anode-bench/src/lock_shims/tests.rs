@@ -0,0 +1,35 @@
+use std::thread;
+use std::time::{Duration, Instant};
+use anode::adaptive_lock::AdaptiveLock;
+use anode::parking_spin_mutex::ParkingSpinMutex;
+use anode::ticket_lock::TicketLock;
+use crate::lock_spec::LockSpec;
+
+const SHORT_WAIT: Duration = Duration::from_millis(10);
+
+#[test]
+fn timed_write() {
+    __timed_write::<ParkingSpinMutex<u64, 100>>();
+    __timed_write::<TicketLock<u64>>();
+    __timed_write::<AdaptiveLock<u64>>();
+}
+
+fn __timed_write<L: for<'a> LockSpec<'a, T = u64>>() {
+    let lock = L::new(0);
+    let guard = lock.try_write(Duration::ZERO).unwrap();
+
+    // a timed attempt waits out its duration before giving up
+    let start = Instant::now();
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert!(start.elapsed() >= SHORT_WAIT);
+
+    // and succeeds if the lock is released in the meantime
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            *lock.try_write(Duration::from_secs(10)).unwrap() = 42;
+        });
+        thread::sleep(SHORT_WAIT);
+        drop(guard);
+    });
+    assert_eq!(42, *lock.try_write(Duration::ZERO).unwrap());
+}
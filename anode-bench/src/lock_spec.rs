@@ -37,7 +37,7 @@ pub struct NoReadGuard<T> {
 impl<T> Default for NoReadGuard<T> {
     fn default() -> Self {
         Self {
-            __phantom_data: PhantomData::default(),
+            __phantom_data: PhantomData,
         }
     }
 }
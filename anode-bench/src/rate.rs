@@ -18,6 +18,7 @@ impl Rate {
         self.0 / 1_000_000.0
     }
 
+    #[allow(clippy::self_named_constructors)]
     pub fn rate(duration: Duration, ops: u64) -> Rate {
         Rate(ops as f64 / duration.as_secs_f64())
     }
@@ -0,0 +1,217 @@
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+use std::{fmt, hint, thread};
+use crate::parking_spin_mutex::RawParkingLock;
+
+unsafe impl<T: ?Sized + Send> Send for AdaptiveLock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for AdaptiveLock<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for AdaptiveGuard<'_, T> {}
+
+/// Governs how an [`AdaptiveLock`] waits for the lock before resorting to parking the thread.
+///
+/// A contending thread progresses through three phases, attempting the lock at every step:
+///
+/// 1. **Spinning**, for up to `spin_iters` attempts, issuing a growing number of
+///    spin-loop hints between attempts: starting at one, and doubling with every attempt
+///    up to `max_backoff`.
+/// 2. **Yielding**, for up to `yield_iters` attempts, yielding the processor between attempts.
+/// 3. **Parking**, until unparked by the releasing thread. If `park_timeout` is set, a parked
+///    thread also wakes after that long to reattempt the lock of its own accord.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpinPolicy {
+    pub spin_iters: u32,
+    pub max_backoff: u32,
+    pub yield_iters: u32,
+    pub park_timeout: Option<Duration>,
+}
+
+impl Default for SpinPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            spin_iters: 10,
+            max_backoff: 64,
+            yield_iters: 10,
+            park_timeout: None,
+        }
+    }
+}
+
+/// A mutual exclusion lock that spins briefly, as prescribed by its [`SpinPolicy`], and then
+/// parks the calling thread, to be unparked when the lock is released.
+///
+/// It is the runtime-configurable counterpart of
+/// [`ParkingSpinMutex`](crate::parking_spin_mutex::ParkingSpinMutex), with which it shares its
+/// parking mechanism.
+///
+/// # Examples
+/// ```
+/// use anode::adaptive_lock::{AdaptiveLock, SpinPolicy};
+/// let lock = AdaptiveLock::with_policy(SpinPolicy { spin_iters: 100, ..SpinPolicy::default() }, 0);
+/// *lock.lock() = 42;
+/// assert_eq!(42, *lock.lock());
+/// ```
+pub struct AdaptiveLock<T: ?Sized> {
+    raw: RawParkingLock,
+    policy: SpinPolicy,
+    data: UnsafeCell<T>,
+}
+
+pub struct AdaptiveGuard<'a, T: ?Sized> {
+    lock: &'a AdaptiveLock<T>,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T> AdaptiveLock<T> {
+    /// Creates a lock with the default [`SpinPolicy`].
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self::with_policy(SpinPolicy::default(), t)
+    }
+
+    #[inline]
+    pub fn with_policy(policy: SpinPolicy, t: T) -> Self {
+        Self {
+            raw: RawParkingLock::new(),
+            policy,
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> AdaptiveLock<T> {
+    #[inline]
+    pub fn lock(&self) -> AdaptiveGuard<'_, T> {
+        let policy = &self.policy;
+        let mut backoff = 1;
+        for _ in 0..policy.spin_iters {
+            if self.acquire_if_free() {
+                return self.guard();
+            }
+            for _ in 0..backoff {
+                hint::spin_loop();
+            }
+            backoff = (backoff * 2).min(policy.max_backoff.max(1));
+        }
+
+        for _ in 0..policy.yield_iters {
+            if self.acquire_if_free() {
+                return self.guard();
+            }
+            thread::yield_now();
+        }
+
+        self.raw.acquire_parking(policy.park_timeout);
+        self.guard()
+    }
+
+    #[inline]
+    pub fn try_lock(&self) -> Option<AdaptiveGuard<'_, T>> {
+        if self.raw.try_acquire() {
+            Some(self.guard())
+        } else {
+            None
+        }
+    }
+
+    /// Releases the lock on behalf of a guard that was forgotten, unparking the
+    /// longest-waiting parked thread (if there is one).
+    ///
+    /// # Safety
+    /// The lock must be held, and not by a live guard.
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        self.raw.release();
+    }
+
+    #[inline]
+    pub fn policy(&self) -> &SpinPolicy {
+        &self.policy
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`AdaptiveLock`] mutably, no actual locking needs to
+    /// take place---the mutable borrow statically guarantees no locks exist.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Attempts the lock only if it appears free, so that spinning threads do not contend
+    /// for the cache line while the lock is held.
+    #[inline]
+    fn acquire_if_free(&self) -> bool {
+        !self.raw.is_locked() && self.raw.try_acquire()
+    }
+
+    #[inline]
+    fn guard(&self) -> AdaptiveGuard<'_, T> {
+        AdaptiveGuard {
+            lock: self,
+            __no_send: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for AdaptiveGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the lock is held by this guard
+        unsafe { self.lock.unlock() };
+    }
+}
+
+impl<T: ?Sized> Deref for AdaptiveGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for AdaptiveGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for AdaptiveGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for AdaptiveLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("AdaptiveLock");
+        match self.try_lock() {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(guard) => {
+                d.field("data", &&*guard);
+            }
+        }
+        d.field("policy", &self.policy);
+        d.finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::adaptive_lock::{AdaptiveLock, SpinPolicy};
+use crate::test_utils::LONG_WAIT;
+use crate::wait;
+use crate::wait::Wait;
+use crate::test_utils;
+
+const PARK_IMMEDIATELY: SpinPolicy = SpinPolicy {
+    spin_iters: 0,
+    max_backoff: 0,
+    yield_iters: 0,
+    park_timeout: None,
+};
+
+#[test]
+fn cycle() {
+    let lock = AdaptiveLock::new(0);
+    let mut guard = lock.lock();
+    *guard = 42;
+    assert!(lock.try_lock().is_none());
+    drop(guard);
+
+    assert_eq!(42, *lock.try_lock().unwrap());
+    assert_eq!(SpinPolicy::default(), *lock.policy());
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn borrow_mut() {
+    let mut lock = AdaptiveLock::new(0);
+    *lock.get_mut() = 42;
+    assert_eq!(42, *lock.lock());
+}
+
+#[test]
+fn parks_after_spinning_and_yielding() {
+    let lock = Arc::new(AdaptiveLock::new(0));
+    let guard = lock.lock();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 exhausts its spins and yields, and parks, because main holds the lock
+            *lock.lock() = 42;
+        })
+    };
+    wait::Spin::wait_for_inequality(|| lock.raw.num_waiters(), Ordering::is_eq, &1, LONG_WAIT).unwrap();
+    assert!(!t_2.is_finished());
+
+    // releasing the lock unparks t_2
+    drop(guard);
+    t_2.join().unwrap();
+    assert_eq!(0, lock.raw.num_waiters());
+    assert_eq!(42, *lock.lock());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn contended() {
+    __contended(PARK_IMMEDIATELY);
+    __contended(SpinPolicy::default());
+    __contended(SpinPolicy {
+        park_timeout: Some(Duration::from_micros(100)),
+        ..PARK_IMMEDIATELY
+    });
+}
+
+fn __contended(policy: SpinPolicy) {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 10_000;
+    let lock = Arc::new(AdaptiveLock::with_policy(policy, 0));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    *lock.lock() += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS, *lock.lock());
+    assert_eq!(0, lock.raw.num_waiters());
+}
+
+#[test]
+fn debug() {
+    let lock = AdaptiveLock::with_policy(PARK_IMMEDIATELY, 42);
+    assert_eq!(
+        "AdaptiveLock { data: 42, policy: SpinPolicy { spin_iters: 0, max_backoff: 0, yield_iters: 0, park_timeout: None }, .. }",
+        format!("{lock:?}")
+    );
+    let _guard = lock.lock();
+    assert!(format!("{lock:?}").starts_with("AdaptiveLock { data: <locked>, "));
+}
@@ -1,8 +1,16 @@
-use std::time::Duration;
-use std::{thread};
-use std::ops::Range;
+#[cfg(not(loom))]
+use core::hint;
+use core::time::Duration;
+#[cfg(all(feature = "std", not(loom)))]
+use core::ops::Range;
+#[cfg(all(feature = "std", not(loom)))]
+use std::thread;
+#[cfg(feature = "std")]
+use crate::deadline::Deadline;
 use crate::inf_iterator::{InfIterator, IntoInfIterator};
-use crate::rand::RandRange;
+use crate::rand::{RandRange, Seeded, Xorshift};
+#[cfg(feature = "std")]
+use crate::rand::{clock_seed, FIXED_DURATION};
 
 #[derive(Debug, Clone, Eq, PartialEq, Copy)]
 pub struct NonzeroDuration(Duration);
@@ -71,6 +79,17 @@ impl ExpBackoff {
             max_sleep: Duration::from_millis(10).into()
         }
     }
+
+    /// Spins briefly, then yields for a while, before sleeping. Suits locks that are usually
+    /// held for short periods, but may occasionally be held for longer.
+    pub fn balanced() -> Self {
+        Self {
+            spin_iters: 10,
+            yield_iters: 10,
+            min_sleep: Duration::from_micros(10).into(),
+            max_sleep: Duration::from_millis(1).into()
+        }
+    }
 }
 
 impl IntoInfIterator for &ExpBackoff {
@@ -105,11 +124,16 @@ pub enum ExpBackoffAction {
 }
 
 impl ExpBackoffAction {
+    /// Carries out the action. Without the `std` feature, there being no scheduler to yield
+    /// to, both yielding and sleeping degrade to a spin-loop hint. Under loom, both yield to
+    /// the model's scheduler.
     #[inline(always)]
     pub fn act<'a, R, D>(&self, randomness: D) where R: RandRange<Duration> + 'a, D: FnOnce() -> &'a mut R  {
         match self {
             ExpBackoffAction::Nop => (),
+            #[cfg(all(feature = "std", not(loom)))]
             ExpBackoffAction::Yield => thread::yield_now(),
+            #[cfg(all(feature = "std", not(loom)))]
             ExpBackoffAction::Sleep(duration) => {
                 let range = Range {
                     start: Duration::ZERO,
@@ -118,10 +142,30 @@ impl ExpBackoffAction {
                 let rng = randomness();
                 thread::sleep(rng.next_range(range));
             }
+            #[cfg(not(feature = "std"))]
+            ExpBackoffAction::Yield | ExpBackoffAction::Sleep(_) => {
+                let _ = randomness;
+                hint::spin_loop();
+            }
+            #[cfg(all(feature = "std", loom))]
+            ExpBackoffAction::Yield | ExpBackoffAction::Sleep(_) => {
+                let _ = randomness;
+                loom::thread::yield_now();
+            }
         }
     }
 }
 
+/// Signals one iteration of a spin-wait loop. Under loom, yields to the model's scheduler,
+/// which would otherwise keep running the spinning thread instead of the one it waits for.
+#[inline(always)]
+pub(crate) fn spin_hint() {
+    #[cfg(not(loom))]
+    hint::spin_loop();
+    #[cfg(loom)]
+    loom::thread::yield_now();
+}
+
 impl InfIterator for ExpBackoffIter {
     type Item = ExpBackoffAction;
 
@@ -143,5 +187,164 @@ impl InfIterator for ExpBackoffIter {
     }
 }
 
+/// A stateful helper for spin-wait loops, escalating through the phases of an [`ExpBackoff`]
+/// with each call to [`snooze`](Self::snooze): busy-spinning, with the number of spin-loop hints
+/// doubling on each call (up to a limit); then yielding the thread; then sleeping for
+/// exponentially growing durations with random jitter, so that contending threads do not wake
+/// in lockstep.
+///
+/// The waiter should snooze between plain loads of the contended location, only attempting
+/// an atomic read-modify-write once the load suggests it will succeed. Spinning on the
+/// read-modify-write itself continually invalidates the cache line for every other waiter.
+///
+/// # Examples
+/// ```
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use anode::backoff::Backoff;
+/// let locked = AtomicBool::new(false);
+/// let mut backoff = Backoff::new();
+/// while locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+///     while locked.load(Ordering::Relaxed) {
+///         backoff.snooze();
+///     }
+/// }
+/// ```
+pub struct Backoff {
+    config: ExpBackoff,
+    iter: ExpBackoffIter,
+    spin_shift: u32,
+    rng: Option<Xorshift>,
+}
+
+impl Backoff {
+    /// The upper bound on the number of spin-loop hints issued by a single snooze, as a power
+    /// of two.
+    const MAX_SPIN_SHIFT: u32 = 6;
+
+    /// Creates a backoff using the [`ExpBackoff::balanced`] configuration.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_config(ExpBackoff::balanced())
+    }
+
+    #[inline]
+    pub fn with_config(config: ExpBackoff) -> Self {
+        let iter = config.into_inf_iter();
+        Self {
+            config,
+            iter,
+            spin_shift: 0,
+            rng: None,
+        }
+    }
+
+    /// Backs off for the duration appropriate to the number of preceding snoozes. Without the
+    /// `std` feature, yielding and sleeping degrade to a spin-loop hint.
+    #[inline]
+    pub fn snooze(&mut self) {
+        match self.iter.next() {
+            ExpBackoffAction::Nop => {
+                // under loom, each hint is a yield to the model's scheduler, and one suffices
+                let spins = if cfg!(loom) { 1 } else { 1u32 << self.spin_shift };
+                for _ in 0..spins {
+                    spin_hint();
+                }
+                self.spin_shift = (self.spin_shift + 1).min(Self::MAX_SPIN_SHIFT);
+            }
+            action => {
+                // the jitter source is seeded on the first sleep, sparing the clock read otherwise
+                action.act(|| self.rng.get_or_insert_with(|| Xorshift::seed(jitter_seed())));
+            }
+        }
+    }
+
+    /// Starts over from the spinning phase, as after a successful acquisition.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.iter = self.config.into_inf_iter();
+        self.spin_shift = 0;
+    }
+}
+
+impl Default for Backoff {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline(always)]
+fn jitter_seed() -> u64 {
+    clock_seed()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline(always)]
+fn jitter_seed() -> u64 {
+    1
+}
+
+/// Repeatedly invokes `attempt`, backing off between invocations, until it succeeds or
+/// `duration` elapses.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn spin_until(duration: Duration, mut attempt: impl FnMut() -> bool) -> bool {
+    if attempt() {
+        return true;
+    }
+
+    let mut deadline = Deadline::lazy_after(duration);
+    let mut rng = FIXED_DURATION;
+    let mut backoff = ExpBackoff {
+        spin_iters: 100,
+        yield_iters: 100,
+        min_sleep: Duration::from_micros(10).into(),
+        max_sleep: Duration::from_millis(1).into(),
+    }.into_inf_iter();
+    while !deadline.remaining().is_zero() {
+        spin_hint();
+        backoff.next().act(|| &mut rng);
+        if attempt() {
+            return true;
+        }
+    }
+    false
+}
+
+/// The time nominally taken by a single attempt of [`spin_until`] without the `std` feature,
+/// by which a finite duration is converted into a budget of attempts in the absence of a clock.
+#[cfg(not(feature = "std"))]
+const NOMINAL_ATTEMPT: Duration = Duration::from_nanos(100);
+
+/// Repeatedly invokes `attempt` until it succeeds, or until `duration` elapses. Without the
+/// `std` feature, there is no clock by which to measure the wait, so a finite `duration` is
+/// approximated by a budget of attempts (one per [`NOMINAL_ATTEMPT`]), and [`Duration::MAX`]
+/// waits indefinitely.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn spin_until(duration: Duration, mut attempt: impl FnMut() -> bool) -> bool {
+    if attempt() {
+        return true;
+    }
+
+    if duration == Duration::MAX {
+        loop {
+            spin_hint();
+            if attempt() {
+                return true;
+            }
+        }
+    }
+
+    for _ in 0..duration.as_nanos() / NOMINAL_ATTEMPT.as_nanos() {
+        spin_hint();
+        if attempt() {
+            return true;
+        }
+    }
+    false
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
 use std::time::Duration;
 use rand::{Rng, thread_rng};
-use crate::backoff::{ExpBackoff, ExpBackoffAction, NonzeroDuration};
+use crate::backoff::{Backoff, ExpBackoff, ExpBackoffAction, NonzeroDuration};
 use crate::inf_iterator::{InfIterator, IntoInfIterator};
 use crate::rand::Rand;
 
@@ -32,13 +35,13 @@ fn exp_backoff() {
     assert_eq!(ExpBackoffAction::Yield, it.next());
     assert_eq!(ExpBackoffAction::Yield, it.next());
     assert_eq!(ExpBackoffAction::Yield, it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(1).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(2).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(4).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(8).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(16).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(30).into()), it.next());
-    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(30).into()), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(1)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(2)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(4)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(8)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(16)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(30)), it.next());
+    assert_eq!(ExpBackoffAction::Sleep(Duration::from_micros(30)), it.next());
 
     let mut it = eb.into_inf_iter();
     assert_eq!(ExpBackoffAction::Nop, it.next());
@@ -55,5 +58,51 @@ fn exp_backoff_act() {
     let mut thread_rng = thread_rng();
     ExpBackoffAction::Nop.act(|| &mut thread_rng);
     ExpBackoffAction::Yield.act(|| &mut thread_rng);
-    ExpBackoffAction::Sleep(Duration::from_micros(10).into()).act(|| &mut thread_rng);
-}
\ No newline at end of file
+    ExpBackoffAction::Sleep(Duration::from_micros(10)).act(|| &mut thread_rng);
+}
+#[test]
+fn backoff_escalates_and_resets() {
+    let mut backoff = Backoff::with_config(ExpBackoff {
+        spin_iters: 8,
+        yield_iters: 2,
+        min_sleep: Duration::from_micros(1).into(),
+        max_sleep: Duration::from_micros(10).into()
+    });
+
+    // the spin count doubles with each snooze, up to the limit
+    for expected_shift in [1, 2, 3, 4, 5, 6, 6, 6] {
+        backoff.snooze();
+        assert_eq!(expected_shift, backoff.spin_shift);
+    }
+    assert!(backoff.rng.is_none());
+
+    // yielding, then sleeping, which seeds the jitter source
+    backoff.snooze();
+    backoff.snooze();
+    assert!(backoff.rng.is_none());
+    backoff.snooze();
+    assert!(backoff.rng.is_some());
+
+    backoff.reset();
+    assert_eq!(0, backoff.spin_shift);
+    backoff.snooze();
+    assert_eq!(1, backoff.spin_shift);
+}
+
+#[test]
+fn backoff_waits_for_flag() {
+    let flag = Arc::new(AtomicBool::new(false));
+    let t_2 = {
+        let flag = flag.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            flag.store(true, Ordering::Relaxed);
+        })
+    };
+
+    let mut backoff = Backoff::default();
+    while !flag.load(Ordering::Relaxed) {
+        backoff.snooze();
+    }
+    t_2.join().unwrap();
+}
@@ -0,0 +1,162 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use std::fmt;
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+
+/// A reusable barrier, enabling a fixed number of threads to synchronize at the end of each
+/// phase of a computation.
+///
+/// Each time the `n`th thread arrives, the barrier trips: all waiting threads are released, the
+/// generation count is incremented and the barrier is reset for the next phase. Unlike
+/// [`std::sync::Barrier`], a thread may wait with a timeout. A thread that times out withdraws
+/// its arrival, so that the barrier trips only once `n` threads are waiting simultaneously.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use anode::barrier::Barrier;
+/// let barrier = Arc::new(Barrier::new(3));
+/// let threads = (0..3)
+///     .map(|_| {
+///         let barrier = barrier.clone();
+///         thread::spawn(move || {
+///             barrier.wait();
+///             barrier.wait().is_leader()
+///         })
+///     })
+///     .collect::<Vec<_>>();
+/// let leaders = threads
+///     .into_iter()
+///     .map(|thread| thread.join().unwrap())
+///     .filter(|&leader| leader)
+///     .count();
+/// assert_eq!(1, leaders);
+/// assert_eq!(2, barrier.generation());
+/// ```
+pub struct Barrier {
+    parties: usize,
+    state: Mutex<State>,
+    cond: TimedCondvar,
+}
+
+struct State {
+    /// The number of threads waiting in the current generation.
+    arrived: usize,
+    /// The number of times the barrier has tripped.
+    generation: u64,
+}
+
+/// The outcome of a successful wait on a [`Barrier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult {
+    leader: bool,
+    generation: u64,
+}
+
+impl BarrierWaitResult {
+    /// Whether this thread tripped the barrier. Exactly one thread per generation is the leader.
+    #[inline]
+    pub fn is_leader(&self) -> bool {
+        self.leader
+    }
+
+    /// The generation that was completed by the trip of the barrier, starting at 1.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Barrier {
+    /// Creates a barrier that trips once `parties` threads are waiting. A barrier with zero
+    /// parties behaves as though it had one: every wait trips it.
+    #[inline]
+    pub const fn new(parties: usize) -> Self {
+        Self {
+            parties,
+            state: Mutex::new(State {
+                arrived: 0,
+                generation: 0,
+            }),
+            cond: TimedCondvar::new(),
+        }
+    }
+
+    /// The number of threads that must wait for the barrier to trip.
+    #[inline]
+    pub fn parties(&self) -> usize {
+        self.parties
+    }
+
+    /// The number of times the barrier has tripped.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.state.lock().remedy().generation
+    }
+
+    /// The number of threads presently waiting for the barrier to trip.
+    #[inline]
+    pub fn waiting(&self) -> usize {
+        self.state.lock().remedy().arrived
+    }
+
+    /// Blocks until all parties are waiting.
+    #[inline]
+    pub fn wait(&self) -> BarrierWaitResult {
+        self.try_wait_until(Deadline::Forever).unwrap()
+    }
+
+    /// Blocks until all parties are waiting, or until the given `duration` elapses. Returns
+    /// `None` if the wait timed out.
+    #[inline]
+    pub fn try_wait(&self, duration: Duration) -> Option<BarrierWaitResult> {
+        self.try_wait_until(Deadline::lazy_after(duration))
+    }
+
+    /// Blocks until all parties are waiting, or until the given `deadline` elapses. Returns
+    /// `None` if the wait timed out.
+    pub fn try_wait_until(&self, deadline: impl Into<Deadline>) -> Option<BarrierWaitResult> {
+        let mut state = self.state.lock().remedy();
+        state.arrived += 1;
+        if state.arrived >= self.parties {
+            state.arrived = 0;
+            state.generation += 1;
+            let generation = state.generation;
+            drop(state);
+            self.cond.notify_all();
+            return Some(BarrierWaitResult {
+                leader: true,
+                generation,
+            });
+        }
+
+        let generation = state.generation;
+        let (mut state, timed_out) =
+            self.cond.wait_while_until(state, |state| state.generation == generation, deadline);
+        if timed_out {
+            state.arrived -= 1;
+            None
+        } else {
+            Some(BarrierWaitResult {
+                leader: false,
+                generation: generation + 1,
+            })
+        }
+    }
+}
+
+impl fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().remedy();
+        f.debug_struct("Barrier")
+            .field("parties", &self.parties)
+            .field("waiting", &state.arrived)
+            .field("generation", &state.generation)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::barrier::Barrier;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::test_utils;
+
+#[test]
+fn single_party() {
+    let barrier = Barrier::new(1);
+    for generation in 1..=3 {
+        let result = barrier.wait();
+        assert!(result.is_leader());
+        assert_eq!(generation, result.generation());
+    }
+    assert_eq!(3, barrier.generation());
+
+    // zero parties behaves as one
+    assert!(Barrier::new(0).try_wait(Duration::ZERO).unwrap().is_leader());
+}
+
+#[test]
+fn reuse_across_phases() {
+    const PARTIES: usize = 4;
+    const PHASES: u64 = 50;
+    let barrier = Arc::new(Barrier::new(PARTIES));
+    let leaders = Arc::new(AtomicUsize::default());
+    let threads = (0..PARTIES)
+        .map(|_| {
+            let barrier = barrier.clone();
+            let leaders = leaders.clone();
+            thread::spawn(move || {
+                for phase in 1..=PHASES {
+                    let result = barrier.try_wait(LONG_WAIT).unwrap();
+                    assert_eq!(phase, result.generation());
+                    if result.is_leader() {
+                        leaders.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(PHASES, barrier.generation());
+    assert_eq!(PHASES as usize, leaders.load(Ordering::Relaxed));
+    assert_eq!(0, barrier.waiting());
+}
+
+#[test]
+fn try_wait_timeout_withdraws() {
+    let barrier = Arc::new(Barrier::new(2));
+    let start = Instant::now();
+    assert!(barrier.try_wait(CHECK_WAIT).is_none());
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+    assert_eq!(0, barrier.waiting());
+    assert_eq!(0, barrier.generation());
+
+    // the timed-out arrival does not count towards the next trip
+    let t_2 = {
+        let barrier = barrier.clone();
+        test_utils::spawn_blocked(move || barrier.try_wait(LONG_WAIT).unwrap())
+    };
+    thread::sleep(CHECK_WAIT);
+    assert!(!t_2.is_finished());
+    assert_eq!(1, barrier.waiting());
+    let result = barrier.wait();
+    let t_2_result = t_2.join().unwrap();
+    assert_ne!(result.is_leader(), t_2_result.is_leader());
+    assert_eq!(1, result.generation());
+    assert_eq!(1, t_2_result.generation());
+}
+
+#[test]
+fn debug() {
+    let barrier = Barrier::new(3);
+    barrier.try_wait(Duration::ZERO);
+    assert_eq!("Barrier { parties: 3, waiting: 0, generation: 0 }", format!("{barrier:?}"));
+}
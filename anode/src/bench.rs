@@ -0,0 +1,222 @@
+//! A harness for comparing locks under a configurable read/write mix, reporting the
+//! throughput and the latency distribution of each. (Available with the `bench` feature.)
+//!
+//! Any [`Locklike`] may be measured with [`run`], and any other lock with [`run_ops`].
+//! [`compare`] measures the standard set of [`CONTENDERS`] — the
+//! [`ReadBiased`](crate::zlock::ReadBiased), [`WriteBiased`](crate::zlock::WriteBiased) and
+//! [`ArrivalOrdered`](crate::zlock::ArrivalOrdered) moderators, alongside
+//! [`std::sync::RwLock`] as a baseline.
+//!
+//! # Examples
+//! ```
+//! use anode::bench::{compare, Workload};
+//! let workload = Workload {
+//!     threads: 2,
+//!     read_ratio: 0.8,
+//!     ops_per_thread: 1_000,
+//!     ..Workload::default()
+//! };
+//! for (name, report) in compare(&workload) {
+//!     println!("{name:>16}: {report}");
+//! }
+//! ```
+
+use crate::rand::{Probability, Rand, Seeded, Xorshift};
+use crate::zlock::locklike::{lock_box_arrival_ordered, lock_box_read_biased, lock_box_std, lock_box_write_biased, LockBoxSized, Locklike};
+use std::fmt::{Display, Formatter};
+use std::hint::black_box;
+use std::sync::Barrier;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The shape of a benchmark run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Workload {
+    /// The number of threads contending for the lock.
+    pub threads: usize,
+
+    /// The fraction of operations that are reads, in the range \[0, 1\]. The remainder are
+    /// writes.
+    pub read_ratio: f64,
+
+    /// The number of operations performed by each thread.
+    pub ops_per_thread: u64,
+
+    /// Whether the latency of each operation is recorded. Timing every operation adds a
+    /// small overhead, so this may be disabled where only the throughput is of interest.
+    pub record_latencies: bool,
+}
+
+impl Default for Workload {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            threads: 4,
+            read_ratio: 0.9,
+            ops_per_thread: 10_000,
+            record_latencies: true,
+        }
+    }
+}
+
+/// The outcome of a benchmark run.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The wall-clock time taken by all threads to complete their operations.
+    pub elapsed: Duration,
+
+    /// The observed latencies (acquisition through to release) of the individual operations,
+    /// in ascending order. Empty unless latencies were recorded.
+    pub latencies: Vec<Duration>,
+
+    pub reads: u64,
+    pub writes: u64,
+}
+
+impl Report {
+    /// The number of operations completed per second, across all threads.
+    #[inline]
+    pub fn throughput(&self) -> f64 {
+        (self.reads + self.writes) as f64 / self.elapsed.as_secs_f64()
+    }
+
+    /// The latency at the given percentile, in the range \[0, 100\].
+    ///
+    /// # Panics
+    /// If latencies were not recorded, or if `percentile` is out of range.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        assert!(!self.latencies.is_empty(), "latencies were not recorded");
+        assert!((0.0..=100.0).contains(&percentile), "percentile ({percentile}) out of range");
+        let index = ((self.latencies.len() - 1) as f64 * percentile / 100.0).round() as usize;
+        self.latencies[index]
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0} ops/s", self.throughput())?;
+        if !self.latencies.is_empty() {
+            write!(
+                f,
+                ", p50 {:?}, p99 {:?}, p99.9 {:?}, max {:?}",
+                self.percentile(50.0),
+                self.percentile(99.0),
+                self.percentile(99.9),
+                self.percentile(100.0)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A named lock implementation, taking part in a [`compare`] run.
+#[derive(Debug, Clone, Copy)]
+pub struct Contender {
+    pub name: &'static str,
+    pub make_lock: fn(u64) -> LockBoxSized<u64>,
+}
+
+/// The locks compared by [`compare`].
+pub const CONTENDERS: [Contender; 4] = [
+    Contender { name: "read_biased", make_lock: lock_box_read_biased },
+    Contender { name: "write_biased", make_lock: lock_box_write_biased },
+    Contender { name: "arrival_ordered", make_lock: lock_box_arrival_ordered },
+    Contender { name: "std", make_lock: lock_box_std },
+];
+
+/// Runs `workload` against `lock`, each thread performing reads and writes in a random order
+/// that, on average, honours the workload's read ratio. The lock's value is incremented on
+/// every write.
+///
+/// # Panics
+/// If the workload has no threads, or if its read ratio lies outside \[0, 1\].
+pub fn run<L>(lock: &L, workload: &Workload) -> Report
+where
+    L: for<'a> Locklike<'a, u64> + ?Sized,
+{
+    run_ops(
+        workload,
+        || {
+            black_box(*lock.read());
+        },
+        || *lock.write() += 1,
+    )
+}
+
+/// Runs `workload`, each thread calling either `read` or `write` for every operation, in a
+/// random order that, on average, honours the workload's read ratio. This is the driver behind
+/// [`run`], through which locks that are not [`Locklike`] may also be measured.
+///
+/// # Panics
+/// If the workload has no threads, or if its read ratio lies outside \[0, 1\].
+pub fn run_ops<R, W>(workload: &Workload, read: R, write: W) -> Report
+where
+    R: Fn() + Sync,
+    W: Fn() + Sync,
+{
+    assert!(workload.threads > 0, "no threads");
+    let read_probability = Probability::new(workload.read_ratio);
+    let start_barrier = Barrier::new(workload.threads + 1);
+
+    thread::scope(|scope| {
+        let threads = (0..workload.threads)
+            .map(|i| {
+                let (start_barrier, read, write) = (&start_barrier, &read, &write);
+                scope.spawn(move || {
+                    let mut rng = Xorshift::seed(i as u64 + 1);
+                    let mut latencies =
+                        Vec::with_capacity(if workload.record_latencies { workload.ops_per_thread as usize } else { 0 });
+                    let (mut reads, mut writes) = (0, 0);
+                    start_barrier.wait();
+                    for _ in 0..workload.ops_per_thread {
+                        let start = workload.record_latencies.then(Instant::now);
+                        if rng.next_bool(read_probability) {
+                            read();
+                            reads += 1;
+                        } else {
+                            write();
+                            writes += 1;
+                        }
+                        if let Some(start) = start {
+                            latencies.push(start.elapsed());
+                        }
+                    }
+                    (latencies, reads, writes)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        start_barrier.wait();
+        let start = Instant::now();
+        let mut report = Report {
+            elapsed: Duration::ZERO,
+            latencies: vec![],
+            reads: 0,
+            writes: 0,
+        };
+        for thread in threads {
+            let (latencies, reads, writes) = thread.join().unwrap();
+            report.latencies.extend(latencies);
+            report.reads += reads;
+            report.writes += writes;
+        }
+        report.elapsed = start.elapsed();
+        report.latencies.sort_unstable();
+        report
+    })
+}
+
+/// Runs `workload` against a fresh lock of each of the [`CONTENDERS`], returning the reports
+/// in the order of the contenders.
+pub fn compare(workload: &Workload) -> Vec<(&'static str, Report)> {
+    CONTENDERS
+        .iter()
+        .map(|contender| {
+            let lock = (contender.make_lock)(0);
+            (contender.name, run(&*lock, workload))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
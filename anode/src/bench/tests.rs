@@ -0,0 +1,107 @@
+use crate::bench::{compare, run, run_ops, Report, Workload, CONTENDERS};
+use crate::zlock::{ReadBiased, ZLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+const WORKLOAD: Workload = Workload {
+    threads: 2,
+    read_ratio: 0.5,
+    ops_per_thread: 100,
+    record_latencies: true,
+};
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn run_accounts_for_every_op() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    let report = run(&lock, &WORKLOAD);
+    assert_eq!(200, report.reads + report.writes);
+    assert_eq!(report.writes, lock.into_inner());
+    assert_eq!(200, report.latencies.len());
+    assert!(report.latencies.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(report.throughput() > 0.0);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn run_without_latencies() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    let report = run(&lock, &Workload { read_ratio: 1.0, record_latencies: false, ..WORKLOAD });
+    assert_eq!((200, 0), (report.reads, report.writes));
+    assert!(report.latencies.is_empty());
+    assert!(report.to_string().ends_with("ops/s"));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn run_ops_calls_every_op() {
+    let (reads, writes) = (AtomicU64::new(0), AtomicU64::new(0));
+    let report = run_ops(
+        &WORKLOAD,
+        || {
+            reads.fetch_add(1, Ordering::Relaxed);
+        },
+        || {
+            writes.fetch_add(1, Ordering::Relaxed);
+        },
+    );
+    assert_eq!((report.reads, report.writes), (reads.into_inner(), writes.into_inner()));
+    assert_eq!(200, report.reads + report.writes);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compare_all_contenders() {
+    let reports = compare(&WORKLOAD);
+    assert_eq!(CONTENDERS.map(|contender| contender.name).to_vec(), reports.iter().map(|(name, _)| *name).collect::<Vec<_>>());
+    for (_, report) in reports {
+        assert_eq!(200, report.reads + report.writes);
+        assert!(report.to_string().contains("p99.9"));
+    }
+}
+
+#[test]
+fn percentile() {
+    let report = Report {
+        elapsed: Duration::from_secs(1),
+        latencies: (1..=100).map(Duration::from_micros).collect(),
+        reads: 60,
+        writes: 40,
+    };
+    assert_eq!(100.0, report.throughput());
+    assert_eq!(Duration::from_micros(1), report.percentile(0.0));
+    assert_eq!(Duration::from_micros(51), report.percentile(50.0));
+    assert_eq!(Duration::from_micros(99), report.percentile(99.0));
+    assert_eq!(Duration::from_micros(100), report.percentile(100.0));
+}
+
+#[test]
+#[should_panic(expected = "latencies were not recorded")]
+fn percentile_without_latencies() {
+    let report = Report {
+        elapsed: Duration::from_secs(1),
+        latencies: vec![],
+        reads: 0,
+        writes: 0,
+    };
+    report.percentile(50.0);
+}
+
+#[test]
+#[should_panic(expected = "no threads")]
+fn run_without_threads() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    run(&lock, &Workload { threads: 0, ..WORKLOAD });
+}
+
+#[test]
+#[should_panic(expected = "percentile (100.1) out of range")]
+fn percentile_out_of_range() {
+    let report = Report {
+        elapsed: Duration::from_secs(1),
+        latencies: vec![Duration::from_micros(1)],
+        reads: 1,
+        writes: 0,
+    };
+    report.percentile(100.1);
+}
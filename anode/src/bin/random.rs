@@ -18,6 +18,7 @@ fn main() {
     }
 }
 
+#[allow(dead_code)]
 #[derive(Debug)]
 struct GeneratorError(String);
 
@@ -155,6 +156,7 @@ fn generate_bin(count: u64, mut rand: Box<dyn Rand>) -> Result<u64, Box<dyn Erro
 }
 
 enum WriteOutcome {
+    #[allow(dead_code)]
     Written(usize),
     BrokenPipe,
 }
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Wake, Waker};
+use std::thread;
+use std::thread::Thread;
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::remedy::Remedy;
+use crate::zlock::{Polled, Wakers};
+
+/// A token that lets one thread cancel the waits of others.
+///
+/// A token starts out active, and is cancelled at most once, by [`cancel`](Self::cancel). Waits
+/// that accept a token (such as [`ZLock::try_write_cancellable`](crate::zlock::ZLock::try_write_cancellable))
+/// return early once it is cancelled, rather than waiting out their timeout. Cancellation is
+/// permanent: subsequent waits with the same token fail immediately.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use anode::cancellation::CancellationToken;
+/// use anode::zlock::{ReadBiased, WaitError, ZLock};
+/// let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+/// let token = Arc::new(CancellationToken::new());
+/// let guard = lock.write();
+/// let waiter = {
+///     let lock = lock.clone();
+///     let token = token.clone();
+///     thread::spawn(move || lock.try_write_cancellable(&token, Duration::MAX).map(|_| ()))
+/// };
+/// token.cancel();
+/// assert_eq!(Err(WaitError::Cancelled), waiter.join().unwrap());
+/// ```
+#[derive(Default)]
+pub struct CancellationToken {
+    cancelled: AtomicBool,
+
+    /// Threads waiting on this token, to be woken upon cancellation.
+    wakers: Mutex<Wakers>,
+}
+
+impl CancellationToken {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token, waking all threads presently waiting with it. Has no effect if the
+    /// token is already cancelled.
+    #[inline]
+    pub fn cancel(&self) {
+        let wakers = {
+            let mut wakers = self.wakers.lock().remedy();
+            self.cancelled.store(true, Ordering::Release);
+            wakers.take()
+        };
+        wakers.wake_all();
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Registers `waker` to be woken upon cancellation. Returns `false` if the token has
+    /// already been cancelled, in which case the waker is not registered.
+    #[inline]
+    fn register(&self, waker: &Waker) -> bool {
+        let mut wakers = self.wakers.lock().remedy();
+        if self.is_cancelled() {
+            false
+        } else {
+            wakers.register(waker);
+            true
+        }
+    }
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// The reason for which a cancellable wait was cut short. See [`wait_cancellable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Interruption {
+    Timeout,
+    Cancelled,
+}
+
+/// The interval between checks of the token, when the wait cannot be woken directly.
+const CHECK_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Unparks a thread when woken.
+struct Unparker(Thread);
+
+impl Wake for Unparker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+thread_local! {
+    /// A waker that unparks the current thread. Being reused across waits, the same waker is
+    /// registered with a token (or a lock) at most once.
+    static THREAD_WAKER: Waker = Waker::from(Arc::new(Unparker(thread::current())));
+}
+
+/// Waits up to `duration` for an acquisition to succeed, or until `token` is cancelled.
+///
+/// `poll` attempts the acquisition, registering the given waker to be woken once it may
+/// succeed, as per [`Moderator::poll_read`](crate::zlock::Moderator::poll_read). Where `poll`
+/// is unsupported, the wait falls back to `try_for`, which blocks for at most the given
+/// duration; the wait is then sliced into short intervals, between which the token is checked.
+pub(crate) fn wait_cancellable(
+    token: &CancellationToken,
+    duration: Duration,
+    mut poll: impl FnMut(&Waker) -> Polled<()>,
+    mut try_for: impl FnMut(Duration) -> bool,
+) -> Result<(), Interruption> {
+    let waker = THREAD_WAKER.with(Waker::clone);
+    if !token.register(&waker) {
+        return Err(Interruption::Cancelled);
+    }
+
+    let mut deadline = Deadline::lazy_after(duration);
+    loop {
+        if token.is_cancelled() {
+            return Err(Interruption::Cancelled);
+        }
+
+        match poll(&waker) {
+            Polled::Acquired(()) => return Ok(()),
+            Polled::Pending => {
+                let remaining = deadline.remaining();
+                if remaining.is_zero() {
+                    return Err(Interruption::Timeout);
+                }
+                thread::park_timeout(remaining);
+            }
+            Polled::Unsupported => {
+                let remaining = deadline.remaining();
+                if try_for(remaining.min(CHECK_INTERVAL)) {
+                    return Ok(());
+                }
+                if remaining <= CHECK_INTERVAL {
+                    return Err(Interruption::Timeout);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::cancellation::{wait_cancellable, CancellationToken, Interruption};
+use crate::test_utils::{spawn_blocked, SHORT_WAIT};
+use crate::zlock::Polled;
+
+#[test]
+fn cancel_is_permanent() {
+    let token = CancellationToken::new();
+    assert!(!token.is_cancelled());
+    assert_eq!("CancellationToken { cancelled: false }", format!("{:?}", token));
+    token.cancel();
+    assert!(token.is_cancelled());
+    token.cancel();
+    assert!(token.is_cancelled());
+    assert_eq!("CancellationToken { cancelled: true }", format!("{:?}", token));
+}
+
+#[test]
+fn cancelled_token_fails_without_attempt() {
+    let token = CancellationToken::new();
+    token.cancel();
+    assert_eq!(
+        Err(Interruption::Cancelled),
+        wait_cancellable(&token, Duration::MAX, |_| unreachable!(), |_| unreachable!())
+    );
+}
+
+#[test]
+fn times_out() {
+    let token = CancellationToken::new();
+    assert_eq!(
+        Err(Interruption::Timeout),
+        wait_cancellable(&token, SHORT_WAIT, |_| Polled::Pending, |_| unreachable!())
+    );
+    assert_eq!(
+        Err(Interruption::Timeout),
+        wait_cancellable(&token, SHORT_WAIT, |_| Polled::Unsupported, |_| false)
+    );
+    assert_eq!(
+        Ok(()),
+        wait_cancellable(&token, Duration::ZERO, |_| Polled::Unsupported, |_| true)
+    );
+}
+
+#[test]
+fn cancel_wakes_pending_waiter() {
+    __cancel_wakes_waiter(|_| Polled::Pending);
+}
+
+#[test]
+fn cancel_interrupts_sliced_waiter() {
+    __cancel_wakes_waiter(|_| Polled::Unsupported);
+}
+
+fn __cancel_wakes_waiter(poll: fn(&std::task::Waker) -> Polled<()>) {
+    let token = Arc::new(CancellationToken::new());
+    let waiter = {
+        let token = token.clone();
+        spawn_blocked(move || {
+            wait_cancellable(&token, Duration::MAX, poll, |slice| {
+                thread::sleep(slice);
+                false
+            })
+        })
+    };
+    token.cancel();
+    assert_eq!(Err(Interruption::Cancelled), waiter.join().unwrap());
+}
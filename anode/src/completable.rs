@@ -1,11 +1,50 @@
 use crate::deadline::Deadline;
+use std::fmt;
+use std::marker::PhantomData;
 use std::ops::{Deref};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use crate::monitor::{Directive, Monitor, SpeculativeMonitor, SpeculativeMonitorGuard};
+use crate::remedy::Remedy;
+
+mod once;
+
+pub use once::{Lazy, Once};
+
+#[cfg(feature = "async")]
+mod asynchronous;
+
+#[cfg(feature = "async")]
+pub use asynchronous::CompletableFuture;
+
+/// A callback registered with [`Completable::on_complete`].
+type Callback<T> = Box<dyn FnOnce(&T) + Send>;
+
+/// Parties to be notified upon completion, other than the threads blocked on the monitor.
+struct Listeners<T> {
+    callbacks: Vec<Callback<T>>,
+
+    #[cfg(feature = "async")]
+    wakers: crate::zlock::Wakers,
+}
+
+impl<T> Default for Listeners<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            callbacks: Vec::new(),
+            #[cfg(feature = "async")]
+            wakers: Default::default(),
+        }
+    }
+}
 
-#[derive(Default, Debug)]
 pub struct Completable<T> {
     monitor: SpeculativeMonitor<Option<T>>,
+
+    /// Listeners awaiting completion. Once the instance is complete, they are drained, and
+    /// subsequently registered callbacks are invoked immediately.
+    listeners: Mutex<Listeners<T>>,
 }
 
 pub struct Completed<'a, T> {
@@ -58,7 +97,8 @@ impl<T> Completable<T> {
     #[inline]
     pub fn new(val: T) -> Self {
         Self {
-            monitor: SpeculativeMonitor::new(Some(val))
+            monitor: SpeculativeMonitor::new(Some(val)),
+            listeners: Mutex::default(),
         }
     }
 
@@ -85,6 +125,9 @@ impl<T> Completable<T> {
                 Directive::Return
             }
         });
+        if f.is_none() {
+            self.notify_listeners();
+        }
         f.is_none()
     }
 
@@ -107,16 +150,95 @@ impl<T> Completable<T> {
                 Directive::Return
             }
         });
+        if returned.is_none() {
+            self.notify_listeners();
+        }
         returned
     }
 
+    /// Registers a callback to be invoked with the completed value.
+    ///
+    /// If the instance is incomplete, `f` is invoked by the thread that completes it, after
+    /// the value has been assigned and waiters have been notified. Otherwise, `f` is invoked
+    /// immediately by the calling thread. Either way, `f` is invoked exactly once, with no
+    /// locks held. As `f` may observe the value while other threads hold a
+    /// [`Completed`] guard, `T` must be [`Sync`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicU64, Ordering};
+    /// use anode::completable::Completable;
+    /// let comp = Completable::default();
+    /// let seen = Arc::new(AtomicU64::default());
+    /// {
+    ///     let seen = seen.clone();
+    ///     comp.on_complete(move |val| seen.store(*val, Ordering::Relaxed));
+    /// }
+    /// assert_eq!(0, seen.load(Ordering::Relaxed));
+    /// comp.complete(42);
+    /// assert_eq!(42, seen.load(Ordering::Relaxed));
+    /// ```
+    pub fn on_complete(&self, f: impl FnOnce(&T) + Send + 'static) where T: Sync {
+        let mut listeners = self.listeners.lock().remedy();
+        // the completer assigns the value before draining the listeners, so holding the
+        // listeners lock while checking for completion ensures that `f` is either drained or
+        // invoked here, but not both
+        if !self.is_complete() {
+            listeners.callbacks.push(Box::new(f));
+            return;
+        }
+        drop(listeners);
+        f(self.__try_get_ref(Duration::ZERO).unwrap());
+    }
+
+    /// Derives a [`Completable`] that is completed with the result of applying `f` to the
+    /// completed value of this instance. The derived instance is shared, as it is completed
+    /// from a callback (see [`on_complete`](Self::on_complete)).
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::completable::Completable;
+    /// let comp = Completable::default();
+    /// let len = comp.map(|val: &String| val.len());
+    /// assert!(!len.is_complete());
+    /// comp.complete(String::from("hello"));
+    /// assert_eq!(5, *len.get());
+    /// ```
+    pub fn map<U: Send + 'static>(&self, f: impl FnOnce(&T) -> U + Send + 'static) -> Arc<Completable<U>>
+    where
+        T: Sync,
+    {
+        let mapped = Arc::new(Completable::default());
+        {
+            let mapped = mapped.clone();
+            self.on_complete(move |val| {
+                mapped.complete(f(val));
+            });
+        }
+        mapped
+    }
+
+    fn notify_listeners(&self) {
+        let listeners = std::mem::take(&mut *self.listeners.lock().remedy());
+        #[cfg(feature = "async")]
+        listeners.wakers.wake_all();
+        if listeners.callbacks.is_empty() {
+            return;
+        }
+        let val = self.__try_get_ref(Duration::ZERO).unwrap();
+        for callback in listeners.callbacks {
+            callback(val);
+        }
+    }
+
     #[inline]
     pub fn is_complete(&self) -> bool {
         self.monitor.lock().is_some()
     }
 
     #[inline]
-    pub fn get(&self) -> Completed<T> {
+    pub fn get(&self) -> Completed<'_, T> {
         Completed {
             guard: self.__try_get(Duration::MAX),
         }
@@ -132,28 +254,215 @@ impl<T> Completable<T> {
         self.__try_get(duration)
     }
 
+    /// Waits until this instance completes or the given `deadline` elapses, whichever is
+    /// sooner, returning the value if complete.
+    #[inline]
+    pub fn try_get_until<'a>(&'a self, deadline: impl Into<Deadline>) -> impl Deref<Target = Option<T>> + 'a {
+        self.__try_get_until(deadline.into())
+    }
+
+    /// Waits up to `duration` for this instance to complete, returning a copy of the
+    /// completed value.
+    ///
+    /// # Panics
+    /// If the instance is still incomplete after `duration` has elapsed.
+    #[inline]
+    pub fn get_or_timeout(&self, duration: Duration) -> T where T: Clone {
+        match &*self.__try_get(duration) {
+            None => panic!("Completable not resolved within {duration:?}"),
+            Some(val) => val.clone(),
+        }
+    }
+
+    /// Obtains the completed value without waiting.
+    ///
+    /// # Panics
+    /// With the given `msg` if the instance is incomplete.
+    #[inline]
+    pub fn expect_complete(&self, msg: &str) -> Completed<'_, T> {
+        let guard = self.__try_get(Duration::ZERO);
+        if guard.is_none() {
+            panic!("{msg}");
+        }
+        Completed { guard }
+    }
+
+    /// Waits up to `duration` for this instance to complete, returning a reference to the
+    /// completed value that is not tied to a guard.
+    #[inline]
+    fn __try_get_ref(&self, duration: Duration) -> Option<&T> {
+        let guard = self.__try_get(duration);
+        let val = guard.as_ref()? as *const T;
+        // SAFETY: a completed value is never altered or moved while the instance is borrowed
+        // immutably -- only `reset` and `into_inner` do so, and they require exclusive access
+        Some(unsafe { &*val })
+    }
+
     /// [`__try_get`] is never exposed directly to avoid coupling the caller to the
     /// [`SpeculativeMonitorGuard`] type, which might change in future implementations. Instead, the return
     /// value is publicly exposed as a [`Deref`] trait.
     #[inline]
-    fn __try_get(&self, duration: Duration) -> SpeculativeMonitorGuard<Option<T>> {
-        if !duration.is_zero() {
-            let mut deadline = Deadline::lazy_after(duration);
-            self.monitor.enter(|state| {
-                if state.is_none() {
-                    Directive::Wait(deadline.remaining())
-                } else {
-                    Directive::Return
-                }
-            });
+    fn __try_get(&self, duration: Duration) -> SpeculativeMonitorGuard<'_, Option<T>> {
+        if duration.is_zero() {
+            self.monitor.lock()
+        } else {
+            self.__try_get_until(Deadline::lazy_after(duration))
         }
+    }
+
+    #[inline]
+    fn __try_get_until(&self, mut deadline: Deadline) -> SpeculativeMonitorGuard<'_, Option<T>> {
+        self.monitor.enter(|state| {
+            if state.is_none() {
+                Directive::Wait(deadline.remaining())
+            } else {
+                Directive::Return
+            }
+        });
         self.monitor.lock()
     }
 
+    /// Returns this instance to the incomplete state, discarding the completed value (if any)
+    /// along with any callbacks awaiting completion.
+    /// This allows a [`Completable`] to be recycled (for example, as part of a pooled object)
+    /// without reallocating.
+    ///
+    /// Resetting requires exclusive access to the instance, which is enforced by the `&mut`
+    /// receiver: no other thread can be waiting on, or holding a reference to, this instance
+    /// at the time of the call. Hence, no locking takes place.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::completable::Completable;
+    /// let mut comp = Completable::new(42);
+    /// comp.reset();
+    /// assert!(!comp.is_complete());
+    /// assert!(comp.complete(69).is_none());
+    /// assert_eq!(69, *comp.get());
+    /// ```
+    #[inline]
+    pub fn reset(&mut self) {
+        *self.monitor.get_mut() = None;
+        *self.listeners.get_mut().remedy() = Listeners::default();
+    }
+
     pub fn into_inner(self) -> Option<T> {
         self.monitor.into_inner()
     }
 }
 
+impl<T> Default for Completable<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            monitor: SpeculativeMonitor::new(None),
+            listeners: Mutex::default(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Completable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Completable")
+            .field("monitor", &self.monitor)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A purpose-built [`Completable`] that resolves to either a success value `T` or an error `E`,
+/// sparing waiters from dealing with a nested `Option<Result<T, E>>`.
+///
+/// The first completion wins, whether it is a success or an error. When [`complete_ok`] and
+/// [`complete_err`] race, neither takes precedence: whichever is applied first determines the
+/// outcome and the other is returned to its caller.
+///
+/// [`complete_ok`]: CompletableResult::complete_ok
+/// [`complete_err`]: CompletableResult::complete_err
+///
+/// # Examples
+/// ```
+/// use anode::completable::CompletableResult;
+/// let comp = CompletableResult::<u64, String>::default();
+/// assert!(comp.complete_ok(42).is_none());
+/// assert_eq!(Some(String::from("too late")), comp.complete_err(String::from("too late")));
+/// assert_eq!(Ok(&42), comp.get());
+/// ```
+///
+/// Since [`get`](Self::get) hands out references that outlive the internal lock, sharing an
+/// instance among threads requires both `T` and `E` to be [`Sync`]:
+/// ```compile_fail
+/// use std::cell::Cell;
+/// use anode::completable::CompletableResult;
+/// fn sync<T: Sync>(_: T) {}
+/// sync(CompletableResult::<Cell<u64>, ()>::default());
+/// ```
+#[derive(Debug)]
+pub struct CompletableResult<T, E> {
+    inner: Completable<Result<T, E>>,
+
+    /// Makes the struct [`Sync`] only if the outcome is, as references to the outcome are
+    /// not confined by a guard.
+    __sync: PhantomData<Result<T, E>>,
+}
+
+impl<T, E> Default for CompletableResult<T, E> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            inner: Completable::default(),
+            __sync: PhantomData,
+        }
+    }
+}
+
+impl<T, E> CompletableResult<T, E> {
+    /// Completes this instance with a success value if the instance is incomplete.
+    ///
+    /// Returns `None` if the value was persisted, or `Some` containing the value if the
+    /// instance was already complete.
+    #[inline]
+    pub fn complete_ok(&self, val: T) -> Option<T> {
+        self.inner.complete(Ok(val)).map(|returned| match returned {
+            Ok(val) => val,
+            Err(_) => unreachable!(),
+        })
+    }
+
+    /// Completes this instance with an error if the instance is incomplete.
+    ///
+    /// Returns `None` if the error was persisted, or `Some` containing the error if the
+    /// instance was already complete.
+    #[inline]
+    pub fn complete_err(&self, err: E) -> Option<E> {
+        self.inner.complete(Err(err)).map(|returned| match returned {
+            Ok(_) => unreachable!(),
+            Err(err) => err,
+        })
+    }
+
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.inner.is_complete()
+    }
+
+    /// Waits for this instance to complete, returning the outcome.
+    #[inline]
+    pub fn get(&self) -> Result<&T, &E> {
+        self.inner.__try_get_ref(Duration::MAX).unwrap().as_ref()
+    }
+
+    /// Waits up to `duration` for this instance to complete, returning the outcome, or `None`
+    /// if the instance is still incomplete.
+    #[inline]
+    pub fn try_get(&self, duration: Duration) -> Option<Result<&T, &E>> {
+        self.inner.__try_get_ref(duration).map(Result::as_ref)
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Option<Result<T, E>> {
+        self.inner.into_inner()
+    }
+}
+
 #[cfg(test)]
-mod tests;
\ No newline at end of file
+mod tests;
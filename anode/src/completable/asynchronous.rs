@@ -0,0 +1,69 @@
+use crate::completable::Completable;
+use crate::remedy::Remedy;
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+impl<T: Sync> Completable<T> {
+    /// Returns a future that resolves to the completed value, without blocking the
+    /// polling thread. A pending future registers its waker, which is woken by the thread
+    /// that completes this instance.
+    ///
+    /// `&Completable` also implements [`IntoFuture`], so that the instance may be awaited
+    /// directly.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::completable::Completable;
+    /// async fn await_it(comp: &Completable<u64>) -> u64 {
+    ///     *comp.as_future().await + *comp.await
+    /// }
+    /// ```
+    #[inline]
+    pub fn as_future(&self) -> CompletableFuture<'_, T> {
+        CompletableFuture { completable: self }
+    }
+}
+
+impl<'a, T: Sync> IntoFuture for &'a Completable<T> {
+    type Output = &'a T;
+    type IntoFuture = CompletableFuture<'a, T>;
+
+    #[inline]
+    fn into_future(self) -> Self::IntoFuture {
+        self.as_future()
+    }
+}
+
+/// A future returned by [`Completable::as_future`].
+#[must_use = "futures do nothing unless polled"]
+pub struct CompletableFuture<'a, T> {
+    completable: &'a Completable<T>,
+}
+
+impl<'a, T: Sync> Future for CompletableFuture<'a, T> {
+    type Output = &'a T;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let completable = self.completable;
+        if let Some(val) = completable.__try_get_ref(Duration::ZERO) {
+            return Poll::Ready(val);
+        }
+
+        // as with callbacks, the completion check is repeated under the listeners lock, so
+        // that the waker is either registered before the listeners are drained, or not at all
+        let mut listeners = completable.listeners.lock().remedy();
+        match completable.__try_get_ref(Duration::ZERO) {
+            Some(val) => Poll::Ready(val),
+            None => {
+                listeners.wakers.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
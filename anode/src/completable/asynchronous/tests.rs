@@ -0,0 +1,76 @@
+use crate::completable::Completable;
+use crate::test_utils::block_on;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn already_complete() {
+    let comp = Completable::new(42);
+    assert_eq!(42, *block_on(comp.as_future()));
+    assert_eq!(42, block_on(async { *(&comp).await }));
+}
+
+#[test]
+fn pending_until_complete() {
+    let comp = Completable::default();
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut future = pin!(comp.as_future());
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    // repeated polls do not register the same waker twice
+    assert!(future.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(0, counter.0.load(Ordering::Relaxed));
+
+    assert!(comp.complete(42).is_none());
+    assert_eq!(1, counter.0.load(Ordering::Relaxed));
+    match future.as_mut().poll(&mut cx) {
+        Poll::Ready(val) => assert_eq!(42, *val),
+        Poll::Pending => panic!("still pending"),
+    }
+
+    // a failed completion wakes no one
+    assert!(comp.complete(69).is_some());
+    assert_eq!(1, counter.0.load(Ordering::Relaxed));
+}
+
+#[test]
+fn complete_from_another_thread() {
+    let comp = Arc::new(Completable::default());
+    let completer = {
+        let comp = comp.clone();
+        thread::spawn(move || comp.complete_exclusive(|| 42))
+    };
+    assert_eq!(42, *block_on(comp.as_future()));
+    assert!(completer.join().unwrap());
+}
+
+#[test]
+fn many_waiters() {
+    const WAITERS: usize = 4;
+    let comp = Arc::new(Completable::default());
+    let waiters = (0..WAITERS)
+        .map(|_| {
+            let comp = comp.clone();
+            thread::spawn(move || block_on(async { *comp.as_future().await }))
+        })
+        .collect::<Vec<_>>();
+    assert!(comp.complete(42).is_none());
+    for waiter in waiters {
+        assert_eq!(42, waiter.join().unwrap());
+    }
+}
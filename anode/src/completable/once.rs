@@ -0,0 +1,265 @@
+use crate::completable::Completable;
+use crate::deadline::Deadline;
+use crate::monitor::{Directive, Monitor};
+use crate::remedy::Remedy;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A cell that is initialized at most once, typically on first use.
+///
+/// Of the threads racing to initialize the cell, one claims the initialization and evaluates
+/// its closure, while the others wait for the outcome. The closure is evaluated outside of
+/// any lock, so that the waiting threads can give up after a timeout (see
+/// [`try_get_or_init`](Self::try_get_or_init)). Should the closure panic, the claim is
+/// withdrawn and one of the waiting threads takes over the initialization.
+///
+/// # Examples
+/// ```
+/// use anode::completable::Once;
+/// let once = Once::new();
+/// assert_eq!(None, once.get());
+/// assert_eq!(&42, once.get_or_init(|| 42));
+/// assert_eq!(&42, once.get_or_init(|| 69));
+/// assert_eq!(Some(&42), once.get());
+/// ```
+pub struct Once<T> {
+    inner: Completable<T>,
+
+    /// Set while a thread is initializing the cell, or once it has been initialized.
+    claimed: AtomicBool,
+
+    /// Makes the struct [`Sync`] only if the value is, as references to the value are not
+    /// confined by a guard.
+    __sync: PhantomData<T>,
+}
+
+impl<T> Once<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            inner: Completable::default(),
+            claimed: AtomicBool::new(false),
+            __sync: PhantomData,
+        }
+    }
+
+    /// Returns the value if the cell has been initialized, without waiting.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.inner.__try_get_ref(Duration::ZERO)
+    }
+
+    /// Waits for the cell to be initialized by another thread, returning the value.
+    #[inline]
+    pub fn wait(&self) -> &T {
+        self.inner.__try_get_ref(Duration::MAX).unwrap()
+    }
+
+    /// Waits up to `duration` for the cell to be initialized by another thread, returning the
+    /// value, or `None` if the cell is still uninitialized.
+    #[inline]
+    pub fn try_wait(&self, duration: Duration) -> Option<&T> {
+        self.inner.__try_get_ref(duration)
+    }
+
+    /// Initializes the cell with `val` if it is uninitialized and no other thread is
+    /// initializing it. Otherwise, `val` is returned to the caller.
+    #[inline]
+    pub fn set(&self, val: T) -> Result<(), T> {
+        if self.claim() {
+            self.publish(val);
+            Ok(())
+        } else {
+            Err(val)
+        }
+    }
+
+    /// Returns the value, initializing the cell with `f` if it is uninitialized. If another
+    /// thread is initializing the cell, waits for it to finish.
+    #[inline]
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        self.try_get_or_init(Duration::MAX, f).unwrap()
+    }
+
+    /// Returns the value, initializing the cell with `f` if it is uninitialized. If another
+    /// thread is initializing the cell, waits up to `duration` for it to finish, returning
+    /// `None` if it does not. `f` is invoked only if this thread claims the initialization,
+    /// in which case the value is always returned.
+    pub fn try_get_or_init(&self, duration: Duration, f: impl FnOnce() -> T) -> Option<&T> {
+        if let Some(val) = self.get() {
+            return Some(val);
+        }
+
+        let mut deadline = Deadline::lazy_after(duration);
+        loop {
+            if self.claim() {
+                let abandon_on_panic = AbandonOnPanic(self);
+                self.publish(f());
+                std::mem::forget(abandon_on_panic);
+                return self.get();
+            }
+
+            // wait until the value is published, or the claim is withdrawn by a panicking thread;
+            // the claim is withdrawn under the monitor, so that its release is never missed
+            let mut abandoned = false;
+            self.inner.monitor.enter(|val| {
+                if val.is_some() {
+                    Directive::Return
+                } else if !self.claimed.load(Ordering::Relaxed) {
+                    abandoned = true;
+                    Directive::Return
+                } else {
+                    Directive::Wait(deadline.remaining())
+                }
+            });
+            if !abandoned {
+                return self.get();
+            }
+        }
+    }
+
+    #[inline]
+    fn claim(&self) -> bool {
+        self.claimed
+            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    #[inline]
+    fn publish(&self, val: T) {
+        let returned = self.inner.complete(val);
+        debug_assert!(returned.is_none(), "cell was initialized without a claim");
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.inner.into_inner()
+    }
+}
+
+/// Withdraws the claim to initialize a [`Once`] if the initializing closure panics.
+struct AbandonOnPanic<'a, T>(&'a Once<T>);
+
+impl<T> Drop for AbandonOnPanic<'_, T> {
+    fn drop(&mut self) {
+        let mut withdrawn = false;
+        self.0.inner.monitor.enter(|_| {
+            if !withdrawn {
+                withdrawn = true;
+                self.0.claimed.store(false, Ordering::Relaxed);
+            }
+            Directive::NotifyAll
+        });
+    }
+}
+
+impl<T> Default for Once<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<T> for Once<T> {
+    #[inline]
+    fn from(val: T) -> Self {
+        Self {
+            inner: Completable::new(val),
+            claimed: AtomicBool::new(true),
+            __sync: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Once<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Once")
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// A value that is initialized on first access, using a [`Once`] cell.
+///
+/// # Examples
+/// ```
+/// use anode::completable::Lazy;
+/// let lazy = Lazy::new(|| vec![1, 2, 3]);
+/// assert_eq!(None, Lazy::get(&lazy));
+/// assert_eq!(3, lazy.len());
+/// assert_eq!(Some(&vec![1, 2, 3]), Lazy::get(&lazy));
+/// ```
+pub struct Lazy<T, F = fn() -> T> {
+    once: Once<T>,
+    init: Mutex<Option<F>>,
+}
+
+impl<T, F: FnOnce() -> T> Lazy<T, F> {
+    #[inline]
+    pub fn new(f: F) -> Self {
+        Self {
+            once: Once::new(),
+            init: Mutex::new(Some(f)),
+        }
+    }
+
+    /// Forces the evaluation of the initializer, returning the value.
+    #[inline]
+    pub fn force(this: &Self) -> &T {
+        this.once.get_or_init(|| Self::take_init(this)())
+    }
+
+    /// Forces the evaluation of the initializer, waiting up to `duration` for another thread
+    /// to finish evaluating it. Returns `None` if the value is still uninitialized.
+    #[inline]
+    pub fn try_force(this: &Self, duration: Duration) -> Option<&T> {
+        this.once.try_get_or_init(duration, || Self::take_init(this)())
+    }
+
+    #[inline]
+    fn take_init(this: &Self) -> F {
+        match this.init.lock().remedy().take() {
+            Some(f) => f,
+            None => panic!("Lazy instance has previously been poisoned"),
+        }
+    }
+}
+
+impl<T, F> Lazy<T, F> {
+    /// Returns the value if it has been initialized, without forcing it.
+    #[inline]
+    pub fn get(this: &Self) -> Option<&T> {
+        this.once.get()
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        Self::force(self)
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default)
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for Lazy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("value", &Self::get(self))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
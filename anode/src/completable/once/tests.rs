@@ -0,0 +1,147 @@
+use crate::completable::{Lazy, Once};
+use crate::test_utils::SHORT_WAIT;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn get_or_init() {
+    let once = Once::new();
+    assert_eq!(None, once.get());
+    assert_eq!(None, once.try_wait(Duration::ZERO));
+    assert_eq!(&42, once.get_or_init(|| 42));
+    assert_eq!(&42, once.get_or_init(|| unreachable!()));
+    assert_eq!(Some(&42), once.try_get_or_init(Duration::ZERO, || unreachable!()));
+    assert_eq!(&42, once.wait());
+    assert_eq!(Err(69), once.set(69));
+    assert_eq!(Some(42), once.into_inner());
+}
+
+#[test]
+fn set_and_from() {
+    let once = Once::default();
+    assert_eq!(Ok(()), once.set(42));
+    assert_eq!(Some(&42), once.get());
+
+    let once = Once::from(42);
+    assert_eq!(Some(&42), once.get());
+    assert_eq!(&42, once.get_or_init(|| unreachable!()));
+    assert_eq!("Once { value: Some(42) }", format!("{:?}", once));
+}
+
+#[test]
+fn try_get_or_init_times_out() {
+    let once = Arc::new(Once::new());
+    let barrier = Arc::new(Barrier::new(2));
+    let initializer = {
+        let once = once.clone();
+        let barrier = barrier.clone();
+        thread::spawn(move || {
+            *once.get_or_init(|| {
+                barrier.wait();
+                barrier.wait();
+                42
+            })
+        })
+    };
+
+    // while the initializer is busy, the cell cannot be claimed
+    barrier.wait();
+    assert_eq!(None, once.try_get_or_init(SHORT_WAIT, || unreachable!()));
+    assert_eq!(Err(69), once.set(69));
+    barrier.wait();
+
+    assert_eq!(&42, once.get_or_init(|| unreachable!()));
+    assert_eq!(42, initializer.join().unwrap());
+}
+
+#[test]
+fn panicking_init_is_abandoned() {
+    let once = Once::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        once.get_or_init(|| panic!("boom"));
+    }));
+    assert!(result.is_err());
+    assert_eq!(None, once.get());
+    assert_eq!(&42, once.get_or_init(|| 42));
+}
+
+#[test]
+fn panicking_init_hands_over_to_waiter() {
+    let once = Arc::new(Once::new());
+    let barrier = Arc::new(Barrier::new(2));
+    let initializer = {
+        let once = once.clone();
+        let barrier = barrier.clone();
+        thread::spawn(move || {
+            once.get_or_init(|| {
+                barrier.wait();
+                thread::sleep(SHORT_WAIT);
+                panic!("boom");
+            });
+        })
+    };
+
+    barrier.wait();
+    assert_eq!(&42, once.get_or_init(|| 42));
+    assert!(initializer.join().is_err());
+}
+
+#[test]
+fn init_races() {
+    const THREADS: usize = 8;
+    let once = Arc::new(Once::new());
+    let invocations = Arc::new(AtomicUsize::default());
+    let threads = (0..THREADS)
+        .map(|i| {
+            let once = once.clone();
+            let invocations = invocations.clone();
+            thread::spawn(move || {
+                *once.get_or_init(|| {
+                    invocations.fetch_add(1, Ordering::Relaxed);
+                    i
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let winner = *once.wait();
+    for thread in threads {
+        assert_eq!(winner, thread.join().unwrap());
+    }
+    assert_eq!(1, invocations.load(Ordering::Relaxed));
+}
+
+#[test]
+fn lazy() {
+    let invocations = AtomicUsize::default();
+    let lazy = Lazy::new(|| {
+        invocations.fetch_add(1, Ordering::Relaxed);
+        42
+    });
+    assert_eq!(None, Lazy::get(&lazy));
+    assert_eq!(42, *lazy);
+    assert_eq!(&42, Lazy::force(&lazy));
+    assert_eq!(Some(&42), Lazy::try_force(&lazy, Duration::ZERO));
+    assert_eq!(Some(&42), Lazy::get(&lazy));
+    assert_eq!(1, invocations.load(Ordering::Relaxed));
+
+    let lazy = Lazy::<Vec<u64>>::default();
+    assert!(lazy.is_empty());
+    assert_eq!("Lazy { value: Some([]), .. }", format!("{:?}", lazy));
+}
+
+#[test]
+fn lazy_poisoned_by_panic() {
+    let lazy: Lazy<u64> = Lazy::new(|| panic!("boom"));
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| *lazy)).is_err());
+    let result = panic::catch_unwind(AssertUnwindSafe(|| *lazy));
+    let err = result.unwrap_err();
+    assert_eq!(
+        Some(&"Lazy instance has previously been poisoned"),
+        err.downcast_ref::<&str>()
+    );
+}
@@ -1,7 +1,10 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Barrier};
 use std::thread;
-use crate::completable::{Completable};
-use crate::test_utils::SHORT_WAIT;
+use std::time::{Duration, Instant};
+use crate::deadline::Deadline;
+use crate::completable::{Completable, CompletableResult};
+use crate::test_utils::{CHECK_WAIT, SHORT_WAIT};
 
 #[test]
 fn complete_later() {
@@ -63,6 +66,32 @@ fn await_complete() {
     t_2.join().unwrap();
 }
 
+#[test]
+fn get_or_timeout() {
+    let comp = Completable::new(42);
+    assert_eq!(42, comp.get_or_timeout(SHORT_WAIT));
+}
+
+#[test]
+#[should_panic(expected = "Completable not resolved within")]
+fn get_or_timeout_incomplete() {
+    let comp = Completable::<i32>::default();
+    comp.get_or_timeout(SHORT_WAIT);
+}
+
+#[test]
+fn expect_complete() {
+    let comp = Completable::new(42);
+    assert_eq!(42, *comp.expect_complete("should be complete"));
+}
+
+#[test]
+#[should_panic(expected = "should be complete")]
+fn expect_complete_incomplete() {
+    let comp = Completable::<i32>::default();
+    comp.expect_complete("should be complete");
+}
+
 #[test]
 fn complete_exclusive() {
     let comp = Completable::default();
@@ -84,6 +113,78 @@ fn complete_exclusive() {
     assert!(!invoked);
 }
 
+#[test]
+fn reset() {
+    let mut comp = Completable::default();
+    comp.reset();
+    assert!(!comp.is_complete());
+
+    assert!(comp.complete(42).is_none());
+    comp.reset();
+    assert!(!comp.is_complete());
+    assert_eq!(None, *comp.peek());
+
+    // the recycled instance behaves as a fresh one, admitting a new value and waking a waiter
+    let comp = Arc::new(comp);
+    let waiter = {
+        let comp = comp.clone();
+        thread::spawn(move || *comp.get())
+    };
+    assert!(comp.complete(69).is_none());
+    assert_eq!(69, waiter.join().unwrap());
+    assert_eq!(Some(69), Arc::try_unwrap(comp).unwrap().into_inner());
+}
+
+#[test]
+fn completable_result() {
+    let comp = CompletableResult::<u64, String>::default();
+    assert!(!comp.is_complete());
+    assert_eq!(None, comp.try_get(SHORT_WAIT));
+
+    assert_eq!(None, comp.complete_err(String::from("failed")));
+    assert!(comp.is_complete());
+    assert_eq!(Err(&String::from("failed")), comp.get());
+    assert_eq!(Some(Err(&String::from("failed"))), comp.try_get(SHORT_WAIT));
+
+    // the first terminal state wins
+    assert_eq!(Some(42), comp.complete_ok(42));
+    assert_eq!(Some(String::from("again")), comp.complete_err(String::from("again")));
+    assert_eq!(Some(Err(String::from("failed"))), comp.into_inner());
+}
+
+#[test]
+fn completable_result_await() {
+    let comp = Arc::new(CompletableResult::<u64, ()>::default());
+    let waiter = {
+        let comp = comp.clone();
+        thread::spawn(move || comp.get().ok().copied())
+    };
+    assert_eq!(None, comp.complete_ok(42));
+    assert_eq!(Some(42), waiter.join().unwrap());
+}
+
+#[test]
+fn completable_result_race() {
+    for _ in 0..10 {
+        let comp = Arc::new(CompletableResult::<u64, u64>::default());
+        let barrier = Arc::new(Barrier::new(2));
+        let t_ok = {
+            let (comp, barrier) = (comp.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                comp.complete_ok(1).is_none()
+            })
+        };
+        barrier.wait();
+        let err_won = comp.complete_err(2).is_none();
+        let ok_won = t_ok.join().unwrap();
+
+        // exactly one of the racing completions prevails, and the outcome reflects it
+        assert_ne!(ok_won, err_won);
+        assert_eq!(if ok_won { Ok(&1) } else { Err(&2) }, comp.get());
+    }
+}
+
 #[test]
 fn completable_is_sync() {
     fn sync<T: Sync>(_: T) {}
@@ -92,4 +193,157 @@ fn completable_is_sync() {
     sync(comp.peek());
     sync(comp.get());
     sync(comp);
-}
\ No newline at end of file
+}
+
+#[test]
+fn completable_result_is_sync() {
+    fn sync<T: Sync>(_: T) {}
+
+    let comp = CompletableResult::<u64, String>::default();
+    sync(comp.try_get(SHORT_WAIT));
+    sync(comp);
+}
+
+#[test]
+fn on_complete_before_and_after() {
+    let comp = Completable::default();
+    let sum = Arc::new(AtomicU64::default());
+    {
+        let sum = sum.clone();
+        comp.on_complete(move |val| {
+            sum.fetch_add(*val, Ordering::Relaxed);
+        });
+    }
+    assert_eq!(0, sum.load(Ordering::Relaxed));
+
+    assert!(comp.complete(42).is_none());
+    assert_eq!(42, sum.load(Ordering::Relaxed));
+
+    // a failed completion does not rerun callbacks
+    assert!(comp.complete(69).is_some());
+    assert_eq!(42, sum.load(Ordering::Relaxed));
+
+    // registered after completion, so run immediately
+    {
+        let sum = sum.clone();
+        comp.on_complete(move |val| {
+            sum.fetch_add(*val, Ordering::Relaxed);
+        });
+    }
+    assert_eq!(84, sum.load(Ordering::Relaxed));
+}
+
+#[test]
+fn on_complete_exclusive() {
+    let comp = Completable::default();
+    let invoked = Arc::new(AtomicUsize::default());
+    {
+        let invoked = invoked.clone();
+        comp.on_complete(move |val| {
+            assert_eq!(42, *val);
+            invoked.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    assert!(comp.complete_exclusive(|| 42));
+    assert!(!comp.complete_exclusive(|| 69));
+    assert_eq!(1, invoked.load(Ordering::Relaxed));
+}
+
+#[test]
+fn on_complete_may_reenter() {
+    let comp = Arc::new(Completable::default());
+    let seen = Arc::new(Completable::default());
+    {
+        let (comp_2, seen) = (comp.clone(), seen.clone());
+        comp.on_complete(move |val: &u64| {
+            // no locks are held while the callback runs
+            assert_eq!(Some(*val), *comp_2.peek());
+            assert!(seen.complete(*val).is_none());
+        });
+    }
+    comp.complete(42);
+    assert_eq!(42, *seen.get());
+}
+
+#[test]
+fn on_complete_race() {
+    const CALLBACKS: usize = 100;
+    for _ in 0..10 {
+        let comp = Arc::new(Completable::default());
+        let invoked = Arc::new(AtomicUsize::default());
+        let barrier = Arc::new(Barrier::new(2));
+        let registrar = {
+            let (comp, invoked, barrier) = (comp.clone(), invoked.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..CALLBACKS {
+                    let invoked = invoked.clone();
+                    comp.on_complete(move |val| {
+                        assert_eq!(42, *val);
+                        invoked.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            })
+        };
+        barrier.wait();
+        comp.complete(42);
+        registrar.join().unwrap();
+
+        // every callback ran exactly once, irrespective of whether it was registered before
+        // or after completion
+        assert_eq!(CALLBACKS, invoked.load(Ordering::Relaxed));
+    }
+}
+
+#[test]
+fn map() {
+    let comp = Completable::default();
+    let doubled = comp.map(|val: &u64| val * 2);
+    let described = doubled.map(|val| format!("{val}"));
+    assert!(!doubled.is_complete());
+    assert!(!described.is_complete());
+
+    comp.complete(21);
+    assert_eq!(42, *doubled.get());
+    assert_eq!("42", *described.get());
+
+    // mapping a complete instance completes the derived instance immediately
+    assert_eq!(Some(43), *comp.map(|val| val * 2 + 1).peek());
+}
+
+#[test]
+fn map_await() {
+    let comp = Arc::new(Completable::default());
+    let mapped = comp.map(|val: &u64| val + 1);
+    let waiter = thread::spawn(move || *mapped.get());
+    comp.complete(41);
+    assert_eq!(42, waiter.join().unwrap());
+}
+
+#[test]
+fn reset_discards_callbacks() {
+    let mut comp = Completable::default();
+    let invoked = Arc::new(AtomicUsize::default());
+    {
+        let invoked = invoked.clone();
+        comp.on_complete(move |_| {
+            invoked.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+    comp.reset();
+    comp.complete(42);
+    assert_eq!(0, invoked.load(Ordering::Relaxed));
+}
+
+#[test]
+fn try_get_until() {
+    let c = Completable::default();
+    let start = Instant::now();
+    assert!(c.try_get_until(Instant::now() + CHECK_WAIT).is_none());
+    assert!(start.elapsed() >= CHECK_WAIT);
+    assert!(c.try_get_until(Deadline::Elapsed).is_none());
+
+    c.complete(42);
+    assert_eq!(Some(42), *c.try_get_until(Duration::ZERO));
+    assert_eq!(Some(42), *c.try_get_until(Deadline::Forever));
+}
@@ -1,6 +1,27 @@
 use std::time::{Duration, Instant};
 
-#[derive(Debug)]
+/// A point in time by which a blocking operation must complete, accepted by the `*_until`
+/// variants of timed operations throughout the crate.
+///
+/// Unlike a [`Duration`], a deadline may be shared across a sequence of operations, bounding
+/// their combined wait. A deadline created with [`lazy_after`](Self::lazy_after) defers reading
+/// the clock until it is first queried, which spares the cost for operations that complete
+/// without waiting; it should be initialized (e.g., with [`after`](Self::after)) before it is
+/// cloned, lest each clone start its own clock. Passing `&mut deadline` to a `*_until` operation
+/// initializes it in place, so that the same deadline may be passed to the next operation.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use anode::deadline::Deadline;
+/// use anode::semaphore::Semaphore;
+/// let (a, b) = (Semaphore::new(1), Semaphore::new(0));
+/// let mut deadline = Deadline::lazy_after(Duration::from_millis(10));
+/// assert!(a.try_acquire_until(1, &mut deadline).is_some());
+/// assert!(b.try_acquire_until(1, &mut deadline).is_none());
+/// assert!(deadline.expired());
+/// ```
+#[derive(Debug, Clone)]
 pub enum Deadline {
     Point(Instant),
     Forever,
@@ -9,11 +30,14 @@ pub enum Deadline {
 }
 
 impl Deadline {
+    /// A deadline that elapses `duration` after it is first queried. A [`Duration::MAX`] is
+    /// never reached, and a [`Duration::ZERO`] has already elapsed.
     #[inline(always)]
     pub fn lazy_after(duration: Duration) -> Self {
         Self::Uninitialized(duration)
     }
 
+    /// A deadline that elapses `duration` from now.
     #[inline(always)]
     pub fn after(duration: Duration) -> Self {
         let mut deadline = Self::lazy_after(duration);
@@ -21,6 +45,12 @@ impl Deadline {
         deadline
     }
 
+    /// A deadline that elapses at the given `instant`, which may be in the past.
+    #[inline(always)]
+    pub fn at(instant: Instant) -> Self {
+        Self::Point(instant)
+    }
+
     #[inline(always)]
     fn saturating_add(instant: Instant, duration: Duration) -> Self {
         match instant.checked_add(duration) {
@@ -42,6 +72,8 @@ impl Deadline {
         }
     }
 
+    /// The time remaining until the deadline elapses, which is zero once it has elapsed, or
+    /// [`Duration::MAX`] for a deadline that never elapses.
     #[inline(always)]
     pub fn remaining(&mut self) -> Duration {
         self.ensure_initialized();
@@ -53,4 +85,39 @@ impl Deadline {
             _ => unreachable!(),
         }
     }
+
+    /// Determines whether the deadline has elapsed.
+    #[inline(always)]
+    pub fn expired(&mut self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+impl From<Duration> for Deadline {
+    /// Equivalent to [`Deadline::lazy_after`].
+    #[inline(always)]
+    fn from(duration: Duration) -> Self {
+        Self::lazy_after(duration)
+    }
 }
+
+impl From<Instant> for Deadline {
+    /// Equivalent to [`Deadline::at`].
+    #[inline(always)]
+    fn from(instant: Instant) -> Self {
+        Self::at(instant)
+    }
+}
+
+impl From<&mut Deadline> for Deadline {
+    /// Initializes the referenced deadline (if lazy) and copies it, such that both elapse at
+    /// the same time.
+    #[inline(always)]
+    fn from(deadline: &mut Deadline) -> Self {
+        deadline.ensure_initialized();
+        deadline.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+use crate::deadline::Deadline;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+
+#[test]
+fn lazy_after_special_durations() {
+    let mut deadline = Deadline::lazy_after(Duration::ZERO);
+    assert!(deadline.expired());
+    assert!(matches!(deadline, Deadline::Elapsed));
+
+    let mut deadline = Deadline::lazy_after(Duration::MAX);
+    assert!(!deadline.expired());
+    assert_eq!(Duration::MAX, deadline.remaining());
+    assert!(matches!(deadline, Deadline::Forever));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn after_and_at() {
+    let mut deadline = Deadline::after(LONG_WAIT);
+    assert!(matches!(deadline, Deadline::Point(_)));
+    assert!(!deadline.expired());
+    assert!(deadline.remaining() <= LONG_WAIT);
+
+    let mut deadline = Deadline::at(Instant::now() + CHECK_WAIT);
+    assert!(!deadline.expired());
+    std::thread::sleep(CHECK_WAIT);
+    assert!(deadline.expired());
+    assert_eq!(Duration::ZERO, deadline.remaining());
+
+    // an instant in the past has already elapsed
+    assert!(Deadline::at(Instant::now() - CHECK_WAIT).expired());
+}
+
+#[test]
+fn conversions() {
+    assert!(matches!(Deadline::from(LONG_WAIT), Deadline::Uninitialized(LONG_WAIT)));
+    let instant = Instant::now();
+    assert!(matches!(Deadline::from(instant), Deadline::Point(point) if point == instant));
+
+    // converting from a reference initializes the referent, so that both share a clock
+    let mut shared = Deadline::lazy_after(LONG_WAIT);
+    let copy = Deadline::from(&mut shared);
+    match (shared, copy) {
+        (Deadline::Point(a), Deadline::Point(b)) => assert_eq!(a, b),
+        other => panic!("unexpected {other:?}"),
+    }
+}
@@ -0,0 +1,182 @@
+//! An opt-in deadlock detector, enabled by the `deadlock_detection` feature.
+//!
+//! When enabled, [`ZLock`](crate::zlock::ZLock) and [`SpinMutex`](crate::spin_mutex::SpinMutex)
+//! register every acquisition, release and blocking wait with a global wait-for graph.
+//! [`check`] inspects the graph and returns any cycles among the waiting threads. Without the
+//! feature, the registration hooks compile to nothing and [`check`] is unavailable.
+//!
+//! A thread that waits for a lock it already holds (for example, when upgrading a read lock)
+//! is not considered to be waiting on itself.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "deadlock_detection")]
+//! # {
+//! use anode::deadlock;
+//! for cycle in deadlock::check() {
+//!     eprintln!("deadlock: {cycle:?}");
+//! }
+//! # }
+//! ```
+
+#[cfg(feature = "deadlock_detection")]
+pub use detector::{check, Cycle, Waiter};
+
+#[cfg(feature = "deadlock_detection")]
+pub(crate) use detector::{acquired, abandoned, released, waiting};
+
+/// Records that the current thread is about to block on the lock at `addr`.
+#[cfg(not(feature = "deadlock_detection"))]
+#[inline(always)]
+pub(crate) fn waiting(_addr: usize) {}
+
+/// Records that the current thread has given up waiting, without acquiring the lock.
+#[cfg(not(feature = "deadlock_detection"))]
+#[inline(always)]
+pub(crate) fn abandoned() {}
+
+/// Records that the current thread holds the lock at `addr`, clearing any wait.
+#[cfg(not(feature = "deadlock_detection"))]
+#[inline(always)]
+pub(crate) fn acquired(_addr: usize) {}
+
+/// Records that one hold of the lock at `addr` has been released.
+#[cfg(not(feature = "deadlock_detection"))]
+#[inline(always)]
+pub(crate) fn released(_addr: usize) {}
+
+/// Obtains the address of a lock, by which it is identified in the wait-for graph.
+#[inline(always)]
+pub(crate) fn addr_of<L: ?Sized>(lock: &L) -> usize {
+    lock as *const L as *const () as usize
+}
+
+#[cfg(feature = "deadlock_detection")]
+mod detector {
+    use std::collections::HashMap;
+    use std::sync::{Mutex, MutexGuard, OnceLock};
+    use std::thread::{self, ThreadId};
+
+    /// A thread that is blocked waiting for a lock.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Waiter {
+        pub thread: ThreadId,
+
+        /// The address of the lock the thread is waiting for.
+        pub lock: usize,
+    }
+
+    /// A cycle in the wait-for graph. Each waiter is blocked on a lock held by the
+    /// next waiter in the sequence; the last is blocked on a lock held by the first.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Cycle {
+        pub waiters: Vec<Waiter>,
+    }
+
+    #[derive(Default)]
+    struct Registry {
+        /// The threads holding each lock. A thread may appear more than once for the same
+        /// lock (e.g., with multiple read guards).
+        holders: HashMap<usize, Vec<ThreadId>>,
+
+        /// The lock that each blocked thread is waiting for.
+        waiting: HashMap<ThreadId, usize>,
+    }
+
+    fn registry() -> MutexGuard<'static, Registry> {
+        static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+        REGISTRY
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) fn waiting(addr: usize) {
+        registry().waiting.insert(thread::current().id(), addr);
+    }
+
+    pub(crate) fn abandoned() {
+        registry().waiting.remove(&thread::current().id());
+    }
+
+    pub(crate) fn acquired(addr: usize) {
+        let thread = thread::current().id();
+        let mut registry = registry();
+        registry.waiting.remove(&thread);
+        registry.holders.entry(addr).or_default().push(thread);
+    }
+
+    pub(crate) fn released(addr: usize) {
+        let thread = thread::current().id();
+        let mut registry = registry();
+        if let Some(holders) = registry.holders.get_mut(&addr) {
+            // guards that may be released on another thread are deregistered on the acquiring
+            // thread beforehand, so only the releasing thread's own hold is dropped
+            if let Some(index) = holders.iter().position(|&holder| holder == thread) {
+                holders.swap_remove(index);
+            }
+            if holders.is_empty() {
+                registry.holders.remove(&addr);
+            }
+        }
+    }
+
+    /// Inspects the wait-for graph, returning the cycles among blocked threads.
+    ///
+    /// At least one cycle is reported for every group of mutually deadlocked threads, although
+    /// not every distinct cycle within a group is necessarily enumerated. The graph is
+    /// a snapshot: a thread that times out of its wait may have since broken the cycle.
+    pub fn check() -> Vec<Cycle> {
+        let registry = registry();
+        let mut cycles = vec![];
+        let mut visited = HashMap::new();
+        for &thread in registry.waiting.keys() {
+            let mut path = vec![];
+            visit(&registry, thread, &mut path, &mut visited, &mut cycles);
+        }
+        cycles
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        OnPath,
+        Done,
+    }
+
+    fn visit(
+        registry: &Registry,
+        thread: ThreadId,
+        path: &mut Vec<Waiter>,
+        visited: &mut HashMap<ThreadId, Mark>,
+        cycles: &mut Vec<Cycle>,
+    ) {
+        match visited.get(&thread) {
+            Some(Mark::Done) => return,
+            Some(Mark::OnPath) => {
+                let start = path.iter().position(|waiter| waiter.thread == thread).unwrap();
+                cycles.push(Cycle {
+                    waiters: path[start..].to_vec(),
+                });
+                return;
+            }
+            None => {}
+        }
+
+        if let Some(&lock) = registry.waiting.get(&thread) {
+            visited.insert(thread, Mark::OnPath);
+            path.push(Waiter { thread, lock });
+            if let Some(holders) = registry.holders.get(&lock) {
+                for (index, &holder) in holders.iter().enumerate() {
+                    if holder != thread && !holders[..index].contains(&holder) {
+                        visit(registry, holder, path, visited, cycles);
+                    }
+                }
+            }
+            path.pop();
+        }
+        visited.insert(thread, Mark::Done);
+    }
+}
+
+#[cfg(all(test, feature = "deadlock_detection"))]
+mod tests;
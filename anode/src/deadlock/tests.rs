@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::deadlock::{addr_of, check, Cycle};
+use crate::spin_mutex::SpinMutex;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::zlock::{ReadBiased, ZLock};
+
+fn involves(cycle: &Cycle, locks: &[usize]) -> bool {
+    cycle.waiters.iter().any(|waiter| locks.contains(&waiter.lock))
+}
+
+fn cycles_involving(locks: &[usize]) -> Vec<Cycle> {
+    check().into_iter().filter(|cycle| involves(cycle, locks)).collect()
+}
+
+/// Acquires `lock` within `duration`, running `f` while the lock is held. Returns `true` if
+/// the lock was acquired.
+type Holder<L> = fn(lock: &L, duration: Duration, f: &mut dyn FnMut()) -> bool;
+
+/// Spawns two threads that acquire a pair of locks in opposite orders, repeatedly attempting
+/// the second acquisition with a short timeout so that the threads eventually exit. Returns
+/// once the resulting cycle has been detected.
+fn __detect_opposite_order<L: Sync + Send + 'static>(a: Arc<L>, b: Arc<L>, hold: Holder<L>) {
+    let locks = [addr_of(&*a), addr_of(&*b)];
+    let stop = Arc::new(AtomicBool::default());
+    let barrier = Arc::new(Barrier::new(2));
+    let threads = [(a.clone(), b.clone()), (b, a)]
+        .into_iter()
+        .map(|(first, second)| {
+            let stop = stop.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                assert!(hold(&first, Duration::MAX, &mut || {
+                    barrier.wait();
+                    while !stop.load(Ordering::Relaxed) {
+                        hold(&second, CHECK_WAIT, &mut || {});
+                    }
+                }));
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // the registry is polled at intervals, as spinning on it would starve the threads
+    // attempting to register their waits
+    let mut deadline = Deadline::lazy_after(LONG_WAIT);
+    let mut cycles = cycles_involving(&locks);
+    while cycles.is_empty() {
+        assert!(!deadline.remaining().is_zero(), "no cycle detected");
+        thread::sleep(CHECK_WAIT);
+        cycles = cycles_involving(&locks);
+    }
+    stop.store(true, Ordering::Relaxed);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(1, cycles.len(), "{cycles:?}");
+    let cycle = cycles.remove(0);
+    assert_eq!(2, cycle.waiters.len());
+    assert_ne!(cycle.waiters[0].thread, cycle.waiters[1].thread);
+    assert_ne!(cycle.waiters[0].lock, cycle.waiters[1].lock);
+    assert!(cycle.waiters.iter().all(|waiter| locks.contains(&waiter.lock)));
+}
+
+#[test]
+fn zlock_opposite_order() {
+    __detect_opposite_order(
+        Arc::new(ZLock::<_, ReadBiased>::new(())),
+        Arc::new(ZLock::<_, ReadBiased>::new(())),
+        |lock, duration, f| lock.try_write(duration).map(|_guard| f()).is_some(),
+    );
+}
+
+#[test]
+fn spin_mutex_opposite_order() {
+    __detect_opposite_order(
+        Arc::new(SpinMutex::new(())),
+        Arc::new(SpinMutex::new(())),
+        |lock, duration, f| lock.try_lock_for(duration).map(|_guard| f()).is_some(),
+    );
+}
+
+#[test]
+fn no_cycle_under_contention() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(()));
+    let locks = [addr_of(&*lock)];
+    let guard = lock.write();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = thread::spawn({
+        let lock = lock.clone();
+        move || {
+            tx.send(()).unwrap();
+            lock.write();
+        }
+    });
+    rx.recv().unwrap();
+    thread::sleep(CHECK_WAIT);
+    assert!(cycles_involving(&locks).is_empty());
+    drop(guard);
+    waiter.join().unwrap();
+}
+
+#[test]
+fn no_cycle_when_upgrading() {
+    let lock = ZLock::<_, ReadBiased>::new(());
+    let locks = [addr_of(&lock)];
+    let guard = lock.read();
+    let guard = guard.upgrade();
+    assert!(cycles_involving(&locks).is_empty());
+    drop(guard);
+}
+
+#[test]
+fn released_locks_do_not_form_cycles() {
+    let a = ZLock::<_, ReadBiased>::new(());
+    let b = SpinMutex::new(());
+    let locks = [addr_of(&a), addr_of(&b)];
+    {
+        let _a = a.read();
+        let _b = b.lock();
+        let _a2 = a.read();
+    }
+    assert!(a.try_write(Duration::ZERO).is_some());
+    assert!(b.try_lock().is_some());
+    assert!(cycles_involving(&locks).is_empty());
+}
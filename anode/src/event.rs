@@ -0,0 +1,193 @@
+//! Signalling events without a payload, after the Win32 and .NET primitives of the same name.
+//!
+//! An event is either signalled or not. A [`ManualResetEvent`] releases every waiter while
+//! signalled, and remains so until explicitly [`reset`](ManualResetEvent::reset). An
+//! [`AutoResetEvent`] releases one waiter per signal, being reset by the waiter that consumes
+//! it. For signalling with a value, use a [`Completable`](crate::completable::Completable).
+
+use crate::deadline::Deadline;
+use std::fmt;
+use std::time::Duration;
+use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
+
+/// An event that, once [`set`](Self::set), releases all current and future waiters until it is
+/// [`reset`](Self::reset).
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use anode::event::ManualResetEvent;
+/// let ready = Arc::new(ManualResetEvent::new(false));
+/// let waiters = (0..3)
+///     .map(|_| {
+///         let ready = ready.clone();
+///         thread::spawn(move || ready.wait())
+///     })
+///     .collect::<Vec<_>>();
+/// ready.set();
+/// waiters.into_iter().for_each(|waiter| waiter.join().unwrap());
+/// assert!(ready.is_set());
+/// ```
+pub struct ManualResetEvent {
+    monitor: SpeculativeMonitor<bool>,
+}
+
+impl ManualResetEvent {
+    #[inline]
+    pub fn new(set: bool) -> Self {
+        Self {
+            monitor: SpeculativeMonitor::new(set),
+        }
+    }
+
+    /// Determines whether the event is presently signalled.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        *self.monitor.lock()
+    }
+
+    /// Signals the event, releasing all waiting threads. Has no effect if the event is
+    /// already signalled.
+    #[inline]
+    pub fn set(&self) {
+        self.monitor.enter(|set| {
+            *set = true;
+            Directive::NotifyAll
+        });
+    }
+
+    /// Clears the signal, such that subsequent waits block until the event is set again.
+    #[inline]
+    pub fn reset(&self) {
+        *self.monitor.lock() = false;
+    }
+
+    /// Blocks until the event is signalled.
+    #[inline]
+    pub fn wait(&self) {
+        self.try_wait(Duration::MAX);
+    }
+
+    /// Blocks until the event is signalled, or until the given `duration` elapses. Returns
+    /// `true` if the event was signalled, or `false` if the wait timed out.
+    #[inline]
+    pub fn try_wait(&self, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut released = false;
+        self.monitor.enter(|set| {
+            if *set {
+                released = true;
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        released
+    }
+}
+
+impl fmt::Debug for ManualResetEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ManualResetEvent")
+            .field("set", &self.is_set())
+            .finish()
+    }
+}
+
+/// An event that releases a single waiter for every [`set`](Self::set), automatically
+/// resetting once the signal is consumed.
+///
+/// A signal raised while no thread is waiting is retained for the next waiter. Signals do not
+/// accumulate: setting an event that is already signalled has no effect. (For counted
+/// signals, use a [`Semaphore`](crate::semaphore::Semaphore).)
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use anode::event::AutoResetEvent;
+/// let event = Arc::new(AutoResetEvent::new(false));
+/// let waiter = {
+///     let event = event.clone();
+///     thread::spawn(move || event.wait())
+/// };
+/// event.set();
+/// waiter.join().unwrap();
+///
+/// // the signal was consumed by the waiter
+/// assert!(!event.try_wait(Duration::ZERO));
+/// ```
+pub struct AutoResetEvent {
+    monitor: SpeculativeMonitor<bool>,
+}
+
+impl AutoResetEvent {
+    #[inline]
+    pub fn new(set: bool) -> Self {
+        Self {
+            monitor: SpeculativeMonitor::new(set),
+        }
+    }
+
+    /// Determines whether the event is presently signalled; i.e., whether the next wait
+    /// will return immediately.
+    #[inline]
+    pub fn is_set(&self) -> bool {
+        *self.monitor.lock()
+    }
+
+    /// Signals the event, releasing one waiting thread, or the next thread to wait if none
+    /// is waiting presently.
+    #[inline]
+    pub fn set(&self) {
+        // all waiters are notified and contend for the signal; notifying just one may lose
+        // the signal to a waiter that is concurrently timing out
+        self.monitor.enter(|set| {
+            *set = true;
+            Directive::NotifyAll
+        });
+    }
+
+    /// Clears the signal, if it has not been consumed already.
+    #[inline]
+    pub fn reset(&self) {
+        *self.monitor.lock() = false;
+    }
+
+    /// Blocks until the event is signalled, consuming the signal.
+    #[inline]
+    pub fn wait(&self) {
+        self.try_wait(Duration::MAX);
+    }
+
+    /// Blocks until the event is signalled, or until the given `duration` elapses. Returns
+    /// `true` if the signal was consumed, or `false` if the wait timed out.
+    #[inline]
+    pub fn try_wait(&self, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut released = false;
+        self.monitor.enter(|set| {
+            if *set {
+                *set = false;
+                released = true;
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        released
+    }
+}
+
+impl fmt::Debug for AutoResetEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutoResetEvent")
+            .field("set", &self.is_set())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
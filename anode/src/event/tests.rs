@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::event::{AutoResetEvent, ManualResetEvent};
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::test_utils;
+
+#[test]
+fn manual_set_reset() {
+    let event = ManualResetEvent::new(false);
+    assert!(!event.is_set());
+    assert!(!event.try_wait(Duration::ZERO));
+
+    event.set();
+    assert!(event.is_set());
+    assert!(event.try_wait(Duration::ZERO));
+    event.wait();
+
+    // the signal is not consumed by waiting
+    assert!(event.is_set());
+    event.set();
+
+    event.reset();
+    assert!(!event.is_set());
+    assert!(!event.try_wait(Duration::ZERO));
+    assert!(ManualResetEvent::new(true).try_wait(Duration::ZERO));
+}
+
+#[test]
+fn manual_try_wait_timeout() {
+    let event = ManualResetEvent::new(false);
+    let start = Instant::now();
+    assert!(!event.try_wait(CHECK_WAIT));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+}
+
+#[test]
+fn manual_releases_all_waiters() {
+    const WAITERS: usize = 4;
+    let event = Arc::new(ManualResetEvent::new(false));
+    let waiters = (0..WAITERS)
+        .map(|_| {
+            let event = event.clone();
+            test_utils::spawn_blocked(move || event.try_wait(LONG_WAIT))
+        })
+        .collect::<Vec<_>>();
+
+    thread::sleep(CHECK_WAIT);
+    assert!(waiters.iter().all(|waiter| !waiter.is_finished()));
+    event.set();
+    for waiter in waiters {
+        assert!(waiter.join().unwrap());
+    }
+}
+
+#[test]
+fn auto_set_consumed_by_wait() {
+    let event = AutoResetEvent::new(true);
+    assert!(event.is_set());
+    assert!(event.try_wait(Duration::ZERO));
+    assert!(!event.is_set());
+    assert!(!event.try_wait(Duration::ZERO));
+
+    // signals do not accumulate
+    event.set();
+    event.set();
+    event.wait();
+    assert!(!event.try_wait(Duration::ZERO));
+
+    event.set();
+    event.reset();
+    assert!(!event.try_wait(Duration::ZERO));
+}
+
+#[test]
+fn auto_try_wait_timeout() {
+    let event = AutoResetEvent::new(false);
+    let start = Instant::now();
+    assert!(!event.try_wait(CHECK_WAIT));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn auto_releases_one_waiter_per_set() {
+    const WAITERS: usize = 4;
+    let event = Arc::new(AutoResetEvent::new(false));
+    let released = Arc::new(AtomicUsize::default());
+    let waiters = (0..WAITERS)
+        .map(|_| {
+            let (event, released) = (event.clone(), released.clone());
+            test_utils::spawn_blocked(move || {
+                let consumed = event.try_wait(LONG_WAIT);
+                released.fetch_add(1, Ordering::Relaxed);
+                consumed
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for set in 1..=WAITERS {
+        event.set();
+        while released.load(Ordering::Relaxed) < set {
+            thread::yield_now();
+        }
+        thread::sleep(CHECK_WAIT);
+        assert_eq!(set, released.load(Ordering::Relaxed));
+    }
+    for waiter in waiters {
+        assert!(waiter.join().unwrap());
+    }
+    assert!(!event.is_set());
+}
@@ -92,7 +92,6 @@ impl ThreadPool {
         };
         let receiver = Arc::new(Mutex::new(receiver));
         let threads = (0..threads)
-            .into_iter()
             .map(|_| {
                 let receiver = receiver.clone();
                 thread::spawn(move || loop {
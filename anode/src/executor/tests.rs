@@ -4,6 +4,7 @@ use crate::completable::Outcome;
 use crate::executor::{Executor, Queue, Submitter, ThreadPool};
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn unbounded_execute_tasks_via_submit() {
     const THREADS: RangeInclusive<usize> = 1..=10;
     const TASKS: u16 = 100;
@@ -24,6 +25,7 @@ fn unbounded_execute_tasks_via_submit() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn bounded_execute_tasks_via_submit() {
     const THREADS: RangeInclusive<usize> = 1..=10;
     const TASKS: u16 = 100;
@@ -42,6 +44,7 @@ fn bounded_execute_tasks_via_submit() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn unbounded_execute_tasks_via_try_submit() {
     const THREADS: RangeInclusive<usize> = 1..=10;
     const TASKS: u16 = 100;
@@ -65,6 +68,7 @@ fn unbounded_execute_tasks_via_try_submit() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn bounded_execute_tasks_via_try_submit() {
     const THREADS: RangeInclusive<usize> = 1..=10;
     const TASKS: u16 = 100;
@@ -0,0 +1,176 @@
+//! An opt-in registry of held locks, enabled by the `held_locks` feature.
+//!
+//! When enabled, [`ZLock`](crate::zlock::ZLock) and [`RawZLock`](crate::zlock::RawZLock) record
+//! every acquisition, release, upgrade and downgrade in a process-wide registry, keyed by lock.
+//! [`held_locks`] lists the locks held by the current thread, for diagnostics. A blocking
+//! acquisition that would certainly deadlock against the current thread's own hold panics
+//! instead: a write acquisition (e.g., [`ZLock::write`]) of a lock that the thread holds in any
+//! mode, or a read acquisition (e.g., [`ZLock::read`]) of a lock that it holds for writing.
+//! Recursive reads are permitted. Bounded acquisitions, upgrades and upgradable reads are not
+//! checked. Without the feature, the hooks compile to nothing and [`held_locks`] is unavailable.
+//!
+//! Only holds that are released on the thread that acquired them are tracked. A guard that may
+//! be released on another thread (e.g., an [`ArcLockReadGuard`], or one relinquished with
+//! [`LockReadGuard::forget`] and later released with [`ZLock::force_unlock_read`]) has its hold
+//! deregistered as it is handed over. A [`RawZLock`] should likewise be released on the thread
+//! that acquired it; a release on any other thread leaves the acquiring thread's hold in place.
+//!
+//! [`ZLock::read`]: crate::zlock::ZLock::read
+//! [`ZLock::write`]: crate::zlock::ZLock::write
+//! [`ZLock::force_unlock_read`]: crate::zlock::ZLock::force_unlock_read
+//! [`LockReadGuard::forget`]: crate::zlock::LockReadGuard::forget
+//! [`ArcLockReadGuard`]: crate::zlock::ArcLockReadGuard
+//! [`RawZLock`]: crate::zlock::RawZLock
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "held_locks")]
+//! # {
+//! use anode::held::held_locks;
+//! use anode::zlock::{ReadBiased, ZLock};
+//! let lock = ZLock::<_, ReadBiased>::new(0);
+//! let guard = lock.read();
+//! assert_eq!(1, held_locks().len());
+//! drop(guard);
+//! assert!(held_locks().is_empty());
+//! # }
+//! ```
+
+use crate::instrument::Mode;
+
+#[cfg(feature = "held_locks")]
+pub use registry::{held_locks, HeldLock};
+
+#[cfg(feature = "held_locks")]
+pub(crate) use registry::{acquired, assert_not_held, converted, released};
+
+/// Records that the current thread holds the lock at `addr` in the given `mode`, moderated by
+/// `moderator`.
+#[cfg(not(feature = "held_locks"))]
+#[inline(always)]
+pub(crate) fn acquired(_addr: usize, _moderator: &'static str, _mode: Mode) {}
+
+/// Records the release of one hold of the lock at `addr`.
+#[cfg(not(feature = "held_locks"))]
+#[inline(always)]
+pub(crate) fn released(_addr: usize) {}
+
+/// Records that a hold of the lock at `addr` has been upgraded or downgraded to `mode`.
+#[cfg(not(feature = "held_locks"))]
+#[inline(always)]
+pub(crate) fn converted(_addr: usize, _mode: Mode) {}
+
+/// Panics if acquiring the lock at `addr` in the given `mode` would deadlock against a hold
+/// of the current thread.
+#[cfg(not(feature = "held_locks"))]
+#[inline(always)]
+pub(crate) fn assert_not_held(_addr: usize, _mode: Mode) {}
+
+#[cfg(feature = "held_locks")]
+mod registry {
+    use super::Mode;
+    use crate::remedy::Remedy;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, MutexGuard, OnceLock};
+    use std::thread::{self, ThreadId};
+
+    /// A lock held by the current thread.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct HeldLock {
+        /// The address of the lock.
+        pub lock: usize,
+
+        /// The type name of the lock's moderator.
+        pub moderator: &'static str,
+    }
+
+    /// A single hold of a lock.
+    #[derive(Debug)]
+    struct Hold {
+        thread: ThreadId,
+        moderator: &'static str,
+        mode: Mode,
+
+        /// Orders the holds by their acquisition.
+        seq: u64,
+    }
+
+    #[derive(Debug, Default)]
+    struct Registry {
+        /// The holds on each lock. A lock appears once for every hold (e.g., with multiple read
+        /// guards), possibly by different threads.
+        holds: HashMap<usize, Vec<Hold>>,
+        next_seq: u64,
+    }
+
+    fn registry() -> MutexGuard<'static, Registry> {
+        static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+        REGISTRY.get_or_init(Mutex::default).lock().remedy()
+    }
+
+    /// Locates the current thread's most recent hold of `addr`, to be released or converted.
+    fn locate(holds: &[Hold]) -> Option<usize> {
+        let thread = thread::current().id();
+        holds.iter().rposition(|hold| hold.thread == thread)
+    }
+
+    /// Lists the locks held by the current thread, in the order of their acquisition.
+    pub fn held_locks() -> Vec<HeldLock> {
+        let thread = thread::current().id();
+        let registry = registry();
+        let mut held = registry
+            .holds
+            .iter()
+            .flat_map(|(&lock, holds)| holds.iter().filter(|hold| hold.thread == thread).map(move |hold| (hold.seq, lock, hold.moderator)))
+            .collect::<Vec<_>>();
+        held.sort_unstable_by_key(|&(seq, ..)| seq);
+        held.into_iter().map(|(_, lock, moderator)| HeldLock { lock, moderator }).collect()
+    }
+
+    pub(crate) fn acquired(addr: usize, moderator: &'static str, mode: Mode) {
+        let thread = thread::current().id();
+        let mut registry = registry();
+        let seq = registry.next_seq;
+        registry.next_seq += 1;
+        registry.holds.entry(addr).or_default().push(Hold { thread, moderator, mode, seq });
+    }
+
+    pub(crate) fn released(addr: usize) {
+        let mut registry = registry();
+        if let Some(holds) = registry.holds.get_mut(&addr) {
+            if let Some(index) = locate(holds) {
+                holds.remove(index);
+            }
+            if holds.is_empty() {
+                registry.holds.remove(&addr);
+            }
+        }
+    }
+
+    pub(crate) fn converted(addr: usize, mode: Mode) {
+        let mut registry = registry();
+        if let Some(holds) = registry.holds.get_mut(&addr) {
+            if let Some(index) = locate(holds) {
+                holds[index].mode = mode;
+            }
+        }
+    }
+
+    pub(crate) fn assert_not_held(addr: usize, mode: Mode) {
+        let thread = thread::current().id();
+        let conflicting = registry().holds.get(&addr).and_then(|holds| {
+            holds
+                .iter()
+                .find(|hold| hold.thread == thread && (mode == Mode::Write || hold.mode == Mode::Write))
+                .map(|hold| (hold.moderator, hold.mode))
+        });
+        if let Some((moderator, held_mode)) = conflicting {
+            panic!(
+                "self-deadlock: acquiring the lock at {addr:#x} (moderated by {moderator}) in {mode:?} mode, while the current thread holds it in {held_mode:?} mode"
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "held_locks"))]
+mod tests;
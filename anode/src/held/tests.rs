@@ -0,0 +1,133 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use crate::deadlock::addr_of;
+use crate::held::{held_locks, HeldLock};
+use crate::zlock::{RawZLock, ReadBiased, SpinModerator, WriteBiased, ZLock};
+
+#[test]
+fn lists_held_locks() {
+    let a = ZLock::<_, ReadBiased>::new(0);
+    let b = ZLock::<_, WriteBiased>::new(0);
+    assert!(held_locks().is_empty());
+
+    let guard_a = a.read();
+    let guard_b = b.write();
+    assert_eq!(
+        vec![
+            HeldLock { lock: addr_of(&a), moderator: core::any::type_name::<ReadBiased>() },
+            HeldLock { lock: addr_of(&b), moderator: core::any::type_name::<WriteBiased>() },
+        ],
+        held_locks()
+    );
+
+    // holds survive downgrades and upgrades, as the lock is retained throughout
+    let guard_b = guard_b.downgrade().upgrade();
+    drop(guard_a);
+    assert_eq!(vec![addr_of(&b)], held_locks().iter().map(|held| held.lock).collect::<Vec<_>>());
+    drop(guard_b);
+    assert!(held_locks().is_empty());
+}
+
+#[test]
+fn bounded_acquisitions_are_not_checked() {
+    let lock = ZLock::<_, SpinModerator>::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert_eq!(2, held_locks().len());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard_1);
+    drop(guard_2);
+    assert!(held_locks().is_empty());
+}
+
+#[test]
+fn self_deadlock_panics() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    let guard = lock.read();
+    let err = panic::catch_unwind(AssertUnwindSafe(|| drop(lock.write()))).unwrap_err();
+    assert!(err.downcast_ref::<String>().unwrap().starts_with("self-deadlock"));
+    drop(guard);
+
+    let guard = lock.write();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| drop(lock.read()))).is_err());
+    drop(guard);
+
+    // timed closures acquire as the unbounded read and write do
+    let guard = lock.read();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| lock.timed_with_write(|_| ()))).is_err());
+    drop(guard);
+    let guard = lock.write();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| lock.timed_with_read(|_| ()))).is_err());
+    drop(guard);
+
+    let raw = RawZLock::<ReadBiased>::new();
+    raw.lock_write();
+    assert!(panic::catch_unwind(AssertUnwindSafe(|| raw.lock_read())).is_err());
+    unsafe { raw.unlock_write() };
+    assert!(held_locks().is_empty());
+}
+
+#[test]
+fn recursive_reads_are_permitted() {
+    let lock = ZLock::<_, WriteBiased>::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    assert_eq!(2, held_locks().len());
+    drop((guard_1, guard_2));
+
+    // a downgraded write lock is held for reading only
+    let guard = lock.write().downgrade();
+    drop(lock.read());
+    drop(guard);
+    assert!(held_locks().is_empty());
+}
+
+#[test]
+fn release_on_another_thread() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+
+    // guards that may be released on another thread are not tracked
+    let guard = lock.clone().read_arc();
+    assert!(held_locks().is_empty());
+    thread::spawn(move || drop(guard)).join().unwrap();
+    assert!(held_locks().is_empty());
+    drop(lock.write());
+
+    let guard = lock.read();
+    assert_eq!(1, held_locks().len());
+    guard.forget();
+    assert!(held_locks().is_empty());
+    thread::scope(|scope| {
+        scope.spawn(|| unsafe { lock.force_unlock_read() });
+    });
+    drop(lock.write());
+}
+
+#[test]
+fn release_on_another_thread_spares_other_holds() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    thread::scope(|scope| {
+        // another thread holds a read lock throughout
+        let (held_tx, held_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let lock_ref = &lock;
+        scope.spawn(move || {
+            let _guard = lock_ref.read();
+            held_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        held_rx.recv().unwrap();
+
+        // an arc guard acquired here and released elsewhere leaves the other thread's hold
+        let guard = lock.clone().read_arc();
+        thread::spawn(move || drop(guard)).join().unwrap();
+        assert!(held_locks().is_empty());
+
+        // this thread holds nothing, so the write waits for the other thread, rather than
+        // being flagged as a self-deadlock
+        release_tx.send(()).unwrap();
+        drop(lock.write());
+    });
+}
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use core::ops::Range;
 
 /// An unbounded iterator that never runs out of values.
 pub trait InfIterator {
@@ -2,6 +2,7 @@ use std::ops::Range;
 use crate::inf_iterator::RangeCycle;
 use super::{BoundedIterator, InfIterator, IntoInfIterator};
 
+#[allow(dead_code)]
 pub struct RangeInfIterator {
     range: Range<usize>,
     pos: usize
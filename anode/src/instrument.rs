@@ -0,0 +1,252 @@
+//! Opt-in instrumentation of lock events, enabled by the `instrument` feature.
+//!
+//! When enabled, a [`ZLock`](crate::zlock::ZLock) or a [`SpinMutex`](crate::spin_mutex::SpinMutex)
+//! may be constructed with a name and an [`EventSink`] (see `ZLock::instrumented` and
+//! `SpinMutex::instrumented`), which then receives an [`Event`] for every acquisition attempt,
+//! contended wait, acquisition, timeout and release of that lock. Locks constructed otherwise
+//! emit no events. Without the feature, the hooks compile to nothing.
+//!
+//! Events are delivered synchronously, on the thread performing the operation, and never
+//! while the lock's internal state is locked. Sinks should nonetheless be quick, as they
+//! delay the operation that emitted the event.
+//!
+//! Hold times are measured from the acquisition to the release of the lock by the same thread.
+//! A lock held across an upgrade or a downgrade is reported as a single hold, ending in the
+//! mode in which it was released. Locks held for too long may be reported by a
+//! [`HoldWatchdog`] sink.
+//!
+//! # Examples
+//! ```
+//! # #[cfg(feature = "instrument")]
+//! # {
+//! use std::sync::Arc;
+//! use anode::instrument::{Event, EventSink};
+//! use anode::zlock::{ReadBiased, ZLock};
+//! let sink: Arc<dyn EventSink> = Arc::new(|name: &str, event: Event| {
+//!     if let Event::Released { held: Some(held), .. } = event {
+//!         println!("{name} held for {held:?}");
+//!     }
+//! });
+//! let lock = ZLock::<_, ReadBiased>::instrumented(0, "counter", sink);
+//! *lock.write() += 1;
+//! # }
+//! ```
+
+#[cfg(feature = "instrument")]
+pub use instrumented::{Event, EventSink};
+
+#[cfg(feature = "instrument")]
+pub use watchdog::{HoldWatchdog, Overheld};
+
+#[cfg(feature = "instrument")]
+pub(crate) use instrumented::Instrumentation;
+
+/// The mode in which a lock is acquired or released. A mutex is always acquired in
+/// [`Write`](Mode::Write) mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Read,
+    Write,
+}
+
+/// The instrumentation of a single lock. Without the `instrument` feature, it is a
+/// zero-sized placeholder.
+#[cfg(not(feature = "instrument"))]
+#[derive(Debug, Default)]
+pub(crate) struct Instrumentation;
+
+#[cfg(not(feature = "instrument"))]
+impl Instrumentation {
+    /// No instrumentation, for use in `const` constructors.
+    pub(crate) const NONE: Self = Self;
+
+    #[inline(always)]
+    pub(crate) fn is_enabled(&self) -> bool {
+        false
+    }
+
+    /// Signals the start of an acquisition attempt in the given `mode`.
+    #[inline(always)]
+    pub(crate) fn begin(&self, _mode: Mode) -> Attempt {
+        Attempt
+    }
+
+    /// Signals the release of one hold of the lock at `addr`.
+    #[inline(always)]
+    pub(crate) fn released(&self, _addr: usize, _mode: Mode) {}
+}
+
+/// An acquisition attempt in progress.
+#[cfg(not(feature = "instrument"))]
+pub(crate) struct Attempt;
+
+#[cfg(not(feature = "instrument"))]
+impl Attempt {
+    /// Signals that the lock could not be acquired immediately.
+    #[inline(always)]
+    pub(crate) fn contended(&self) {}
+
+    /// Signals that the lock at `addr` was acquired.
+    #[inline(always)]
+    pub(crate) fn acquired(self, _addr: usize) {}
+
+    /// Signals that the attempt timed out.
+    #[inline(always)]
+    pub(crate) fn timed_out(self) {}
+}
+
+#[cfg(feature = "instrument")]
+mod instrumented {
+    use std::cell::RefCell;
+    use std::fmt;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use crate::instrument::Mode;
+
+    /// An event in the lifecycle of an instrumented lock.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Event {
+        /// An acquisition was attempted.
+        Attempted { mode: Mode },
+
+        /// The lock could not be acquired immediately; the thread is about to wait for it.
+        Contended { mode: Mode },
+
+        /// The lock was acquired, after waiting for `waited`.
+        Acquired { mode: Mode, waited: Duration },
+
+        /// The lock could not be acquired within the permitted duration, after waiting
+        /// for `waited`.
+        TimedOut { mode: Mode, waited: Duration },
+
+        /// The lock was released, having been held for `held`. The hold time is `None` if
+        /// the lock was released by a thread other than the one that acquired it.
+        Released { mode: Mode, held: Option<Duration> },
+    }
+
+    /// Receives the events of instrumented locks, along with the name of the lock.
+    pub trait EventSink: Send + Sync {
+        fn on_event(&self, name: &str, event: Event);
+    }
+
+    impl<F: Fn(&str, Event) + Send + Sync> EventSink for F {
+        #[inline]
+        fn on_event(&self, name: &str, event: Event) {
+            self(name, event)
+        }
+    }
+
+    struct Sink {
+        name: String,
+        sink: Arc<dyn EventSink>,
+    }
+
+    #[derive(Default)]
+    pub(crate) struct Instrumentation(Option<Box<Sink>>);
+
+    thread_local! {
+        /// The locks held by the current thread, along with the time of acquisition.
+        static HOLDS: RefCell<Vec<(usize, Instant)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl Instrumentation {
+        pub(crate) const NONE: Self = Self(None);
+
+        #[inline]
+        pub(crate) fn new(name: impl Into<String>, sink: Arc<dyn EventSink>) -> Self {
+            Self(Some(Box::new(Sink { name: name.into(), sink })))
+        }
+
+        #[inline]
+        pub(crate) fn is_enabled(&self) -> bool {
+            self.0.is_some()
+        }
+
+        #[inline]
+        pub(crate) fn name(&self) -> Option<&str> {
+            self.0.as_ref().map(|sink| sink.name.as_str())
+        }
+
+        #[inline]
+        fn emit(&self, event: Event) {
+            if let Some(sink) = &self.0 {
+                sink.sink.on_event(&sink.name, event);
+            }
+        }
+
+        #[inline]
+        pub(crate) fn begin(&self, mode: Mode) -> Attempt<'_> {
+            let start = self.0.as_ref().map(|_| {
+                self.emit(Event::Attempted { mode });
+                Instant::now()
+            });
+            Attempt {
+                instrumentation: self,
+                mode,
+                start,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn released(&self, addr: usize, mode: Mode) {
+            if self.is_enabled() {
+                let acquired = HOLDS.with_borrow_mut(|holds| {
+                    let index = holds.iter().rposition(|&(held, _)| held == addr)?;
+                    Some(holds.swap_remove(index).1)
+                });
+                self.emit(Event::Released {
+                    mode,
+                    held: acquired.map(|acquired| acquired.elapsed()),
+                });
+            }
+        }
+    }
+
+    impl fmt::Debug for Instrumentation {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("Instrumentation").field(&self.name()).finish()
+        }
+    }
+
+    pub(crate) struct Attempt<'a> {
+        instrumentation: &'a Instrumentation,
+        mode: Mode,
+        /// Set only if the lock is instrumented.
+        start: Option<Instant>,
+    }
+
+    impl Attempt<'_> {
+        #[inline]
+        pub(crate) fn contended(&self) {
+            self.instrumentation.emit(Event::Contended { mode: self.mode });
+        }
+
+        #[inline]
+        pub(crate) fn acquired(self, addr: usize) {
+            if let Some(start) = self.start {
+                let now = Instant::now();
+                HOLDS.with_borrow_mut(|holds| holds.push((addr, now)));
+                self.instrumentation.emit(Event::Acquired {
+                    mode: self.mode,
+                    waited: now - start,
+                });
+            }
+        }
+
+        #[inline]
+        pub(crate) fn timed_out(self) {
+            if let Some(start) = self.start {
+                self.instrumentation.emit(Event::TimedOut {
+                    mode: self.mode,
+                    waited: start.elapsed(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(feature = "instrument")]
+mod watchdog;
+
+#[cfg(all(test, feature = "instrument"))]
+mod tests;
@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::instrument::{Event, EventSink, Instrumentation, Mode};
+use crate::remedy::Remedy;
+use crate::spin_mutex::SpinMutex;
+use crate::test_utils::{CHECK_WAIT, SHORT_WAIT};
+use crate::zlock::{ReadBiased, ZLock};
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<(String, Event)>>,
+}
+
+impl Recorder {
+    fn take(&self) -> Vec<Event> {
+        self.events.lock().remedy().drain(..).map(|(_, event)| event).collect()
+    }
+}
+
+impl EventSink for Recorder {
+    fn on_event(&self, name: &str, event: Event) {
+        self.events.lock().remedy().push((name.into(), event));
+    }
+}
+
+fn recorder() -> (Arc<Recorder>, Arc<dyn EventSink>) {
+    let recorder = Arc::new(Recorder::default());
+    let sink = recorder.clone();
+    (recorder, sink)
+}
+
+#[test]
+fn instrumentation_debug() {
+    assert!(!Instrumentation::NONE.is_enabled());
+    assert_eq!("Instrumentation(None)", format!("{:?}", Instrumentation::NONE));
+
+    let (_, sink) = recorder();
+    let instrumentation = Instrumentation::new("lock", sink);
+    assert!(instrumentation.is_enabled());
+    assert_eq!("Instrumentation(Some(\"lock\"))", format!("{instrumentation:?}"));
+}
+
+#[test]
+fn zlock_uncontended() {
+    let (recorder, sink) = recorder();
+    let lock = ZLock::<_, ReadBiased>::instrumented(0, "zlock", sink);
+
+    drop(lock.read());
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert_eq!(Event::Attempted { mode: Mode::Read }, events[0]);
+    assert!(matches!(events[1], Event::Acquired { mode: Mode::Read, .. }));
+    assert!(matches!(events[2], Event::Released { mode: Mode::Read, held: Some(_) }));
+
+    *lock.write() += 1;
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert_eq!(Event::Attempted { mode: Mode::Write }, events[0]);
+    assert!(matches!(events[1], Event::Acquired { mode: Mode::Write, .. }));
+    assert!(matches!(events[2], Event::Released { mode: Mode::Write, held: Some(_) }));
+
+}
+
+#[test]
+fn zlock_sink_receives_name() {
+    let names = Arc::new(Mutex::new(Vec::new()));
+    let sink: Arc<dyn EventSink> = {
+        let names = names.clone();
+        Arc::new(move |name: &str, _| names.lock().remedy().push(name.to_owned()))
+    };
+    let lock = ZLock::<_, ReadBiased>::instrumented((), "named", sink);
+    drop(lock.write());
+    assert_eq!(vec!["named"; 3], *names.lock().remedy());
+}
+
+#[test]
+fn zlock_contended_timeout() {
+    let (recorder, sink) = recorder();
+    let lock = ZLock::<_, ReadBiased>::instrumented(0, "zlock", sink);
+    let guard = lock.write();
+    recorder.take();
+
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert_eq!(Event::Attempted { mode: Mode::Read }, events[0]);
+    assert_eq!(Event::Contended { mode: Mode::Read }, events[1]);
+    assert!(matches!(events[2], Event::TimedOut { mode: Mode::Read, .. }));
+
+    // a zero-duration attempt is not reported as contended
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    let events = recorder.take();
+    assert_eq!(2, events.len(), "{events:?}");
+    assert!(matches!(events[1], Event::TimedOut { mode: Mode::Write, .. }));
+    drop(guard);
+}
+
+#[test]
+fn zlock_contended_acquire() {
+    let (recorder, sink) = recorder();
+    let lock = Arc::new(ZLock::<_, ReadBiased>::instrumented(0, "zlock", sink));
+    let guard = lock.write();
+    let waiter = thread::spawn({
+        let lock = lock.clone();
+        move || *lock.write() += 1
+    });
+    thread::sleep(CHECK_WAIT);
+    drop(guard);
+    waiter.join().unwrap();
+
+    let events = recorder.take();
+    assert!(events.contains(&Event::Contended { mode: Mode::Write }), "{events:?}");
+    let waited = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Acquired { waited, .. } => Some(*waited),
+            _ => None,
+        })
+        .max()
+        .unwrap();
+    assert!(waited > Duration::ZERO);
+}
+
+#[test]
+fn zlock_downgrade_is_single_hold() {
+    let (recorder, sink) = recorder();
+    let lock = ZLock::<_, ReadBiased>::instrumented(0, "zlock", sink);
+    drop(lock.write().downgrade());
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert!(matches!(events[1], Event::Acquired { mode: Mode::Write, .. }));
+    assert!(matches!(events[2], Event::Released { mode: Mode::Read, held: Some(_) }));
+}
+
+#[test]
+fn spin_mutex_events() {
+    let (recorder, sink) = recorder();
+    let lock = SpinMutex::instrumented(0, "spin", sink);
+    *lock.lock() += 1;
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert_eq!(Event::Attempted { mode: Mode::Write }, events[0]);
+    assert!(matches!(events[1], Event::Acquired { mode: Mode::Write, .. }));
+    assert!(matches!(events[2], Event::Released { mode: Mode::Write, held: Some(_) }));
+
+    let guard = lock.lock();
+    recorder.take();
+    assert!(lock.try_lock().is_none());
+    let events = recorder.take();
+    assert_eq!(2, events.len(), "{events:?}");
+    assert!(matches!(events[1], Event::TimedOut { mode: Mode::Write, .. }));
+
+    assert!(lock.try_lock_for(SHORT_WAIT).is_none());
+    let events = recorder.take();
+    assert_eq!(3, events.len(), "{events:?}");
+    assert_eq!(Event::Contended { mode: Mode::Write }, events[1]);
+    assert!(matches!(events[2], Event::TimedOut { mode: Mode::Write, .. }));
+    drop(guard);
+}
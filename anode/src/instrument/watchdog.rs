@@ -0,0 +1,122 @@
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::time::Duration;
+use crate::instrument::{Event, EventSink, Mode};
+
+thread_local! {
+    /// The backtraces captured at each acquisition by the current thread, keyed by the address
+    /// of the capturing watchdog and the name of the lock.
+    static ACQUISITIONS: RefCell<Vec<(usize, String, Backtrace)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A lock that was held for longer than the threshold of a [`HoldWatchdog`].
+#[derive(Debug)]
+pub struct Overheld<'a> {
+    /// The name of the lock.
+    pub name: &'a str,
+
+    /// The mode in which the lock was released.
+    pub mode: Mode,
+
+    /// How long the lock was held for.
+    pub held: Duration,
+
+    /// Where the lock was acquired, if the watchdog captures backtraces.
+    pub backtrace: Option<&'a Backtrace>,
+}
+
+/// An [`EventSink`] that reports locks held for longer than a given threshold, such as locks
+/// accidentally held across blocking I/O.
+///
+/// The report is made on release, on the releasing thread. A lock released by a thread other
+/// than the one that acquired it has no measured hold time, and so is never reported.
+///
+/// Capturing a backtrace at every acquisition (see [`with_backtraces`](Self::with_backtraces))
+/// pinpoints the offending acquisition, at a considerable cost; it is best reserved for
+/// debugging.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::thread;
+/// use std::time::Duration;
+/// use anode::instrument::{Overheld, HoldWatchdog};
+/// use anode::zlock::{ReadBiased, ZLock};
+/// let tripped = Arc::new(AtomicBool::default());
+/// let watchdog = HoldWatchdog::new(Duration::from_millis(1), {
+///     let tripped = tripped.clone();
+///     move |overheld: Overheld| {
+///         println!("{} held for {:?}", overheld.name, overheld.held);
+///         tripped.store(true, Ordering::Relaxed);
+///     }
+/// });
+/// let lock = ZLock::<_, ReadBiased>::instrumented(0, "counter", Arc::new(watchdog));
+/// let guard = lock.write();
+/// thread::sleep(Duration::from_millis(5));
+/// drop(guard);
+/// assert!(tripped.load(Ordering::Relaxed));
+/// ```
+pub struct HoldWatchdog<F> {
+    threshold: Duration,
+    backtraces: bool,
+    on_overheld: F,
+}
+
+impl<F: Fn(Overheld) + Send + Sync> HoldWatchdog<F> {
+    /// Creates a watchdog that invokes `on_overheld` whenever a lock is released after being
+    /// held for longer than `threshold`.
+    #[inline]
+    pub fn new(threshold: Duration, on_overheld: F) -> Self {
+        Self {
+            threshold,
+            backtraces: false,
+            on_overheld,
+        }
+    }
+
+    /// Captures a backtrace at every acquisition, to be included in the report should the
+    /// lock be held for too long.
+    #[inline]
+    pub fn with_backtraces(mut self) -> Self {
+        self.backtraces = true;
+        self
+    }
+
+    #[inline]
+    fn addr(&self) -> usize {
+        self as *const Self as usize
+    }
+}
+
+impl<F: Fn(Overheld) + Send + Sync> EventSink for HoldWatchdog<F> {
+    fn on_event(&self, name: &str, event: Event) {
+        match event {
+            Event::Acquired { .. } if self.backtraces => {
+                let backtrace = Backtrace::force_capture();
+                ACQUISITIONS.with_borrow_mut(|acquisitions| acquisitions.push((self.addr(), name.into(), backtrace)));
+            }
+            Event::Released { mode, held } => {
+                let backtrace = self.backtraces.then(|| {
+                    ACQUISITIONS.with_borrow_mut(|acquisitions| {
+                        let index = acquisitions.iter().rposition(|(addr, acquired, _)| *addr == self.addr() && acquired == name)?;
+                        Some(acquisitions.remove(index).2)
+                    })
+                }).flatten();
+
+                if let Some(held) = held.filter(|&held| held > self.threshold) {
+                    (self.on_overheld)(Overheld {
+                        name,
+                        mode,
+                        held,
+                        backtrace: backtrace.as_ref(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
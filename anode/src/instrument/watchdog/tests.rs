@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::instrument::{HoldWatchdog, Mode, Overheld};
+use crate::remedy::Remedy;
+use crate::test_utils::CHECK_WAIT;
+use crate::zlock::{ReadBiased, ZLock};
+
+type Reports = Arc<Mutex<Vec<(String, Mode, Duration, Option<String>)>>>;
+
+fn watchdog(threshold: Duration) -> (Reports, HoldWatchdog<impl Fn(Overheld) + Send + Sync>) {
+    let reports = Reports::default();
+    let watchdog = HoldWatchdog::new(threshold, {
+        let reports = reports.clone();
+        move |overheld: Overheld| {
+            let backtrace = overheld.backtrace.map(ToString::to_string);
+            reports.lock().remedy().push((overheld.name.into(), overheld.mode, overheld.held, backtrace));
+        }
+    });
+    (reports, watchdog)
+}
+
+#[test]
+fn reports_only_overheld() {
+    let (reports, watchdog) = watchdog(CHECK_WAIT);
+    let lock = ZLock::<_, ReadBiased>::instrumented(0, "lock", Arc::new(watchdog));
+
+    *lock.write() += 1;
+    drop(lock.read());
+    assert!(reports.lock().remedy().is_empty());
+
+    let guard = lock.read();
+    thread::sleep(CHECK_WAIT * 2);
+    drop(guard);
+    let reports = reports.lock().remedy().drain(..).collect::<Vec<_>>();
+    assert_eq!(1, reports.len());
+    let (name, mode, held, backtrace) = &reports[0];
+    assert_eq!("lock", name);
+    assert_eq!(Mode::Read, *mode);
+    assert!(*held > CHECK_WAIT);
+    assert!(backtrace.is_none());
+}
+
+#[test]
+fn captures_backtrace_at_acquisition() {
+    let (reports, watchdog) = watchdog(Duration::ZERO);
+    let lock = ZLock::<_, ReadBiased>::instrumented(0, "lock", Arc::new(watchdog.with_backtraces()));
+
+    // nested holds are matched to their own acquisitions
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    drop(guard_2);
+    drop(guard_1);
+    let reports = reports.lock().remedy().drain(..).collect::<Vec<_>>();
+    assert_eq!(2, reports.len());
+    assert!(reports.iter().all(|(_, _, _, backtrace)| backtrace.is_some()));
+}
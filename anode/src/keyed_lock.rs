@@ -0,0 +1,188 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+
+/// A lock over individual keys, serializing access to resources identified by key (e.g., files
+/// or accounts) without a lock per resource. Holding one key excludes others from that key
+/// alone.
+///
+/// A key occupies an entry in an internal map only while it is held or awaited; the entry is
+/// removed once the last guard over the key is dropped and no waiters remain. Each entry has its
+/// own condition variable, so that releasing a key wakes only a waiter for that key. Waiters are
+/// not served in arrival order.
+///
+/// The lock is not reentrant: a thread that locks a key it already holds deadlocks.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use anode::keyed_lock::KeyedLock;
+/// let lock = KeyedLock::new();
+/// let alice = lock.lock("alice");
+/// assert!(lock.try_lock("alice", Duration::ZERO).is_none());
+/// assert!(lock.try_lock("bob", Duration::ZERO).is_some());
+/// drop(alice);
+/// assert!(lock.is_empty());
+/// ```
+pub struct KeyedLock<K, S = RandomState> {
+    entries: Mutex<HashMap<K, Entry, S>>,
+}
+
+struct Entry {
+    locked: bool,
+
+    /// The number of threads waiting for the key.
+    waiters: usize,
+
+    cond: Arc<TimedCondvar>,
+}
+
+/// An RAII guard over a key held in a [`KeyedLock`], releasing the key when dropped.
+#[must_use = "if unused, the key will be immediately released"]
+pub struct KeyGuard<'a, K: Eq + Hash, S: BuildHasher = RandomState> {
+    lock: &'a KeyedLock<K, S>,
+    key: K,
+}
+
+impl<K: Eq + Hash + Clone> KeyedLock<K> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for KeyedLock<K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash + Clone, S: BuildHasher> KeyedLock<K, S> {
+    #[inline]
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::with_hasher(hasher)),
+        }
+    }
+
+    /// Acquires `key`, blocking until it is available.
+    #[inline]
+    pub fn lock(&self, key: K) -> KeyGuard<'_, K, S> {
+        self.try_lock_until(key, Deadline::Forever).unwrap()
+    }
+
+    /// Attempts to acquire `key` within the given `duration`. A zero `duration` makes a single,
+    /// non-blocking attempt.
+    #[inline]
+    pub fn try_lock(&self, key: K, duration: Duration) -> Option<KeyGuard<'_, K, S>> {
+        self.try_lock_until(key, Deadline::lazy_after(duration))
+    }
+
+    /// Attempts to acquire `key` before the given `deadline` elapses.
+    pub fn try_lock_until(&self, key: K, deadline: impl Into<Deadline>) -> Option<KeyGuard<'_, K, S>> {
+        let mut entries = self.entries.lock().remedy();
+        let cond = match entries.get_mut(&key) {
+            None => {
+                entries.insert(key.clone(), Entry {
+                    locked: true,
+                    waiters: 0,
+                    cond: Arc::new(TimedCondvar::new()),
+                });
+                return Some(KeyGuard { lock: self, key });
+            }
+            Some(entry) => {
+                entry.waiters += 1;
+                entry.cond.clone()
+            }
+        };
+
+        let (mut entries, timed_out) = cond.wait_while_until(entries, |entries| entries[&key].locked, deadline);
+        let entry = entries.get_mut(&key).unwrap();
+        entry.waiters -= 1;
+        if timed_out {
+            return None;
+        }
+        entry.locked = true;
+        Some(KeyGuard { lock: self, key })
+    }
+
+    /// Determines whether `key` is held. The result is a racy snapshot.
+    #[inline]
+    pub fn is_locked<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.entries.lock().remedy().get(key).is_some_and(|entry| entry.locked)
+    }
+
+    /// The number of keys that are held or awaited. The result is a racy snapshot.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.lock().remedy().len()
+    }
+
+    /// Determines whether no key is held or awaited. The result is a racy snapshot.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> KeyedLock<K, S> {
+    #[inline]
+    fn unlock(&self, key: &K) {
+        let mut entries = self.entries.lock().remedy();
+        let entry = entries.get_mut(key).unwrap();
+        debug_assert!(entry.locked);
+        if entry.waiters == 0 {
+            entries.remove(key);
+        } else {
+            entry.locked = false;
+            entry.cond.notify_one();
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone + Debug, S: BuildHasher> Debug for KeyedLock<K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.lock().remedy();
+        let locked = entries.iter().filter(|(_, entry)| entry.locked).map(|(key, _)| key).collect::<Vec<_>>();
+        f.debug_struct("KeyedLock")
+            .field("locked", &locked)
+            .finish()
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> KeyGuard<'_, K, S> {
+    /// The key held by this guard.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: Eq + Hash, S: BuildHasher> Drop for KeyGuard<'_, K, S> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.unlock(&self.key);
+    }
+}
+
+impl<K: Eq + Hash + Debug, S: BuildHasher> Debug for KeyGuard<'_, K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyGuard")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::keyed_lock::KeyedLock;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::test_utils;
+
+#[test]
+fn lock_and_release() {
+    let lock = KeyedLock::new();
+    assert!(lock.is_empty());
+    let guard_1 = lock.lock(1);
+    assert_eq!(&1, guard_1.key());
+    assert!(lock.is_locked(&1));
+    assert!(!lock.is_locked(&2));
+    assert!(lock.try_lock(1, Duration::ZERO).is_none());
+    assert!(lock.try_lock(1, SHORT_WAIT).is_none());
+
+    let guard_2 = lock.try_lock(2, Duration::ZERO).unwrap();
+    assert_eq!(2, lock.len());
+    drop(guard_1);
+    assert!(!lock.is_locked(&1));
+    assert_eq!(1, lock.len());
+    drop(guard_2);
+    assert!(lock.is_empty());
+}
+
+#[test]
+fn borrowed_lookup() {
+    let lock = KeyedLock::new();
+    let _guard = lock.lock(String::from("alice"));
+    assert!(lock.is_locked("alice"));
+    assert!(!lock.is_locked("bob"));
+}
+
+#[test]
+fn timed_out_waiter_is_removed() {
+    let lock = KeyedLock::new();
+    let guard = lock.lock("key");
+    let start = Instant::now();
+    assert!(lock.try_lock("key", SHORT_WAIT).is_none());
+    assert!(start.elapsed() >= SHORT_WAIT);
+    drop(guard);
+    assert!(lock.is_empty());
+}
+
+#[test]
+fn release_wakes_waiter() {
+    let lock = Arc::new(KeyedLock::new());
+    let guard = lock.lock(42);
+    let acquired = Arc::new(AtomicBool::default());
+    let waiter = test_utils::spawn_blocked({
+        let (lock, acquired) = (lock.clone(), acquired.clone());
+        move || {
+            let guard = lock.try_lock(42, LONG_WAIT).unwrap();
+            acquired.store(true, Ordering::Relaxed);
+            drop(guard);
+        }
+    });
+
+    thread::sleep(CHECK_WAIT);
+    assert!(!acquired.load(Ordering::Relaxed));
+    drop(guard);
+    waiter.join().unwrap();
+    assert!(acquired.load(Ordering::Relaxed));
+    assert!(lock.is_empty());
+}
+
+#[test]
+fn serializes_per_key() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 100;
+    const KEYS: usize = 3;
+    let lock = KeyedLock::new();
+    let inside: Vec<AtomicUsize> = (0..KEYS).map(|_| AtomicUsize::default()).collect();
+    thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let (lock, inside) = (&lock, &inside);
+            scope.spawn(move || {
+                for iteration in 0..ITERATIONS {
+                    let key = (thread + iteration) % KEYS;
+                    let _guard = lock.lock(key);
+                    assert_eq!(0, inside[key].fetch_add(1, Ordering::Relaxed));
+                    thread::yield_now();
+                    inside[key].fetch_sub(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    assert!(lock.is_empty());
+}
+
+#[test]
+fn debug() {
+    let lock = KeyedLock::new();
+    let guard = lock.lock("foo");
+    assert_eq!(r#"KeyedLock { locked: ["foo"] }"#, format!("{:?}", lock));
+    assert_eq!(r#"KeyGuard { key: "foo", .. }"#, format!("{:?}", guard));
+}
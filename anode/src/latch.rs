@@ -0,0 +1,103 @@
+use crate::deadline::Deadline;
+use std::fmt;
+use std::time::Duration;
+use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
+
+/// A countdown latch, allowing threads to wait until a set number of events has occurred.
+///
+/// The latch is initialized with a count, which is decremented by [`count_down`](Self::count_down).
+/// Once the count reaches zero, all waiting threads are released and subsequent waits return
+/// immediately. The latch cannot be reset; for repeated synchronization, use a
+/// [`Barrier`](crate::barrier::Barrier).
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use anode::latch::Latch;
+/// let started = Arc::new(Latch::new(3));
+/// for _ in 0..3 {
+///     let started = started.clone();
+///     thread::spawn(move || {
+///         started.count_down();
+///         // ...
+///     });
+/// }
+/// started.wait();
+/// assert_eq!(0, started.count());
+/// ```
+pub struct Latch {
+    monitor: SpeculativeMonitor<u64>,
+}
+
+impl Latch {
+    #[inline]
+    pub fn new(count: u64) -> Self {
+        Self {
+            monitor: SpeculativeMonitor::new(count),
+        }
+    }
+
+    /// The number of outstanding events before the latch is released.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        *self.monitor.lock()
+    }
+
+    /// Decrements the count, releasing all waiting threads if the count reaches zero. Has no
+    /// effect if the count is already zero.
+    #[inline]
+    pub fn count_down(&self) {
+        let mut decremented = false;
+        let mut released = false;
+        self.monitor.enter(|count| {
+            if !decremented {
+                decremented = true;
+                if *count > 0 {
+                    *count -= 1;
+                    released = *count == 0;
+                }
+            }
+
+            if released {
+                Directive::NotifyAll
+            } else {
+                Directive::Return
+            }
+        });
+    }
+
+    /// Blocks until the count reaches zero.
+    #[inline]
+    pub fn wait(&self) {
+        self.try_wait(Duration::MAX);
+    }
+
+    /// Blocks until the count reaches zero, or until the given `duration` elapses. Returns
+    /// `true` if the count reached zero, or `false` if the wait timed out.
+    #[inline]
+    pub fn try_wait(&self, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut released = false;
+        self.monitor.enter(|count| {
+            if *count == 0 {
+                released = true;
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        released
+    }
+}
+
+impl fmt::Debug for Latch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Latch")
+            .field("count", &self.count())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::latch::Latch;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::test_utils;
+
+#[test]
+fn count_down_to_zero() {
+    let latch = Latch::new(2);
+    assert!(!latch.try_wait(Duration::ZERO));
+    latch.count_down();
+    assert_eq!(1, latch.count());
+    assert!(!latch.try_wait(Duration::ZERO));
+    latch.count_down();
+    assert_eq!(0, latch.count());
+    assert!(latch.try_wait(Duration::ZERO));
+
+    // counting down past zero has no effect
+    latch.count_down();
+    assert_eq!(0, latch.count());
+    latch.wait();
+}
+
+#[test]
+fn zero_count_is_released() {
+    let latch = Latch::new(0);
+    assert!(latch.try_wait(Duration::ZERO));
+    latch.wait();
+}
+
+#[test]
+fn try_wait_timeout() {
+    let latch = Latch::new(1);
+    let start = Instant::now();
+    assert!(!latch.try_wait(CHECK_WAIT));
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+}
+
+#[test]
+fn await_count_down() {
+    const WAITERS: usize = 4;
+    let latch = Arc::new(Latch::new(2));
+    let waiters = (0..WAITERS)
+        .map(|_| {
+            let latch = latch.clone();
+            test_utils::spawn_blocked(move || latch.try_wait(LONG_WAIT))
+        })
+        .collect::<Vec<_>>();
+
+    latch.count_down();
+    thread::sleep(CHECK_WAIT);
+    assert!(waiters.iter().all(|waiter| !waiter.is_finished()));
+    latch.count_down();
+    for waiter in waiters {
+        assert!(waiter.join().unwrap());
+    }
+}
+
+#[test]
+fn concurrent_count_down() {
+    const THREADS: u64 = 8;
+    let latch = Arc::new(Latch::new(THREADS));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let latch = latch.clone();
+            thread::spawn(move || latch.count_down())
+        })
+        .collect::<Vec<_>>();
+    assert!(latch.try_wait(LONG_WAIT));
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(0, latch.count());
+}
+
+#[test]
+fn debug() {
+    let latch = Latch::new(3);
+    latch.count_down();
+    assert_eq!("Latch { count: 2 }", format!("{latch:?}"));
+}
@@ -1,15 +1,66 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod adaptive_lock;
 pub mod backoff;
+#[cfg(feature = "std")]
+pub mod barrier;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "std")]
+pub mod cancellation;
+#[cfg(feature = "std")]
 pub mod chalice;
+#[cfg(feature = "std")]
 pub mod completable;
+#[cfg(feature = "std")]
 pub mod deadline;
+pub mod deadlock;
+#[cfg(feature = "std")]
+pub mod event;
+#[cfg(feature = "std")]
 pub mod executor;
+pub mod held;
 pub mod inf_iterator;
+pub mod instrument;
+#[cfg(feature = "std")]
+pub mod keyed_lock;
+#[cfg(feature = "std")]
+pub mod latch;
+#[cfg(feature = "std")]
 pub mod monitor;
+#[cfg(feature = "std")]
+pub mod parking_spin_mutex;
+#[cfg(feature = "std")]
 pub mod remedy;
 pub mod rand;
+#[cfg(feature = "std")]
+pub mod rcu_cell;
+#[cfg(feature = "std")]
+pub mod reentrant_lock;
+#[cfg(feature = "std")]
+pub mod semaphore;
+#[cfg(feature = "std")]
+pub mod seq_lock;
+pub mod shm;
+#[cfg(feature = "std")]
+mod snapshot;
 pub mod spin_mutex;
+#[cfg(feature = "std")]
+pub mod stamped_lock;
+#[cfg(feature = "std")]
+pub mod sync_queue;
+#[cfg(feature = "std")]
+pub mod ticket_lock;
+mod trace;
 pub mod zlock;
+#[cfg(feature = "std")]
 pub mod wait;
 
-#[cfg(test)]
+#[cfg(feature = "held_locks")]
+pub use held::held_locks;
+
+#[cfg(any(test, feature = "test_utils"))]
 pub mod test_utils;
@@ -1,11 +1,44 @@
 use std::fmt;
 use std::ops::{Deref, DerefMut};
 use crate::spin_mutex::{SpinGuard, SpinMutex};
-use crate::remedy;
+#[cfg(not(loom))]
+use crate::remedy::cond_wait_remedy;
+#[cfg(loom)]
+use crate::remedy::loom_cond_wait_remedy as cond_wait_remedy;
 use crate::remedy::Remedy;
+#[cfg(not(loom))]
 use std::sync::{Condvar, Mutex};
+#[cfg(loom)]
+use loom::sync::{Condvar, Mutex};
 use std::time::Duration;
 
+mod classed;
+
+pub use classed::{ClassedDirective, ClassedMonitor, ClassedMonitorGuard, Wake};
+
+#[cfg(test)]
+thread_local! {
+    static WAIT_HOOK: std::cell::Cell<Option<fn()>> = const { std::cell::Cell::new(None) };
+}
+
+/// Runs `f` with `hook` installed on the current thread, to be invoked by any monitor just
+/// before the thread blocks on a condvar; e.g., to inject a panic into the wait.
+#[cfg(test)]
+pub(crate) fn with_wait_hook<R>(hook: fn(), f: impl FnOnce() -> R) -> R {
+    WAIT_HOOK.with(|cell| cell.set(Some(hook)));
+    let result = f();
+    WAIT_HOOK.with(|cell| cell.set(None));
+    result
+}
+
+#[cfg(test)]
+#[inline]
+fn before_wait() {
+    if let Some(hook) = WAIT_HOOK.with(std::cell::Cell::get) {
+        hook();
+    }
+}
+
 pub trait MonitorGuard<'a, S: ?Sized>: DerefMut<Target = S> {}
 
 pub trait Monitor<'a, S: ?Sized> {
@@ -74,6 +107,12 @@ struct Tracker<S: ?Sized> {
     data: S,
 }
 
+/// A monitor that speculatively evaluates its closure under a [`SpinMutex`], resorting to a
+/// [`Mutex`] and [`Condvar`] only when the closure needs to wait, or to notify threads that
+/// are already waiting.
+///
+/// An uncontended [`enter`](Monitor::enter) thus costs a single spin lock acquisition: no
+/// mutex is taken, no condvar is signalled, and nothing is allocated.
 pub struct SpeculativeMonitor<S: ?Sized> {
     mutex: Mutex<()>,
     cond: Condvar,
@@ -90,7 +129,7 @@ impl<S> SpeculativeMonitor<S> {
     #[inline(always)]
     pub fn new(s: S) -> Self {
         Self {
-            tracker: SpinMutex::new(Tracker {
+            tracker: SpinMutex::untracked(Tracker {
                 data: s,
                 waiting: 0,
             }),
@@ -108,6 +147,22 @@ impl<S: ?Sized> SpeculativeMonitor<S> {
     pub fn num_waiting(&self) -> u32 {
         self.tracker.lock().waiting
     }
+
+    /// Returns a mutable reference to the encapsulated state.
+    ///
+    /// Since this call borrows the [`SpeculativeMonitor`] mutably, no actual locking needs to
+    /// take place---the mutable borrow statically guarantees no locks exist.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.tracker.get_mut().data
+    }
+
+    /// Attempts to lock the encapsulated state without blocking, returning `None` if the
+    /// state is presently locked by another thread.
+    #[inline(always)]
+    pub fn try_lock(&self) -> Option<SpeculativeMonitorGuard<'_, S>> {
+        self.tracker.try_lock().map(|spin_guard| SpeculativeMonitorGuard { spin_guard })
+    }
 }
 
 impl<'a, S: 'a> Monitor<'a, S> for SpeculativeMonitor<S> {
@@ -140,11 +195,13 @@ impl<'a, S: 'a> Monitor<'a, S> for SpeculativeMonitor<S> {
                                 mutex_guard = Some(self.mutex.lock().remedy());
                             }
                             Some(guard) => {
+                                #[cfg(test)]
+                                before_wait();
                                 spin_guard.waiting += 1;
                                 drop(spin_guard);
 
                                 let (guard, timed_out) =
-                                    remedy::cond_wait_remedy(&self.cond, guard, duration);
+                                    cond_wait_remedy(&self.cond, guard, duration);
 
                                 if timed_out {
                                     // println!("timed out");
@@ -191,7 +248,7 @@ impl<'a, S: 'a> Monitor<'a, S> for SpeculativeMonitor<S> {
     }
 
     #[inline(always)]
-    fn lock(&self) -> SpeculativeMonitorGuard<S> {
+    fn lock(&self) -> SpeculativeMonitorGuard<'_, S> {
         SpeculativeMonitorGuard {
             spin_guard: self.tracker.lock()
         }
@@ -212,7 +269,7 @@ impl<T: ?Sized + fmt::Debug> fmt::Debug for SpeculativeMonitor<T> {
                 d.field("data", &LockedPlaceholder);
             }
             Some(guard) => {
-                d.field("data", &&(*guard).data);
+                d.field("data", &&guard.data);
             }
         }
         d.finish_non_exhaustive()
@@ -0,0 +1,309 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+#[cfg(not(loom))]
+use std::sync::{Condvar, Mutex};
+#[cfg(loom)]
+use loom::sync::{Condvar, Mutex};
+use std::time::Duration;
+use crate::monitor::{Directive, Monitor, MonitorGuard};
+#[cfg(not(loom))]
+use crate::remedy::cond_wait_remedy;
+#[cfg(loom)]
+use crate::remedy::loom_cond_wait_remedy as cond_wait_remedy;
+use crate::remedy::Remedy;
+use crate::spin_mutex::{SpinGuard, SpinMutex};
+
+/// The outcome of evaluating the closure of [`ClassedMonitor::enter_classed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassedDirective {
+    Return,
+
+    /// Waits as a member of the given class, for at most the given duration.
+    Wait(usize, Duration),
+
+    /// Notifies the waiters selected by the given [`Wake`], then returns.
+    Notify(Wake),
+}
+
+/// Selects the waiters to notify, by class. Notifications are only delivered to classes that
+/// presently have waiters.
+///
+/// # Examples
+/// ```
+/// use anode::monitor::Wake;
+/// const READERS: usize = 0;
+/// const WRITERS: usize = 1;
+/// let wake = Wake::NONE.all(READERS).one(WRITERS);
+/// assert!(wake.is_all(READERS));
+/// assert!(wake.is_one(WRITERS));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Wake {
+    one: u32,
+    all: u32,
+}
+
+impl Wake {
+    pub const NONE: Self = Self { one: 0, all: 0 };
+
+    /// Every waiter of every class.
+    pub const ALL: Self = Self { one: 0, all: u32::MAX };
+
+    /// Additionally notifies one waiter of the given `class`.
+    #[inline(always)]
+    pub const fn one(self, class: usize) -> Self {
+        Self { one: self.one | 1 << class, ..self }
+    }
+
+    /// Additionally notifies every waiter of the given `class`.
+    #[inline(always)]
+    pub const fn all(self, class: usize) -> Self {
+        Self { all: self.all | 1 << class, ..self }
+    }
+
+    #[inline(always)]
+    pub const fn is_one(&self, class: usize) -> bool {
+        self.one & 1 << class != 0 && !self.is_all(class)
+    }
+
+    #[inline(always)]
+    pub const fn is_all(&self, class: usize) -> bool {
+        self.all & 1 << class != 0
+    }
+
+    #[inline(always)]
+    const fn includes(&self, class: usize) -> bool {
+        (self.one | self.all) & 1 << class != 0
+    }
+}
+
+struct Tracker<S, const C: usize> {
+    waiting: [u32; C],
+    data: S,
+}
+
+/// A [`SpeculativeMonitor`](crate::monitor::SpeculativeMonitor) whose waiters are partitioned
+/// into `C` classes (at most 32), each waiting on a condvar of its own. A notification may
+/// thus be directed at the class of waiters whose condition it affects, rather than waking all
+/// waiters to re-evaluate their conditions (the "thundering herd").
+///
+/// As with the speculative monitor, an uncontended [`enter_classed`](Self::enter_classed) costs
+/// a single spin lock acquisition, and no notification is delivered to a class without waiters.
+///
+/// Also implements [`Monitor`], wherein [`Directive::Wait`] waits in class 0,
+/// [`Directive::NotifyOne`] notifies one waiter of class 0, and [`Directive::NotifyAll`]
+/// notifies every waiter of every class.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use std::time::Duration;
+/// use anode::monitor::{ClassedDirective, ClassedMonitor, Wake};
+/// const EVEN: usize = 0;
+/// const ODD: usize = 1;
+/// let monitor = Arc::new(ClassedMonitor::<_, 2>::new(0));
+/// let odd = {
+///     let monitor = monitor.clone();
+///     thread::spawn(move || {
+///         monitor.enter_classed(|count| {
+///             if *count % 2 == 1 {
+///                 ClassedDirective::Return
+///             } else {
+///                 ClassedDirective::Wait(ODD, Duration::MAX)
+///             }
+///         });
+///     })
+/// };
+/// monitor.enter_classed(|count| {
+///     *count = 1;
+///     ClassedDirective::Notify(Wake::NONE.all(ODD))
+/// });
+/// odd.join().unwrap();
+/// ```
+pub struct ClassedMonitor<S, const C: usize> {
+    mutex: Mutex<()>,
+    conds: [Condvar; C],
+    tracker: SpinMutex<Tracker<S, C>>,
+}
+
+impl<S: Default, const C: usize> Default for ClassedMonitor<S, C> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S, const C: usize> ClassedMonitor<S, C> {
+    #[inline(always)]
+    pub fn new(s: S) -> Self {
+        assert!(C <= 32, "too many classes: {C}");
+        Self {
+            tracker: SpinMutex::untracked(Tracker {
+                data: s,
+                waiting: [0; C],
+            }),
+            mutex: Mutex::new(()),
+            conds: std::array::from_fn(|_| Condvar::new()),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.tracker.into_inner().data
+    }
+
+    /// The number of threads waiting, across all classes.
+    pub fn num_waiting(&self) -> u32 {
+        self.tracker.lock().waiting.iter().sum()
+    }
+
+    /// The number of threads waiting in the given `class`.
+    pub fn num_waiting_in(&self, class: usize) -> u32 {
+        self.tracker.lock().waiting[class]
+    }
+
+    /// Returns a mutable reference to the encapsulated state, without locking.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.tracker.get_mut().data
+    }
+
+    /// Attempts to lock the encapsulated state without blocking, returning `None` if the
+    /// state is presently locked by another thread.
+    #[inline(always)]
+    pub fn try_lock(&self) -> Option<ClassedMonitorGuard<'_, S, C>> {
+        self.tracker.try_lock().map(|spin_guard| ClassedMonitorGuard { spin_guard })
+    }
+
+    /// Repeatedly evaluates `f` over the encapsulated state, acting on the returned
+    /// [`ClassedDirective`], until `f` directs the monitor to return (or to notify, or a wait
+    /// times out). As with [`Monitor::enter`], `f` may be evaluated more than once, and must
+    /// therefore be idempotent.
+    #[inline(always)]
+    pub fn enter_classed<F: FnMut(&mut S) -> ClassedDirective>(&self, mut f: F) {
+        let mut mutex_guard = None;
+        let mut woken = None;
+        loop {
+            let mut spin_guard = self.tracker.lock();
+            if let Some(class) = woken.take() {
+                spin_guard.waiting[class] -= 1;
+            }
+            match f(&mut spin_guard.data) {
+                ClassedDirective::Return => {
+                    return
+                }
+                ClassedDirective::Wait(class, duration) => {
+                    if duration.is_zero() {
+                        return
+                    }
+                    match mutex_guard.take() {
+                        None => {
+                            drop(spin_guard);
+                            mutex_guard = Some(self.mutex.lock().remedy());
+                        }
+                        Some(guard) => {
+                            #[cfg(test)]
+                            crate::monitor::before_wait();
+                            spin_guard.waiting[class] += 1;
+                            drop(spin_guard);
+
+                            let (guard, timed_out) =
+                                cond_wait_remedy(&self.conds[class], guard, duration);
+
+                            if timed_out {
+                                self.tracker.lock().waiting[class] -= 1;
+                                return
+                            } else {
+                                mutex_guard = Some(guard);
+                                woken = Some(class);
+                            }
+                        }
+                    }
+                }
+                ClassedDirective::Notify(wake) => {
+                    if !(0..C).any(|class| wake.includes(class) && spin_guard.waiting[class] > 0) {
+                        return
+                    }
+                    drop(spin_guard);
+                    match mutex_guard.take() {
+                        None => {
+                            mutex_guard = Some(self.mutex.lock().remedy());
+                        }
+                        Some(guard) => {
+                            drop(guard);
+                            for (class, cond) in self.conds.iter().enumerate() {
+                                if wake.is_all(class) {
+                                    cond.notify_all();
+                                } else if wake.is_one(class) {
+                                    cond.notify_one();
+                                }
+                            }
+                            return
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a, S: 'a, const C: usize> Monitor<'a, S> for ClassedMonitor<S, C> {
+    type Guard = ClassedMonitorGuard<'a, S, C>;
+
+    #[inline(always)]
+    fn enter<F: FnMut(&mut S) -> Directive>(&self, mut f: F) {
+        self.enter_classed(|state| match f(state) {
+            Directive::Return => ClassedDirective::Return,
+            Directive::Wait(duration) => ClassedDirective::Wait(0, duration),
+            Directive::NotifyOne => ClassedDirective::Notify(Wake::NONE.one(0)),
+            Directive::NotifyAll => ClassedDirective::Notify(Wake::ALL),
+        });
+    }
+
+    #[inline(always)]
+    fn lock(&self) -> ClassedMonitorGuard<'_, S, C> {
+        ClassedMonitorGuard {
+            spin_guard: self.tracker.lock()
+        }
+    }
+}
+
+impl<S: fmt::Debug, const C: usize> fmt::Debug for ClassedMonitor<S, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("ClassedMonitor");
+        match self.tracker.try_lock() {
+            None => {
+                d.field("data", &format_args!("<locked>"));
+            }
+            Some(guard) => {
+                d.field("data", &guard.data);
+                d.field("waiting", &guard.waiting);
+            }
+        }
+        d.finish_non_exhaustive()
+    }
+}
+
+pub struct ClassedMonitorGuard<'a, S, const C: usize> {
+    spin_guard: SpinGuard<'a, Tracker<S, C>>
+}
+
+impl<S, const C: usize> Deref for ClassedMonitorGuard<'_, S, C> {
+    type Target = S;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.spin_guard.data
+    }
+}
+
+impl<S, const C: usize> DerefMut for ClassedMonitorGuard<'_, S, C> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.spin_guard.data
+    }
+}
+
+impl<'a, S, const C: usize> MonitorGuard<'a, S> for ClassedMonitorGuard<'a, S, C> {}
+
+#[cfg(test)]
+mod tests;
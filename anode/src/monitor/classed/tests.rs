@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::monitor::{ClassedDirective, ClassedMonitor, Directive, Monitor, Wake};
+use crate::{test_utils, wait};
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::wait::{Wait, WaitResult};
+
+const RED: usize = 0;
+const BLUE: usize = 1;
+
+#[derive(Debug, Default)]
+struct State {
+    permits: [u32; 2],
+    evaluations: [u32; 2],
+}
+
+/// Waits in the given class until a permit of that class is available, then consumes it.
+fn take_permit(monitor: &ClassedMonitor<State, 2>, class: usize) {
+    let mut taken = false;
+    monitor.enter_classed(|state| {
+        state.evaluations[class] += 1;
+        if !taken && state.permits[class] > 0 {
+            taken = true;
+            state.permits[class] -= 1;
+        }
+
+        if taken {
+            ClassedDirective::Return
+        } else {
+            ClassedDirective::Wait(class, Duration::MAX)
+        }
+    });
+}
+
+/// Issues a permit of the given class, notifying its waiters per the given `wake`.
+fn issue_permit(monitor: &ClassedMonitor<State, 2>, class: usize, wake: Wake) {
+    let mut issued = false;
+    monitor.enter_classed(|state| {
+        if !issued {
+            issued = true;
+            state.permits[class] += 1;
+        }
+        ClassedDirective::Notify(wake)
+    });
+}
+
+#[test]
+fn wake_builder() {
+    assert_eq!(Wake::NONE, Wake::default());
+    let wake = Wake::NONE.one(RED).all(BLUE);
+    assert!(wake.is_one(RED));
+    assert!(!wake.is_all(RED));
+    assert!(!wake.is_one(BLUE));
+    assert!(wake.is_all(BLUE));
+
+    // notifying all subsumes notifying one
+    let wake = wake.all(RED);
+    assert!(!wake.is_one(RED));
+    assert!(wake.is_all(RED));
+
+    assert!(Wake::ALL.is_all(RED));
+    assert!(Wake::ALL.is_all(31));
+}
+
+#[test]
+fn return_immediately() {
+    let mut monitor = ClassedMonitor::<_, 2>::new(0);
+    let mut invocations = 0;
+    monitor.enter_classed(|val| {
+        *val = 42;
+        invocations += 1;
+        ClassedDirective::Return
+    });
+    assert_eq!(1, invocations);
+    assert_eq!(42, *monitor.lock());
+    assert_eq!(0, monitor.num_waiting());
+
+    // notifying without waiters returns without re-evaluating the closure
+    let mut invocations = 0;
+    monitor.enter_classed(|_| {
+        invocations += 1;
+        ClassedDirective::Notify(Wake::ALL)
+    });
+    assert_eq!(1, invocations);
+
+    *monitor.get_mut() = 69;
+    assert_eq!(69, *monitor.try_lock().unwrap());
+    assert_eq!(69, monitor.into_inner());
+}
+
+#[test]
+fn wait_times_out() {
+    let monitor = ClassedMonitor::<_, 2>::new(State::default());
+    let mut invocations = 0;
+    monitor.enter_classed(|_| {
+        invocations += 1;
+        ClassedDirective::Wait(BLUE, Duration::ZERO)
+    });
+    assert_eq!(1, invocations);
+
+    let mut invocations = 0;
+    monitor.enter_classed(|_| {
+        invocations += 1;
+        ClassedDirective::Wait(BLUE, SHORT_WAIT)
+    });
+    assert_eq!(2, invocations);
+    assert_eq!(0, monitor.num_waiting_in(BLUE));
+}
+
+#[test]
+fn notify_wakes_selected_class_only() {
+    let monitor = Arc::new(ClassedMonitor::<_, 2>::new(State::default()));
+    let t_red = {
+        let monitor = monitor.clone();
+        test_utils::spawn_blocked(move || take_permit(&monitor, RED))
+    };
+    let t_blue = {
+        let monitor = monitor.clone();
+        test_utils::spawn_blocked(move || take_permit(&monitor, BLUE))
+    };
+    monitor.wait_for_num_waiting(Ordering::is_eq, 2, LONG_WAIT).unwrap();
+    let red_evaluations = monitor.lock().evaluations[RED];
+
+    // a blue permit only wakes the blue waiter
+    issue_permit(&monitor, BLUE, Wake::NONE.all(BLUE));
+    t_blue.join().unwrap();
+    thread::sleep(CHECK_WAIT);
+    assert_eq!(red_evaluations, monitor.lock().evaluations[RED]);
+    assert_eq!(1, monitor.num_waiting_in(RED));
+    assert!(!t_red.is_finished());
+
+    issue_permit(&monitor, RED, Wake::NONE.one(RED));
+    t_red.join().unwrap();
+    assert_eq!(0, monitor.num_waiting());
+}
+
+#[test]
+fn notify_one_wakes_one_in_class() {
+    let monitor = Arc::new(ClassedMonitor::<_, 2>::new(State::default()));
+    let threads = (0..2)
+        .map(|_| {
+            let monitor = monitor.clone();
+            test_utils::spawn_blocked(move || take_permit(&monitor, RED))
+        })
+        .collect::<Vec<_>>();
+    monitor.wait_for_num_waiting(Ordering::is_eq, 2, LONG_WAIT).unwrap();
+
+    issue_permit(&monitor, RED, Wake::NONE.one(RED));
+    monitor.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    thread::sleep(CHECK_WAIT);
+    assert_eq!(1, monitor.num_waiting_in(RED));
+
+    issue_permit(&monitor, RED, Wake::NONE.one(RED));
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(0, monitor.num_waiting());
+}
+
+#[test]
+fn monitor_trait_notify_all_wakes_every_class() {
+    let monitor = Arc::new(ClassedMonitor::<_, 2>::new(State::default()));
+    let threads = [RED, BLUE]
+        .map(|class| {
+            let monitor = monitor.clone();
+            test_utils::spawn_blocked(move || take_permit(&monitor, class))
+        });
+    monitor.wait_for_num_waiting(Ordering::is_eq, 2, LONG_WAIT).unwrap();
+
+    monitor.enter(|state| {
+        state.permits = [1, 1];
+        Directive::NotifyAll
+    });
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!([0, 0], monitor.lock().permits);
+}
+
+#[test]
+fn implements_debug() {
+    let monitor = ClassedMonitor::<_, 2>::new("foobar");
+    assert!(format!("{:?}", monitor).contains("ClassedMonitor"), "{:?}", monitor);
+    assert!(format!("{:?}", monitor).contains("foobar"), "{:?}", monitor);
+
+    let guard = monitor.lock();
+    assert!(format!("{:?}", monitor).contains("<locked>"), "{:?}", monitor);
+    drop(guard);
+}
+
+impl<S, const C: usize> ClassedMonitor<S, C> {
+    fn wait_for_num_waiting(&self, cmp: impl FnMut(Ordering) -> bool, target: u32, duration: Duration) -> WaitResult {
+        wait::Spin::wait_for_inequality(|| self.num_waiting(), cmp, &target, duration)
+    }
+}
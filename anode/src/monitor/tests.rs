@@ -79,6 +79,22 @@ fn notify_nothing() {
     assert_eq!(1, invocations);
 }
 
+#[test]
+fn uncontended_enter_bypasses_mutex() {
+    let monitor = SpeculativeMonitor::new(0);
+
+    // were any of the following to take the mutex, they would fail to return
+    let _mutex_guard = monitor.mutex.try_lock().unwrap();
+    monitor.enter(|val| {
+        *val += 1;
+        Directive::Return
+    });
+    monitor.enter(|_| Directive::Wait(Duration::ZERO));
+    monitor.enter(|_| Directive::NotifyOne);
+    monitor.enter(|_| Directive::NotifyAll);
+    assert_eq!(1, *monitor.lock());
+}
+
 #[test]
 fn wait_for_notify() {
     for _ in 0..10 {
@@ -0,0 +1,257 @@
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use std::thread::Thread;
+use std::time::Duration;
+use std::{fmt, hint, thread};
+use crate::remedy::Remedy;
+
+unsafe impl<T: ?Sized + Send, const SPINS: usize> Send for ParkingSpinMutex<T, SPINS> {}
+unsafe impl<T: ?Sized + Send, const SPINS: usize> Sync for ParkingSpinMutex<T, SPINS> {}
+unsafe impl<T: ?Sized + Sync, const SPINS: usize> Sync for ParkingSpinGuard<'_, T, SPINS> {}
+
+/// Set while the lock is held.
+const LOCKED: u8 = 0b01;
+
+/// Set while there is at least one parked (or parking) thread in the waiter queue.
+const HAS_WAITERS: u8 = 0b10;
+
+/// A mutual exclusion lock that spins for up to `SPINS` iterations before parking the
+/// calling thread, to be unparked when the lock is released. Short waits thus avoid the
+/// cost of parking, while long waits do not burn a core.
+///
+/// The uncontended paths of [`lock`](Self::lock) and [`unlock`](Self::unlock) are
+/// a single atomic read-modify-write each, as with [`SpinMutex`](crate::spin_mutex::SpinMutex).
+/// The waiter queue is only consulted once a thread has exhausted its spins.
+///
+/// # Examples
+/// ```
+/// use anode::parking_spin_mutex::ParkingSpinMutex;
+/// let lock = ParkingSpinMutex::<_, 100>::new(0);
+/// *lock.lock() = 42;
+/// assert_eq!(42, *lock.lock());
+/// ```
+pub struct ParkingSpinMutex<T: ?Sized, const SPINS: usize> {
+    raw: RawParkingLock,
+    data: UnsafeCell<T>,
+}
+
+pub struct ParkingSpinGuard<'a, T: ?Sized, const SPINS: usize> {
+    lock: &'a ParkingSpinMutex<T, SPINS>,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T, const SPINS: usize> ParkingSpinMutex<T, SPINS> {
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self {
+            raw: RawParkingLock::new(),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized, const SPINS: usize> ParkingSpinMutex<T, SPINS> {
+    #[inline]
+    pub fn lock(&self) -> ParkingSpinGuard<'_, T, SPINS> {
+        for _ in 0..SPINS {
+            if !self.raw.is_locked() && self.raw.try_acquire() {
+                return self.guard();
+            }
+            hint::spin_loop();
+        }
+        self.raw.acquire_parking(None);
+        self.guard()
+    }
+
+    #[inline]
+    pub fn try_lock(&self) -> Option<ParkingSpinGuard<'_, T, SPINS>> {
+        if self.raw.try_acquire() {
+            Some(self.guard())
+        } else {
+            None
+        }
+    }
+
+    /// Releases the lock on behalf of a guard that was forgotten, unparking the
+    /// longest-waiting parked thread (if there is one).
+    ///
+    /// # Safety
+    /// The lock must be held, and not by a live guard.
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        self.raw.release();
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`ParkingSpinMutex`] mutably, no actual locking needs to
+    /// take place---the mutable borrow statically guarantees no locks exist.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    #[inline]
+    fn guard(&self) -> ParkingSpinGuard<'_, T, SPINS> {
+        ParkingSpinGuard {
+            lock: self,
+            __no_send: PhantomData,
+        }
+    }
+
+    #[cfg(test)]
+    fn num_waiters(&self) -> usize {
+        self.raw.num_waiters()
+    }
+}
+
+/// The state of a lock whose waiters park once they are done spinning, with a queue of the
+/// parked threads. Shared by the parking locks in this crate, which differ only in how
+/// they spin before parking.
+pub(crate) struct RawParkingLock {
+    state: AtomicU8,
+    waiters: Mutex<VecDeque<Thread>>,
+}
+
+impl RawParkingLock {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            state: AtomicU8::new(0),
+            waiters: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & LOCKED != 0
+    }
+
+    #[inline]
+    pub(crate) fn try_acquire(&self) -> bool {
+        self.state.fetch_or(LOCKED, Ordering::Acquire) & LOCKED == 0
+    }
+
+    /// Acquires the lock, parking the current thread between attempts. If `park_timeout` is
+    /// set, each park lasts at most that long before the thread reattempts the acquisition.
+    #[cold]
+    pub(crate) fn acquire_parking(&self, park_timeout: Option<Duration>) {
+        loop {
+            if self.try_acquire() {
+                return;
+            }
+
+            // the waiter is enqueued and the HAS_WAITERS flag raised before the final attempt,
+            // so that an unlock that is concurrent with the attempt is guaranteed to observe
+            // the flag and unpark a waiter
+            {
+                let mut waiters = self.waiters.lock().remedy();
+                waiters.push_back(thread::current());
+                self.state.fetch_or(HAS_WAITERS, Ordering::Relaxed);
+            }
+            if self.try_acquire() {
+                self.deregister();
+                return;
+            }
+
+            // unparking may be spurious, in which case this thread is still enqueued
+            match park_timeout {
+                None => thread::park(),
+                Some(timeout) => thread::park_timeout(timeout),
+            }
+            self.deregister();
+        }
+    }
+
+    #[inline]
+    pub(crate) fn release(&self) {
+        let prev = self.state.fetch_and(!LOCKED, Ordering::Release);
+        if prev & HAS_WAITERS != 0 {
+            self.unpark_one();
+        }
+    }
+
+    #[cold]
+    fn unpark_one(&self) {
+        let mut waiters = self.waiters.lock().remedy();
+        if let Some(waiter) = waiters.pop_front() {
+            if waiters.is_empty() {
+                self.state.fetch_and(!HAS_WAITERS, Ordering::Relaxed);
+            }
+            drop(waiters);
+            waiter.unpark();
+        }
+    }
+
+    #[cold]
+    fn deregister(&self) {
+        let current = thread::current().id();
+        let mut waiters = self.waiters.lock().remedy();
+        waiters.retain(|waiter| waiter.id() != current);
+        if waiters.is_empty() {
+            self.state.fetch_and(!HAS_WAITERS, Ordering::Relaxed);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn num_waiters(&self) -> usize {
+        self.waiters.lock().remedy().len()
+    }
+}
+
+impl<T: ?Sized, const SPINS: usize> Drop for ParkingSpinGuard<'_, T, SPINS> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the lock is held by this guard
+        unsafe { self.lock.unlock() };
+    }
+}
+
+impl<T: ?Sized, const SPINS: usize> Deref for ParkingSpinGuard<'_, T, SPINS> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized, const SPINS: usize> DerefMut for ParkingSpinGuard<'_, T, SPINS> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, const SPINS: usize> fmt::Debug for ParkingSpinMutex<T, SPINS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("ParkingSpinMutex");
+        match self.try_lock() {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(guard) => {
+                d.field("data", &&*guard);
+            }
+        }
+        d.finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::thread;
+use crate::parking_spin_mutex::ParkingSpinMutex;
+use crate::test_utils::LONG_WAIT;
+use crate::wait;
+use crate::wait::Wait;
+use crate::test_utils;
+
+#[test]
+fn cycle() {
+    let lock = ParkingSpinMutex::<_, 10>::new(0);
+    let mut guard = lock.lock();
+    *guard = 42;
+    assert!(lock.try_lock().is_none());
+    drop(guard);
+
+    assert_eq!(42, *lock.try_lock().unwrap());
+    assert_eq!(0, lock.num_waiters());
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn borrow_mut() {
+    let mut lock = ParkingSpinMutex::<_, 10>::new(0);
+    *lock.get_mut() = 42;
+    assert_eq!(42, *lock.lock());
+}
+
+#[test]
+fn parks_after_spinning() {
+    let lock = Arc::new(ParkingSpinMutex::<_, 100>::new(0));
+    let guard = lock.lock();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 exhausts its spins and parks, because main holds the lock
+            *lock.lock() = 42;
+        })
+    };
+    wait::Spin::wait_for_inequality(|| lock.num_waiters(), Ordering::is_eq, &1, LONG_WAIT).unwrap();
+    assert!(!t_2.is_finished());
+
+    // releasing the lock unparks t_2
+    drop(guard);
+    t_2.join().unwrap();
+    assert_eq!(0, lock.num_waiters());
+    assert_eq!(42, *lock.lock());
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn contended_without_spinning() {
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 10_000;
+    let lock = Arc::new(ParkingSpinMutex::<_, 0>::new(0));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    *lock.lock() += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS, *lock.lock());
+    assert_eq!(0, lock.num_waiters());
+}
+
+#[test]
+fn debug() {
+    let lock = ParkingSpinMutex::<_, 10>::new(42);
+    assert_eq!("ParkingSpinMutex { data: 42, .. }", format!("{lock:?}"));
+    let _guard = lock.lock();
+    assert_eq!("ParkingSpinMutex { data: <locked>, .. }", format!("{lock:?}"));
+}
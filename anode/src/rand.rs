@@ -1,6 +1,8 @@
 use crate::inf_iterator::InfIterator;
-use std::ops::Range;
-use std::time::{Duration, SystemTime};
+use core::ops::Range;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
 
 /// A minimal specification of a 64-bit random number generator.
 pub trait Rand {
@@ -59,6 +61,9 @@ impl Probability {
     /// Creates a new [`Probability`] value, without checking the bounds. If a
     /// probability is created outside the range \[0, 1\], its behaviour with an
     /// RNG is undefined.
+    ///
+    /// # Safety
+    /// The caller must ensure that `p` lies in the range \[0, 1\].
     #[inline(always)]
     pub const unsafe fn new_unchecked(p: f64) -> Self {
         Self(p)
@@ -343,6 +348,7 @@ impl<S: Seeded, F: FnOnce() -> u64> Rand for LazyRand64<S, F> {
 
 /// Derives a seed from the system clock by XORing the upper 64 bits of the nanosecond timestamp
 /// with the lower 64 bits.
+#[cfg(feature = "std")]
 pub fn clock_seed() -> u64 {
     let time = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -0,0 +1,88 @@
+use crate::rcu_cell::RcuCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn load_and_update() {
+    let cell = RcuCell::new(42);
+    let initial = cell.load();
+    assert_eq!(42, *initial);
+
+    let updated = cell.update(|&val| val + 1);
+    assert_eq!(43, *updated);
+    assert_eq!(43, *cell.load());
+    assert_eq!(42, *initial);
+
+    let replaced = cell.replace(69);
+    assert_eq!(43, *replaced);
+    assert_eq!(69, *cell.load());
+}
+
+#[test]
+fn releases_versions() {
+    let first = Arc::new(0);
+    let cell = RcuCell::from_arc(first.clone());
+    assert_eq!(2, Arc::strong_count(&first));
+
+    let snapshot = cell.load();
+    assert_eq!(3, Arc::strong_count(&first));
+    let second = cell.update(|&val| val + 1);
+    assert_eq!(2, Arc::strong_count(&first));
+    drop(snapshot);
+    assert_eq!(1, Arc::strong_count(&first));
+
+    assert_eq!(2, Arc::strong_count(&second));
+    drop(cell);
+    assert_eq!(1, Arc::strong_count(&second));
+}
+
+#[test]
+fn implements_debug() {
+    let cell = RcuCell::new(42);
+    assert_eq!("RcuCell { data: 42 }", format!("{:?}", cell));
+}
+
+#[test]
+fn concurrent_updates() {
+    const WRITERS: usize = 2;
+    const WRITES: u64 = 10_000;
+    let cell = Arc::new(RcuCell::new([0u64; 4]));
+    let running = Arc::new(AtomicBool::new(true));
+
+    // writers keep all elements equal; readers must never observe a partial update
+    let writers = (0..WRITERS)
+        .map(|_| {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                for _ in 0..WRITES {
+                    cell.update(|arr| arr.map(|val| val + 1));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let readers = (0..2)
+        .map(|_| {
+            let cell = cell.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                let mut last = 0;
+                while running.load(Ordering::Relaxed) {
+                    let snapshot = cell.load();
+                    assert!(snapshot.iter().all(|&val| val == snapshot[0]), "{snapshot:?}");
+                    assert!(snapshot[0] >= last);
+                    last = snapshot[0];
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    running.store(false, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!([WRITERS as u64 * WRITES; 4], *cell.load());
+}
@@ -0,0 +1,206 @@
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use std::fmt;
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+
+unsafe impl<T: ?Sized + Send> Send for ReentrantLock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for ReentrantLock<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for ReentrantGuard<'_, T> {}
+
+/// Identifies the current thread. Unlike an address-based identifier, it is never reused
+/// after the thread exits.
+#[inline]
+fn current_thread_id() -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+    thread_local! {
+        static ID: usize = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    }
+    ID.with(|id| *id)
+}
+
+/// A mutual exclusion lock that may be reacquired by the thread that holds it, without
+/// deadlocking.
+///
+/// The lock records the owning thread and the number of times that thread has acquired it;
+/// it is released once every guard is dropped. Since multiple guards of the owning thread may
+/// coexist, guards only permit shared access to the data. Mutation requires interior mutability,
+/// e.g., a [`RefCell`](std::cell::RefCell).
+///
+/// # Examples
+/// ```
+/// use std::cell::RefCell;
+/// use anode::reentrant_lock::ReentrantLock;
+/// let lock = ReentrantLock::new(RefCell::new(0));
+/// let outer = lock.lock();
+/// let inner = lock.lock();
+/// *inner.borrow_mut() += 1;
+/// drop(inner);
+/// assert_eq!(1, *outer.borrow());
+/// ```
+pub struct ReentrantLock<T: ?Sized> {
+    /// The identifier of the owning thread, or 0 if the lock is free.
+    owner: AtomicUsize,
+    /// The number of guards held by the owner. Only ever accessed by the owning thread.
+    count: Cell<u32>,
+    locked: Mutex<bool>,
+    cond: TimedCondvar,
+    data: T,
+}
+
+pub struct ReentrantGuard<'a, T: ?Sized> {
+    lock: &'a ReentrantLock<T>,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T> ReentrantLock<T> {
+    #[inline]
+    pub const fn new(t: T) -> Self {
+        Self {
+            owner: AtomicUsize::new(0),
+            count: Cell::new(0),
+            locked: Mutex::new(false),
+            cond: TimedCondvar::new(),
+            data: t,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+}
+
+impl<T: ?Sized> ReentrantLock<T> {
+    /// Acquires the lock, blocking until it is available, unless the current thread already
+    /// holds it.
+    ///
+    /// # Panics
+    /// If the lock has been reacquired [`u32::MAX`] times by the current thread.
+    #[inline]
+    pub fn lock(&self) -> ReentrantGuard<'_, T> {
+        self.try_lock_until(Deadline::Forever).unwrap()
+    }
+
+    /// Attempts to acquire the lock without blocking.
+    #[inline]
+    pub fn try_lock(&self) -> Option<ReentrantGuard<'_, T>> {
+        self.try_lock_for(Duration::ZERO)
+    }
+
+    /// Attempts to acquire the lock within the given `duration`. Reacquisition by the current
+    /// thread always succeeds immediately.
+    #[inline]
+    pub fn try_lock_for(&self, duration: Duration) -> Option<ReentrantGuard<'_, T>> {
+        self.try_lock_until(Deadline::lazy_after(duration))
+    }
+
+    /// Attempts to acquire the lock before the given `deadline` elapses. Reacquisition by the
+    /// current thread always succeeds immediately.
+    pub fn try_lock_until(&self, deadline: impl Into<Deadline>) -> Option<ReentrantGuard<'_, T>> {
+        let current = current_thread_id();
+
+        // only the current thread could have set the owner to its own identifier, so a
+        // relaxed load suffices for the comparison
+        if self.owner.load(Ordering::Relaxed) == current {
+            let count = self.count.get().checked_add(1).expect("lock count overflow");
+            self.count.set(count);
+            return Some(self.guard());
+        }
+
+        let locked = self.locked.lock().remedy();
+        let (mut locked, timed_out) = self.cond.wait_while_until(locked, |locked| *locked, deadline);
+        if timed_out {
+            return None;
+        }
+        *locked = true;
+        drop(locked);
+        self.owner.store(current, Ordering::Relaxed);
+        self.count.set(1);
+        Some(self.guard())
+    }
+
+    /// Determines whether the lock is held by the current thread.
+    #[inline]
+    pub fn is_held_by_current_thread(&self) -> bool {
+        self.owner.load(Ordering::Relaxed) == current_thread_id()
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`ReentrantLock`] mutably, no actual locking needs to
+    /// take place---the mutable borrow statically guarantees no locks exist.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    #[inline]
+    fn guard(&self) -> ReentrantGuard<'_, T> {
+        ReentrantGuard {
+            lock: self,
+            __no_send: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn unlock(&self) {
+        let count = self.count.get() - 1;
+        self.count.set(count);
+        if count == 0 {
+            self.owner.store(0, Ordering::Relaxed);
+            *self.locked.lock().remedy() = false;
+            self.cond.notify_one();
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for ReentrantGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.unlock();
+    }
+}
+
+impl<T: ?Sized> Deref for ReentrantGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.lock.data
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for ReentrantGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for ReentrantLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("ReentrantLock");
+        match self.try_lock() {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(guard) => {
+                d.field("data", &&*guard);
+            }
+        }
+        d.finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
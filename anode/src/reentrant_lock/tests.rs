@@ -0,0 +1,122 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::reentrant_lock::ReentrantLock;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::test_utils;
+
+#[test]
+fn reentry() {
+    let lock = ReentrantLock::new(RefCell::new(0));
+    assert!(!lock.is_held_by_current_thread());
+
+    let guard_1 = lock.lock();
+    let guard_2 = lock.lock();
+    let guard_3 = lock.try_lock().unwrap();
+    assert!(lock.is_held_by_current_thread());
+    assert_eq!(3, lock.count.get());
+    *guard_3.borrow_mut() = 42;
+
+    drop(guard_1);
+    drop(guard_3);
+    assert_eq!(42, *guard_2.borrow());
+    assert!(lock.is_held_by_current_thread());
+    drop(guard_2);
+
+    assert!(!lock.is_held_by_current_thread());
+    assert_eq!(0, lock.count.get());
+    assert_eq!(42, lock.into_inner().into_inner());
+}
+
+#[test]
+fn excludes_other_threads_until_fully_released() {
+    let lock = Arc::new(ReentrantLock::new(RefCell::new(0)));
+    let guard_1 = lock.lock();
+    let guard_2 = lock.lock();
+
+    {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            assert!(lock.try_lock().is_none());
+            assert!(!lock.is_held_by_current_thread());
+        })
+        .join()
+        .unwrap();
+    }
+
+    // t_2 blocks until main releases both of its guards
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            let guard = lock.try_lock_for(LONG_WAIT).unwrap();
+            *guard.borrow_mut() = 42;
+        })
+    };
+    drop(guard_1);
+    thread::sleep(CHECK_WAIT);
+    assert!(!t_2.is_finished());
+    drop(guard_2);
+    t_2.join().unwrap();
+    assert_eq!(42, *lock.lock().borrow());
+}
+
+#[test]
+fn try_lock_for_timeout() {
+    let lock = Arc::new(ReentrantLock::new(0));
+    let guard = lock.lock();
+    let elapsed = {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let start = Instant::now();
+            assert!(lock.try_lock_for(CHECK_WAIT).is_none());
+            start.elapsed()
+        })
+        .join()
+        .unwrap()
+    };
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+
+    // the owner reacquires without waiting, irrespective of the duration
+    assert!(lock.try_lock_for(Duration::ZERO).is_some());
+    drop(guard);
+}
+
+#[test]
+fn contended() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 1_000;
+    let lock = Arc::new(ReentrantLock::new(RefCell::new(0)));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    let outer = lock.lock();
+                    let inner = lock.lock();
+                    *inner.borrow_mut() += 1;
+                    drop(inner);
+                    *outer.borrow_mut() += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS * 2, *lock.lock().borrow());
+}
+
+#[test]
+fn debug() {
+    let lock = Arc::new(ReentrantLock::new(42));
+    let _guard = lock.lock();
+
+    // the owning thread can see the data, as it can reacquire the lock
+    assert_eq!("ReentrantLock { data: 42, .. }", format!("{lock:?}"));
+    let debug = {
+        let lock = lock.clone();
+        thread::spawn(move || format!("{lock:?}")).join().unwrap()
+    };
+    assert_eq!("ReentrantLock { data: <locked>, .. }", debug);
+}
@@ -1,5 +1,6 @@
 use std::sync::{Condvar, LockResult, MutexGuard, TryLockError, TryLockResult};
 use std::time::{Duration};
+use crate::deadline::Deadline;
 
 /// From _Poison_, by _The Prodigy_ (1994).
 /// I got the poison,
@@ -39,6 +40,12 @@ impl<T> Remedy<T> for TryLockResult<T> {
     }
 }
 
+/// Waits on `cond` for at most `duration`, returning the reacquired guard along with a flag
+/// indicating whether the wait timed out. A zero `duration` returns immediately (timed out),
+/// while [`Duration::MAX`] waits indefinitely. Poisoning is disregarded.
+///
+/// The wait may end prematurely due to a spurious wakeup; the caller is expected to
+/// re-evaluate its condition. [`TimedCondvar`] does this on the caller's behalf.
 #[inline(always)]
 pub fn cond_wait_remedy<'a, T>(
     cond: &Condvar,
@@ -54,4 +61,111 @@ pub fn cond_wait_remedy<'a, T>(
         let (guard, maybe_timed_out) = cond.wait_timeout(guard, duration).remedy();
         (guard, maybe_timed_out.timed_out())
     }
-}
\ No newline at end of file
+}
+
+/// The counterpart of [`cond_wait_remedy`] for loom's condvar, which never times out. Only
+/// zero and unbounded waits are thus modelled faithfully.
+#[cfg(loom)]
+#[inline(always)]
+pub(crate) fn loom_cond_wait_remedy<'a, T>(
+    cond: &loom::sync::Condvar,
+    guard: loom::sync::MutexGuard<'a, T>,
+    duration: Duration,
+) -> (loom::sync::MutexGuard<'a, T>, bool) {
+    if duration.is_zero() {
+        (guard, true)
+    } else {
+        (cond.wait(guard).remedy(), false)
+    }
+}
+
+/// A [`Condvar`] wrapper for waiting on a condition until a [`Deadline`], taking care of the
+/// details that are easy to get wrong when waiting on a [`Condvar`] directly.
+///
+/// * **Spurious wakeups:** the condition is re-evaluated after every wakeup, and the wait
+///   resumes with the time remaining until the deadline -- not with the original duration.
+/// * **Poisoning:** a poisoned mutex is treated as though it were not poisoned, as per
+///   [`Remedy`]. The mutex itself stays poisoned; it is up to the caller to deal with that,
+///   should it matter.
+///
+/// # Examples
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use std::thread;
+/// use std::time::Duration;
+/// use anode::deadline::Deadline;
+/// use anode::remedy::TimedCondvar;
+///
+/// let pair = Arc::new((Mutex::new(false), TimedCondvar::new()));
+/// let t_2 = {
+///     let pair = pair.clone();
+///     thread::spawn(move || {
+///         *pair.0.lock().unwrap() = true;
+///         pair.1.notify_all();
+///     })
+/// };
+/// let guard = pair.0.lock().unwrap();
+/// let (guard, timed_out) = pair.1.wait_while_until(guard, |ready| !*ready, Deadline::lazy_after(Duration::from_secs(10)));
+/// assert!(!timed_out);
+/// assert!(*guard);
+/// # drop(guard);
+/// # t_2.join().unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct TimedCondvar(Condvar);
+
+impl TimedCondvar {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Condvar::new())
+    }
+
+    /// Blocks the current thread while `condition` holds, until `deadline` elapses.
+    ///
+    /// Returns the guard, along with a flag that is `true` if the wait timed out (i.e., the
+    /// condition still held when the deadline elapsed) or `false` if the condition ceased to
+    /// hold. The condition is always evaluated at least once, and is never evaluated without
+    /// the lock held.
+    #[inline]
+    pub fn wait_while_until<'a, T>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+        deadline: impl Into<Deadline>,
+    ) -> (MutexGuard<'a, T>, bool) {
+        let mut deadline = deadline.into();
+        while condition(&mut guard) {
+            let remaining = deadline.remaining();
+            if remaining.is_zero() {
+                return (guard, true);
+            }
+            (guard, _) = cond_wait_remedy(&self.0, guard, remaining);
+        }
+        (guard, false)
+    }
+
+    /// A variant of [`wait_while_until`](Self::wait_while_until) that waits for at most
+    /// `duration`.
+    #[inline]
+    pub fn wait_while_for<'a, T>(
+        &self,
+        guard: MutexGuard<'a, T>,
+        condition: impl FnMut(&mut T) -> bool,
+        duration: Duration,
+    ) -> (MutexGuard<'a, T>, bool) {
+        self.wait_while_until(guard, condition, Deadline::lazy_after(duration))
+    }
+
+    #[inline]
+    pub fn notify_one(&self) {
+        self.0.notify_one();
+    }
+
+    #[inline]
+    pub fn notify_all(&self) {
+        self.0.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,63 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+
+#[test]
+fn wait_times_out() {
+    let mutex = Mutex::new(0);
+    let cond = TimedCondvar::new();
+
+    // a zero deadline evaluates the condition once, without waiting
+    let (guard, timed_out) = cond.wait_while_until(mutex.lock().unwrap(), |_| true, Deadline::lazy_after(Duration::ZERO));
+    assert!(timed_out);
+
+    let start = Instant::now();
+    let (guard, timed_out) = cond.wait_while_for(guard, |_| true, SHORT_WAIT);
+    assert!(timed_out);
+    assert!(start.elapsed() >= SHORT_WAIT);
+
+    // the condition does not hold, so no waiting occurs
+    let (_guard, timed_out) = cond.wait_while_for(guard, |val| *val > 0, Duration::MAX);
+    assert!(!timed_out);
+}
+
+#[test]
+fn wait_survives_spurious_wakeups() {
+    let pair = Arc::new((Mutex::new(0), TimedCondvar::new()));
+    let t_2 = {
+        let pair = pair.clone();
+        thread::spawn(move || {
+            let guard = pair.0.lock().unwrap();
+            let (guard, timed_out) = pair.1.wait_while_for(guard, |val| *val < 3, LONG_WAIT);
+            (*guard, timed_out)
+        })
+    };
+
+    // each notification is premature, save for the last
+    for _ in 0..3 {
+        thread::sleep(CHECK_WAIT);
+        *pair.0.lock().unwrap() += 1;
+        pair.1.notify_all();
+    }
+    assert_eq!((3, false), t_2.join().unwrap());
+}
+
+#[test]
+fn wait_recovers_from_poison() {
+    let mutex = Mutex::new(42);
+    let cond = TimedCondvar::default();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = mutex.lock().unwrap();
+        panic!("boom");
+    }));
+    assert!(result.is_err());
+    assert!(mutex.is_poisoned());
+
+    let (guard, timed_out) = cond.wait_while_for(mutex.lock().remedy(), |val| *val != 42, SHORT_WAIT);
+    assert!(!timed_out);
+    assert_eq!(42, *guard);
+}
@@ -0,0 +1,142 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use std::fmt;
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+
+/// A counting semaphore, maintaining a number of permits that threads acquire and release.
+///
+/// Permits are acquired in batches of `n`; an acquisition blocks until at least `n` permits
+/// are available, and takes them all at once. Acquired permits are returned to the semaphore
+/// when the [`SemaphorePermit`] is dropped.
+///
+/// Waiters are not served in arrival order: a released permit goes to whichever waiter is able
+/// to proceed first. Consequently, a waiter requesting many permits may be overtaken
+/// indefinitely by waiters requesting fewer.
+///
+/// # Examples
+/// ```
+/// use anode::semaphore::Semaphore;
+/// let semaphore = Semaphore::new(3);
+/// let permit = semaphore.acquire(2);
+/// assert_eq!(1, semaphore.available());
+/// drop(permit);
+/// assert_eq!(3, semaphore.available());
+/// ```
+#[derive(Default)]
+pub struct Semaphore {
+    permits: Mutex<usize>,
+    cond: TimedCondvar,
+}
+
+/// An RAII guard over permits acquired from a [`Semaphore`], releasing them when dropped.
+#[must_use = "if unused, the permits will be immediately released"]
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+    permits: usize,
+}
+
+impl Semaphore {
+    #[inline]
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            permits: Mutex::new(permits),
+            cond: TimedCondvar::new(),
+        }
+    }
+
+    /// The number of permits currently available for acquisition.
+    #[inline]
+    pub fn available(&self) -> usize {
+        *self.permits.lock().remedy()
+    }
+
+    /// Acquires `n` permits, blocking until they are available.
+    #[inline]
+    pub fn acquire(&self, n: usize) -> SemaphorePermit<'_> {
+        self.try_acquire_until(n, Deadline::Forever).unwrap()
+    }
+
+    /// Attempts to acquire `n` permits within the given `duration`. A zero `duration` makes
+    /// a single, non-blocking attempt.
+    #[inline]
+    pub fn try_acquire(&self, n: usize, duration: Duration) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire_until(n, Deadline::lazy_after(duration))
+    }
+
+    /// Attempts to acquire `n` permits before the given `deadline` elapses.
+    pub fn try_acquire_until(&self, n: usize, deadline: impl Into<Deadline>) -> Option<SemaphorePermit<'_>> {
+        let permits = self.permits.lock().remedy();
+        let (mut permits, timed_out) = self.cond.wait_while_until(permits, |permits| *permits < n, deadline);
+        if timed_out {
+            return None;
+        }
+        *permits -= n;
+        Some(SemaphorePermit {
+            semaphore: self,
+            permits: n,
+        })
+    }
+
+    /// Adds `n` permits to the semaphore, waking any waiters that may now proceed.
+    ///
+    /// Permits acquired through a [`SemaphorePermit`] are released automatically; this method
+    /// is for growing the semaphore, or for returning permits previously relinquished with
+    /// [`SemaphorePermit::forget`].
+    ///
+    /// # Panics
+    /// If the number of available permits would overflow a `usize`.
+    #[inline]
+    pub fn release(&self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let mut permits = self.permits.lock().remedy();
+        *permits = permits.checked_add(n).expect("permit count overflow");
+        drop(permits);
+
+        // waiters may be after different numbers of permits, so all are given a chance
+        self.cond.notify_all();
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore")
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+impl SemaphorePermit<'_> {
+    /// The number of permits held by this guard.
+    #[inline]
+    pub fn permits(&self) -> usize {
+        self.permits
+    }
+
+    /// Consumes the guard without releasing its permits, which are thereby removed from the
+    /// semaphore (unless later returned with [`Semaphore::release`]).
+    #[inline]
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.semaphore.release(self.permits);
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
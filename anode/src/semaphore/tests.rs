@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::semaphore::Semaphore;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::test_utils;
+
+#[test]
+fn acquire_and_release() {
+    let semaphore = Semaphore::new(5);
+    let permit_1 = semaphore.acquire(2);
+    assert_eq!(2, permit_1.permits());
+    let permit_2 = semaphore.try_acquire(3, Duration::ZERO).unwrap();
+    assert_eq!(0, semaphore.available());
+    assert!(semaphore.try_acquire(1, Duration::ZERO).is_none());
+
+    drop(permit_1);
+    assert_eq!(2, semaphore.available());
+    assert!(semaphore.try_acquire(3, Duration::ZERO).is_none());
+    drop(permit_2);
+    assert_eq!(5, semaphore.available());
+
+    // acquiring zero permits always succeeds
+    let _permit = semaphore.acquire(5);
+    assert_eq!(0, semaphore.try_acquire(0, Duration::ZERO).unwrap().permits());
+}
+
+#[test]
+fn forget_and_release() {
+    let semaphore = Semaphore::new(2);
+    semaphore.acquire(2).forget();
+    assert_eq!(0, semaphore.available());
+    semaphore.release(3);
+    assert_eq!(3, semaphore.available());
+}
+
+#[test]
+fn try_acquire_timeout() {
+    let semaphore = Semaphore::new(1);
+    let start = Instant::now();
+    assert!(semaphore.try_acquire(2, CHECK_WAIT).is_none());
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+    assert_eq!(1, semaphore.available());
+}
+
+#[test]
+fn try_acquire_await_release() {
+    let semaphore = Arc::new(Semaphore::new(3));
+    let permit = semaphore.acquire(2);
+
+    // t_2 needs more permits than are available, and must wait for main to release its own
+    let t_2 = {
+        let semaphore = semaphore.clone();
+        test_utils::spawn_blocked(move || {
+            semaphore.try_acquire(3, LONG_WAIT).unwrap().forget();
+        })
+    };
+    thread::sleep(CHECK_WAIT);
+    assert!(!t_2.is_finished());
+    drop(permit);
+    t_2.join().unwrap();
+    assert_eq!(0, semaphore.available());
+}
+
+#[test]
+#[should_panic(expected = "permit count overflow")]
+fn release_overflow() {
+    Semaphore::new(usize::MAX).release(1);
+}
+
+#[test]
+fn bounds_concurrency() {
+    const PERMITS: usize = 3;
+    const THREADS: usize = 8;
+    const ITERATIONS: usize = 100;
+    let semaphore = Arc::new(Semaphore::new(PERMITS));
+    let active = Arc::new(AtomicUsize::default());
+    let max_active = Arc::new(AtomicUsize::default());
+    let threads = (0..THREADS)
+        .map(|i| {
+            let semaphore = semaphore.clone();
+            let active = active.clone();
+            let max_active = max_active.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    let _permit = semaphore.acquire(1 + i % 2);
+                    let now_active = active.fetch_add(1 + i % 2, Ordering::Relaxed) + 1 + i % 2;
+                    max_active.fetch_max(now_active, Ordering::Relaxed);
+                    thread::yield_now();
+                    active.fetch_sub(1 + i % 2, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert!(max_active.load(Ordering::Relaxed) <= PERMITS);
+    assert_eq!(PERMITS, semaphore.available());
+}
+
+#[test]
+fn debug() {
+    let semaphore = Semaphore::new(3);
+    let permit = semaphore.acquire(2);
+    assert_eq!("Semaphore { available: 1 }", format!("{semaphore:?}"));
+    assert_eq!("SemaphorePermit { permits: 2, .. }", format!("{permit:?}"));
+}
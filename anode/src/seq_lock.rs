@@ -0,0 +1,192 @@
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+use std::time::Duration;
+use crate::backoff::spin_until;
+use crate::snapshot::read_validated;
+
+unsafe impl<T: Copy + Send> Send for SeqLock<T> {}
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+unsafe impl<T: Copy + Sync> Sync for SeqWriteGuard<'_, T> {}
+
+/// A sequence lock, for small, read-mostly [`Copy`] data, such as counters and timestamps.
+///
+/// Readers never write to shared memory: a read copies the data out optimistically, and
+/// retries should a writer have intervened, as indicated by a change in the sequence number.
+/// Readers thus never block writers, nor contend with each other; however, a steady stream of
+/// writes may delay a reader indefinitely. Writers are mutually exclusive.
+///
+/// # Examples
+/// ```
+/// use anode::seq_lock::SeqLock;
+/// let lock = SeqLock::new((0u64, 0u64));
+/// {
+///     let mut guard = lock.write();
+///     guard.0 += 1;
+///     guard.1 += 2;
+/// }
+/// assert_eq!((1, 2), lock.read());
+/// ```
+pub struct SeqLock<T: Copy> {
+    /// Even while the lock is free; odd while a writer holds it.
+    seq: AtomicUsize,
+    data: UnsafeCell<T>,
+}
+
+/// An RAII guard over the data of a [`SeqLock`], obtained from [`SeqLock::write`]. Readers
+/// are made to retry until the guard is dropped.
+#[must_use = "if unused, the SeqLock will immediately unlock"]
+pub struct SeqWriteGuard<'a, T: Copy> {
+    lock: &'a SeqLock<T>,
+    seq: usize,
+}
+
+impl<T: Copy> SeqLock<T> {
+    #[inline]
+    pub const fn new(t: T) -> Self {
+        Self {
+            seq: AtomicUsize::new(0),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+
+    /// Returns a consistent snapshot of the data, retrying (with backoff) for as long as
+    /// writers interfere.
+    #[inline]
+    pub fn read(&self) -> T {
+        let mut val = None;
+        spin_until(Duration::MAX, || {
+            val = self.try_read();
+            val.is_some()
+        });
+        val.unwrap()
+    }
+
+    /// Makes a single attempt at reading a consistent snapshot of the data, returning `None`
+    /// if a writer interfered.
+    #[inline]
+    pub fn try_read(&self) -> Option<T> {
+        let seq = self.seq.load(Ordering::Acquire);
+        if seq & 1 != 0 {
+            return None;
+        }
+
+        // the copy is discarded if a writer intervened
+        unsafe {
+            read_validated(self.data.get(), || {
+                fence(Ordering::Acquire);
+                self.seq.load(Ordering::Relaxed) == seq
+            })
+        }
+    }
+
+    /// The current sequence number, which is incremented twice by every write. Intended for
+    /// diagnostics; an odd number indicates that a write is in progress.
+    #[inline]
+    pub fn seq(&self) -> usize {
+        self.seq.load(Ordering::Relaxed)
+    }
+
+    /// Acquires exclusive write access, spinning (with backoff) while another writer holds
+    /// the lock.
+    #[inline]
+    pub fn write(&self) -> SeqWriteGuard<'_, T> {
+        let mut guard = None;
+        spin_until(Duration::MAX, || {
+            guard = self.try_write();
+            guard.is_some()
+        });
+        guard.unwrap()
+    }
+
+    /// Attempts to acquire exclusive write access without waiting.
+    #[inline]
+    pub fn try_write(&self) -> Option<SeqWriteGuard<'_, T>> {
+        let seq = self.seq.load(Ordering::Relaxed);
+        if seq & 1 != 0 {
+            return None;
+        }
+
+        match self.seq.compare_exchange(seq, seq.wrapping_add(1), Ordering::Acquire, Ordering::Relaxed) {
+            Err(_) => None,
+            Ok(_) => {
+                // the odd sequence number must be visible before any of the writes to the data
+                fence(Ordering::Release);
+                Some(SeqWriteGuard { lock: self, seq })
+            }
+        }
+    }
+
+    /// Replaces the data with `t`.
+    #[inline]
+    pub fn set(&self, t: T) {
+        *self.write() = t;
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the lock mutably, no actual locking needs to take place.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Copy + Default> Default for SeqLock<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for SeqLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("SeqLock");
+        match self.try_read() {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(val) => {
+                d.field("data", &val);
+            }
+        }
+        d.finish()
+    }
+}
+
+impl<T: Copy> Drop for SeqWriteGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.seq.store(self.seq.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl<T: Copy> Deref for SeqWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: Copy> DerefMut for SeqWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests;
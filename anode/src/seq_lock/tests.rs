@@ -0,0 +1,88 @@
+use crate::seq_lock::SeqLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn read_and_write() {
+    let mut lock = SeqLock::new(42);
+    assert_eq!(42, lock.read());
+    assert_eq!(0, lock.seq());
+
+    *lock.write() += 1;
+    assert_eq!(43, lock.read());
+    assert_eq!(2, lock.seq());
+
+    lock.set(69);
+    assert_eq!(Some(69), lock.try_read());
+    *lock.get_mut() = 7;
+    assert_eq!(7, lock.into_inner());
+}
+
+#[test]
+fn writer_excludes() {
+    let lock = SeqLock::new(0);
+    let mut guard = lock.write();
+    *guard = 42;
+    assert_eq!(1, lock.seq());
+    assert!(lock.try_read().is_none());
+    assert!(lock.try_write().is_none());
+    drop(guard);
+
+    assert_eq!(Some(42), lock.try_read());
+    assert!(lock.try_write().is_some());
+}
+
+#[test]
+fn implements_debug() {
+    let lock = SeqLock::new(42);
+    assert_eq!("SeqLock { data: 42 }", format!("{:?}", lock));
+    let _guard = lock.write();
+    assert_eq!("SeqLock { data: <locked> }", format!("{:?}", lock));
+}
+
+// optimistic reads race with writers by design, discarding torn copies; Miri reports the race
+#[test]
+#[cfg_attr(miri, ignore)]
+fn consistent_snapshots() {
+    const WRITERS: usize = 2;
+    const WRITES: u64 = 10_000;
+    let lock = Arc::new(SeqLock::new([0u64; 4]));
+    let running = Arc::new(AtomicBool::new(true));
+
+    // writers keep all elements equal; a torn read would observe a mix of old and new values
+    let writers = (0..WRITERS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..WRITES {
+                    let mut guard = lock.write();
+                    for val in guard.iter_mut() {
+                        *val += 1;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let readers = (0..2)
+        .map(|_| {
+            let lock = lock.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    let snapshot = lock.read();
+                    assert!(snapshot.iter().all(|&val| val == snapshot[0]), "{snapshot:?}");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    running.store(false, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!([WRITERS as u64 * WRITES; 4], lock.read());
+}
@@ -0,0 +1,340 @@
+//! Locks that may be placed in memory shared between processes (e.g., a memory-mapped file or
+//! a POSIX shared memory object).
+//!
+//! The locks are `#[repr(C)]` structs comprising a single [`AtomicU32`], with no pointers,
+//! thread identifiers or OS handles, so that every process mapping the region observes the
+//! same lock at whatever address the region is mapped. (Lock-free 32-bit atomics are required,
+//! which is the case on all mainstream targets.) Waiting is by spinning with backoff, as there
+//! is no portable way to block on memory shared between processes.
+//!
+//! A lock is placed in a region with [`init`](ShmSpinLock::init), by the process that creates
+//! the region, and attached to with [`from_ptr`](ShmSpinLock::from_ptr) by the others. The locks
+//! guard no data of their own; the data they protect resides in the region alongside them.
+//!
+//! A process that dies while holding a [`ShmSpinLock`] or a [`ShmRwLock`] leaves it locked.
+//! [`RobustLock`] records its holder, so that the lock may be recovered from a dead one.
+//!
+//! # Examples
+//! ```
+//! use anode::shm::ShmSpinLock;
+//! // stands in for a memory-mapped region
+//! let mut region = [0u32; 2];
+//! let lock = unsafe { ShmSpinLock::init(region.as_mut_ptr().cast()) };
+//! let guard = lock.lock();
+//! assert!(lock.try_lock().is_none());
+//! drop(guard);
+//!
+//! let attached = unsafe { ShmSpinLock::from_ptr(region.as_mut_ptr().cast()) };
+//! assert!(attached.try_lock().is_some());
+//! ```
+
+use core::fmt;
+use core::mem;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+use crate::backoff::spin_until;
+
+mod robust;
+
+pub use robust::{RobustGuard, RobustLock};
+
+/// Checks that `ptr` is suitably aligned for a lock of type `L`.
+#[inline]
+fn check_alignment<L>(ptr: *mut u8) {
+    assert!(!ptr.is_null(), "null region");
+    assert_eq!(0, ptr.align_offset(mem::align_of::<L>()), "misaligned region");
+}
+
+/// A process-shared spin lock.
+#[repr(C)]
+pub struct ShmSpinLock {
+    state: AtomicU32,
+}
+
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+
+impl ShmSpinLock {
+    /// The number of bytes occupied by the lock within a region.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
+    /// Creates an unlocked lock, for placing into a region by other means than
+    /// [`init`](Self::init).
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(UNLOCKED),
+        }
+    }
+
+    /// Initializes an unlocked lock at `ptr`, returning a reference to it.
+    ///
+    /// # Safety
+    /// `ptr` must point to [`SIZE`](Self::SIZE) writable bytes that remain mapped for `'a`, and
+    /// that no process is concurrently using as a lock. The bytes may subsequently only be
+    /// accessed through references to the lock.
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn init<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        let lock = ptr.cast::<Self>();
+        lock.write(Self::new());
+        &*lock
+    }
+
+    /// Attaches to a lock previously initialized at `ptr` (possibly by another process).
+    ///
+    /// # Safety
+    /// `ptr` must point to a lock initialized with [`init`](Self::init) (or an all-zero region
+    /// of [`SIZE`](Self::SIZE) bytes, which is equivalent), that remains mapped for `'a`.
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn from_ptr<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        &*ptr.cast::<Self>()
+    }
+
+    #[inline]
+    pub fn lock(&self) -> ShmSpinGuard<'_> {
+        self.try_lock_for(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire the lock without waiting.
+    #[inline]
+    pub fn try_lock(&self) -> Option<ShmSpinGuard<'_>> {
+        self.try_lock_for(Duration::ZERO)
+    }
+
+    /// Attempts to acquire the lock within the given `duration`. Without the `std` feature,
+    /// a finite duration is approximated by a budget of attempts (nominally one per 100 ns).
+    #[inline]
+    pub fn try_lock_for(&self, duration: Duration) -> Option<ShmSpinGuard<'_>> {
+        let acquired = spin_until(duration, || {
+            self.state.load(Ordering::Relaxed) == UNLOCKED
+                && self.state.compare_exchange_weak(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok()
+        });
+        acquired.then(|| ShmSpinGuard { lock: self })
+    }
+
+    /// Determines whether the lock is held by some thread (in any process). The result is
+    /// a racy snapshot.
+    #[inline]
+    pub fn is_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) != UNLOCKED
+    }
+
+    /// Releases the lock on behalf of a guard that was forgotten (e.g., by a process that
+    /// handed the lock over to another process).
+    ///
+    /// # Safety
+    /// The lock must be held, and not by a live guard.
+    #[inline]
+    pub unsafe fn force_unlock(&self) {
+        self.state.store(UNLOCKED, Ordering::Release);
+    }
+}
+
+impl Default for ShmSpinLock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ShmSpinLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmSpinLock")
+            .field("locked", &self.is_locked())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct ShmSpinGuard<'a> {
+    lock: &'a ShmSpinLock,
+}
+
+impl Drop for ShmSpinGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.lock.force_unlock() }
+    }
+}
+
+/// A process-shared reader-writer lock.
+///
+/// Readers are admitted whenever there is no writer; i.e., the lock is read-biased and a writer
+/// may be starved by a continuous stream of overlapping readers. Up to 2<sup>31</sup> − 1
+/// readers may hold the lock at once.
+#[repr(C)]
+pub struct ShmRwLock {
+    state: AtomicU32,
+}
+
+const WRITER: u32 = 1 << 31;
+
+const READERS: u32 = !WRITER;
+
+impl ShmRwLock {
+    /// The number of bytes occupied by the lock within a region.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
+    /// Creates an unlocked lock, for placing into a region by other means than
+    /// [`init`](Self::init).
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU32::new(0),
+        }
+    }
+
+    /// Initializes an unlocked lock at `ptr`, returning a reference to it.
+    ///
+    /// # Safety
+    /// As per [`ShmSpinLock::init`].
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn init<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        let lock = ptr.cast::<Self>();
+        lock.write(Self::new());
+        &*lock
+    }
+
+    /// Attaches to a lock previously initialized at `ptr` (possibly by another process).
+    ///
+    /// # Safety
+    /// As per [`ShmSpinLock::from_ptr`].
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn from_ptr<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        &*ptr.cast::<Self>()
+    }
+
+    #[inline]
+    pub fn read(&self) -> ShmReadGuard<'_> {
+        self.try_read(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire a read lock within the given `duration`. Without the `std` feature,
+    /// a finite duration is approximated by a budget of attempts (nominally one per 100 ns).
+    #[inline]
+    pub fn try_read(&self, duration: Duration) -> Option<ShmReadGuard<'_>> {
+        let acquired = spin_until(duration, || {
+            self.state.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & WRITER == 0 {
+                    assert_ne!(READERS, state, "too many readers");
+                    Some(state + 1)
+                } else {
+                    None
+                }
+            }).is_ok()
+        });
+        acquired.then(|| ShmReadGuard { lock: self })
+    }
+
+    #[inline]
+    pub fn write(&self) -> ShmWriteGuard<'_> {
+        self.try_write(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire a write lock within the given `duration`. Without the `std` feature,
+    /// a finite duration is approximated by a budget of attempts (nominally one per 100 ns).
+    #[inline]
+    pub fn try_write(&self, duration: Duration) -> Option<ShmWriteGuard<'_>> {
+        let acquired = spin_until(duration, || {
+            self.state.load(Ordering::Relaxed) == 0
+                && self.state.compare_exchange_weak(0, WRITER, Ordering::Acquire, Ordering::Relaxed).is_ok()
+        });
+        acquired.then(|| ShmWriteGuard { lock: self })
+    }
+
+    /// The number of readers presently holding the lock. The result is a racy snapshot.
+    #[inline]
+    pub fn readers(&self) -> u32 {
+        self.state.load(Ordering::Relaxed) & READERS
+    }
+
+    /// Determines whether the lock is write-locked. The result is a racy snapshot.
+    #[inline]
+    pub fn is_write_locked(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & WRITER != 0
+    }
+
+    #[inline]
+    fn read_unlock(&self) {
+        let _prev = self.state.fetch_sub(1, Ordering::Release);
+        debug_assert!(_prev & READERS > 0, "readers: {}", _prev & READERS);
+    }
+
+    #[inline]
+    fn write_unlock(&self) {
+        let _prev = self.state.swap(0, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev);
+    }
+}
+
+impl Default for ShmRwLock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for ShmRwLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmRwLock")
+            .field("readers", &self.readers())
+            .field("writer", &self.is_write_locked())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct ShmReadGuard<'a> {
+    lock: &'a ShmRwLock,
+}
+
+impl Drop for ShmReadGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.read_unlock();
+    }
+}
+
+#[derive(Debug)]
+pub struct ShmWriteGuard<'a> {
+    lock: &'a ShmRwLock,
+}
+
+impl<'a> ShmWriteGuard<'a> {
+    /// Atomically downgrades the write lock to a read lock, without admitting another writer
+    /// in the interim.
+    #[inline]
+    pub fn downgrade(self) -> ShmReadGuard<'a> {
+        let lock = self.lock;
+        mem::forget(self);
+        let _prev = lock.state.swap(1, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev);
+        ShmReadGuard { lock }
+    }
+}
+
+impl Drop for ShmWriteGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.write_unlock();
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests;
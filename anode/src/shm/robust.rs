@@ -0,0 +1,209 @@
+use core::fmt;
+use core::mem;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+use crate::backoff::spin_until;
+use crate::shm::check_alignment;
+
+/// A process-shared lock that records the identity of its holder, so that the lock may be
+/// recovered should the holder die (or be cancelled) without unlocking it.
+///
+/// Holders identify themselves with a nonzero `u32`, such as a process ID (from
+/// [`std::process::id`]) or an application-assigned thread ID. The lock cannot tell whether
+/// a holder is alive; rather, [`try_recover`](Self::try_recover) consults a caller-supplied
+/// liveness check. (Process IDs may be reused by the OS, so a liveness check based solely on
+/// the existence of a process can be fooled by a newer process with the same ID.)
+///
+/// A lock recovered from a dead holder is flagged as _inconsistent_, as the holder may have
+/// left the protected data in an intermediate state. The flag persists across acquisitions,
+/// being reported by every guard, until one of them calls
+/// [`mark_consistent`](RobustGuard::mark_consistent) once the data has been repaired.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use anode::shm::RobustLock;
+/// let lock = RobustLock::new();
+/// // holder 1 acquires the lock and dies without releasing it
+/// core::mem::forget(lock.lock(1));
+/// assert!(lock.try_lock(2, Duration::ZERO).is_none());
+///
+/// let mut guard = lock.try_recover(2, |holder| holder != 1).unwrap();
+/// assert!(guard.is_inconsistent());
+/// // ...repair the protected data...
+/// guard.mark_consistent();
+/// drop(guard);
+/// assert!(!lock.lock(3).is_inconsistent());
+/// ```
+#[repr(C)]
+pub struct RobustLock {
+    /// The ID of the holder, or zero if unlocked.
+    holder: AtomicU32,
+
+    /// Nonzero if the lock was recovered from a dead holder and has not since been marked as
+    /// consistent. Only accessed while holding the lock.
+    inconsistent: AtomicU32,
+}
+
+const UNLOCKED: u32 = 0;
+
+impl RobustLock {
+    /// The number of bytes occupied by the lock within a region.
+    pub const SIZE: usize = mem::size_of::<Self>();
+
+    /// Creates an unlocked, consistent lock, for placing into a region by other means than
+    /// [`init`](Self::init).
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            holder: AtomicU32::new(UNLOCKED),
+            inconsistent: AtomicU32::new(0),
+        }
+    }
+
+    /// Initializes an unlocked, consistent lock at `ptr`, returning a reference to it.
+    ///
+    /// # Safety
+    /// As per [`ShmSpinLock::init`](crate::shm::ShmSpinLock::init).
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn init<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        let lock = ptr.cast::<Self>();
+        lock.write(Self::new());
+        &*lock
+    }
+
+    /// Attaches to a lock previously initialized at `ptr` (possibly by another process).
+    ///
+    /// # Safety
+    /// As per [`ShmSpinLock::from_ptr`](crate::shm::ShmSpinLock::from_ptr).
+    ///
+    /// # Panics
+    /// If `ptr` is null or is not aligned to 4 bytes.
+    #[inline]
+    pub unsafe fn from_ptr<'a>(ptr: *mut u8) -> &'a Self {
+        check_alignment::<Self>(ptr);
+        &*ptr.cast::<Self>()
+    }
+
+    /// Acquires the lock on behalf of `holder`, waiting indefinitely. A lock whose holder has
+    /// died is never released; a caller that suspects as much should use
+    /// [`try_lock`](Self::try_lock) with a timeout, followed by
+    /// [`try_recover`](Self::try_recover).
+    ///
+    /// # Panics
+    /// If `holder` is zero.
+    #[inline]
+    pub fn lock(&self, holder: u32) -> RobustGuard<'_> {
+        self.try_lock(holder, Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire the lock on behalf of `holder` within the given `duration`. Without
+    /// the `std` feature, a finite duration is approximated by a budget of attempts (nominally
+    /// one per 100 ns).
+    ///
+    /// # Panics
+    /// If `holder` is zero.
+    #[inline]
+    pub fn try_lock(&self, holder: u32, duration: Duration) -> Option<RobustGuard<'_>> {
+        assert_ne!(UNLOCKED, holder, "zero holder");
+        let acquired = spin_until(duration, || {
+            self.holder.load(Ordering::Relaxed) == UNLOCKED
+                && self.holder.compare_exchange_weak(UNLOCKED, holder, Ordering::Acquire, Ordering::Relaxed).is_ok()
+        });
+        acquired.then(|| RobustGuard { lock: self })
+    }
+
+    /// Attempts to acquire the lock on behalf of `holder` without waiting, taking it over from
+    /// the current holder if `is_alive` reports the latter as dead. The guard of a lock so
+    /// recovered is [inconsistent](RobustGuard::is_inconsistent). An unlocked lock is acquired
+    /// as per [`try_lock`](Self::try_lock), without consulting `is_alive`.
+    ///
+    /// Of several threads concurrently recovering from the same dead holder, one succeeds.
+    ///
+    /// # Panics
+    /// If `holder` is zero.
+    pub fn try_recover(&self, holder: u32, is_alive: impl FnOnce(u32) -> bool) -> Option<RobustGuard<'_>> {
+        assert_ne!(UNLOCKED, holder, "zero holder");
+        match self.holder.compare_exchange(UNLOCKED, holder, Ordering::Acquire, Ordering::Relaxed) {
+            Ok(_) => Some(RobustGuard { lock: self }),
+            Err(dead) if !is_alive(dead) => {
+                self.holder.compare_exchange(dead, holder, Ordering::Acquire, Ordering::Relaxed).ok()?;
+                self.inconsistent.store(1, Ordering::Relaxed);
+                Some(RobustGuard { lock: self })
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// The ID of the current holder, if the lock is held. The result is a racy snapshot.
+    #[inline]
+    pub fn holder(&self) -> Option<u32> {
+        match self.holder.load(Ordering::Relaxed) {
+            UNLOCKED => None,
+            holder => Some(holder),
+        }
+    }
+}
+
+impl Default for RobustLock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for RobustLock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RobustLock")
+            .field("holder", &self.holder())
+            .finish()
+    }
+}
+
+pub struct RobustGuard<'a> {
+    lock: &'a RobustLock,
+}
+
+impl RobustGuard<'_> {
+    /// Determines whether the lock was recovered from a dead holder, and has not since been
+    /// marked as consistent.
+    #[inline]
+    pub fn is_inconsistent(&self) -> bool {
+        self.lock.inconsistent.load(Ordering::Relaxed) != 0
+    }
+
+    /// Clears the inconsistent flag, once the protected data has been repaired.
+    #[inline]
+    pub fn mark_consistent(&mut self) {
+        self.lock.inconsistent.store(0, Ordering::Relaxed);
+    }
+
+    /// The ID of the holder.
+    #[inline]
+    pub fn holder(&self) -> u32 {
+        self.lock.holder.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for RobustGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RobustGuard")
+            .field("holder", &self.holder())
+            .field("inconsistent", &self.is_inconsistent())
+            .finish()
+    }
+}
+
+impl Drop for RobustGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.holder.store(UNLOCKED, Ordering::Release);
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests;
@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+use crate::shm::RobustLock;
+use crate::test_utils::SHORT_WAIT;
+
+#[test]
+fn cycle() {
+    let lock = RobustLock::new();
+    assert_eq!(None, lock.holder());
+    let guard = lock.lock(1);
+    assert_eq!(1, guard.holder());
+    assert_eq!(Some(1), lock.holder());
+    assert!(!guard.is_inconsistent());
+    assert!(lock.try_lock(2, Duration::ZERO).is_none());
+    assert!(lock.try_lock(2, SHORT_WAIT).is_none());
+    drop(guard);
+    assert_eq!(None, lock.holder());
+    assert_eq!(2, lock.try_lock(2, Duration::ZERO).unwrap().holder());
+}
+
+#[test]
+#[should_panic(expected = "zero holder")]
+fn zero_holder() {
+    RobustLock::new().lock(0);
+}
+
+#[test]
+fn recover_from_live_holder() {
+    let lock = RobustLock::new();
+    let _guard = lock.lock(1);
+    let mut checked = None;
+    assert!(lock.try_recover(2, |holder| {
+        checked = Some(holder);
+        true
+    }).is_none());
+    assert_eq!(Some(1), checked);
+    assert_eq!(Some(1), lock.holder());
+}
+
+#[test]
+fn recover_unlocked() {
+    let lock = RobustLock::new();
+    let guard = lock.try_recover(1, |_| panic!("liveness checked")).unwrap();
+    assert!(!guard.is_inconsistent());
+}
+
+#[test]
+fn recover_from_dead_holder() {
+    let lock = RobustLock::new();
+    core::mem::forget(lock.lock(1));
+    let guard = lock.try_recover(2, |_| false).unwrap();
+    assert_eq!(2, guard.holder());
+    assert!(guard.is_inconsistent());
+    drop(guard);
+
+    // the flag persists until marked consistent
+    let mut guard = lock.lock(3);
+    assert!(guard.is_inconsistent());
+    guard.mark_consistent();
+    assert!(!guard.is_inconsistent());
+    drop(guard);
+    assert!(!lock.lock(3).is_inconsistent());
+}
+
+#[test]
+fn recover_from_dead_thread() {
+    let lock = RobustLock::new();
+    thread::scope(|scope| {
+        scope.spawn(|| core::mem::forget(lock.lock(1)));
+    });
+    let recovered = AtomicUsize::default();
+    thread::scope(|scope| {
+        for holder in 2..6 {
+            let (lock, recovered) = (&lock, &recovered);
+            scope.spawn(move || {
+                if let Some(guard) = lock.try_recover(holder, |holder| holder != 1) {
+                    if guard.is_inconsistent() {
+                        recovered.fetch_add(1, Ordering::Relaxed);
+                    }
+                    core::mem::forget(guard);
+                }
+            });
+        }
+    });
+    assert_eq!(1, recovered.load(Ordering::Relaxed));
+}
+
+#[test]
+fn shared_region() {
+    #[repr(C, align(8))]
+    struct Region([u32; 2]);
+
+    let mut region = Region([0; 2]);
+    let ptr = region.0.as_mut_ptr().cast();
+    assert_eq!(8, RobustLock::SIZE);
+    let lock = unsafe { RobustLock::init(ptr) };
+    core::mem::forget(lock.lock(std::process::id()));
+    let attached = unsafe { RobustLock::from_ptr(ptr) };
+    assert_eq!(Some(std::process::id()), attached.holder());
+    assert!(attached.try_recover(std::process::id() + 1, |_| false).unwrap().is_inconsistent());
+}
+
+#[test]
+fn debug() {
+    let lock = RobustLock::new();
+    let guard = lock.lock(7);
+    assert_eq!("RobustLock { holder: Some(7) }", format!("{:?}", lock));
+    assert_eq!("RobustGuard { holder: 7, inconsistent: false }", format!("{:?}", guard));
+}
@@ -0,0 +1,181 @@
+use std::thread;
+use std::time::Duration;
+use crate::shm::{ShmRwLock, ShmSpinLock};
+use crate::test_utils::SHORT_WAIT;
+
+/// A stand-in for a shared memory region, holding a lock in its first word and data after.
+#[repr(C, align(8))]
+struct Region([u32; 4]);
+
+impl Region {
+    fn new() -> Self {
+        Self([0; 4])
+    }
+
+    fn ptr(&mut self) -> *mut u8 {
+        self.0.as_mut_ptr().cast()
+    }
+}
+
+#[test]
+fn layout() {
+    assert_eq!(4, ShmSpinLock::SIZE);
+    assert_eq!(4, core::mem::align_of::<ShmSpinLock>());
+    assert_eq!(4, ShmRwLock::SIZE);
+    assert_eq!(4, core::mem::align_of::<ShmRwLock>());
+}
+
+#[test]
+#[should_panic(expected = "misaligned region")]
+fn misaligned() {
+    let mut region = Region::new();
+    unsafe { ShmSpinLock::from_ptr(region.ptr().add(1)) };
+}
+
+#[test]
+fn spin_lock_cycle() {
+    let mut region = Region::new();
+    let ptr = region.ptr();
+    let lock = unsafe { ShmSpinLock::init(ptr) };
+    let attached = unsafe { ShmSpinLock::from_ptr(ptr) };
+    assert!(!attached.is_locked());
+
+    let guard = lock.lock();
+    assert!(attached.is_locked());
+    assert!(attached.try_lock().is_none());
+    assert!(attached.try_lock_for(SHORT_WAIT).is_none());
+    drop(guard);
+    assert!(attached.try_lock().is_some());
+    assert!(!lock.is_locked());
+}
+
+#[test]
+fn spin_lock_init_resets() {
+    let mut region = Region::new();
+    let ptr = region.ptr();
+    core::mem::forget(unsafe { ShmSpinLock::init(ptr) }.lock());
+    assert!(unsafe { ShmSpinLock::from_ptr(ptr) }.is_locked());
+    assert!(!unsafe { ShmSpinLock::init(ptr) }.is_locked());
+}
+
+#[test]
+fn spin_lock_force_unlock() {
+    let mut region = Region::new();
+    let lock = unsafe { ShmSpinLock::init(region.ptr()) };
+    core::mem::forget(lock.lock());
+    assert!(lock.try_lock().is_none());
+    unsafe { lock.force_unlock() };
+    assert!(lock.try_lock().is_some());
+}
+
+#[test]
+fn spin_lock_contention() {
+    const THREADS: usize = 4;
+    const ITERATIONS: u32 = 1_000;
+    let mut region = Region::new();
+    let ptr = region.ptr() as usize;
+    unsafe { ShmSpinLock::init(ptr as *mut u8) };
+
+    // each thread attaches to the region independently, as would separate processes
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(move || {
+                let ptr = ptr as *mut u8;
+                let lock = unsafe { ShmSpinLock::from_ptr(ptr) };
+                let data = unsafe { ptr.add(ShmSpinLock::SIZE).cast::<u32>() };
+                for _ in 0..ITERATIONS {
+                    let _guard = lock.lock();
+                    unsafe { data.write(data.read() + 1) };
+                }
+            });
+        }
+    });
+    assert_eq!(THREADS as u32 * ITERATIONS, region.0[1]);
+}
+
+#[test]
+fn rwlock_cycle() {
+    let mut region = Region::new();
+    let ptr = region.ptr();
+    let lock = unsafe { ShmRwLock::init(ptr) };
+    let attached = unsafe { ShmRwLock::from_ptr(ptr) };
+
+    let reader_1 = lock.read();
+    let reader_2 = attached.try_read(Duration::ZERO).unwrap();
+    assert_eq!(2, lock.readers());
+    assert!(attached.try_write(Duration::ZERO).is_none());
+    drop(reader_1);
+    assert!(attached.try_write(SHORT_WAIT).is_none());
+    drop(reader_2);
+
+    let writer = attached.write();
+    assert!(lock.is_write_locked());
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(writer);
+    assert!(!lock.is_write_locked());
+    assert_eq!(0, lock.readers());
+}
+
+#[test]
+fn rwlock_downgrade() {
+    let mut region = Region::new();
+    let lock = unsafe { ShmRwLock::init(region.ptr()) };
+    let reader = lock.write().downgrade();
+    assert!(!lock.is_write_locked());
+    assert_eq!(1, lock.readers());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    let other = lock.try_read(Duration::ZERO).unwrap();
+    drop(reader);
+    drop(other);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn rwlock_debug() {
+    let lock = ShmRwLock::new();
+    let _guard = lock.read();
+    assert_eq!("ShmRwLock { readers: 1, writer: false }", format!("{:?}", lock));
+}
+
+#[test]
+fn rwlock_contention() {
+    const READERS: usize = 4;
+    const WRITERS: usize = 2;
+    const ITERATIONS: u32 = 1_000;
+    let mut region = Region::new();
+    let ptr = region.ptr() as usize;
+    unsafe { ShmRwLock::init(ptr as *mut u8) };
+
+    // a writer keeps the two data words equal; readers must never observe them apart
+    thread::scope(|scope| {
+        for _ in 0..WRITERS {
+            scope.spawn(move || {
+                let ptr = ptr as *mut u8;
+                let lock = unsafe { ShmRwLock::from_ptr(ptr) };
+                let data = unsafe { ptr.add(ShmRwLock::SIZE).cast::<u32>() };
+                for _ in 0..ITERATIONS {
+                    let _guard = lock.write();
+                    unsafe {
+                        data.write(data.read() + 1);
+                        data.add(1).write(data.add(1).read() + 1);
+                    }
+                }
+            });
+        }
+        for _ in 0..READERS {
+            scope.spawn(move || {
+                let ptr = ptr as *mut u8;
+                let lock = unsafe { ShmRwLock::from_ptr(ptr) };
+                let data = unsafe { ptr.add(ShmRwLock::SIZE).cast::<u32>() };
+                for _ in 0..ITERATIONS {
+                    let _guard = lock.read();
+                    unsafe { assert_eq!(data.read(), data.add(1).read()) };
+                }
+            });
+        }
+    });
+    assert_eq!(WRITERS as u32 * ITERATIONS, region.0[1]);
+    assert_eq!(WRITERS as u32 * ITERATIONS, region.0[2]);
+}
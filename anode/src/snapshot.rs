@@ -0,0 +1,30 @@
+//! Copying of data that may be concurrently modified, for optimistic readers that validate
+//! their copy after the fact (e.g., against a sequence number).
+
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// Copies the value at `src`, returning it if `validate` subsequently confirms that no writer
+/// intervened, or `None` otherwise.
+///
+/// A copy that races with a writer may be torn, and a torn value need not be a valid `T`
+/// (consider a `bool`, an enum or a reference). The value is therefore copied as a
+/// [`MaybeUninit`], and only assumed to be initialised once validated. The copy is volatile,
+/// so that the compiler does not assume the data to be stable.
+///
+/// # Safety
+/// `src` must be valid for reads and properly aligned, and `validate` must return `true` only
+/// if no write to `*src` overlapped the copy. `validate` is responsible for ordering the copy
+/// before its own checks (typically with an acquire fence).
+#[inline(always)]
+pub(crate) unsafe fn read_validated<T: Copy>(src: *const T, validate: impl FnOnce() -> bool) -> Option<T> {
+    let val = ptr::read_volatile(src.cast::<MaybeUninit<T>>());
+    if validate() {
+        Some(val.assume_init())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests;
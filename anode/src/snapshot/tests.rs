@@ -0,0 +1,21 @@
+use crate::snapshot::read_validated;
+use std::num::NonZeroU64;
+
+#[test]
+fn validated_copy() {
+    let data = (true, NonZeroU64::new(42).unwrap());
+    assert_eq!(Some(data), unsafe { read_validated(&data, || true) });
+}
+
+#[test]
+fn invalidated_copy() {
+    let data = 'x';
+    let mut validated = false;
+    assert_eq!(None, unsafe {
+        read_validated(&data, || {
+            validated = true;
+            false
+        })
+    });
+    assert!(validated);
+}
@@ -1,36 +1,132 @@
-use std::cell::UnsafeCell;
-use std::{fmt, hint};
-use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicBool, Ordering};
-use crate::backoff::ExpBackoff;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::Ordering;
+#[cfg(feature = "std")]
+use std::sync::{LockResult, PoisonError};
+#[cfg(feature = "std")]
+use std::thread;
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicBool;
+#[cfg(loom)]
+use loom::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use core::time::Duration;
+use crate::backoff::Backoff;
+#[cfg(feature = "std")]
+use crate::backoff::{spin_hint, ExpBackoff, ExpBackoffAction};
+#[cfg(feature = "std")]
+use crate::deadline::Deadline;
+use crate::deadlock;
+use crate::instrument::{Instrumentation, Mode};
+#[cfg(feature = "std")]
 use crate::inf_iterator::{InfIterator, IntoInfIterator};
-use crate::rand::FIXED_DURATION;
+#[cfg(feature = "std")]
+use crate::rand::{RandRange, FIXED_DURATION};
 
 unsafe impl<T: ?Sized + Send> Send for SpinMutex<T> {}
 unsafe impl<T: ?Sized + Send> Sync for SpinMutex<T> {}
 unsafe impl<T: ?Sized + Sync> Sync for SpinGuard<'_, T> {}
 
+/// A spinning mutual exclusion lock.
+///
+/// Dynamically-sized data (such as slices) is supported through unsized coercion, which
+/// applies to any pointer type holding a sized [`SpinMutex`].
+///
+/// # Examples
+/// ```
+/// use anode::spin_mutex::SpinMutex;
+/// let lock: Box<SpinMutex<[u8]>> = Box::new(SpinMutex::new([0u8; 4]));
+/// lock.lock()[1] = 42;
+/// assert_eq!(&[0, 42, 0, 0], &*lock.lock());
+/// ```
+///
+/// # Poisoning
+/// A [`SpinMutex`] acquired through [`lock`](Self::lock) never poisons. Where the detection
+/// of a panic under the lock is desired, use [`lock_checked`](Self::lock_checked) instead.
+///
+/// # `no_std`
+/// The lock is available without the (default) `std` feature, less the operations that
+/// depend on the standard library: timed acquisition and poisoning. Backoff yields and sleeps
+/// degrade to spinning.
 pub struct SpinMutex<T: ?Sized> {
     locked: AtomicBool,
+    poisoned: AtomicBool,
+    /// Whether acquisitions are registered with the [deadlock detector](crate::deadlock).
+    tracked: bool,
+    instrument: Instrumentation,
     data: UnsafeCell<T>,
 }
 
 pub struct SpinGuard<'a, T: ?Sized> {
     lock: &'a SpinMutex<T>,
+    /// Whether the guard still holds the lock. Cleared for the duration of [`SpinGuard::unlocked`].
+    locked: bool,
+    /// Whether the lock is poisoned should the guard be dropped during a panic.
+    #[cfg(feature = "std")]
+    checked: bool,
     /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
     __no_send: PhantomData<*const ()>,
 }
 
 impl<T> SpinMutex<T> {
+    /// Creates an unlocked mutex. Being a `const fn`, it may initialize a `static`.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::spin_mutex::SpinMutex;
+    /// static COUNTER: SpinMutex<u64> = SpinMutex::new(0);
+    /// *COUNTER.lock() += 1;
+    /// assert_eq!(1, *COUNTER.lock());
+    /// ```
+    #[cfg(not(loom))]
+    #[inline]
+    pub const fn new(t: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            poisoned: AtomicBool::new(false),
+            tracked: true,
+            instrument: Instrumentation::NONE,
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Loom's atomics cannot be constructed in a `const` context.
+    #[cfg(loom)]
     #[inline]
     pub fn new(t: T) -> Self {
         Self {
             locked: AtomicBool::new(false),
+            poisoned: AtomicBool::new(false),
+            tracked: true,
+            instrument: Instrumentation::NONE,
             data: UnsafeCell::new(t),
         }
     }
 
+    /// Creates a lock whose events are reported to `sink` under the given `name`. See the
+    /// [`instrument`](crate::instrument) module.
+    #[cfg(feature = "instrument")]
+    #[inline]
+    pub fn instrumented(t: T, name: impl Into<String>, sink: alloc::sync::Arc<dyn crate::instrument::EventSink>) -> Self {
+        Self {
+            instrument: Instrumentation::new(name, sink),
+            ..Self::new(t)
+        }
+    }
+
+    /// Creates a lock that is invisible to the [deadlock detector](crate::deadlock). Used
+    /// internally by other locks, whose own acquisitions are registered instead.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn untracked(t: T) -> Self {
+        Self {
+            tracked: false,
+            ..Self::new(t)
+        }
+    }
+
     pub fn into_inner(self) -> T {
         self.data.into_inner()
     }
@@ -38,7 +134,43 @@ impl<T> SpinMutex<T> {
 
 impl<'a, T: ?Sized> Drop for SpinGuard<'a, T> {
     fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if self.checked && thread::panicking() {
+            self.lock.poisoned.store(true, Ordering::Relaxed);
+        }
+        if self.locked {
+            self.lock.unlock();
+        }
+    }
+}
+
+impl<'a, T: ?Sized> SpinGuard<'a, T> {
+    /// Temporarily releases the lock while `f` runs, reacquiring it before returning the
+    /// result of `f`.
+    ///
+    /// Useful for performing a long-running or blocking operation (such as a system call)
+    /// without holding onto the spinlock. The protected data may be altered by other threads
+    /// while `f` runs. Should `f` panic, the lock is not reacquired and the guard releases
+    /// nothing when dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::spin_mutex::SpinMutex;
+    /// let lock = SpinMutex::new(0);
+    /// let mut guard = lock.lock();
+    /// guard.unlocked(|| {
+    ///     *lock.try_lock().unwrap() = 42;
+    /// });
+    /// assert_eq!(42, *guard);
+    /// ```
+    pub fn unlocked<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        self.locked = false;
         self.lock.unlock();
+        let result = f();
+        let guard = self.lock.lock();
+        core::mem::forget(guard);
+        self.locked = true;
+        result
     }
 }
 
@@ -59,41 +191,198 @@ impl<'a, T: ?Sized> DerefMut for SpinGuard<'a, T> {
 }
 
 impl<T: ?Sized> SpinMutex<T> {
+    /// Acquires the lock, snoozing with a [`Backoff`] while it is held by another thread.
+    #[inline]
+    pub fn lock(&self) -> SpinGuard<'_, T> {
+        self.lock_with(Backoff::new())
+    }
+
+    /// Acquires the lock, snoozing with the given `backoff` while it is held by another thread.
+    ///
+    /// The lock is only attempted once a relaxed load has observed it released, so that
+    /// waiters spin on their own cached copy of the lock flag rather than continually
+    /// invalidating it with failed compare-and-swaps. A backoff configured with
+    /// [`ExpBackoff::spinny`](crate::backoff::ExpBackoff::spinny) never yields nor sleeps,
+    /// suiting locks that are held for no more than a handful of instructions.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::backoff::{Backoff, ExpBackoff};
+    /// use anode::spin_mutex::SpinMutex;
+    /// let lock = SpinMutex::new(0);
+    /// *lock.lock_with(Backoff::with_config(ExpBackoff::spinny())) = 42;
+    /// assert_eq!(42, *lock.lock());
+    /// ```
     #[inline]
-    pub fn lock(&self) -> SpinGuard<T> {
+    pub fn lock_with(&self, mut backoff: Backoff) -> SpinGuard<'_, T> {
+        let attempt = self.instrument.begin(Mode::Write);
         // a [TTAS](https://en.wikipedia.org/wiki/Test_and_test-and-set) implementation that does not result in
         // continuous cache line invalidation
+        let mut contended = false;
         loop {
-            match self.try_lock() {
+            match self.try_acquire() {
                 None => {
-                    // let mut rng = LazyRand64::<Xorshift, _>::lazy(clock_seed);
-                    let mut rng = FIXED_DURATION;
-                    let mut backoff = ExpBackoff::sleepy().into_inf_iter();
+                    if !contended {
+                        contended = true;
+                        attempt.contended();
+                    }
+                    self.deadlock_hook(deadlock::waiting);
                     while self.locked.load(Ordering::Relaxed) {
-                        hint::spin_loop();
-                        backoff.next().act(|| &mut rng)
+                        backoff.snooze();
                     }
                 }
-                Some(guard) => return guard,
+                Some(guard) => {
+                    attempt.acquired(deadlock::addr_of(self));
+                    return guard;
+                }
             }
         }
     }
 
+    /// Acquires the lock, detecting whether a previous holder (that also acquired the lock
+    /// through this method) panicked while holding it. The returned guard will, in turn, poison
+    /// the lock if it is dropped during a panic.
+    ///
+    /// # Errors
+    /// If the lock is poisoned, the guard is returned inside a [`PoisonError`], from which it may
+    /// be recovered using [`PoisonError::into_inner`]. The lock remains poisoned until
+    /// [`clear_poison`](Self::clear_poison) is called.
+    #[cfg(feature = "std")]
     #[inline]
-    pub fn try_lock(&self) -> Option<SpinGuard<T>> {
-        if self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Acquire).is_ok() {
+    pub fn lock_checked(&self) -> LockResult<SpinGuard<'_, T>> {
+        let mut guard = self.lock();
+        guard.checked = true;
+        if self.is_poisoned() {
+            Err(PoisonError::new(guard))
+        } else {
+            Ok(guard)
+        }
+    }
+
+    /// Determines whether the lock is poisoned. Only guards obtained through
+    /// [`lock_checked`](Self::lock_checked) poison the lock.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::Relaxed)
+    }
+
+    /// Clears the poisoned state of the lock.
+    #[inline]
+    pub fn clear_poison(&self) {
+        self.poisoned.store(false, Ordering::Relaxed);
+    }
+
+    /// Attempts to acquire the lock without blocking.
+    ///
+    /// A successful acquisition uses [`Ordering::Acquire`], synchronizing with the
+    /// [`Ordering::Release`] store in [`unlock`](Self::unlock) of the previous holder, so that
+    /// all writes made under the lock by that holder are visible to this one. A failed
+    /// attempt does not enter the critical section and so needs no more than
+    /// [`Ordering::Relaxed`].
+    #[inline]
+    pub fn try_lock(&self) -> Option<SpinGuard<'_, T>> {
+        let attempt = self.instrument.begin(Mode::Write);
+        let guard = self.try_acquire();
+        match &guard {
+            None => attempt.timed_out(),
+            Some(_) => attempt.acquired(deadlock::addr_of(self)),
+        }
+        guard
+    }
+
+    /// A single, uninstrumented acquisition attempt.
+    #[inline(always)]
+    fn try_acquire(&self) -> Option<SpinGuard<'_, T>> {
+        if self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+            self.deadlock_hook(deadlock::acquired);
             Some(SpinGuard {
                 lock: self,
-                __no_send: PhantomData::default()
+                locked: true,
+                #[cfg(feature = "std")]
+                checked: false,
+                __no_send: PhantomData
             })
         } else {
             None
         }
     }
 
+    /// Attempts to acquire the lock within the given `duration`, spinning with exponential
+    /// backoff while the lock is held. A zero `duration` makes a single attempt, as
+    /// [`try_lock`](Self::try_lock) does.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::spin_mutex::SpinMutex;
+    /// let lock = SpinMutex::new(0);
+    /// let guard = lock.lock();
+    /// assert!(lock.try_lock_for(Duration::from_millis(1)).is_none());
+    /// drop(guard);
+    /// assert!(lock.try_lock_for(Duration::from_millis(1)).is_some());
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_lock_for(&self, duration: Duration) -> Option<SpinGuard<'_, T>> {
+        self.try_lock_until(Deadline::lazy_after(duration))
+    }
+
+    /// Attempts to acquire the lock before the given `deadline` elapses, spinning with
+    /// exponential backoff while the lock is held. Backoff sleeps are truncated to the
+    /// time remaining, so that the deadline is not overshot.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_lock_until(&self, deadline: impl Into<Deadline>) -> Option<SpinGuard<'_, T>> {
+        let mut deadline = deadline.into();
+        let attempt = self.instrument.begin(Mode::Write);
+        let mut rng = FIXED_DURATION;
+        let mut backoff = ExpBackoff::sleepy().into_inf_iter();
+        let mut contended = false;
+        loop {
+            if let Some(guard) = self.try_acquire() {
+                attempt.acquired(deadlock::addr_of(self));
+                return Some(guard);
+            }
+
+            self.deadlock_hook(deadlock::waiting);
+            while self.locked.load(Ordering::Relaxed) {
+                let remaining = deadline.remaining();
+                if remaining.is_zero() {
+                    if self.tracked {
+                        deadlock::abandoned();
+                    }
+                    attempt.timed_out();
+                    return None;
+                }
+                if !contended {
+                    contended = true;
+                    attempt.contended();
+                }
+                spin_hint();
+                match backoff.next() {
+                    ExpBackoffAction::Sleep(sleep) => {
+                        thread::sleep(rng.next_range(Duration::ZERO..sleep).min(remaining));
+                    }
+                    action => action.act(|| &mut rng),
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn deadlock_hook(&self, hook: fn(usize)) {
+        if self.tracked {
+            hook(deadlock::addr_of(self));
+        }
+    }
+
+    /// Releases the lock with [`Ordering::Release`], publishing all writes made under the lock
+    /// to the next thread that acquires it.
     #[inline]
     pub fn unlock(&self) {
+        self.deadlock_hook(deadlock::released);
         self.locked.store(false, Ordering::Release);
+        self.instrument.released(deadlock::addr_of(self), Mode::Write);
     }
 
     /// Returns a mutable reference to the underlying data.
@@ -106,6 +395,12 @@ impl<T: ?Sized> SpinMutex<T> {
     }
 }
 
+impl<T: ?Sized + fmt::Debug> fmt::Debug for SpinGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
 impl<T: ?Sized + fmt::Debug> fmt::Debug for SpinMutex<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut d = f.debug_struct("SpinMutex");
@@ -127,8 +422,51 @@ impl<T: ?Sized + fmt::Debug> fmt::Debug for SpinMutex<T> {
     }
 }
 
+impl<T: Default> Default for SpinMutex<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for SpinMutex<T> {
+    #[inline]
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+/// Clones the protected value under the lock, blocking while the lock is held elsewhere. The
+/// clone is a new, unlocked and unpoisoned mutex.
+impl<T: Clone> Clone for SpinMutex<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.lock().clone())
+    }
+}
+
+/// Serializes the protected value under the lock, blocking while the lock is held elsewhere.
+/// A poisoned lock is serialized as though it were not.
+#[cfg(feature = "serde")]
+impl<T: ?Sized + serde::Serialize> serde::Serialize for SpinMutex<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.lock().serialize(serializer)
+    }
+}
+
+/// Deserializes the value into a new, unlocked mutex.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SpinMutex<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
 #[cfg(test)]
 mod tests;
 
 #[cfg(test)]
-mod std_tests;
\ No newline at end of file
+mod std_tests;
+
+#[cfg(all(test, loom))]
+mod loom_tests;
\ No newline at end of file
@@ -0,0 +1,49 @@
+//! Model-checks the memory ordering of [`SpinMutex`] under [loom](https://github.com/tokio-rs/loom).
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test -p anode --lib --release spin_mutex::loom_tests`.
+
+use loom::cell::UnsafeCell;
+use loom::sync::Arc;
+use loom::thread;
+use crate::spin_mutex::{SpinGuard, SpinMutex};
+
+/// Spins on [`SpinMutex::try_lock`], yielding to the loom scheduler between attempts.
+fn lock<T>(lock: &SpinMutex<T>) -> SpinGuard<'_, T> {
+    loop {
+        match lock.try_lock() {
+            None => thread::yield_now(),
+            Some(guard) => return guard,
+        }
+    }
+}
+
+#[test]
+fn write_then_read_is_published() {
+    loom::model(|| {
+        let mutex = Arc::new(SpinMutex::new(UnsafeCell::new(0)));
+
+        let writer = {
+            let mutex = mutex.clone();
+            thread::spawn(move || {
+                let guard = lock(&mutex);
+                guard.with_mut(|val| unsafe { *val = 42 });
+            })
+        };
+
+        let reader = {
+            let mutex = mutex.clone();
+            thread::spawn(move || {
+                let guard = lock(&mutex);
+                guard.with(|val| unsafe { *val })
+            })
+        };
+
+        writer.join().unwrap();
+        let observed = reader.join().unwrap();
+        assert!(observed == 0 || observed == 42, "observed: {observed}");
+
+        // once the writer has released, any subsequent locker must observe its write
+        let guard = lock(&mutex);
+        assert_eq!(42, guard.with(|val| unsafe { *val }));
+    });
+}
@@ -1,6 +1,22 @@
 use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::backoff::{Backoff, ExpBackoff};
+use crate::deadline::Deadline;
 use crate::spin_mutex::SpinMutex;
 use crate::test_utils;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+
+#[cfg(not(loom))]
+static STATIC_LOCK: SpinMutex<u64> = SpinMutex::new(0);
+
+#[cfg(not(loom))]
+#[test]
+fn static_lock() {
+    *STATIC_LOCK.lock() = 42;
+    assert_eq!(42, *STATIC_LOCK.lock());
+    assert!(STATIC_LOCK.try_lock().is_some());
+}
 
 #[test]
 fn cycle() {
@@ -20,6 +36,25 @@ fn cycle() {
     drop(guard_3);
 }
 
+#[test]
+fn lock_with_spinny_backoff() {
+    let lock = Arc::new(SpinMutex::new(0));
+    let threads = (0..4)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..1_000 {
+                    *lock.lock_with(Backoff::with_config(ExpBackoff::spinny())) += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(4_000, *lock.lock());
+}
+
 #[test]
 fn borrow_mut() {
     let mut lock = SpinMutex::new(0);
@@ -81,6 +116,141 @@ fn await_release() {
     }
 }
 
+#[test]
+fn unsized_slice() {
+    let lock: Arc<SpinMutex<[u8]>> = Arc::new(SpinMutex::new([0; 4]));
+    {
+        let mut guard = lock.lock();
+        assert_eq!(4, guard.len());
+        guard[0] = 42;
+    }
+
+    let t_2 = {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let mut guard = lock.lock();
+            assert_eq!(&[42, 0, 0, 0], &*guard);
+            guard.copy_from_slice(&[1, 2, 3, 4]);
+        })
+    };
+    t_2.join().unwrap();
+
+    let guard = lock.try_lock().unwrap();
+    let slice: &[u8] = &guard;
+    assert_eq!(&[1, 2, 3, 4], slice);
+    drop(guard);
+
+    let mut boxed: Box<SpinMutex<[u8]>> = Box::new(SpinMutex::new([0; 2]));
+    boxed.get_mut()[1] = 69;
+    assert_eq!(&[0, 69], &*boxed.lock());
+}
+
+#[test]
+fn unlocked_admits_other_thread() {
+    let lock = Arc::new(SpinMutex::new(0));
+    let mut guard = lock.lock();
+    *guard = 1;
+
+    let result = guard.unlocked(|| {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let mut guard = lock.try_lock().unwrap();
+            assert_eq!(1, *guard);
+            *guard = 42;
+        }).join().unwrap();
+        "done"
+    });
+    assert_eq!("done", result);
+    assert_eq!(42, *guard);
+    assert!(lock.try_lock().is_none());
+
+    drop(guard);
+    assert!(lock.try_lock().is_some());
+}
+
+#[test]
+fn unlocked_panic_does_not_double_unlock() {
+    let lock = SpinMutex::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut guard = lock.lock();
+        guard.unlocked(|| {
+            // another owner acquires the lock while the guard has released it
+            std::mem::forget(lock.try_lock().unwrap());
+            panic!("boom");
+        });
+    }));
+    assert!(result.is_err());
+    // the dropped guard must not have released the lock held by the other owner
+    assert!(lock.try_lock().is_none());
+}
+
+#[test]
+fn lock_checked_poisons_on_panic() {
+    let lock = SpinMutex::new(0);
+    assert_eq!(0, *lock.lock_checked().unwrap());
+
+    // an unchecked guard does not poison
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = lock.lock();
+        panic!("boom");
+    }));
+    assert!(result.is_err());
+    assert!(!lock.is_poisoned());
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut guard = lock.lock_checked().unwrap();
+        *guard = 42;
+        panic!("boom");
+    }));
+    assert!(result.is_err());
+    assert!(lock.is_poisoned());
+
+    // the lock was released and the guard is recoverable from the error
+    let guard = lock.lock_checked().unwrap_err().into_inner();
+    assert_eq!(42, *guard);
+    drop(guard);
+
+    // plain locking is unaffected by poisoning
+    assert_eq!(42, *lock.lock());
+
+    lock.clear_poison();
+    assert!(!lock.is_poisoned());
+    assert_eq!(42, *lock.lock_checked().unwrap());
+}
+
+#[test]
+fn try_lock_for_timeout() {
+    let lock = SpinMutex::new(0);
+    let guard = lock.lock();
+    assert!(lock.try_lock_for(Duration::ZERO).is_none());
+
+    let start = Instant::now();
+    assert!(lock.try_lock_for(CHECK_WAIT).is_none());
+    let elapsed = start.elapsed();
+    assert!(elapsed >= CHECK_WAIT, "elapsed: {elapsed:?}");
+    assert!(lock.try_lock_until(Deadline::lazy_after(CHECK_WAIT)).is_none());
+    drop(guard);
+
+    assert!(lock.try_lock_for(Duration::ZERO).is_some());
+}
+
+#[test]
+fn try_lock_for_await_release() {
+    let lock = Arc::new(SpinMutex::new(0));
+    let guard = lock.lock();
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 backs off while main holds the lock
+            *lock.try_lock_for(LONG_WAIT).unwrap() = 42;
+        })
+    };
+    thread::sleep(CHECK_WAIT);
+    drop(guard);
+    t_2.join().unwrap();
+    assert_eq!(42, *lock.lock());
+}
+
 #[test]
 fn debug() {
     let lock = SpinMutex::new("foobar");
@@ -90,4 +260,48 @@ fn debug() {
     let guard = lock.lock();
     assert!(format!("{:?}", lock).contains("<locked>"), "{:?}", lock);
     drop(guard);
-}
\ No newline at end of file
+}
+#[test]
+fn default_from_clone() {
+    let lock = SpinMutex::<Vec<i32>>::default();
+    assert!(lock.lock().is_empty());
+
+    let lock = SpinMutex::from(vec![1, 2, 3]);
+    let clone = lock.clone();
+    assert!(lock.try_lock().is_some());
+    clone.lock().push(4);
+    assert_eq!(vec![1, 2, 3, 4], *clone.lock());
+    assert_eq!(vec![1, 2, 3], *lock.lock());
+
+    // the clone of a poisoned mutex is unpoisoned
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = lock.lock_checked().unwrap();
+        panic!("boom");
+    }));
+    assert!(result.is_err());
+    assert!(lock.is_poisoned());
+    assert!(!lock.clone().is_poisoned());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let lock = SpinMutex::new(vec![1, 2, 3]);
+    let json = serde_json::to_string(&lock).unwrap();
+    assert_eq!("[1,2,3]", json);
+    assert!(lock.try_lock().is_some());
+
+    // poisoning does not impede serialization
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _guard = lock.lock_checked().unwrap();
+        panic!("boom");
+    }));
+    assert!(result.is_err());
+    assert!(lock.is_poisoned());
+    assert_eq!(json, serde_json::to_string(&lock).unwrap());
+
+    let lock = serde_json::from_str::<SpinMutex<Vec<i32>>>(&json).unwrap();
+    assert!(!lock.is_poisoned());
+    assert_eq!(vec![1, 2, 3], *lock.lock());
+    assert!(serde_json::from_str::<SpinMutex<Vec<i32>>>("{}").is_err());
+}
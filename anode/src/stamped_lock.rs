@@ -0,0 +1,295 @@
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::time::Duration;
+use crate::backoff::spin_until;
+use crate::snapshot::read_validated;
+
+unsafe impl<T: ?Sized + Send> Send for StampedLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for StampedLock<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for StampedReadGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for StampedWriteGuard<'_, T> {}
+
+/// The number of bits of the state devoted to the reader count.
+const READER_BITS: u32 = 24;
+
+/// Masks the reader count.
+const READERS: u64 = (1 << READER_BITS) - 1;
+
+/// One increment of the write sequence, which occupies the bits above the reader count. The
+/// sequence is odd while a writer holds the lock.
+const SEQ_UNIT: u64 = 1 << READER_BITS;
+
+/// A reader-writer lock that also admits optimistic reads, modeled on Java's `StampedLock`.
+///
+/// An optimistic read acquires nothing: [`try_optimistic_read`](Self::try_optimistic_read)
+/// returns a [`Stamp`] capturing the write sequence, and [`validate`](Self::validate) later
+/// confirms that no writer intervened in the meantime. Optimistic readers therefore never block
+/// writers, nor contend with each other. Should validation fail, the reader typically retries,
+/// or falls back to a conventional read lock.
+///
+/// Since an optimistic read may race with a writer, the data can only be inspected
+/// optimistically when it is [`Copy`] (see [`read_optimistic`](Self::read_optimistic) and
+/// [`get`](Self::get)); other data must be read under a lock. Readers and writers spin (with
+/// backoff) while waiting, so the lock is intended for short critical sections.
+///
+/// # Examples
+/// ```
+/// use anode::stamped_lock::StampedLock;
+/// let lock = StampedLock::new((0u64, 0u64));
+/// let stamp = lock.try_optimistic_read().unwrap();
+/// {
+///     let mut guard = lock.write();
+///     guard.0 += 1;
+///     guard.1 += 2;
+/// }
+/// assert!(!lock.validate(stamp));
+/// assert_eq!(Some((1, 2)), lock.read_optimistic());
+/// assert_eq!((1, 2), *lock.read());
+/// ```
+pub struct StampedLock<T: ?Sized> {
+    /// The write sequence in the upper bits and the reader count in the lower bits.
+    state: AtomicU64,
+    data: UnsafeCell<T>,
+}
+
+/// The write sequence of a [`StampedLock`], as observed by an optimistic read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamp(u64);
+
+/// An RAII guard over the data of a [`StampedLock`], obtained from [`StampedLock::read`] or
+/// [`StampedLock::try_read`]. Writers are excluded until the guard is dropped.
+#[must_use = "if unused, the StampedLock will immediately unlock"]
+pub struct StampedReadGuard<'a, T: ?Sized> {
+    lock: &'a StampedLock<T>,
+    __no_send: PhantomData<*const ()>,
+}
+
+/// An RAII guard over the data of a [`StampedLock`], obtained from [`StampedLock::write`] or
+/// [`StampedLock::try_write`]. All other readers and writers are excluded, and outstanding
+/// stamps are invalidated, by the time the guard is dropped.
+#[must_use = "if unused, the StampedLock will immediately unlock"]
+pub struct StampedWriteGuard<'a, T: ?Sized> {
+    lock: &'a StampedLock<T>,
+    __no_send: PhantomData<*const ()>,
+}
+
+#[inline]
+fn is_write_locked(state: u64) -> bool {
+    state & SEQ_UNIT != 0
+}
+
+impl<T> StampedLock<T> {
+    #[inline]
+    pub const fn new(t: T) -> Self {
+        Self {
+            state: AtomicU64::new(0),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> StampedLock<T> {
+    /// Obtains a stamp for a subsequent optimistic read, or `None` if the lock is presently
+    /// write-locked.
+    #[inline]
+    pub fn try_optimistic_read(&self) -> Option<Stamp> {
+        let state = self.state.load(Ordering::Acquire);
+        if is_write_locked(state) {
+            None
+        } else {
+            Some(Stamp(state & !READERS))
+        }
+    }
+
+    /// Determines whether no writer has acquired the lock since the given `stamp` was issued,
+    /// in which case any data read optimistically in the interim is consistent.
+    #[inline]
+    pub fn validate(&self, stamp: Stamp) -> bool {
+        // orders the preceding (optimistic) reads of the data before the reload of the state
+        fence(Ordering::Acquire);
+        self.state.load(Ordering::Relaxed) & !READERS == stamp.0
+    }
+
+    /// Acquires a read lock, spinning (with backoff) while a writer holds the lock.
+    #[inline]
+    pub fn read(&self) -> StampedReadGuard<'_, T> {
+        self.try_read(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire a read lock within the given `duration`.
+    #[inline]
+    pub fn try_read(&self, duration: Duration) -> Option<StampedReadGuard<'_, T>> {
+        if spin_until(duration, || self.try_acquire_read()) {
+            Some(StampedReadGuard {
+                lock: self,
+                __no_send: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn try_acquire_read(&self) -> bool {
+        let state = self.state.load(Ordering::Relaxed);
+        if is_write_locked(state) {
+            return false;
+        }
+        assert_ne!(READERS, state & READERS, "too many readers");
+        self.state
+            .compare_exchange_weak(state, state + 1, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    /// Acquires a write lock, spinning (with backoff) while other readers or a writer hold the
+    /// lock.
+    #[inline]
+    pub fn write(&self) -> StampedWriteGuard<'_, T> {
+        self.try_write(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire a write lock within the given `duration`.
+    #[inline]
+    pub fn try_write(&self, duration: Duration) -> Option<StampedWriteGuard<'_, T>> {
+        if spin_until(duration, || self.try_acquire_write()) {
+            Some(StampedWriteGuard {
+                lock: self,
+                __no_send: PhantomData,
+            })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn try_acquire_write(&self) -> bool {
+        let state = self.state.load(Ordering::Relaxed);
+        if is_write_locked(state) || state & READERS != 0 {
+            return false;
+        }
+        match self.state.compare_exchange_weak(state, state.wrapping_add(SEQ_UNIT), Ordering::Acquire, Ordering::Relaxed) {
+            Err(_) => false,
+            Ok(_) => {
+                // the odd sequence must be visible before any of the writes to the data, lest an
+                // optimistic reader observe new data alongside the old sequence
+                fence(Ordering::Release);
+                true
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the lock mutably, no actual locking needs to take place.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: Copy> StampedLock<T> {
+    /// Makes a single attempt at reading the data optimistically, returning `None` if a writer
+    /// held the lock or intervened.
+    #[inline]
+    pub fn read_optimistic(&self) -> Option<T> {
+        let stamp = self.try_optimistic_read()?;
+        unsafe { read_validated(self.data.get(), || self.validate(stamp)) }
+    }
+
+    /// Returns a consistent copy of the data, reading optimistically at first and falling back
+    /// to a read lock should a writer interfere.
+    #[inline]
+    pub fn get(&self) -> T {
+        match self.read_optimistic() {
+            Some(val) => val,
+            None => *self.read(),
+        }
+    }
+}
+
+impl<T: Default> Default for StampedLock<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for StampedLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("StampedLock");
+        match self.try_read(Duration::ZERO) {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(guard) => {
+                d.field("data", &&*guard);
+            }
+        }
+        d.finish()
+    }
+}
+
+impl<T: ?Sized> StampedReadGuard<'_, T> {
+    /// The write sequence under this read lock, which remains valid for as long as the guard
+    /// is held (and beyond, until the next writer acquires the lock).
+    #[inline]
+    pub fn stamp(&self) -> Stamp {
+        Stamp(self.lock.state.load(Ordering::Relaxed) & !READERS)
+    }
+}
+
+impl<T: ?Sized> Drop for StampedReadGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.lock.state.fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<T: ?Sized> Deref for StampedReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for StampedWriteGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // restores an even sequence, distinct from that of any stamp issued before the write
+        self.lock.state.fetch_add(SEQ_UNIT, Ordering::Release);
+    }
+}
+
+impl<T: ?Sized> Deref for StampedWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for StampedWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests;
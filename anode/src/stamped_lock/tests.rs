@@ -0,0 +1,121 @@
+use crate::stamped_lock::StampedLock;
+use crate::test_utils::SHORT_WAIT;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn read_and_write() {
+    let mut lock = StampedLock::new(42);
+    assert_eq!(42, *lock.read());
+    assert_eq!(42, lock.get());
+
+    *lock.write() += 1;
+    assert_eq!(43, *lock.read());
+    assert_eq!(Some(43), lock.read_optimistic());
+    *lock.get_mut() = 7;
+    assert_eq!(7, lock.into_inner());
+}
+
+#[test]
+fn optimistic_read_invalidated_by_write() {
+    let lock = StampedLock::new(0);
+    let stamp = lock.try_optimistic_read().unwrap();
+    assert!(lock.validate(stamp));
+
+    // readers leave the stamp intact
+    {
+        let guard = lock.read();
+        assert_eq!(stamp, guard.stamp());
+        assert!(lock.validate(stamp));
+    }
+    assert!(lock.validate(stamp));
+
+    let guard = lock.write();
+    assert!(!lock.validate(stamp));
+    assert!(lock.try_optimistic_read().is_none());
+    assert!(lock.read_optimistic().is_none());
+    drop(guard);
+
+    assert!(!lock.validate(stamp));
+    let next = lock.try_optimistic_read().unwrap();
+    assert_ne!(stamp, next);
+    assert!(lock.validate(next));
+}
+
+#[test]
+fn readers_exclude_writer() {
+    let lock = StampedLock::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    drop(guard_1);
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard_2);
+
+    let guard = lock.try_write(Duration::ZERO).unwrap();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+    assert!(lock.try_read(Duration::ZERO).is_some());
+}
+
+#[test]
+fn implements_debug() {
+    let lock = StampedLock::new(42);
+    assert_eq!("StampedLock { data: 42 }", format!("{:?}", lock));
+    let _guard = lock.write();
+    assert_eq!("StampedLock { data: <locked> }", format!("{:?}", lock));
+}
+
+// optimistic reads race with writers by design, discarding torn copies; Miri reports the race
+#[test]
+#[cfg_attr(miri, ignore)]
+fn consistent_snapshots() {
+    const WRITERS: usize = 2;
+    const WRITES: u64 = 10_000;
+    let lock = Arc::new(StampedLock::new([0u64; 4]));
+    let running = Arc::new(AtomicBool::new(true));
+
+    // writers keep all elements equal; a torn read would observe a mix of old and new values
+    let writers = (0..WRITERS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..WRITES {
+                    let mut guard = lock.write();
+                    for val in guard.iter_mut() {
+                        *val += 1;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let readers = (0..2)
+        .map(|_| {
+            let lock = lock.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    let snapshot = lock.get();
+                    assert!(snapshot.iter().all(|&val| val == snapshot[0]), "{snapshot:?}");
+                    if let Some(snapshot) = lock.read_optimistic() {
+                        assert!(snapshot.iter().all(|&val| val == snapshot[0]), "{snapshot:?}");
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    running.store(false, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!([WRITERS as u64 * WRITES; 4], lock.get());
+}
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+
+/// A bounded, blocking, multi-producer, multi-consumer FIFO queue.
+///
+/// Producers block while the queue is full and consumers block while it is empty; either may
+/// wait with a timeout. Elements are handed out in the order in which they were pushed.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use anode::sync_queue::SyncQueue;
+/// let queue = Arc::new(SyncQueue::new(2));
+/// let producer = {
+///     let queue = queue.clone();
+///     thread::spawn(move || {
+///         for i in 0..10 {
+///             queue.push(i);
+///         }
+///     })
+/// };
+/// let received = (0..10).map(|_| queue.pop()).collect::<Vec<_>>();
+/// assert_eq!((0..10).collect::<Vec<_>>(), received);
+/// producer.join().unwrap();
+/// ```
+pub struct SyncQueue<T> {
+    capacity: usize,
+    elements: Mutex<VecDeque<T>>,
+    not_empty: TimedCondvar,
+    not_full: TimedCondvar,
+}
+
+impl<T> SyncQueue<T> {
+    /// Creates a queue holding at most `capacity` elements.
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            capacity,
+            elements: Mutex::new(VecDeque::with_capacity(capacity)),
+            not_empty: TimedCondvar::new(),
+            not_full: TimedCondvar::new(),
+        }
+    }
+
+    /// The maximum number of elements that the queue can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of elements presently in the queue.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.elements.lock().remedy().len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `t` to the back of the queue, blocking while the queue is full.
+    #[inline]
+    pub fn push(&self, t: T) {
+        if self.try_push_until(t, Deadline::Forever).is_err() {
+            unreachable!()
+        }
+    }
+
+    /// Appends `t` to the back of the queue, blocking while the queue is full, until the given
+    /// `duration` elapses. If the wait times out, `t` is returned to the caller.
+    #[inline]
+    pub fn try_push(&self, t: T, duration: Duration) -> Result<(), T> {
+        self.try_push_until(t, Deadline::lazy_after(duration))
+    }
+
+    /// Appends `t` to the back of the queue, blocking while the queue is full, until the given
+    /// `deadline` elapses. If the wait times out, `t` is returned to the caller.
+    pub fn try_push_until(&self, t: T, deadline: impl Into<Deadline>) -> Result<(), T> {
+        let elements = self.elements.lock().remedy();
+        let (mut elements, timed_out) =
+            self.not_full.wait_while_until(elements, |elements| elements.len() == self.capacity, deadline);
+        if timed_out {
+            return Err(t);
+        }
+
+        elements.push_back(t);
+        drop(elements);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Removes the element at the front of the queue, blocking while the queue is empty.
+    #[inline]
+    pub fn pop(&self) -> T {
+        self.try_pop_until(Deadline::Forever).unwrap()
+    }
+
+    /// Removes the element at the front of the queue, blocking while the queue is empty, until
+    /// the given `duration` elapses. Returns `None` if the wait timed out.
+    #[inline]
+    pub fn try_pop(&self, duration: Duration) -> Option<T> {
+        self.try_pop_until(Deadline::lazy_after(duration))
+    }
+
+    /// Removes the element at the front of the queue, blocking while the queue is empty, until
+    /// the given `deadline` elapses. Returns `None` if the wait timed out.
+    pub fn try_pop_until(&self, deadline: impl Into<Deadline>) -> Option<T> {
+        let elements = self.elements.lock().remedy();
+        let (mut elements, timed_out) =
+            self.not_empty.wait_while_until(elements, |elements| elements.is_empty(), deadline);
+        if timed_out {
+            return None;
+        }
+
+        let t = elements.pop_front();
+        drop(elements);
+        self.not_full.notify_one();
+        t
+    }
+
+    /// Consumes the queue, returning the remaining elements in FIFO order.
+    #[inline]
+    pub fn into_inner(self) -> VecDeque<T> {
+        self.elements.into_inner().remedy()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SyncQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elements = self.elements.lock().remedy();
+        f.debug_struct("SyncQueue")
+            .field("capacity", &self.capacity)
+            .field("elements", &*elements)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::sync_queue::SyncQueue;
+use crate::test_utils::{spawn_blocked, CHECK_WAIT, SHORT_WAIT};
+
+#[test]
+fn push_and_pop() {
+    let queue = SyncQueue::new(2);
+    assert_eq!(2, queue.capacity());
+    assert!(queue.is_empty());
+    assert_eq!(None, queue.try_pop(Duration::ZERO));
+
+    queue.push(1);
+    assert_eq!(Ok(()), queue.try_push(2, Duration::ZERO));
+    assert_eq!(2, queue.len());
+    assert_eq!(Err(3), queue.try_push(3, SHORT_WAIT));
+    assert_eq!("SyncQueue { capacity: 2, elements: [1, 2] }", format!("{:?}", queue));
+
+    assert_eq!(1, queue.pop());
+    assert_eq!(Ok(()), queue.try_push(3, Duration::ZERO));
+    assert_eq!(Some(2), queue.try_pop(SHORT_WAIT));
+    assert_eq!(vec![3], Vec::from(queue.into_inner()));
+}
+
+#[test]
+#[should_panic(expected = "capacity must be positive")]
+fn zero_capacity() {
+    SyncQueue::<()>::new(0);
+}
+
+#[test]
+fn pop_blocks_until_push() {
+    let queue = Arc::new(SyncQueue::new(1));
+    let consumer = {
+        let queue = queue.clone();
+        spawn_blocked(move || queue.pop())
+    };
+    thread::sleep(CHECK_WAIT);
+    assert!(!consumer.is_finished());
+
+    queue.push(42);
+    assert_eq!(42, consumer.join().unwrap());
+}
+
+#[test]
+fn push_blocks_until_pop() {
+    let queue = Arc::new(SyncQueue::new(1));
+    queue.push(1);
+    let producer = {
+        let queue = queue.clone();
+        spawn_blocked(move || queue.push(2))
+    };
+    thread::sleep(CHECK_WAIT);
+    assert!(!producer.is_finished());
+
+    assert_eq!(1, queue.pop());
+    producer.join().unwrap();
+    assert_eq!(2, queue.pop());
+}
+
+#[test]
+fn multiple_producers_and_consumers() {
+    const PRODUCERS: u64 = 4;
+    const CONSUMERS: u64 = 4;
+    const ELEMENTS: u64 = 1_000;
+    let queue = Arc::new(SyncQueue::new(8));
+    let producers = (0..PRODUCERS)
+        .map(|producer| {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                for i in 0..ELEMENTS {
+                    queue.push(producer * ELEMENTS + i);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    let consumers = (0..CONSUMERS)
+        .map(|_| {
+            let queue = queue.clone();
+            thread::spawn(move || {
+                (0..PRODUCERS * ELEMENTS / CONSUMERS)
+                    .map(|_| queue.pop())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let mut received = consumers
+        .into_iter()
+        .flat_map(|consumer| consumer.join().unwrap())
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert_eq!((0..PRODUCERS * ELEMENTS).collect::<Vec<_>>(), received);
+    assert!(queue.is_empty());
+}
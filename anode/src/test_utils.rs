@@ -2,7 +2,10 @@ use std::cell::{Ref, RefCell, RefMut};
 use std::fmt::{Debug, Formatter};
 use std::panic::RefUnwindSafe;
 use std::sync::{Arc};
-use std::thread::JoinHandle;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{JoinHandle, Thread};
 use std::time::Duration;
 use std::{fmt, thread};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,6 +13,9 @@ use crate::deadline::Deadline;
 use crate::wait;
 use crate::wait::Wait;
 
+pub mod conformance;
+pub mod stress;
+
 // Constants used for waiting in tests.rs.
 pub const SHORT_WAIT: Duration = Duration::from_micros(1);
 pub const LONG_WAIT: Duration = Duration::from_secs(10);
@@ -32,11 +38,11 @@ impl<T> UnwindableRefCell<T> {
 }
 
 impl<T: ?Sized> UnwindableRefCell<T> {
-    pub fn borrow(&self) -> Ref<T> {
+    pub fn borrow(&self) -> Ref<'_, T> {
         self.inner.borrow()
     }
 
-    pub fn borrow_mut(&self) -> RefMut<T> {
+    pub fn borrow_mut(&self) -> RefMut<'_, T> {
         self.inner.borrow_mut()
     }
 }
@@ -53,7 +59,7 @@ impl<T> RefUnwindSafe for UnwindableRefCell<T> {}
 /// add a [`thread::sleep`].
 ///
 /// # Examples (not compiled)
-/// ```
+/// ```ignore
 /// use anode::test_utils::spawn_blocked;
 /// let thread = spawn_blocked(|| {
 ///     // wait_for_something_important
@@ -86,3 +92,24 @@ impl<T: Debug> Debug for NoPrettyPrint<T> {
         write!(f, "{:?}", self.0)
     }
 }
+
+/// A minimal executor that parks the current thread until the future is woken.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unparker(Thread);
+
+    impl Wake for Unparker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unparker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park_timeout(LONG_WAIT),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! A conformance suite for [`Moderator`] implementations, checking the contract that
+//! [`ZLock`] relies upon: shared reads, exclusive writes, bounded waits, atomic downgrades and
+//! upgrades, the upgradable slot, release by a thread other than the acquirer, and
+//! non-blocking diagnostics. The suite concludes with a [`stress`](super::stress) run.
+//!
+//! Each check is a function generic over the moderator. The [`moderator_conformance!`]
+//! macro generates a test for every check.
+//!
+//! [`moderator_conformance!`]: crate::moderator_conformance
+
+use crate::test_utils::stress::{self, Ledger, StressConfig};
+use crate::test_utils::SHORT_WAIT;
+use crate::zlock::{Moderator, ZLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Generates a module of tests named `$name`, running every check in the
+/// [`conformance`](crate::test_utils::conformance) suite against the moderator `$moderator`.
+///
+/// # Examples
+/// ```
+/// use anode::moderator_conformance;
+/// use anode::zlock::ReadBiased;
+/// moderator_conformance!(read_biased, ReadBiased);
+/// ```
+#[macro_export]
+macro_rules! moderator_conformance {
+    ($name:ident, $moderator:ty) => {
+        #[cfg(test)]
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[test]
+            fn readers_share() {
+                $crate::test_utils::conformance::readers_share::<$moderator>();
+            }
+
+            #[test]
+            fn writer_excludes() {
+                $crate::test_utils::conformance::writer_excludes::<$moderator>();
+            }
+
+            #[test]
+            fn timeout_is_honoured() {
+                $crate::test_utils::conformance::timeout_is_honoured::<$moderator>();
+            }
+
+            #[test]
+            fn downgrade_and_upgrade() {
+                $crate::test_utils::conformance::downgrade_and_upgrade::<$moderator>();
+            }
+
+            #[test]
+            fn upgradable_slot() {
+                $crate::test_utils::conformance::upgradable_slot::<$moderator>();
+            }
+
+            #[test]
+            fn force_unlock_on_other_thread() {
+                $crate::test_utils::conformance::force_unlock_on_other_thread::<$moderator>();
+            }
+
+            #[test]
+            fn debug_while_locked() {
+                $crate::test_utils::conformance::debug_while_locked::<$moderator>();
+            }
+
+            #[test]
+            #[cfg_attr(miri, ignore)]
+            fn stress() {
+                $crate::test_utils::conformance::stress::<$moderator>();
+            }
+        }
+    };
+}
+
+/// Any number of readers may hold the lock at once, to the exclusion of writers.
+pub fn readers_share<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(42);
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).expect("second reader refused");
+    assert_eq!(*guard_1, *guard_2);
+    assert!(lock.try_write(Duration::ZERO).is_none(), "writer admitted alongside readers");
+    drop(guard_1);
+    assert!(lock.try_write(SHORT_WAIT).is_none(), "writer admitted alongside a reader");
+    drop(guard_2);
+    assert!(lock.try_write(Duration::ZERO).is_some(), "writer refused by an unlocked lock");
+}
+
+/// A writer holds the lock to the exclusion of readers and other writers.
+pub fn writer_excludes<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let mut guard = lock.write();
+    *guard = 42;
+    assert!(lock.try_read(Duration::ZERO).is_none(), "reader admitted alongside a writer");
+    assert!(lock.try_read(SHORT_WAIT).is_none(), "reader admitted alongside a writer");
+    assert!(lock.try_write(Duration::ZERO).is_none(), "writer admitted alongside a writer");
+    assert!(lock.try_write(SHORT_WAIT).is_none(), "writer admitted alongside a writer");
+    drop(guard);
+    assert_eq!(42, *lock.try_read(Duration::ZERO).expect("reader refused by an unlocked lock"));
+}
+
+/// A bounded wait gives up no sooner than its duration elapses, but does eventually give up.
+pub fn timeout_is_honoured<M: Moderator>() {
+    const TIMEOUT: Duration = Duration::from_millis(5);
+    let lock = ZLock::<_, M>::new(());
+    let guard = lock.write();
+    let start = Instant::now();
+    assert!(lock.try_read(TIMEOUT).is_none());
+    assert!(lock.try_write(TIMEOUT).is_none());
+    assert!(start.elapsed() >= TIMEOUT * 2, "gave up after {:?}", start.elapsed());
+    drop(guard);
+}
+
+/// A downgrade retains the lock throughout, and an upgrade waits for the other readers.
+pub fn downgrade_and_upgrade<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let mut guard = lock.write();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert!(lock.try_write(Duration::ZERO).is_none(), "writer admitted after downgrade");
+    let reader = lock.try_read(Duration::ZERO).expect("reader refused after downgrade");
+    assert_eq!(42, *reader);
+
+    let guard = guard.try_upgrade(SHORT_WAIT).unchanged().expect("upgraded alongside a reader");
+    drop(reader);
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().expect("upgrade refused to a sole reader");
+    *guard = 69;
+    assert!(lock.try_read(Duration::ZERO).is_none(), "reader admitted after upgrade");
+    drop(guard);
+    assert_eq!(69, *lock.read());
+}
+
+/// The upgradable slot is held by one thread at a time, coexisting with plain readers, and is
+/// released by upgrading, downgrading or dropping its guard.
+pub fn upgradable_slot<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let guard = lock.read_upgradable();
+    let reader = lock.try_read(Duration::ZERO).expect("reader refused alongside the upgradable slot");
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_none(), "upgradable slot claimed twice");
+    assert!(lock.try_read_upgradable(SHORT_WAIT).is_none(), "upgradable slot claimed twice");
+
+    let guard = guard.try_upgrade(SHORT_WAIT).unchanged().expect("upgraded alongside a reader");
+    drop(reader);
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().expect("upgrade refused to a sole reader");
+    *guard = 42;
+    let guard = guard.downgrade();
+    let upgradable = lock.try_read_upgradable(Duration::ZERO).expect("upgradable slot retained by upgrade");
+    drop(guard);
+
+    let reader = upgradable.downgrade();
+    let upgradable = lock.try_read_upgradable(Duration::ZERO).expect("upgradable slot retained by downgrade");
+    drop(reader);
+    drop(upgradable);
+    assert_eq!(42, *lock.try_write(Duration::ZERO).expect("upgradable slot retained by drop"));
+}
+
+/// A lock may be released by a thread other than the one that acquired it.
+pub fn force_unlock_on_other_thread<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    lock.write().forget();
+    thread::scope(|scope| {
+        scope.spawn(|| unsafe { lock.force_unlock_write() });
+    });
+    lock.try_read(Duration::ZERO).expect("reader refused after force unlock").forget();
+    thread::scope(|scope| {
+        scope.spawn(|| unsafe { lock.force_unlock_read() });
+    });
+    assert!(lock.try_write(Duration::ZERO).is_some(), "lock retained after force unlock");
+}
+
+/// The moderator's state may be formatted without blocking, even while write-locked.
+pub fn debug_while_locked<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let guard = lock.write();
+    assert!(format!("{:?}", lock).contains("<locked>"));
+    drop(guard);
+    assert!(format!("{:?}", lock).contains('0'));
+}
+
+/// Readers, writers and upgraders contend for the lock without violating its invariants. See
+/// [`stress::stress`].
+pub fn stress<M: Moderator + 'static>() {
+    let lock = ZLock::<_, M>::new(Ledger::default());
+    stress::stress(&lock, StressConfig {
+        duration: Duration::from_millis(20),
+        ..StressConfig::default()
+    });
+}
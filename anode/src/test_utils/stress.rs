@@ -0,0 +1,168 @@
+//! A stress harness for [`Locklike`] implementations, for validating custom
+//! [`Moderator`](crate::zlock::Moderator)s.
+//!
+//! Readers, writers and upgraders are spawned over a shared [`Ledger`] and run for a given
+//! duration, continuously checking that the lock never exposes a torn write and that the
+//! ledger only ever advances. Any violation panics the offending thread, and the panic is
+//! propagated to the caller of [`stress`].
+//!
+//! # Examples
+//! ```
+//! use std::time::Duration;
+//! use anode::test_utils::stress::{stress, Ledger, StressConfig};
+//! use anode::zlock::{ReadBiased, ZLock};
+//! let lock = ZLock::<_, ReadBiased>::new(Ledger::default());
+//! let report = stress(&lock, StressConfig {
+//!     duration: Duration::from_millis(10),
+//!     ..StressConfig::default()
+//! });
+//! assert_eq!(report.writes + report.upgrades, lock.read().value());
+//! ```
+
+use crate::zlock::locklike::{LockUpgradableGuardlike, Locklike};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// The data under stress. Every update advances a pair of counters in two separate steps,
+/// such that a reader that is not properly excluded from a writer may observe them apart.
+#[derive(Debug, Default)]
+pub struct Ledger {
+    value: u64,
+    mirror: u64,
+}
+
+impl Ledger {
+    /// The number of updates applied to the ledger.
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    fn advance(&mut self) {
+        self.value += 1;
+        // keep the intermediate state observable to an unexcluded reader
+        thread::yield_now();
+        self.mirror += 1;
+    }
+
+    fn verify(&self, last_seen: &mut u64) {
+        assert_eq!(self.value, self.mirror, "torn read");
+        assert!(self.value >= *last_seen, "ledger regressed from {} to {}", last_seen, self.value);
+        *last_seen = self.value;
+    }
+}
+
+/// The shape of a stress run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StressConfig {
+    /// The number of threads that repeatedly read-acquire the lock.
+    pub readers: usize,
+
+    /// The number of threads that repeatedly write-acquire the lock.
+    pub writers: usize,
+
+    /// The number of threads that repeatedly acquire an upgradable read lock and upgrade it.
+    pub upgraders: usize,
+
+    /// How long the threads run for.
+    pub duration: Duration,
+}
+
+impl Default for StressConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            readers: 4,
+            writers: 2,
+            upgraders: 2,
+            duration: Duration::from_millis(100),
+        }
+    }
+}
+
+/// The number of acquisitions completed over a stress run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StressReport {
+    pub reads: u64,
+    pub writes: u64,
+    pub upgrades: u64,
+}
+
+enum Role {
+    Reader,
+    Writer,
+    Upgrader,
+}
+
+/// Stresses `lock` as prescribed by `config`, returning the number of acquisitions by each
+/// role.
+///
+/// # Panics
+/// If any invariant is violated, or if the ledger's final value does not account for every
+/// write and upgrade.
+pub fn stress<L>(lock: &L, config: StressConfig) -> StressReport
+where
+    L: for<'a> Locklike<'a, Ledger> + ?Sized,
+{
+    let initial = lock.read().value;
+    let running = AtomicBool::new(true);
+    let roles = (0..config.readers)
+        .map(|_| Role::Reader)
+        .chain((0..config.writers).map(|_| Role::Writer))
+        .chain((0..config.upgraders).map(|_| Role::Upgrader));
+
+    let report = thread::scope(|scope| {
+        let threads = roles
+            .map(|role| {
+                let running = &running;
+                scope.spawn(move || {
+                    let mut last_seen = 0;
+                    let mut count = 0;
+                    while running.load(Ordering::Relaxed) {
+                        match role {
+                            Role::Reader => lock.read().verify(&mut last_seen),
+                            Role::Writer => {
+                                let mut guard = lock.write();
+                                guard.verify(&mut last_seen);
+                                guard.advance();
+                            }
+                            Role::Upgrader => {
+                                let guard = lock.read_upgradable();
+                                guard.verify(&mut last_seen);
+                                let mut guard = guard.upgrade();
+                                guard.verify(&mut last_seen);
+                                guard.advance();
+                            }
+                        }
+                        count += 1;
+                    }
+                    (role, count)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        thread::sleep(config.duration);
+        running.store(false, Ordering::Relaxed);
+
+        let mut report = StressReport::default();
+        for thread in threads {
+            match thread.join() {
+                Ok((Role::Reader, count)) => report.reads += count,
+                Ok((Role::Writer, count)) => report.writes += count,
+                Ok((Role::Upgrader, count)) => report.upgrades += count,
+                Err(err) => panic::resume_unwind(err),
+            }
+        }
+        report
+    });
+
+    let ledger = lock.read();
+    ledger.verify(&mut 0);
+    assert_eq!(initial + report.writes + report.upgrades, ledger.value, "lost updates");
+    report
+}
+
+#[cfg(test)]
+mod tests;
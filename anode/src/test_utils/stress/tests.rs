@@ -0,0 +1,30 @@
+use crate::test_utils::stress::{stress, Ledger, StressConfig};
+use crate::zlock::locklike::MODERATOR_KINDS;
+use std::time::Duration;
+
+const CONFIG: StressConfig = StressConfig {
+    readers: 2,
+    writers: 2,
+    upgraders: 2,
+    duration: Duration::from_millis(20),
+};
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn all_moderators() {
+    for moderator in MODERATOR_KINDS {
+        let lock = moderator.make_lock_for_test(Ledger::default());
+        let report = stress(&*lock, CONFIG);
+        assert!(report.reads + report.writes + report.upgrades > 0);
+        assert_eq!(report.writes + report.upgrades, lock.into_inner().value());
+    }
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn readers_only() {
+    let lock = MODERATOR_KINDS[0].make_lock_for_test(Ledger::default());
+    let report = stress(&*lock, StressConfig { writers: 0, upgraders: 0, ..CONFIG });
+    assert_eq!(0, report.writes + report.upgrades);
+    assert_eq!(0, lock.into_inner().value());
+}
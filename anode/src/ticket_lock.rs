@@ -0,0 +1,179 @@
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{fmt, hint, thread};
+
+unsafe impl<T: ?Sized + Send> Send for TicketLock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for TicketLock<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for TicketGuard<'_, T> {}
+
+/// The number of spins between yields while waiting for a ticket to be served.
+const SPINS_PER_YIELD: u32 = 100;
+
+/// A fair spinning mutual exclusion lock, admitting threads in the order of their arrival.
+///
+/// Each arriving thread draws a ticket from one counter and spins until a second counter --
+/// the ticket being served -- reaches it. Unlike [`SpinMutex`](crate::spin_mutex::SpinMutex),
+/// a waiting thread cannot be overtaken, and is thus never starved. The flip side is that
+/// the lock is handed over strictly in turn: should the next thread in line be descheduled,
+/// every thread behind it waits too. Ticket locks are, therefore, best suited to
+/// short critical sections with no more contending threads than cores.
+///
+/// # Examples
+/// ```
+/// use anode::ticket_lock::TicketLock;
+/// let lock = TicketLock::new(0);
+/// *lock.lock() = 42;
+/// assert_eq!(42, *lock.lock());
+/// ```
+pub struct TicketLock<T: ?Sized> {
+    next_ticket: AtomicUsize,
+    serving: AtomicUsize,
+    data: UnsafeCell<T>,
+}
+
+pub struct TicketGuard<'a, T: ?Sized> {
+    lock: &'a TicketLock<T>,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T> TicketLock<T> {
+    #[inline]
+    pub const fn new(t: T) -> Self {
+        Self {
+            next_ticket: AtomicUsize::new(0),
+            serving: AtomicUsize::new(0),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized> TicketLock<T> {
+    /// Acquires the lock, spinning (and periodically yielding) until this thread's
+    /// ticket is served.
+    #[inline]
+    pub fn lock(&self) -> TicketGuard<'_, T> {
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut spins = 0;
+        while self.serving.load(Ordering::Acquire) != ticket {
+            hint::spin_loop();
+            spins += 1;
+            if spins == SPINS_PER_YIELD {
+                spins = 0;
+                thread::yield_now();
+            }
+        }
+        self.guard()
+    }
+
+    /// Attempts to acquire the lock without blocking, succeeding only if the lock is free
+    /// and no other thread is waiting for it.
+    #[inline]
+    pub fn try_lock(&self) -> Option<TicketGuard<'_, T>> {
+        let serving = self.serving.load(Ordering::Relaxed);
+        if self
+            .next_ticket
+            .compare_exchange(serving, serving.wrapping_add(1), Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            Some(self.guard())
+        } else {
+            None
+        }
+    }
+
+    /// Releases the lock on behalf of a guard that was forgotten, serving the next ticket.
+    ///
+    /// # Safety
+    /// The lock must be held, and not by a live guard. Releasing a lock that is not held
+    /// serves a ticket that was never taken, admitting two holders at once.
+    #[inline]
+    pub unsafe fn unlock(&self) {
+        self.serving.fetch_add(1, Ordering::Release);
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the [`TicketLock`] mutably, no actual locking needs to
+    /// take place---the mutable borrow statically guarantees no locks exist.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    #[inline]
+    fn guard(&self) -> TicketGuard<'_, T> {
+        TicketGuard {
+            lock: self,
+            __no_send: PhantomData,
+        }
+    }
+
+    /// The number of threads holding or waiting for the lock.
+    #[cfg(test)]
+    fn queued(&self) -> usize {
+        self.next_ticket
+            .load(Ordering::Relaxed)
+            .wrapping_sub(self.serving.load(Ordering::Relaxed))
+    }
+}
+
+impl<T: ?Sized> Drop for TicketGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the lock is held by this guard
+        unsafe { self.lock.unlock() };
+    }
+}
+
+impl<T: ?Sized> Deref for TicketGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for TicketGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TicketGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for TicketLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("TicketLock");
+        match self.try_lock() {
+            None => {
+                struct LockedPlaceholder;
+                impl fmt::Debug for LockedPlaceholder {
+                    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("<locked>")
+                    }
+                }
+                d.field("data", &LockedPlaceholder);
+            }
+            Some(guard) => {
+                d.field("data", &&*guard);
+            }
+        }
+        d.finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,69 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use crate::remedy::Remedy;
+use crate::test_utils::LONG_WAIT;
+use crate::ticket_lock::TicketLock;
+use crate::wait;
+use crate::wait::Wait;
+
+#[test]
+fn cycle() {
+    let lock = TicketLock::new(0);
+    let mut guard_1 = lock.lock();
+    assert_eq!(0, *guard_1);
+    *guard_1 = 42;
+
+    assert!(lock.try_lock().is_none());
+    drop(guard_1);
+
+    let mut guard_3 = lock.try_lock().unwrap();
+    assert_eq!(42, *guard_3);
+    *guard_3 = 69;
+    drop(guard_3);
+    assert_eq!(69, lock.into_inner());
+}
+
+#[test]
+fn borrow_mut() {
+    let mut lock = TicketLock::new(0);
+    *lock.get_mut() = 42;
+    assert_eq!(42, *lock.lock());
+}
+
+#[test]
+fn acquired_in_arrival_order() {
+    const THREADS: usize = 4;
+    let lock = Arc::new(TicketLock::new(()));
+    let order = Arc::new(Mutex::new(vec![]));
+    let guard = lock.lock();
+
+    // the threads are queued one at a time, so that their arrival order is known
+    let threads = (0..THREADS)
+        .map(|i| {
+            let t = {
+                let lock = lock.clone();
+                let order = order.clone();
+                thread::spawn(move || {
+                    let _guard = lock.lock();
+                    order.lock().remedy().push(i);
+                })
+            };
+            wait::Spin::wait_for(|| lock.queued() == i + 2, LONG_WAIT).unwrap();
+            t
+        })
+        .collect::<Vec<_>>();
+
+    drop(guard);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!((0..THREADS).collect::<Vec<_>>(), *order.lock().remedy());
+}
+
+#[test]
+fn debug() {
+    let lock = TicketLock::new(42);
+    assert_eq!("TicketLock { data: 42, .. }", format!("{lock:?}"));
+    let _guard = lock.lock();
+    assert_eq!("TicketLock { data: <locked>, .. }", format!("{lock:?}"));
+}
@@ -0,0 +1,121 @@
+//! Integration with the [`tracing`](https://docs.rs/tracing) crate, enabled by the `tracing`
+//! feature.
+//!
+//! When enabled, every [`ZLock`](crate::zlock::ZLock) acquisition opens a `zlock` span at the
+//! `TRACE` level, which is entered when the guard is created and closed when the lock is released.
+//! The span records the moderator, the [`Mode`](crate::instrument::Mode) of acquisition and the time spent waiting
+//! for the lock; upon release, it also records the time for which the lock was held. A lock held
+//! across an upgrade or a downgrade is covered by a single span. Acquisitions that time out
+//! are reported as `DEBUG` events.
+//!
+//! Without the feature, the hooks compile to nothing.
+
+#[cfg(not(feature = "tracing"))]
+use crate::instrument::Mode;
+
+#[cfg(feature = "tracing")]
+pub(crate) use traced::Attempt;
+
+/// An acquisition attempt in progress.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Attempt;
+
+#[cfg(not(feature = "tracing"))]
+impl Attempt {
+    /// Signals the start of an acquisition attempt in the given `mode`, for a lock governed
+    /// by the named `moderator`.
+    #[inline(always)]
+    pub(crate) fn begin(_moderator: &'static str, _mode: Mode) -> Self {
+        Self
+    }
+
+    /// Signals that the lock at `addr` was acquired.
+    #[inline(always)]
+    pub(crate) fn acquired(self, _addr: usize) {}
+
+    /// Signals that the attempt timed out.
+    #[inline(always)]
+    pub(crate) fn timed_out(self) {}
+}
+
+/// Signals the release of the lock at `addr`, closing its span.
+#[cfg(not(feature = "tracing"))]
+#[inline(always)]
+pub(crate) fn released(_addr: usize) {}
+
+#[cfg(feature = "tracing")]
+pub(crate) use traced::released;
+
+#[cfg(feature = "tracing")]
+mod traced {
+    use std::cell::RefCell;
+    use std::time::Instant;
+    use tracing::field;
+    use tracing::span::EnteredSpan;
+    use crate::instrument::Mode;
+    use crate::zlock::short_type_name;
+
+    thread_local! {
+        /// The spans of locks held by the current thread, along with the time of acquisition.
+        pub(super) static HELD: RefCell<Vec<(usize, EnteredSpan, Instant)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub(crate) struct Attempt {
+        moderator: &'static str,
+        mode: Mode,
+        start: Instant,
+    }
+
+    impl Attempt {
+        #[inline]
+        pub(crate) fn begin(moderator: &'static str, mode: Mode) -> Self {
+            Self {
+                moderator,
+                mode,
+                start: Instant::now(),
+            }
+        }
+
+        #[inline]
+        pub(crate) fn acquired(self, addr: usize) {
+            let now = Instant::now();
+            let span = tracing::trace_span!(
+                "zlock",
+                moderator = %short_type_name(self.moderator),
+                mode = ?self.mode,
+                waited = ?(now - self.start),
+                held = field::Empty,
+            );
+            if !span.is_disabled() {
+                let span = span.entered();
+                tracing::trace!("lock acquired");
+                HELD.with_borrow_mut(|held| held.push((addr, span, now)));
+            }
+        }
+
+        #[inline]
+        pub(crate) fn timed_out(self) {
+            tracing::debug!(
+                moderator = %short_type_name(self.moderator),
+                mode = ?self.mode,
+                waited = ?self.start.elapsed(),
+                "lock acquisition timed out"
+            );
+        }
+    }
+
+    #[inline]
+    pub(crate) fn released(addr: usize) {
+        let held = HELD.with_borrow_mut(|held| {
+            let index = held.iter().rposition(|&(held, _, _)| held == addr)?;
+            Some(held.swap_remove(index))
+        });
+        if let Some((_, span, acquired)) = held {
+            span.record("held", field::debug(acquired.elapsed()));
+            tracing::trace!(parent: &*span, "lock released");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests;
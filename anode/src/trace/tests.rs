@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use crate::remedy::Remedy;
+use crate::test_utils::SHORT_WAIT;
+use crate::trace::traced::HELD;
+use crate::zlock::{ArrivalOrdered, ReadBiased, ZLock};
+
+#[derive(Debug, Default)]
+struct SpanRecord {
+    name: &'static str,
+    fields: HashMap<&'static str, String>,
+}
+
+#[derive(Debug)]
+struct EventRecord {
+    level: tracing::Level,
+    parent: Option<u64>,
+    fields: HashMap<&'static str, String>,
+}
+
+#[derive(Default)]
+struct Recorded {
+    spans: Mutex<Vec<SpanRecord>>,
+    events: Mutex<Vec<EventRecord>>,
+    current: Mutex<Vec<u64>>,
+    next_id: AtomicU64,
+}
+
+/// A subscriber that records all spans and events.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Recorded>);
+
+struct FieldVisitor<'a>(&'a mut HashMap<&'static str, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut record = SpanRecord {
+            name: span.metadata().name(),
+            ..SpanRecord::default()
+        };
+        span.record(&mut FieldVisitor(&mut record.fields));
+        self.0.spans.lock().remedy().push(record);
+        Id::from_u64(self.0.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.0.spans.lock().remedy();
+        values.record(&mut FieldVisitor(&mut spans[span.into_u64() as usize - 1].fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = HashMap::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        let parent = event
+            .parent()
+            .map(Id::into_u64)
+            .or_else(|| self.0.current.lock().remedy().last().copied());
+        self.0.events.lock().remedy().push(EventRecord {
+            level: *event.metadata().level(),
+            parent,
+            fields,
+        });
+    }
+
+    fn enter(&self, span: &Id) {
+        self.0.current.lock().remedy().push(span.into_u64());
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut current = self.0.current.lock().remedy();
+        let index = current.iter().rposition(|&id| id == span.into_u64()).unwrap();
+        current.remove(index);
+    }
+}
+
+impl Recorder {
+    fn spans(&self) -> std::sync::MutexGuard<'_, Vec<SpanRecord>> {
+        self.0.spans.lock().remedy()
+    }
+
+    fn messages(&self) -> Vec<(tracing::Level, Option<u64>, String)> {
+        self.0
+            .events
+            .lock()
+            .remedy()
+            .iter()
+            .map(|event| (event.level, event.parent, event.fields["message"].clone()))
+            .collect()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.0.current.lock().remedy().is_empty()
+    }
+}
+
+fn held() -> usize {
+    HELD.with_borrow(Vec::len)
+}
+
+#[test]
+fn write_span() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let lock = ZLock::<_, ReadBiased>::new(0);
+        let guard = lock.write();
+        assert_eq!(1, held());
+        {
+            let spans = recorder.spans();
+            assert_eq!(1, spans.len());
+            assert_eq!("zlock", spans[0].name);
+            assert_eq!("ReadBiased", spans[0].fields["moderator"]);
+            assert_eq!("Write", spans[0].fields["mode"]);
+            assert!(spans[0].fields.contains_key("waited"));
+            assert!(!spans[0].fields.contains_key("held"));
+        }
+        assert!(!recorder.is_idle());
+
+        drop(guard);
+        assert_eq!(0, held());
+        assert!(recorder.spans()[0].fields.contains_key("held"));
+        assert!(recorder.is_idle());
+        assert_eq!(
+            vec![
+                (tracing::Level::TRACE, Some(1), "lock acquired".to_owned()),
+                (tracing::Level::TRACE, Some(1), "lock released".to_owned())
+            ],
+            recorder.messages()
+        );
+    });
+}
+
+#[test]
+fn downgrade_is_single_span() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let lock = ZLock::<_, ArrivalOrdered>::new(0);
+        drop(lock.write().downgrade());
+        let spans = recorder.spans();
+        assert_eq!(1, spans.len());
+        assert_eq!("ArrivalOrdered", spans[0].fields["moderator"]);
+        assert!(spans[0].fields.contains_key("held"));
+        assert_eq!(0, held());
+    });
+}
+
+#[test]
+fn nested_spans_released_out_of_order() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let lock_1 = ZLock::<_, ReadBiased>::new(0);
+        let lock_2 = ZLock::<_, ReadBiased>::new(0);
+        let guard_1 = lock_1.read();
+        let guard_2 = lock_2.read();
+        assert_eq!(2, held());
+        drop(guard_1);
+        assert!(recorder.spans()[0].fields.contains_key("held"));
+        assert!(!recorder.spans()[1].fields.contains_key("held"));
+        drop(guard_2);
+        assert_eq!(0, held());
+        assert!(recorder.is_idle());
+
+        let released = recorder
+            .messages()
+            .into_iter()
+            .filter(|(_, _, message)| message == "lock released")
+            .map(|(_, parent, _)| parent)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![Some(1), Some(2)], released);
+    });
+}
+
+#[test]
+fn timed_out() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let lock = ZLock::<_, ReadBiased>::new(0);
+        let guard = lock.write();
+        assert!(lock.try_read(SHORT_WAIT).is_none());
+        assert!(lock.try_write(Duration::ZERO).is_none());
+        drop(guard);
+
+        let timeouts = recorder
+            .0
+            .events
+            .lock()
+            .remedy()
+            .iter()
+            .filter(|event| event.level == tracing::Level::DEBUG)
+            .map(|event| (event.fields["mode"].clone(), event.fields["message"].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("Read".to_owned(), "lock acquisition timed out".to_owned()),
+                ("Write".to_owned(), "lock acquisition timed out".to_owned())
+            ],
+            timeouts
+        );
+        assert_eq!(1, recorder.spans().len());
+    });
+}
+
+#[test]
+fn no_subscriber() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    drop(lock.write());
+    assert_eq!(0, held());
+}
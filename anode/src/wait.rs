@@ -8,6 +8,7 @@ use crate::rand::{FIXED_DURATION};
 
 pub type WaitResult = Result<(), ()>;
 
+#[allow(clippy::result_unit_err)]
 pub trait Wait {
     fn wait_until<C>(condition: C, deadline: Deadline) -> WaitResult
     where
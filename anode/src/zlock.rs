@@ -1,32 +1,136 @@
-use std::cell::UnsafeCell;
-use std::fmt;
-use std::fmt::Debug;
-use std::marker::PhantomData;
-use std::ops::{Deref, DerefMut};
-use std::ptr::NonNull;
-use std::time::Duration;
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::task::Waker;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use crate::deadline::Deadline;
+use crate::deadlock;
+use crate::held;
+use crate::instrument::{Instrumentation, Mode};
+use crate::trace;
 
+#[cfg(feature = "std")]
 mod read_biased;
+#[cfg(feature = "std")]
 mod write_biased;
+#[cfg(feature = "std")]
 mod arrival_ordered;
+#[cfg(feature = "std")]
 mod stochastic;
+#[cfg(feature = "std")]
+mod registration;
+mod spin_moderator;
+#[cfg(feature = "std")]
+mod upgrade_biased;
+#[cfg(feature = "std")]
+mod priority_ordered;
+#[cfg(feature = "std")]
+mod scheduler_aware;
+#[cfg(feature = "std")]
 mod legacy_read_biased;
+#[cfg(feature = "std")]
 mod legacy_write_biased;
+#[cfg(feature = "std")]
 mod legacy_arrival_ordered;
+#[cfg(feature = "std")]
+mod lock_condvar;
+#[cfg(feature = "std")]
+mod hierarchical_lock;
+mod raw_lock;
+mod arc_guard;
+#[cfg(feature = "std")]
+mod cancellable;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod multi_lock;
+#[cfg(feature = "std")]
+mod striped_lock;
+#[cfg(feature = "std")]
+mod brave_lock;
+#[cfg(feature = "std")]
+mod optimistic_lock;
+#[cfg(feature = "htm")]
+mod elided_lock;
+#[cfg(all(feature = "futex", target_os = "linux"))]
+mod futex;
 
+#[cfg(feature = "std")]
 pub use read_biased::ReadBiased;
+#[cfg(feature = "std")]
 pub use write_biased::WriteBiased;
+#[cfg(feature = "std")]
 pub use arrival_ordered::ArrivalOrdered;
+#[cfg(feature = "std")]
 pub use stochastic::Stochastic;
+pub use spin_moderator::SpinModerator;
+#[cfg(feature = "std")]
+pub use upgrade_biased::UpgradeBiased;
+#[cfg(feature = "std")]
+pub use priority_ordered::PriorityOrdered;
+#[cfg(feature = "std")]
+pub use scheduler_aware::SchedulerAware;
+#[cfg(feature = "std")]
 pub use legacy_read_biased::LegacyReadBiased;
+#[cfg(feature = "std")]
 pub use legacy_write_biased::LegacyWriteBiased;
+#[cfg(feature = "std")]
 pub use legacy_arrival_ordered::LegacyArrivalOrdered;
+#[cfg(feature = "std")]
+pub use lock_condvar::LockCondvar;
+#[cfg(feature = "std")]
+pub use hierarchical_lock::{HierarchicalLock, HierarchicalReadGuard, HierarchicalWriteGuard};
+pub use raw_lock::RawZLock;
+pub use arc_guard::{ArcLockReadGuard, ArcLockWriteGuard};
+#[cfg(feature = "std")]
+pub use cancellable::WaitError;
+#[cfg(feature = "std")]
+pub use stats::{LockStats, Stats};
+#[cfg(feature = "std")]
+pub use multi_lock::{__acquire_all, lock_both};
+#[cfg(feature = "std")]
+pub use striped_lock::StripedLock;
+#[cfg(feature = "std")]
+pub use brave_lock::{BraveLock, BraveReadGuard, BraveWriteGuard};
+#[cfg(feature = "std")]
+pub use optimistic_lock::{OptimisticLock, OptimisticReadGuard, OptimisticWriteGuard};
+#[cfg(feature = "htm")]
+pub use elided_lock::ElidedLock;
+#[cfg(all(feature = "futex", target_os = "linux"))]
+pub use futex::Futex;
 
 unsafe impl<T: ?Sized + Send, M: Moderator> Send for ZLock<T, M> {}
 unsafe impl<T: ?Sized + Send + Sync, M: Moderator> Sync for ZLock<T, M> {}
 unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for LockReadGuard<'_, T, M> {}
 unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for LockWriteGuard<'_, T, M> {}
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for LockUpgradableGuard<'_, T, M> {}
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for LockDowngradableGuard<'_, T, M> {}
 
+/// Governs the admission of readers and writers to a [`ZLock`], holding its state in
+/// [`Sync`](Self::Sync).
+///
+/// Besides mutual exclusion, an implementation must honour bounded waits of any duration,
+/// perform [`downgrade`](Self::downgrade) and [`try_upgrade`](Self::try_upgrade) without
+/// admitting another writer in the interim, and permit a lock to be released by a thread other
+/// than the one that acquired it (see [`ZLock::force_unlock_write`]). These requirements rule out
+/// thinly wrapping most OS-native locks: e.g., Windows `SRWLOCK` has neither timed acquisition
+/// nor atomic downgrades, and macOS `os_unfair_lock` has no shared mode and must be unlocked by
+/// its owner. Such locks are instead offered as [`Locklike`](locklike::Locklike) adapters; see
+/// [`lock_box_native`](locklike::lock_box_native). (The moderators built on
+/// [`Mutex`](std::sync::Mutex) and [`Condvar`](std::sync::Condvar) already use the native
+/// primitives of each platform, by way of the standard library.)
 pub trait Moderator: Debug {
     type Sync;
 
@@ -43,10 +147,181 @@ pub trait Moderator: Debug {
     fn downgrade(sync: &Self::Sync);
 
     fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool;
+
+    /// Claims the lock's upgradable slot, waiting at most `duration` while another thread
+    /// holds it. The slot is claimed by at most one thread at a time, and is independent of
+    /// the read and write locks: it neither excludes nor is excluded by readers and writers,
+    /// only by other claimants. Returns `true` if the slot was claimed.
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool;
+
+    /// Releases the upgradable slot, waking any threads waiting to claim it.
+    fn release_upgradable(sync: &Self::Sync);
+
+    /// Formats the live state of the lock (reader and writer counts, etc.) for diagnostic
+    /// purposes. Implementations must not block; if the state cannot be accessed immediately,
+    /// a non-exhaustive placeholder should be written instead.
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /// Determines whether a writer is presently waiting to acquire the lock. The result is a
+    /// racy snapshot, suitable only for heuristics (such as readers voluntarily yielding to
+    /// a writer).
+    ///
+    /// The default implementation returns `false`, for moderators that do not track
+    /// waiting writers.
+    #[inline]
+    fn is_writer_waiting(_sync: &Self::Sync) -> bool {
+        false
+    }
+
+    /// Attempts to acquire a read lock without blocking. If the lock cannot be acquired,
+    /// `waker` is registered, to be woken when the lock is next released, such that an
+    /// external executor may wait for the lock without parking a thread.
+    ///
+    /// A woken waker must poll again, and its poll may still fail, as another thread may
+    /// have acquired the lock in the meantime. I.e., wakeups are a hint, never a guarantee.
+    ///
+    /// The default implementation returns [`Polled::Unsupported`].
+    #[inline]
+    fn poll_read(_sync: &Self::Sync, _waker: &Waker) -> Polled<()> {
+        Polled::Unsupported
+    }
+
+    /// The write equivalent of [`poll_read`](Self::poll_read), subject to the same contract.
+    ///
+    /// The default implementation returns [`Polled::Unsupported`].
+    #[inline]
+    fn poll_write(_sync: &Self::Sync, _waker: &Waker) -> Polled<()> {
+        Polled::Unsupported
+    }
+}
+
+/// A [`Moderator`] whose initial state may be constructed in a `const` context, such that its
+/// locks may be placed in `static` items using [`ZLock::const_new`].
+pub trait ConstModerator: Moderator {
+    /// The initial state of the lock, equivalent to that returned by [`Moderator::new`].
+    const INIT: Self::Sync;
+}
+
+/// A [`Moderator`] whose initial state is derived from a user-supplied configuration, such
+/// that its locks may be created using [`ZLock::with_config`].
+///
+/// # Examples
+/// ```
+/// use anode::backoff::ExpBackoff;
+/// use anode::zlock::{SchedulerAware, ZLock};
+/// let lock = ZLock::<_, SchedulerAware>::with_config(ExpBackoff::yieldy(), 0);
+/// *lock.write() = 42;
+/// assert_eq!(42, *lock.read());
+/// ```
+pub trait ConfigurableModerator: Moderator {
+    type Config;
+
+    /// Creates the state of the lock from `config`. The state returned by [`Moderator::new`]
+    /// should be equivalent to that created from the default configuration.
+    fn new_with(config: Self::Config) -> Self::Sync;
+}
+
+/// The outcome of polling a lock on behalf of an external waiter.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Polled<G> {
+    /// The lock was acquired.
+    Acquired(G),
+
+    /// The lock could not be acquired; the waker has been registered.
+    Pending,
+
+    /// The moderator does not support external waiters.
+    Unsupported,
+}
+
+impl<G> Polled<G> {
+    #[inline]
+    pub fn is_acquired(&self) -> bool {
+        matches!(self, Polled::Acquired(_))
+    }
+
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Polled::Pending)
+    }
+
+    #[inline]
+    pub fn is_unsupported(&self) -> bool {
+        matches!(self, Polled::Unsupported)
+    }
+
+    #[inline]
+    pub fn acquired(self) -> Option<G> {
+        match self {
+            Polled::Acquired(guard) => Some(guard),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn map<GG>(self, f: impl FnOnce(G) -> GG) -> Polled<GG> {
+        match self {
+            Polled::Acquired(guard) => Polled::Acquired(f(guard)),
+            Polled::Pending => Polled::Pending,
+            Polled::Unsupported => Polled::Unsupported,
+        }
+    }
+}
+
+/// A set of wakers belonging to external waiters, for moderators that support them.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub(crate) struct Wakers(Vec<Waker>);
+
+#[cfg(feature = "std")]
+impl Wakers {
+    #[inline]
+    pub(crate) fn register(&mut self, waker: &Waker) {
+        if !self.0.iter().any(|registered| registered.will_wake(waker)) {
+            self.0.push(waker.clone());
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take(&mut self) -> Self {
+        Self(core::mem::take(&mut self.0))
+    }
+
+    /// Wakes all wakers in the set. Should only be called once the moderator's internal
+    /// state has been unlocked, as a waker may execute arbitrary code.
+    #[inline]
+    pub(crate) fn wake_all(self) {
+        self.0.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Performs a (potentially blocking) acquisition of the lock at `addr`, moderated by
+/// `moderator`, registering the wait and the resulting hold with the deadlock detector and
+/// the held lock registry.
+#[inline(always)]
+fn acquire_tracked(addr: usize, moderator: &'static str, mode: Mode, duration: Duration, f: impl FnOnce() -> bool) -> bool {
+    if !duration.is_zero() {
+        deadlock::waiting(addr);
+    }
+    let acquired = f();
+    if acquired {
+        deadlock::acquired(addr);
+        held::acquired(addr, moderator, mode);
+    } else if !duration.is_zero() {
+        deadlock::abandoned();
+    }
+    acquired
 }
 
 pub struct ZLock<T: ?Sized, M: Moderator> {
     sync: M::Sync,
+    instrument: Instrumentation,
+
+    /// Guards hold no pointer to the data of their own; each dereference derives a fresh
+    /// reference from [`UnsafeCell::get`], bounded by the borrow of the guard. The moderator
+    /// makes this sound: a shared reference is only derived while a read lock is held (or a write
+    /// lock, through `&self`), and a unique reference only while a write lock is held, through
+    /// `&mut self`.
     data: UnsafeCell<T>,
 }
 
@@ -55,76 +330,560 @@ impl<T, M: Moderator> ZLock<T, M> {
     pub fn new(t: T) -> Self {
         Self {
             sync: M::new(),
+            instrument: Instrumentation::NONE,
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Creates a lock in a `const` context, making it suitable for a `static`. Available for
+    /// moderators implementing [`ConstModerator`].
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{SpinModerator, ZLock};
+    /// static COUNTER: ZLock<u64, SpinModerator> = ZLock::const_new(0);
+    /// *COUNTER.write() += 1;
+    /// assert_eq!(1, *COUNTER.read());
+    /// ```
+    #[inline]
+    pub const fn const_new(t: T) -> Self
+    where
+        M: ConstModerator,
+    {
+        Self {
+            sync: M::INIT,
+            instrument: Instrumentation::NONE,
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Creates a lock whose events are reported to `sink` under the given `name`. See the
+    /// [`instrument`](crate::instrument) module.
+    #[cfg(feature = "instrument")]
+    #[inline]
+    pub fn instrumented(t: T, name: impl Into<String>, sink: alloc::sync::Arc<dyn crate::instrument::EventSink>) -> Self {
+        Self {
+            sync: M::new(),
+            instrument: Instrumentation::new(name, sink),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    /// Creates a lock using a preconfigured moderator state, for moderators that offer
+    /// configuration beyond their defaults. (E.g., [`ReadBiased::with_writer_grace`].)
+    #[inline]
+    pub fn with_sync(sync: M::Sync, t: T) -> Self {
+        Self {
+            sync,
+            instrument: Instrumentation::NONE,
             data: UnsafeCell::new(t),
         }
     }
 
+    /// Creates a lock whose moderator state is derived from `config`. Available for moderators
+    /// implementing [`ConfigurableModerator`].
+    #[inline]
+    pub fn with_config(config: M::Config, t: T) -> Self
+    where
+        M: ConfigurableModerator,
+    {
+        Self::with_sync(M::new_with(config), t)
+    }
+
     pub fn into_inner(self) -> T {
         self.data.into_inner()
     }
+
+    /// Replaces the guarded value with `t` under a write lock, returning the old value.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(42);
+    /// assert_eq!(42, lock.replace(69));
+    /// assert_eq!(69, *lock.read());
+    /// ```
+    #[inline]
+    pub fn replace(&self, t: T) -> T {
+        core::mem::replace(&mut *self.write(), t)
+    }
+
+    /// Swaps the guarded values of `self` and `other`, write-locking both.
+    ///
+    /// The two locks are always acquired in the order of their addresses (lowest first),
+    /// irrespective of which is `self` and which is `other`. Thus, two threads swapping the
+    /// same pair of locks in opposite argument order cannot deadlock. Any other code that
+    /// holds one of these locks while acquiring the other should follow the same rule.
+    ///
+    /// Swapping a lock with itself is a no-op.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let a = ZLock::<_, ReadBiased>::new(42);
+    /// let b = ZLock::<_, ReadBiased>::new(69);
+    /// a.swap(&b);
+    /// assert_eq!(69, *a.read());
+    /// assert_eq!(42, *b.read());
+    /// ```
+    #[inline]
+    pub fn swap(&self, other: &ZLock<T, M>) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+
+        let (first, second) = if (self as *const Self) < (other as *const Self) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first = first.write();
+        let mut second = second.write();
+        core::mem::swap(&mut *first, &mut *second);
+    }
 }
 
 impl<T: ?Sized, M: Moderator> ZLock<T, M> {
     #[inline]
     pub fn read(&self) -> LockReadGuard<'_, T, M> {
+        held::assert_not_held(deadlock::addr_of(self), Mode::Read);
         self.try_read(Duration::MAX).unwrap()
     }
 
     #[inline]
     pub fn try_read(&self, duration: Duration) -> Option<LockReadGuard<'_, T, M>> {
-        if M::try_read(&self.sync, duration) {
-            let data = unsafe { NonNull::new_unchecked(self.data.get()) };
+        if self.acquire(Mode::Read, duration, |duration| M::try_read(&self.sync, duration)) {
             Some(LockReadGuard {
-                data,
                 lock: self,
                 locked: true,
-                __no_send: PhantomData::default(),
+                __no_send: PhantomData,
             })
         } else {
             None
         }
     }
 
+    /// Attempts to acquire a read lock within the given `duration`, returning a [`Timeout`]
+    /// error if the lock could not be acquired in time. This is a variant of
+    /// [`try_read`](Self::try_read) that composes with `?`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::zlock::{ReadBiased, Timeout, ZLock};
+    /// fn read_it(lock: &ZLock<u64, ReadBiased>) -> Result<u64, Timeout> {
+    ///     let guard = lock.read_timeout(Duration::from_millis(10))?;
+    ///     Ok(*guard)
+    /// }
+    /// let lock = ZLock::new(42);
+    /// assert_eq!(Ok(42), read_it(&lock));
+    /// let _guard = lock.write();
+    /// assert_eq!(Err(Timeout::new(Duration::from_millis(10))), read_it(&lock));
+    /// ```
+    #[inline]
+    pub fn read_timeout(&self, duration: Duration) -> Result<LockReadGuard<'_, T, M>, Timeout> {
+        self.try_read(duration).ok_or(Timeout::new(duration))
+    }
+
+    /// Runs `f` under a read lock, which is released when `f` returns.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(vec![1, 2]);
+    /// lock.with_write(|vec| vec.push(3));
+    /// assert_eq!(3, lock.with_read(|vec| vec.len()));
+    /// ```
+    #[inline]
+    pub fn with_read<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        f(&self.read())
+    }
+
+    /// Runs `f` under a read lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    pub fn try_with_read<U>(&self, duration: Duration, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.try_read(duration).map(|guard| f(&guard))
+    }
+
+    /// Attempts to acquire a read lock within the given `duration`, returning the guard along
+    /// with the portion of `duration` that was left unused, as measured from the start of the
+    /// attempt. A [`Duration::MAX`] budget is never consumed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_read_budgeted(&self, duration: Duration) -> Option<(LockReadGuard<'_, T, M>, Duration)> {
+        // a single attempt, so as not to forfeit the waiter's place in the moderator's queue
+        let mut deadline = Deadline::after(duration);
+        self.try_read(duration)
+            .map(|guard| (guard, deadline.remaining()))
+    }
+
+    /// Attempts to acquire a read lock before the given `deadline` elapses. A deadline shared
+    /// across several acquisitions bounds their combined wait.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::deadline::Deadline;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let (a, b) = (ZLock::<_, ReadBiased>::new(1), ZLock::<_, ReadBiased>::new(2));
+    /// let mut deadline = Deadline::lazy_after(Duration::from_millis(10));
+    /// let guard_a = a.try_read_until(&mut deadline).unwrap();
+    /// let guard_b = b.try_read_until(&mut deadline).unwrap();
+    /// assert_eq!(3, *guard_a + *guard_b);
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_read_until(&self, deadline: impl Into<Deadline>) -> Option<LockReadGuard<'_, T, M>> {
+        self.try_read(deadline.into().remaining())
+    }
+
+    /// Determines whether a writer is presently waiting to acquire this lock, allowing readers
+    /// to apply backpressure by voluntarily releasing their locks. The result is a racy snapshot.
+    /// See [`Moderator::is_writer_waiting`].
+    #[inline]
+    pub fn is_writer_waiting(&self) -> bool {
+        M::is_writer_waiting(&self.sync)
+    }
+
+    /// Attempts to acquire a read lock on behalf of an external waiter, registering `waker`
+    /// if the lock cannot be acquired immediately. See [`Moderator::poll_read`] for the contract.
+    #[inline]
+    pub fn poll_read(&self, waker: &Waker) -> Polled<LockReadGuard<'_, T, M>> {
+        M::poll_read(&self.sync, waker).map(|_| {
+            deadlock::acquired(deadlock::addr_of(self));
+            held::acquired(deadlock::addr_of(self), core::any::type_name::<M>(), Mode::Read);
+            self.instrument.begin(Mode::Read).acquired(deadlock::addr_of(self));
+            trace::Attempt::begin(core::any::type_name::<M>(), Mode::Read).acquired(deadlock::addr_of(self));
+            LockReadGuard {
+                lock: self,
+                locked: true,
+                __no_send: PhantomData,
+            }
+        })
+    }
+
+    /// Acquires the lock in the given `mode` by invoking `f` with the permitted wait duration.
+    /// An instrumented lock first makes a non-blocking attempt, so that contention is reported
+    /// before the thread begins waiting.
+    #[inline(always)]
+    fn acquire(&self, mode: Mode, duration: Duration, mut f: impl FnMut(Duration) -> bool) -> bool {
+        let addr = deadlock::addr_of(self);
+        let moderator = core::any::type_name::<M>();
+        let attempt = self.instrument.begin(mode);
+        let traced = trace::Attempt::begin(moderator, mode);
+        if self.instrument.is_enabled() && !duration.is_zero() {
+            if acquire_tracked(addr, moderator, mode, Duration::ZERO, || f(Duration::ZERO)) {
+                attempt.acquired(addr);
+                traced.acquired(addr);
+                return true;
+            }
+            attempt.contended();
+        }
+
+        if acquire_tracked(addr, moderator, mode, duration, || f(duration)) {
+            attempt.acquired(addr);
+            traced.acquired(addr);
+            true
+        } else {
+            attempt.timed_out();
+            traced.timed_out();
+            false
+        }
+    }
+
     #[inline]
     fn read_unlock(&self) {
-        M::read_unlock(&self.sync);
+        release::<M>(&self.sync, &self.instrument, deadlock::addr_of(self), Mode::Read);
+    }
+
+    /// Releases a read lock whose hold is no longer registered with the deadlock detector and
+    /// the held lock registry (see [`disown`](Self::disown)).
+    #[inline]
+    fn read_unlock_untracked(&self) {
+        release_untracked::<M>(&self.sync, &self.instrument, deadlock::addr_of(self), Mode::Read);
+    }
+
+    /// Deregisters the current thread's hold of this lock from the deadlock detector and the
+    /// held lock registry, on behalf of a guard that is about to be forgotten, or that may be
+    /// released on another thread. The registries only track holds that are released on the
+    /// thread that acquired them.
+    #[inline]
+    fn disown(&self) {
+        let addr = deadlock::addr_of(self);
+        deadlock::released(addr);
+        held::released(addr);
+    }
+
+    /// Releases a read lock whose guard was relinquished with [`LockReadGuard::forget`].
+    ///
+    /// # Safety
+    /// The lock must be read-locked, and the read lock must not be owned by a live guard:
+    /// every call must be matched with a prior [`LockReadGuard::forget`]. Releasing a read lock
+    /// that is not held corrupts the moderator's state, and may admit a writer alongside readers.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(42);
+    /// lock.read().forget();
+    /// assert!(lock.try_write(Duration::ZERO).is_none());
+    /// unsafe { lock.force_unlock_read() };
+    /// assert!(lock.try_write(Duration::ZERO).is_some());
+    /// ```
+    #[inline]
+    pub unsafe fn force_unlock_read(&self) {
+        self.read_unlock_untracked();
     }
 
     #[inline]
     pub fn write(&self) -> LockWriteGuard<'_, T, M> {
+        held::assert_not_held(deadlock::addr_of(self), Mode::Write);
         self.try_write(Duration::MAX).unwrap()
     }
 
     #[inline]
     pub fn try_write(&self, duration: Duration) -> Option<LockWriteGuard<'_, T, M>> {
-        if M::try_write(&self.sync, duration) {
+        if self.acquire(Mode::Write, duration, |duration| M::try_write(&self.sync, duration)) {
             Some(LockWriteGuard {
                 lock: self,
                 locked: true,
-                __no_send: PhantomData::default(),
+                __no_send: PhantomData,
             })
         } else {
             None
         }
     }
 
+    /// Attempts to acquire a write lock within the given `duration`, returning a [`Timeout`]
+    /// error if the lock could not be acquired in time. This is a variant of
+    /// [`try_write`](Self::try_write) that composes with `?`.
+    #[inline]
+    pub fn write_timeout(&self, duration: Duration) -> Result<LockWriteGuard<'_, T, M>, Timeout> {
+        self.try_write(duration).ok_or(Timeout::new(duration))
+    }
+
+    /// Runs `f` under a write lock, which is released when `f` returns.
+    #[inline]
+    pub fn with_write<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        f(&mut self.write())
+    }
+
+    /// Runs `f` under a write lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    pub fn try_with_write<U>(&self, duration: Duration, f: impl FnOnce(&mut T) -> U) -> Option<U> {
+        self.try_write(duration).map(|mut guard| f(&mut guard))
+    }
+
+    /// Attempts to acquire a write lock within the given `duration`, returning the guard along
+    /// with the portion of `duration` that was left unused, as measured from the start of the
+    /// attempt. A [`Duration::MAX`] budget is never consumed.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_write_budgeted(&self, duration: Duration) -> Option<(LockWriteGuard<'_, T, M>, Duration)> {
+        // a single attempt, so as not to forfeit the waiter's place in the moderator's queue
+        let mut deadline = Deadline::after(duration);
+        self.try_write(duration)
+            .map(|guard| (guard, deadline.remaining()))
+    }
+
+    /// Attempts to acquire a write lock before the given `deadline` elapses.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn try_write_until(&self, deadline: impl Into<Deadline>) -> Option<LockWriteGuard<'_, T, M>> {
+        self.try_write(deadline.into().remaining())
+    }
+
+    /// Attempts to acquire a write lock on behalf of an external waiter, registering `waker`
+    /// if the lock cannot be acquired immediately. See [`Moderator::poll_write`] for the contract.
+    #[inline]
+    pub fn poll_write(&self, waker: &Waker) -> Polled<LockWriteGuard<'_, T, M>> {
+        M::poll_write(&self.sync, waker).map(|_| {
+            deadlock::acquired(deadlock::addr_of(self));
+            held::acquired(deadlock::addr_of(self), core::any::type_name::<M>(), Mode::Write);
+            self.instrument.begin(Mode::Write).acquired(deadlock::addr_of(self));
+            trace::Attempt::begin(core::any::type_name::<M>(), Mode::Write).acquired(deadlock::addr_of(self));
+            LockWriteGuard {
+                lock: self,
+                locked: true,
+                __no_send: PhantomData,
+            }
+        })
+    }
+
     #[inline]
     fn write_unlock(&self) {
-        M::write_unlock(&self.sync);
+        release::<M>(&self.sync, &self.instrument, deadlock::addr_of(self), Mode::Write);
+    }
+
+    /// Releases a write lock whose hold is no longer registered. See
+    /// [`read_unlock_untracked`](Self::read_unlock_untracked).
+    #[inline]
+    fn write_unlock_untracked(&self) {
+        release_untracked::<M>(&self.sync, &self.instrument, deadlock::addr_of(self), Mode::Write);
+    }
+
+    /// An [`Unlocker`] that releases this lock in the given `mode`, on behalf of mapped guards.
+    #[inline]
+    fn unlocker(&self, mode: Mode) -> Unlocker<'_> {
+        Unlocker {
+            sync: NonNull::from(&self.sync).cast(),
+            instrument: &self.instrument,
+            addr: deadlock::addr_of(self),
+            mode,
+            release: |sync, instrument, addr, mode| {
+                // SAFETY: the pointer was derived from a reference to the moderator state of this
+                // lock, which outlives the unlocker
+                release::<M>(unsafe { sync.cast::<M::Sync>().as_ref() }, instrument, addr, mode)
+            },
+        }
+    }
+
+    /// Releases a write lock whose guard was relinquished with [`LockWriteGuard::forget`].
+    ///
+    /// # Safety
+    /// The lock must be write-locked, and the write lock must not be owned by a live guard:
+    /// every call must be matched with a prior [`LockWriteGuard::forget`]. Any references to
+    /// the data obtained through the forgotten guard must no longer be in use.
+    #[inline]
+    pub unsafe fn force_unlock_write(&self) {
+        self.write_unlock_untracked();
     }
 
     #[inline]
-    pub fn downgrade(&self) -> LockReadGuard<T, M> {
+    pub fn downgrade(&self) -> LockReadGuard<'_, T, M> {
         M::downgrade(&self.sync);
-        let data = unsafe { NonNull::new_unchecked(self.data.get()) };
+        held::converted(deadlock::addr_of(self), Mode::Read);
         LockReadGuard {
-            data,
             lock: self,
             locked: true,
-            __no_send: PhantomData::default(),
+            __no_send: PhantomData,
         }
     }
 
+    /// Acquires an upgradable read lock. An upgradable guard coexists with plain readers, but
+    /// at most one upgradable guard exists at any time; a second caller blocks until the
+    /// first guard is released, upgraded or downgraded.
+    ///
+    /// Because of this exclusivity, [`LockUpgradableGuard::upgrade`] cannot deadlock against
+    /// another upgradable guard, as [`LockReadGuard::upgrade`] does when two readers upgrade
+    /// concurrently. (An upgradable guard may still deadlock with a plain reader that
+    /// attempts to upgrade at the same time.)
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(41);
+    /// let guard = lock.read_upgradable();
+    /// let reader = lock.read();
+    /// assert_eq!(41, *guard);
+    /// drop(reader);
+    /// let mut guard = guard.upgrade();
+    /// *guard += 1;
+    /// drop(guard);
+    /// assert_eq!(42, *lock.read());
+    /// ```
+    #[inline]
+    pub fn read_upgradable(&self) -> LockUpgradableGuard<'_, T, M> {
+        self.try_read_upgradable(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire an upgradable read lock within the given `duration`. The wait
+    /// for the upgradable slot and the wait for the read lock are both bounded by `duration`.
+    #[inline]
+    pub fn try_read_upgradable(&self, duration: Duration) -> Option<LockUpgradableGuard<'_, T, M>> {
+        #[cfg(feature = "std")]
+        let mut deadline = Deadline::after(duration);
+        if !self.claim_upgradable(duration) {
+            return None;
+        }
+
+        // without a clock, the time spent claiming the slot cannot be deducted, and so each
+        // wait is bounded by `duration` in full
+        #[cfg(feature = "std")]
+        let duration = deadline.remaining();
+        match self.try_read(duration) {
+            None => {
+                M::release_upgradable(&self.sync);
+                None
+            }
+            Some(mut guard) => {
+                guard.locked = false;
+                Some(LockUpgradableGuard {
+                    lock: self,
+                    locked: true,
+                    __no_send: PhantomData,
+                })
+            }
+        }
+    }
+
+    /// Acquires a write lock along with the upgradable slot, so that the write lock may be
+    /// downgraded to a [`LockUpgradableGuard`] without another thread claiming the slot in the
+    /// interim. While the guard exists, other callers of [`read_upgradable`](Self::read_upgradable)
+    /// and `write_downgradable` block, as they would for an upgradable guard.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(0);
+    /// let mut guard = lock.write_downgradable();
+    /// *guard = 42;
+    /// let guard = guard.downgrade();
+    /// assert_eq!(42, *lock.read());
+    /// let mut guard = guard.upgrade();
+    /// *guard += 1;
+    /// ```
+    #[inline]
+    pub fn write_downgradable(&self) -> LockDowngradableGuard<'_, T, M> {
+        self.try_write_downgradable(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to acquire a downgradable write lock within the given `duration`. The wait
+    /// for the upgradable slot and the wait for the write lock are both bounded by `duration`.
+    #[inline]
+    pub fn try_write_downgradable(&self, duration: Duration) -> Option<LockDowngradableGuard<'_, T, M>> {
+        #[cfg(feature = "std")]
+        let mut deadline = Deadline::after(duration);
+        if !self.claim_upgradable(duration) {
+            return None;
+        }
+
+        // without a clock, the time spent claiming the slot cannot be deducted, and so each
+        // wait is bounded by `duration` in full
+        #[cfg(feature = "std")]
+        let duration = deadline.remaining();
+        match self.try_write(duration) {
+            None => {
+                M::release_upgradable(&self.sync);
+                None
+            }
+            Some(mut guard) => {
+                guard.locked = false;
+                Some(LockDowngradableGuard {
+                    lock: self,
+                    locked: true,
+                    __no_send: PhantomData,
+                })
+            }
+        }
+    }
+
+    #[inline]
+    fn claim_upgradable(&self, duration: Duration) -> bool {
+        // the slot is not a hold of its own, so the claim is only registered as a wait
+        if !duration.is_zero() {
+            deadlock::waiting(deadlock::addr_of(self));
+        }
+        let claimed = M::try_claim_upgradable(&self.sync, duration);
+        if !duration.is_zero() {
+            deadlock::abandoned();
+        }
+        claimed
+    }
+
     #[inline]
     fn upgrade(&self) -> LockWriteGuard<'_, T, M> {
         self.try_upgrade(Duration::MAX).unwrap()
@@ -132,17 +891,161 @@ impl<T: ?Sized, M: Moderator> ZLock<T, M> {
 
     #[inline]
     fn try_upgrade(&self, duration: Duration) -> Option<LockWriteGuard<'_, T, M>> {
-        if M::try_upgrade(&self.sync, duration) {
+        if self.try_upgrade_untracked(duration) {
+            held::converted(deadlock::addr_of(self), Mode::Write);
             Some(LockWriteGuard {
                 lock: self,
                 locked: true,
-                __no_send: PhantomData::default(),
+                __no_send: PhantomData,
             })
         } else {
             None
         }
     }
 
+    /// Upgrades a read lock without converting its registered hold, for a hold that is not
+    /// registered (see [`disown`](Self::disown)).
+    #[inline]
+    fn try_upgrade_untracked(&self, duration: Duration) -> bool {
+        // the upgrading thread already holds the lock, so no further hold is recorded
+        if !duration.is_zero() {
+            deadlock::waiting(deadlock::addr_of(self));
+        }
+        let upgraded = M::try_upgrade(&self.sync, duration);
+        if !duration.is_zero() {
+            deadlock::abandoned();
+        }
+        upgraded
+    }
+
+    /// Acquires a read lock, ensuring that the guarded data satisfies `check` before
+    /// returning. If it doesn't, the lock is escalated to a write lock, `check` is re-evaluated
+    /// (another writer may have gotten in first) and `init` is invoked only if the check
+    /// still fails. The write lock is then downgraded to a read lock, so that no other writer
+    /// can intervene between `init` and the caller's subsequent reads.
+    ///
+    /// The escalation first attempts a non-blocking upgrade. If that fails (because other
+    /// readers are present), the read lock is released before waiting for the write lock,
+    /// so that concurrent callers cannot deadlock by each waiting on the other's read lock
+    /// to be released.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(None);
+    /// let guard = lock.read_or_write_with(Option::is_some, |val| *val = Some(42));
+    /// assert_eq!(Some(42), *guard);
+    /// ```
+    #[inline]
+    pub fn read_or_write_with(
+        &self,
+        check: impl Fn(&T) -> bool,
+        init: impl FnOnce(&mut T),
+    ) -> LockReadGuard<'_, T, M> {
+        let guard = self.read();
+        if check(&guard) {
+            return guard;
+        }
+
+        let mut guard = match guard.try_upgrade(Duration::ZERO) {
+            UpgradeOutcome::Upgraded(guard) => guard,
+            UpgradeOutcome::Unchanged(guard) => {
+                drop(guard);
+                self.write()
+            }
+        };
+        if !check(&guard) {
+            init(&mut guard);
+        }
+        guard.downgrade()
+    }
+
+    /// Applies `update` to the guarded data if and only if it satisfies `predicate`, evaluating
+    /// both under a single write lock, so that no other writer may intervene between the check
+    /// and the update.
+    ///
+    /// Returns `true` if `update` was applied.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(41);
+    /// assert!(lock.update_if(|val| *val % 2 == 1, |val| *val += 1));
+    /// assert!(!lock.update_if(|val| *val % 2 == 1, |val| *val += 1));
+    /// assert_eq!(42, *lock.read());
+    /// ```
+    #[inline]
+    pub fn update_if(&self, predicate: impl FnOnce(&T) -> bool, update: impl FnOnce(&mut T)) -> bool {
+        self.try_update_if(Duration::MAX, predicate, update).unwrap()
+    }
+
+    /// A variant of [`update_if`](Self::update_if) that waits at most `duration` for the
+    /// write lock, returning `None` if the lock could not be acquired in time.
+    #[inline]
+    pub fn try_update_if(
+        &self,
+        duration: Duration,
+        predicate: impl FnOnce(&T) -> bool,
+        update: impl FnOnce(&mut T),
+    ) -> Option<bool> {
+        let mut guard = self.try_write(duration)?;
+        if predicate(&guard) {
+            update(&mut guard);
+            Some(true)
+        } else {
+            Some(false)
+        }
+    }
+
+    /// Applies `f` to the guarded data under a read lock, returning the result of `f` along
+    /// with the [`LockTiming`] of the critical section.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(42);
+    /// let (val, timing) = lock.timed_with_read(|val| *val);
+    /// assert_eq!(42, val);
+    /// println!("acquired in {:?}, held for {:?}", timing.acquire, timing.hold);
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn timed_with_read<R>(&self, f: impl FnOnce(&T) -> R) -> (R, LockTiming) {
+        let start = Instant::now();
+        let guard = self.read();
+        let acquired = Instant::now();
+        let result = f(&guard);
+        let released = Instant::now();
+        drop(guard);
+        (result, LockTiming::new(start, acquired, released))
+    }
+
+    /// Applies `f` to the guarded data under a write lock, returning the result of `f` along
+    /// with the [`LockTiming`] of the critical section.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(41);
+    /// let (val, timing) = lock.timed_with_write(|val| {
+    ///     *val += 1;
+    ///     *val
+    /// });
+    /// assert_eq!(42, val);
+    /// println!("acquired in {:?}, held for {:?}", timing.acquire, timing.hold);
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn timed_with_write<R>(&self, f: impl FnOnce(&mut T) -> R) -> (R, LockTiming) {
+        let start = Instant::now();
+        let mut guard = self.write();
+        let acquired = Instant::now();
+        let result = f(&mut guard);
+        let released = Instant::now();
+        drop(guard);
+        (result, LockTiming::new(start, acquired, released))
+    }
+
     /// Returns a mutable reference to the underlying data.
     ///
     /// Since this call borrows the [`MultiLock`] mutably, no actual locking needs to
@@ -154,7 +1057,6 @@ impl<T: ?Sized, M: Moderator> ZLock<T, M> {
 }
 
 pub struct LockReadGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
-    data: NonNull<T>,
     lock: &'a ZLock<T, M>,
     locked: bool,
 
@@ -172,12 +1074,37 @@ impl<T: ?Sized, M: Moderator> Drop for LockReadGuard<'_, T, M> {
 }
 
 impl<'a, T: ?Sized, M: Moderator> LockReadGuard<'a, T, M> {
+    /// Upgrades the read lock to a write lock, waiting for the other readers to drain. The
+    /// read lock is held throughout, so no writer can intervene between the read and the write.
+    ///
+    /// Two readers upgrading concurrently wait on each other indefinitely. Where several threads
+    /// may upgrade, acquire the lock with [`ZLock::read_upgradable`], which admits one upgrader at
+    /// a time, or bound the wait with [`try_upgrade`](Self::try_upgrade) and release the read
+    /// lock upon timing out.
     #[inline]
     pub fn upgrade(mut self) -> LockWriteGuard<'a, T, M> {
+        // the read lock is relinquished only once the upgrade succeeds; should the upgrade panic
+        // while waiting, the read lock is released when this guard is dropped during unwinding
+        let guard = self.lock.upgrade();
         self.locked = false;
-        self.lock.upgrade()
+        guard
     }
 
+    /// Attempts to upgrade the read lock to a write lock within the given `duration`, returning
+    /// the read guard intact if the other readers do not drain in time.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(0);
+    /// let guard = lock.read();
+    /// let other = lock.read();
+    /// let guard = guard.try_upgrade(Duration::ZERO).unchanged().unwrap();
+    /// drop(other);
+    /// *guard.try_upgrade(Duration::ZERO).upgraded().unwrap() = 42;
+    /// assert_eq!(42, *lock.read());
+    /// ```
     #[inline]
     pub fn try_upgrade(mut self, duration: Duration) -> LockUpgradeOutcome<'a, T, M> {
         match self.lock.try_upgrade(duration) {
@@ -188,6 +1115,30 @@ impl<'a, T: ?Sized, M: Moderator> LockReadGuard<'a, T, M> {
             }
         }
     }
+
+    /// Consumes the guard without releasing the read lock, which remains held until it is
+    /// released with [`ZLock::force_unlock_read`]. This decouples the critical section from
+    /// the lifetime of the guard; e.g., for locks held across an FFI boundary. As the lock may
+    /// then be released on any thread, the hold is no longer reported by the held lock
+    /// registry or the deadlock detector.
+    #[inline]
+    pub fn forget(mut self) {
+        self.lock.disown();
+        self.locked = false;
+    }
+
+    /// Temporarily releases the read lock while `f` runs, reacquiring it before returning. The
+    /// read counterpart of [`LockWriteGuard::unlocked`], subject to the same caveats.
+    #[inline]
+    pub fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> U {
+        self.locked = false;
+        self.lock.read_unlock();
+        let result = f();
+        // the reacquired hold is transferred to this guard
+        self.lock.read().locked = false;
+        self.locked = true;
+        result
+    }
 }
 
 impl<T: ?Sized, M: Moderator> Deref for LockReadGuard<'_, T, M> {
@@ -195,7 +1146,125 @@ impl<T: ?Sized, M: Moderator> Deref for LockReadGuard<'_, T, M> {
 
     #[inline]
     fn deref(&self) -> &T {
-        unsafe { self.data.as_ref() }
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+/// A read guard that holds the lock's upgradable slot, obtained from
+/// [`ZLock::read_upgradable`]. Both the read lock and the slot are released when the guard
+/// is dropped.
+pub struct LockUpgradableGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    lock: &'a ZLock<T, M>,
+    locked: bool,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T: ?Sized, M: Moderator> Drop for LockUpgradableGuard<'_, T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.locked {
+            M::release_upgradable(&self.lock.sync);
+            self.lock.read_unlock();
+        }
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockUpgradableGuard<'a, T, M> {
+    /// Upgrades the read lock to a write lock, waiting for the plain readers to drain. The
+    /// upgradable slot is released once the upgrade succeeds.
+    #[inline]
+    pub fn upgrade(mut self) -> LockWriteGuard<'a, T, M> {
+        // as with LockReadGuard::upgrade, should the upgrade panic while waiting, the read lock
+        // and the slot are released when this guard is dropped during unwinding
+        let guard = self.lock.upgrade();
+        self.locked = false;
+        M::release_upgradable(&self.lock.sync);
+        guard
+    }
+
+    #[inline]
+    pub fn try_upgrade(mut self, duration: Duration) -> UpgradeOutcome<LockWriteGuard<'a, T, M>, Self> {
+        match self.lock.try_upgrade(duration) {
+            None => UpgradeOutcome::Unchanged(self),
+            Some(guard) => {
+                self.locked = false;
+                M::release_upgradable(&self.lock.sync);
+                UpgradeOutcome::Upgraded(guard)
+            }
+        }
+    }
+
+    /// Releases the upgradable slot, retaining the read lock as a plain [`LockReadGuard`].
+    #[inline]
+    pub fn downgrade(mut self) -> LockReadGuard<'a, T, M> {
+        self.locked = false;
+        M::release_upgradable(&self.lock.sync);
+        LockReadGuard {
+            lock: self.lock,
+            locked: true,
+            __no_send: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for LockUpgradableGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+/// A write guard that also holds the lock's upgradable slot, obtained from
+/// [`ZLock::write_downgradable`]. Both the write lock and the slot are released when the
+/// guard is dropped.
+pub struct LockDowngradableGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    lock: &'a ZLock<T, M>,
+    locked: bool,
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+impl<T: ?Sized, M: Moderator> Drop for LockDowngradableGuard<'_, T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.locked {
+            self.lock.write_unlock();
+            M::release_upgradable(&self.lock.sync);
+        }
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockDowngradableGuard<'a, T, M> {
+    /// Atomically downgrades the write lock to a read lock, retaining the upgradable slot.
+    #[inline]
+    pub fn downgrade(mut self) -> LockUpgradableGuard<'a, T, M> {
+        M::downgrade(&self.lock.sync);
+        held::converted(deadlock::addr_of(self.lock), Mode::Read);
+        self.locked = false;
+        LockUpgradableGuard {
+            lock: self.lock,
+            locked: true,
+            __no_send: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for LockDowngradableGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> DerefMut for LockDowngradableGuard<'_, T, M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
     }
 }
 
@@ -218,8 +1287,351 @@ impl<T: ?Sized, M: Moderator> Drop for LockWriteGuard<'_, T, M> {
 impl<'a, T: ?Sized, M: Moderator> LockWriteGuard<'a, T, M> {
     #[inline]
     pub fn downgrade(mut self) -> LockReadGuard<'a, T, M> {
+        let guard = self.lock.downgrade();
+        self.locked = false;
+        guard
+    }
+
+    /// Consumes the guard without releasing the write lock, which remains held until it is
+    /// released with [`ZLock::force_unlock_write`]. As with [`LockReadGuard::forget`], the hold
+    /// is no longer reported by the held lock registry or the deadlock detector.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(0);
+    /// let mut guard = lock.write();
+    /// *guard = 42;
+    /// guard.forget();
+    /// assert!(lock.try_read(Duration::ZERO).is_none());
+    /// unsafe { lock.force_unlock_write() };
+    /// assert_eq!(42, *lock.read());
+    /// ```
+    #[inline]
+    pub fn forget(mut self) {
+        self.lock.disown();
+        self.locked = false;
+    }
+
+    /// Temporarily releases the write lock while `f` runs, reacquiring it before returning; e.g.,
+    /// to avoid holding the lock across slow I/O. Other threads may acquire the lock in the
+    /// interim, so the guarded data may have changed by the time `f` returns. Should `f` panic,
+    /// the lock is not reacquired.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new(0);
+    /// let mut guard = lock.write();
+    /// *guard = 42;
+    /// guard.unlocked(|| assert_eq!(42, *lock.read()));
+    /// assert!(lock.try_read(Duration::ZERO).is_none());
+    /// ```
+    #[inline]
+    pub fn unlocked<U>(&mut self, f: impl FnOnce() -> U) -> U {
         self.locked = false;
-        self.lock.downgrade()
+        self.lock.write_unlock();
+        let result = f();
+        self.lock.write().locked = false;
+        self.locked = true;
+        result
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockReadGuard<'a, T, M> {
+    /// Projects this guard onto a part of the guarded data, as selected by `f`. The read lock
+    /// is held until the mapped guard is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new((42, String::from("foo")));
+    /// let name = lock.read().map(|pair| &pair.1);
+    /// assert_eq!("foo", *name);
+    /// ```
+    #[inline]
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&T) -> &U) -> MappedLockReadGuard<'a, U> {
+        let data = NonNull::from(f(&self));
+        MappedLockReadGuard::new(data, self.detach())
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case the original guard is handed back.
+    #[inline]
+    pub fn try_map<U: ?Sized>(self, f: impl FnOnce(&T) -> Option<&U>) -> Result<MappedLockReadGuard<'a, U>, Self> {
+        match f(&self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockReadGuard::new(data, self.detach())),
+        }
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockWriteGuard<'a, T, M> {
+    /// Projects this guard onto a part of the guarded data, as selected by `f`. The write lock
+    /// is held until the mapped guard is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new((42, String::new()));
+    /// let mut name = lock.write().map(|pair| &mut pair.1);
+    /// name.push_str("foo");
+    /// drop(name);
+    /// assert_eq!((42, String::from("foo")), *lock.read());
+    /// ```
+    #[inline]
+    pub fn map<U: ?Sized>(mut self, f: impl FnOnce(&mut T) -> &mut U) -> MappedLockWriteGuard<'a, U> {
+        let data = NonNull::from(f(&mut self));
+        MappedLockWriteGuard::new(data, self.detach())
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case the original guard is handed back.
+    #[inline]
+    pub fn try_map<U: ?Sized>(mut self, f: impl FnOnce(&mut T) -> Option<&mut U>) -> Result<MappedLockWriteGuard<'a, U>, Self> {
+        match f(&mut self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockWriteGuard::new(data, self.detach())),
+        }
+    }
+
+    /// Splits this guard into two guards over disjoint parts of the guarded data, as
+    /// selected by `f`. The write lock is held until both guards have been dropped.
+    ///
+    /// Disjointness is enforced by the borrow checker, as `f` derives both references from a
+    /// single `&mut T`, so that the parts may be borrowed independently of one another.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::new((0, String::new()));
+    /// let (mut count, mut name) = lock.write().map_split(|pair| (&mut pair.0, &mut pair.1));
+    /// *count = 42;
+    /// name.push_str("foo");
+    /// drop((count, name));
+    /// assert_eq!((42, String::from("foo")), *lock.read());
+    /// ```
+    #[inline]
+    pub fn map_split<U: ?Sized, V: ?Sized>(
+        mut self,
+        f: impl FnOnce(&mut T) -> (&mut U, &mut V),
+    ) -> (MappedLockWriteGuard<'a, U>, MappedLockWriteGuard<'a, V>) {
+        let (u, v) = f(&mut self);
+        let (u, v) = (NonNull::from(u), NonNull::from(v));
+
+        // the lock is released when the last of the mapped guards drops its reference to the
+        // shared owner; should f panic, the original guard releases the lock as it unwinds
+        let owner = Rc::new(self.detach());
+        (MappedLockWriteGuard::new(u, Owner::Shared(owner.clone())), MappedLockWriteGuard::new(v, Owner::Shared(owner)))
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockReadGuard<'a, T, M> {
+    /// Hands the read lock over to an [`Owner`], on behalf of mapped guards.
+    #[inline]
+    fn detach(mut self) -> Owner<'a> {
+        self.locked = false;
+        Owner::Lock(self.lock.unlocker(Mode::Read))
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockWriteGuard<'a, T, M> {
+    /// Hands the write lock over to an [`Owner`], on behalf of mapped guards.
+    #[inline]
+    fn detach(mut self) -> Owner<'a> {
+        self.locked = false;
+        Owner::Lock(self.lock.unlocker(Mode::Write))
+    }
+}
+
+/// Releases a lock in the given `mode`, deregistering the current thread's hold.
+#[inline]
+fn release<M: Moderator>(sync: &M::Sync, instrument: &Instrumentation, addr: usize, mode: Mode) {
+    deadlock::released(addr);
+    held::released(addr);
+    release_untracked::<M>(sync, instrument, addr, mode);
+}
+
+/// Releases a lock in the given `mode`, whose hold is no longer registered.
+#[inline]
+fn release_untracked<M: Moderator>(sync: &M::Sync, instrument: &Instrumentation, addr: usize, mode: Mode) {
+    match mode {
+        Mode::Read => M::read_unlock(sync),
+        Mode::Write => M::write_unlock(sync),
+    }
+    instrument.released(addr, mode);
+    trace::released(addr);
+}
+
+/// Releases a [`ZLock`] when dropped, without naming the type of its data or moderator. Unlike
+/// a boxed guard, an unlocker requires no allocation.
+pub(crate) struct Unlocker<'a> {
+    sync: NonNull<()>,
+    instrument: &'a Instrumentation,
+    addr: usize,
+    mode: Mode,
+    release: fn(NonNull<()>, &Instrumentation, usize, Mode),
+}
+
+impl Drop for Unlocker<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        (self.release)(self.sync, self.instrument, self.addr, self.mode);
+    }
+}
+
+/// A guard of any type, erased for holding its lock on behalf of mapped guards.
+pub(crate) trait AnyGuard {}
+
+impl<G: ?Sized> AnyGuard for G {}
+
+/// Holds the lock on behalf of one or more mapped guards, releasing it when dropped. Mapped
+/// guards are released on the thread that acquired the lock, as is any other guard; hence,
+/// they are not [`Send`].
+// the variants are only held to be dropped
+#[allow(dead_code)]
+pub(crate) enum Owner<'a> {
+    /// The lock of a [`ZLock`] guard.
+    Lock(Unlocker<'a>),
+
+    /// The guard of a dynamically dispatched lock, which may only be released by its guard.
+    Guard(Box<dyn AnyGuard + 'a>),
+
+    /// An owner shared among the parts of a [`LockWriteGuard::map_split`], releasing the lock
+    /// once the last of them is dropped.
+    Shared(Rc<Owner<'a>>),
+}
+
+/// A read guard over a part of the data protected by a [`ZLock`], obtained by projecting a
+/// [`LockReadGuard`] with [`LockReadGuard::map`]. The read lock is released when the mapped
+/// guard is dropped.
+pub struct MappedLockReadGuard<'a, U: ?Sized> {
+    data: NonNull<U>,
+    owner: Owner<'a>,
+    __marker: PhantomData<&'a U>,
+}
+
+// SAFETY: a shared mapped guard only dereferences its data; the lock is released by the owner
+// of the guard. (Mapped guards are not Send.)
+unsafe impl<U: ?Sized + Sync> Sync for MappedLockReadGuard<'_, U> {}
+
+impl<'a, U: ?Sized> MappedLockReadGuard<'a, U> {
+    #[inline]
+    pub(crate) fn new(data: NonNull<U>, owner: Owner<'a>) -> Self {
+        Self {
+            data,
+            owner,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Further projects this guard onto a part of its data.
+    #[inline]
+    pub fn map<V: ?Sized>(self, f: impl FnOnce(&U) -> &V) -> MappedLockReadGuard<'a, V> {
+        let data = NonNull::from(f(&self));
+        MappedLockReadGuard::new(data, self.owner)
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case this guard is handed back.
+    #[inline]
+    pub fn try_map<V: ?Sized>(self, f: impl FnOnce(&U) -> Option<&V>) -> Result<MappedLockReadGuard<'a, V>, Self> {
+        match f(&self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockReadGuard::new(data, self.owner)),
+        }
+    }
+}
+
+impl<U: ?Sized + Debug> Debug for MappedLockReadGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: ?Sized> Deref for MappedLockReadGuard<'_, U> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &U {
+        unsafe { self.data.as_ref() }
+    }
+}
+
+/// A write guard over a part of the data protected by a [`ZLock`], obtained by projecting a
+/// [`LockWriteGuard`] with [`LockWriteGuard::map`] or splitting it with
+/// [`LockWriteGuard::map_split`]. The underlying write lock is shared among all parts, and
+/// is released when the last of them is dropped.
+///
+/// As with the guard it was projected from, a mapped guard must be released on the thread
+/// that acquired the lock, and cannot be sent to another thread:
+/// ```compile_fail
+/// use std::thread;
+/// use anode::zlock::{ReadBiased, ZLock};
+/// let lock = ZLock::<_, ReadBiased>::new((0, 0));
+/// let mut first = lock.write().map(|pair| &mut pair.0);
+/// thread::scope(|scope| {
+///     scope.spawn(move || *first += 1);
+/// });
+/// ```
+pub struct MappedLockWriteGuard<'a, U: ?Sized> {
+    data: NonNull<U>,
+    owner: Owner<'a>,
+    __marker: PhantomData<&'a mut U>,
+}
+
+// SAFETY: as for MappedLockReadGuard, a shared mapped guard only dereferences its data.
+unsafe impl<U: ?Sized + Sync> Sync for MappedLockWriteGuard<'_, U> {}
+
+impl<'a, U: ?Sized> MappedLockWriteGuard<'a, U> {
+    #[inline]
+    pub(crate) fn new(data: NonNull<U>, owner: Owner<'a>) -> Self {
+        Self {
+            data,
+            owner,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Further projects this guard onto a part of its data.
+    #[inline]
+    pub fn map<V: ?Sized>(mut self, f: impl FnOnce(&mut U) -> &mut V) -> MappedLockWriteGuard<'a, V> {
+        let data = NonNull::from(f(&mut self));
+        MappedLockWriteGuard::new(data, self.owner)
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case this guard is handed back.
+    #[inline]
+    pub fn try_map<V: ?Sized>(mut self, f: impl FnOnce(&mut U) -> Option<&mut V>) -> Result<MappedLockWriteGuard<'a, V>, Self> {
+        match f(&mut self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockWriteGuard::new(data, self.owner)),
+        }
+    }
+}
+
+impl<U: ?Sized + Debug> Debug for MappedLockWriteGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: ?Sized> Deref for MappedLockWriteGuard<'_, U> {
+    type Target = U;
+
+    #[inline]
+    fn deref(&self) -> &U {
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<U: ?Sized> DerefMut for MappedLockWriteGuard<'_, U> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut U {
+        unsafe { self.data.as_mut() }
     }
 }
 
@@ -239,6 +1651,56 @@ impl<T: ?Sized, M: Moderator> DerefMut for LockWriteGuard<'_, T, M> {
     }
 }
 
+/// The error returned when a lock could not be acquired within the requested duration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    duration: Duration,
+}
+
+impl Timeout {
+    #[inline]
+    pub fn new(duration: Duration) -> Self {
+        Self { duration }
+    }
+
+    /// The duration that was allowed for acquiring the lock.
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lock not acquired within {:?}", self.duration)
+    }
+}
+
+impl core::error::Error for Timeout {}
+
+/// The timing of a critical section, as measured by [`ZLock::timed_with_read`] and
+/// [`ZLock::timed_with_write`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockTiming {
+    /// The time taken to acquire the lock.
+    pub acquire: Duration,
+
+    /// The time for which the lock was held; i.e., the time taken by the closure.
+    pub hold: Duration,
+}
+
+#[cfg(feature = "std")]
+impl LockTiming {
+    #[inline]
+    fn new(start: Instant, acquired: Instant, released: Instant) -> Self {
+        Self {
+            acquire: acquired - start,
+            hold: released - acquired,
+        }
+    }
+}
+
 pub type LockUpgradeOutcome<'a, T, M> = UpgradeOutcome<LockWriteGuard<'a, T, M>, LockReadGuard<'a, T, M>>;
 
 pub enum UpgradeOutcome<W, R> {
@@ -282,9 +1744,42 @@ impl<W, R> UpgradeOutcome<W, R> {
     }
 }
 
+/// Strips the module paths from a type name, as produced by [`std::any::type_name`]; e.g.,
+/// `anode::zlock::read_biased::ReadBiased` becomes `ReadBiased`. Paths nested within
+/// generic parameters are stripped too.
+pub(crate) fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment_start = 0;
+    for (i, ch) in name.char_indices() {
+        if matches!(ch, '<' | '>' | ',' | ' ' | '&' | '(' | ')' | '[' | ']' | ';') {
+            short.push_str(name[segment_start..i].rsplit("::").next().unwrap());
+            short.push(ch);
+            segment_start = i + ch.len_utf8();
+        }
+    }
+    short.push_str(name[segment_start..].rsplit("::").next().unwrap());
+    short
+}
+
+/// Formats the lock as a single line, naming the moderator type and showing both the
+/// moderator's state and the guarded data; e.g.,
+/// `ZLock<ReadBiased> { moderator: ReadBiased { readers: 2, writer: false, .. }, data: 42, .. }`.
+///
+/// Formatting never blocks: the data is read with a zero-duration [`try_read`](ZLock::try_read),
+/// and is shown as `<locked>` if a writer holds the lock.
 impl<T: ?Sized + Debug, M: Moderator> Debug for ZLock<T, M> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut d = f.debug_struct("ZLock");
+        struct ModeratorState<'a, M: Moderator>(&'a M::Sync);
+        impl<M: Moderator> Debug for ModeratorState<'_, M> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                M::debug_state(self.0, f)
+            }
+        }
+
+        let name = format!("ZLock<{}>", short_type_name(core::any::type_name::<M>()));
+        let mut d = f.debug_struct(&name);
+        // the moderator state is captured before read-locking the data, which would otherwise skew it
+        d.field("moderator", &ModeratorState::<M>(&self.sync));
         match self.try_read(Duration::ZERO) {
             None => {
                 struct LockedPlaceholder;
@@ -303,9 +1798,58 @@ impl<T: ?Sized + Debug, M: Moderator> Debug for ZLock<T, M> {
     }
 }
 
-#[cfg(test)]
+#[cfg(feature = "std")]
 pub mod locklike;
 
+#[cfg(feature = "std")]
+pub mod any_lock;
+
+#[cfg(feature = "std")]
+pub mod shared_cell;
+
+#[cfg(feature = "std")]
+pub mod asynchronous;
+
+impl<T: Default, M: Moderator> Default for ZLock<T, M> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, M: Moderator> From<T> for ZLock<T, M> {
+    #[inline]
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+/// Clones the protected value under a read lock, blocking while the lock is write-locked. The
+/// clone is a new, unlocked lock with the default moderator state; neither the configuration
+/// of a lock created [`with_sync`](ZLock::with_sync) nor its instrumentation carry over.
+impl<T: Clone, M: Moderator> Clone for ZLock<T, M> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.read().clone())
+    }
+}
+
+/// Serializes the protected value under a read lock, blocking while the lock is write-locked.
+#[cfg(feature = "serde")]
+impl<T: ?Sized + serde::Serialize, M: Moderator> serde::Serialize for ZLock<T, M> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.read().serialize(serializer)
+    }
+}
+
+/// Deserializes the value into a new, unlocked lock.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, M: Moderator> serde::Deserialize<'de> for ZLock<T, M> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
 #[cfg(test)]
 mod tests;
 
@@ -313,4 +1857,6 @@ mod tests;
 mod tr_tests;
 
 #[cfg(test)]
-mod std_tests;
\ No newline at end of file
+mod std_tests;
+#[cfg(all(test, loom))]
+mod loom_tests;
@@ -0,0 +1,199 @@
+use crate::zlock::locklike::ModeratorKind;
+use crate::zlock::{ArrivalOrdered, LockReadGuard, LockWriteGuard, PriorityOrdered, ReadBiased, SpinModerator, Stochastic, UpgradeBiased, UpgradeOutcome, WriteBiased, ZLock};
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+/// Expands `$body` for every variant of `$ty`, binding the variant's payload to `$inner`.
+macro_rules! dispatch {
+    ($ty:ident, $value:expr, $inner:ident => $body:expr) => {
+        match $value {
+            $ty::ReadBiased($inner) => $body,
+            $ty::WriteBiased($inner) => $body,
+            $ty::ArrivalOrdered($inner) => $body,
+            $ty::Stochastic($inner) => $body,
+            $ty::SpinModerator($inner) => $body,
+            $ty::UpgradeBiased($inner) => $body,
+            $ty::PriorityOrdered($inner) => $body,
+        }
+    };
+}
+
+/// A [`ZLock`] whose moderator is selected at runtime, dispatching statically (via a `match`)
+/// to the concrete lock.
+///
+/// Unlike a [`LockBox`](crate::zlock::locklike::LockBox), neither the lock nor its guards are
+/// boxed, trading a vtable call for a branch on every operation.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::any_lock::AnyLock;
+/// use anode::zlock::locklike::ModeratorKind;
+/// let lock = AnyLock::new(ModeratorKind::WriteBiased, 0);
+/// *lock.write() = 42;
+/// assert_eq!(42, *lock.read());
+/// ```
+pub enum AnyLock<T> {
+    ReadBiased(ZLock<T, ReadBiased>),
+    WriteBiased(ZLock<T, WriteBiased>),
+    ArrivalOrdered(ZLock<T, ArrivalOrdered>),
+    Stochastic(ZLock<T, Stochastic>),
+    SpinModerator(ZLock<T, SpinModerator>),
+    UpgradeBiased(ZLock<T, UpgradeBiased>),
+    PriorityOrdered(ZLock<T, PriorityOrdered>),
+}
+
+impl<T> AnyLock<T> {
+    #[inline]
+    pub fn new(kind: ModeratorKind, t: T) -> Self {
+        match kind {
+            ModeratorKind::ReadBiased => AnyLock::ReadBiased(ZLock::new(t)),
+            ModeratorKind::WriteBiased => AnyLock::WriteBiased(ZLock::new(t)),
+            ModeratorKind::ArrivalOrdered => AnyLock::ArrivalOrdered(ZLock::new(t)),
+            ModeratorKind::Stochastic => AnyLock::Stochastic(ZLock::new(t)),
+            ModeratorKind::SpinModerator => AnyLock::SpinModerator(ZLock::new(t)),
+            ModeratorKind::UpgradeBiased => AnyLock::UpgradeBiased(ZLock::new(t)),
+            ModeratorKind::PriorityOrdered => AnyLock::PriorityOrdered(ZLock::new(t)),
+        }
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        dispatch!(AnyLock, self, lock => lock.into_inner())
+    }
+
+    #[inline]
+    pub fn kind(&self) -> ModeratorKind {
+        match self {
+            AnyLock::ReadBiased(_) => ModeratorKind::ReadBiased,
+            AnyLock::WriteBiased(_) => ModeratorKind::WriteBiased,
+            AnyLock::ArrivalOrdered(_) => ModeratorKind::ArrivalOrdered,
+            AnyLock::Stochastic(_) => ModeratorKind::Stochastic,
+            AnyLock::SpinModerator(_) => ModeratorKind::SpinModerator,
+            AnyLock::UpgradeBiased(_) => ModeratorKind::UpgradeBiased,
+            AnyLock::PriorityOrdered(_) => ModeratorKind::PriorityOrdered,
+        }
+    }
+
+    #[inline]
+    pub fn read(&self) -> AnyReadGuard<'_, T> {
+        dispatch!(AnyLock, self, lock => lock.read().into())
+    }
+
+    #[inline]
+    pub fn try_read(&self, duration: Duration) -> Option<AnyReadGuard<'_, T>> {
+        dispatch!(AnyLock, self, lock => lock.try_read(duration).map(AnyReadGuard::from))
+    }
+
+    #[inline]
+    pub fn write(&self) -> AnyWriteGuard<'_, T> {
+        dispatch!(AnyLock, self, lock => lock.write().into())
+    }
+
+    #[inline]
+    pub fn try_write(&self, duration: Duration) -> Option<AnyWriteGuard<'_, T>> {
+        dispatch!(AnyLock, self, lock => lock.try_write(duration).map(AnyWriteGuard::from))
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        dispatch!(AnyLock, self, lock => lock.get_mut())
+    }
+}
+
+impl<T: Debug> Debug for AnyLock<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        dispatch!(AnyLock, self, lock => Debug::fmt(lock, f))
+    }
+}
+
+pub enum AnyReadGuard<'a, T: ?Sized> {
+    ReadBiased(LockReadGuard<'a, T, ReadBiased>),
+    WriteBiased(LockReadGuard<'a, T, WriteBiased>),
+    ArrivalOrdered(LockReadGuard<'a, T, ArrivalOrdered>),
+    Stochastic(LockReadGuard<'a, T, Stochastic>),
+    SpinModerator(LockReadGuard<'a, T, SpinModerator>),
+    UpgradeBiased(LockReadGuard<'a, T, UpgradeBiased>),
+    PriorityOrdered(LockReadGuard<'a, T, PriorityOrdered>),
+}
+
+impl<'a, T: ?Sized> AnyReadGuard<'a, T> {
+    #[inline]
+    pub fn upgrade(self) -> AnyWriteGuard<'a, T> {
+        dispatch!(AnyReadGuard, self, guard => guard.upgrade().into())
+    }
+
+    #[inline]
+    pub fn try_upgrade(self, duration: Duration) -> UpgradeOutcome<AnyWriteGuard<'a, T>, AnyReadGuard<'a, T>> {
+        dispatch!(AnyReadGuard, self, guard => guard
+            .try_upgrade(duration)
+            .map(AnyWriteGuard::from, AnyReadGuard::from))
+    }
+}
+
+impl<T: ?Sized> Deref for AnyReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        dispatch!(AnyReadGuard, self, guard => guard)
+    }
+}
+
+pub enum AnyWriteGuard<'a, T: ?Sized> {
+    ReadBiased(LockWriteGuard<'a, T, ReadBiased>),
+    WriteBiased(LockWriteGuard<'a, T, WriteBiased>),
+    ArrivalOrdered(LockWriteGuard<'a, T, ArrivalOrdered>),
+    Stochastic(LockWriteGuard<'a, T, Stochastic>),
+    SpinModerator(LockWriteGuard<'a, T, SpinModerator>),
+    UpgradeBiased(LockWriteGuard<'a, T, UpgradeBiased>),
+    PriorityOrdered(LockWriteGuard<'a, T, PriorityOrdered>),
+}
+
+impl<'a, T: ?Sized> AnyWriteGuard<'a, T> {
+    #[inline]
+    pub fn downgrade(self) -> AnyReadGuard<'a, T> {
+        dispatch!(AnyWriteGuard, self, guard => guard.downgrade().into())
+    }
+}
+
+impl<T: ?Sized> Deref for AnyWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        dispatch!(AnyWriteGuard, self, guard => guard)
+    }
+}
+
+impl<T: ?Sized> DerefMut for AnyWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        dispatch!(AnyWriteGuard, self, guard => guard)
+    }
+}
+
+macro_rules! impl_from_guards {
+    ($($moderator:ident),*) => {
+        $(
+            impl<'a, T: ?Sized> From<LockReadGuard<'a, T, $moderator>> for AnyReadGuard<'a, T> {
+                #[inline]
+                fn from(guard: LockReadGuard<'a, T, $moderator>) -> Self {
+                    AnyReadGuard::$moderator(guard)
+                }
+            }
+
+            impl<'a, T: ?Sized> From<LockWriteGuard<'a, T, $moderator>> for AnyWriteGuard<'a, T> {
+                #[inline]
+                fn from(guard: LockWriteGuard<'a, T, $moderator>) -> Self {
+                    AnyWriteGuard::$moderator(guard)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_guards!(ReadBiased, WriteBiased, ArrivalOrdered, Stochastic, SpinModerator, UpgradeBiased, PriorityOrdered);
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,60 @@
+use crate::zlock::any_lock::AnyLock;
+use crate::zlock::locklike::MODERATOR_KINDS;
+use std::time::Duration;
+
+#[test]
+fn kind() {
+    for kind in MODERATOR_KINDS {
+        assert_eq!(kind, AnyLock::new(kind, ()).kind());
+    }
+}
+
+#[test]
+fn read_write_cycle() {
+    for kind in MODERATOR_KINDS {
+        let lock = AnyLock::new(kind, 0);
+        let read_1 = lock.read();
+        let read_2 = lock.try_read(Duration::ZERO).unwrap();
+        assert_eq!(0, *read_1);
+        assert!(lock.try_write(Duration::ZERO).is_none());
+
+        // upgrade is refused while another reader is present
+        let read_1 = read_1.try_upgrade(Duration::ZERO).unchanged().unwrap();
+        drop(read_2);
+
+        let mut write = read_1.try_upgrade(Duration::ZERO).upgraded().unwrap();
+        *write = 42;
+        assert!(lock.try_read(Duration::ZERO).is_none());
+
+        let read = write.downgrade();
+        assert_eq!(42, *read);
+        assert!(lock.try_read(Duration::ZERO).is_some());
+        assert!(lock.try_write(Duration::ZERO).is_none());
+
+        let mut write = read.upgrade();
+        *write += 1;
+        drop(write);
+
+        let mut write = lock.write();
+        *write += 1;
+        drop(write);
+        assert_eq!(44, lock.into_inner());
+    }
+}
+
+#[test]
+fn get_mut() {
+    for kind in MODERATOR_KINDS {
+        let mut lock = AnyLock::new(kind, 0);
+        *lock.get_mut() = 42;
+        assert_eq!(42, *lock.read());
+    }
+}
+
+#[test]
+fn debug() {
+    let lock = AnyLock::new(crate::zlock::locklike::ModeratorKind::ReadBiased, 42);
+    let formatted = format!("{lock:?}");
+    assert!(formatted.starts_with("ZLock"), "{formatted}");
+    assert!(formatted.contains("data: 42"), "{formatted}");
+}
@@ -0,0 +1,175 @@
+use crate::zlock::{Moderator, UpgradeOutcome, ZLock};
+use core::ops::{Deref, DerefMut};
+use alloc::sync::Arc;
+use core::time::Duration;
+
+// SAFETY: the guards own their lock through an Arc, and may be released from any thread, their
+// holds having been deregistered from the (per-thread) registries upon acquisition. Moving a
+// guard to another thread moves access to the data along with it, hence the bounds mirror those
+// under which the lock itself may be shared.
+unsafe impl<T: ?Sized + Send + Sync, M: Moderator> Send for ArcLockReadGuard<T, M> {}
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for ArcLockReadGuard<T, M> {}
+unsafe impl<T: ?Sized + Send + Sync, M: Moderator> Send for ArcLockWriteGuard<T, M> {}
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for ArcLockWriteGuard<T, M> {}
+
+impl<T: ?Sized, M: Moderator> ZLock<T, M> {
+    /// Acquires a read lock, returning a guard that keeps this `Arc` alive rather than
+    /// borrowing the lock. The guard thus has a `'static` lifetime (provided that `T` does),
+    /// and may be stored in a struct or a future, or sent to another thread.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = Arc::new(ZLock::<_, ReadBiased>::new(42));
+    /// let guard = lock.read_arc();
+    /// let val = thread::spawn(move || *guard).join().unwrap();
+    /// assert_eq!(42, val);
+    /// ```
+    #[inline]
+    pub fn read_arc(self: &Arc<Self>) -> ArcLockReadGuard<T, M> {
+        self.try_read_arc(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_read_arc(self: &Arc<Self>, duration: Duration) -> Option<ArcLockReadGuard<T, M>> {
+        self.try_read(duration).map(|guard| {
+            // the hold is transferred to the owned guard, which may be released on any thread
+            guard.forget();
+            ArcLockReadGuard {
+                lock: self.clone(),
+                locked: true,
+            }
+        })
+    }
+
+    /// Acquires a write lock, returning a guard that keeps this `Arc` alive rather than
+    /// borrowing the lock. See [`read_arc`](Self::read_arc).
+    #[inline]
+    pub fn write_arc(self: &Arc<Self>) -> ArcLockWriteGuard<T, M> {
+        self.try_write_arc(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_write_arc(self: &Arc<Self>, duration: Duration) -> Option<ArcLockWriteGuard<T, M>> {
+        self.try_write(duration).map(|guard| {
+            guard.forget();
+            ArcLockWriteGuard {
+                lock: self.clone(),
+                locked: true,
+            }
+        })
+    }
+}
+
+/// A read guard that owns a reference to its [`ZLock`], obtained from [`ZLock::read_arc`].
+pub struct ArcLockReadGuard<T: ?Sized, M: Moderator> {
+    lock: Arc<ZLock<T, M>>,
+    locked: bool,
+}
+
+impl<T: ?Sized, M: Moderator> ArcLockReadGuard<T, M> {
+    /// The lock that this guard was acquired from. This is an associated function, so as not
+    /// to shadow methods of `T` reached through deref.
+    #[inline]
+    pub fn lock(this: &Self) -> &Arc<ZLock<T, M>> {
+        &this.lock
+    }
+
+    #[inline]
+    pub fn upgrade(mut self) -> ArcLockWriteGuard<T, M> {
+        // should the upgrade panic while waiting, the read lock is released when this guard
+        // is dropped during unwinding
+        assert!(self.lock.try_upgrade_untracked(Duration::MAX));
+        self.locked = false;
+        ArcLockWriteGuard {
+            lock: self.lock.clone(),
+            locked: true,
+        }
+    }
+
+    #[inline]
+    pub fn try_upgrade(mut self, duration: Duration) -> UpgradeOutcome<ArcLockWriteGuard<T, M>, Self> {
+        if self.lock.try_upgrade_untracked(duration) {
+            self.locked = false;
+            UpgradeOutcome::Upgraded(ArcLockWriteGuard {
+                lock: self.lock.clone(),
+                locked: true,
+            })
+        } else {
+            UpgradeOutcome::Unchanged(self)
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Drop for ArcLockReadGuard<T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.locked {
+            self.lock.read_unlock_untracked();
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for ArcLockReadGuard<T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+/// A write guard that owns a reference to its [`ZLock`], obtained from [`ZLock::write_arc`].
+pub struct ArcLockWriteGuard<T: ?Sized, M: Moderator> {
+    lock: Arc<ZLock<T, M>>,
+    locked: bool,
+}
+
+impl<T: ?Sized, M: Moderator> ArcLockWriteGuard<T, M> {
+    /// The lock that this guard was acquired from. This is an associated function, so as not
+    /// to shadow methods of `T` reached through deref.
+    #[inline]
+    pub fn lock(this: &Self) -> &Arc<ZLock<T, M>> {
+        &this.lock
+    }
+
+    #[inline]
+    pub fn downgrade(mut self) -> ArcLockReadGuard<T, M> {
+        M::downgrade(&self.lock.sync);
+        self.locked = false;
+        ArcLockReadGuard {
+            lock: self.lock.clone(),
+            locked: true,
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Drop for ArcLockWriteGuard<T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.locked {
+            self.lock.write_unlock_untracked();
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for ArcLockWriteGuard<T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> DerefMut for ArcLockWriteGuard<T, M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,76 @@
+use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
+use crate::zlock::{ArcLockReadGuard, ArcLockWriteGuard, Moderator, ReadBiased, SpinModerator, WriteBiased, ZLock};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn arc_guard_cycle() {
+    __arc_guard_cycle::<ReadBiased>();
+    __arc_guard_cycle::<WriteBiased>();
+    __arc_guard_cycle::<SpinModerator>();
+}
+
+fn __arc_guard_cycle<M: Moderator>() {
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+
+    // read -> upgrade -> downgrade -> release
+    let guard = lock.read_arc();
+    assert_eq!(2, Arc::strong_count(&lock));
+    assert!(Arc::ptr_eq(&lock, ArcLockReadGuard::lock(&guard)));
+    let guard = guard.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    let guard = guard.downgrade();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+    assert_eq!(1, Arc::strong_count(&lock));
+
+    // write -> release
+    let mut guard = lock.write_arc();
+    *guard = 42;
+    assert!(Arc::ptr_eq(&lock, ArcLockWriteGuard::lock(&guard)));
+    assert!(lock.try_read_arc(SHORT_WAIT).is_none());
+    drop(guard);
+
+    // a contended upgrade leaves the read lock in place
+    let guard = lock.read_arc();
+    let other = lock.read();
+    let guard = guard.try_upgrade(SHORT_WAIT).unchanged().unwrap();
+    drop(other);
+    let guard = guard.upgrade();
+    assert!(lock.try_write_arc(Duration::ZERO).is_none());
+    drop(guard);
+    assert_eq!(42, *lock.try_read_arc(Duration::ZERO).unwrap());
+    assert_eq!(1, Arc::strong_count(&lock));
+}
+
+#[test]
+fn guard_outlives_borrow() {
+    struct Holder {
+        guard: ArcLockWriteGuard<Vec<u64>, ReadBiased>,
+    }
+
+    let holder = {
+        let lock = Arc::new(ZLock::<_, ReadBiased>::new(vec![]));
+        Holder { guard: lock.write_arc() }
+    };
+    let mut holder = holder;
+    holder.guard.push(42);
+    let lock = ArcLockWriteGuard::lock(&holder.guard).clone();
+    drop(holder);
+    assert_eq!(vec![42], *lock.read());
+}
+
+#[test]
+fn guard_sent_across_threads() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let mut guard = lock.write_arc();
+    *guard = 42;
+
+    // the guard is released on another thread, which unblocks main
+    let t_2 = thread::spawn(move || {
+        *guard += 1;
+    });
+    assert_eq!(43, *lock.try_read(LONG_WAIT).unwrap());
+    t_2.join().unwrap();
+}
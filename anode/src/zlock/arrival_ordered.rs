@@ -1,21 +1,83 @@
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt;
 use std::time::Duration;
 use crate::deadline::Deadline;
-use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
-use crate::zlock::Moderator;
+use crate::monitor::{ClassedDirective, ClassedMonitor, Wake};
+use crate::zlock::{ConfigurableModerator, Moderator};
+use crate::zlock::registration::Registration;
 
+/// A moderator that admits readers and writers in the order of their arrival.
+///
+/// Ticket holders wait on a single queue, as any of them may be next in line; upgraders and
+/// claimants of the upgradable lock wait separately, so that the queue is not woken on their
+/// account (nor they on the queue's).
+///
+/// Strict arrival order may be relaxed for read throughput by constructing the lock
+/// [`with_batch`](Self::with_batch), such that arriving readers may join the readers presently
+/// holding the lock, ahead of queued writers, up to a given number of readers per read phase.
 #[derive(Debug)]
 pub struct ArrivalOrdered;
 
+impl ArrivalOrdered {
+    /// Creates the moderator state for a lock that admits up to `batch` readers out of turn
+    /// while it is read-locked, rather than queueing them behind a waiting writer. A writer
+    /// thus waits for at most `batch` more readers than it would under strict arrival order.
+    /// A `batch` of zero is equivalent to strict arrival order.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ArrivalOrdered, ZLock};
+    /// let lock = ZLock::<_, ArrivalOrdered>::with_sync(ArrivalOrdered::with_batch(8), 0);
+    /// *lock.write() = 42;
+    /// assert_eq!(42, *lock.read());
+    /// ```
+    #[inline]
+    pub fn with_batch(batch: u32) -> ArrivalOrderedSync {
+        ArrivalOrderedSync::new(batch)
+    }
+}
+
+const QUEUED: usize = 0;
+const UPGRADERS: usize = 1;
+const CLAIMANTS: usize = 2;
+
 pub struct ArrivalOrderedSync {
-    monitor: SpeculativeMonitor<ArrivalOrderedState>,
+    monitor: ClassedMonitor<ArrivalOrderedState, 3>,
+}
+
+impl ArrivalOrderedSync {
+    #[inline]
+    fn new(batch: u32) -> Self {
+        Self {
+            monitor: ClassedMonitor::new(ArrivalOrderedState {
+                readers: 0,
+                writer: false,
+                upgradable: false,
+                next_ticket: 1,
+                serving: 1,
+                abandoned: BTreeSet::new(),
+                batch,
+                barged: 0,
+            }),
+        }
+    }
 }
 
 #[derive(Debug)]
 struct ArrivalOrderedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
     next_ticket: u64,
-    serviced_tickets: u64
+    serving: u64,
+    abandoned: BTreeSet<u64>,
+
+    /// The number of readers that may be admitted out of turn in a single read phase.
+    batch: u32,
+
+    /// The number of readers admitted out of turn in the current read phase.
+    barged: u32,
 }
 
 impl ArrivalOrderedState {
@@ -25,6 +87,44 @@ impl ArrivalOrderedState {
         self.next_ticket = next + 1;
         next
     }
+
+    /// Moves on to the next ticket, skipping over any tickets whose holders have since
+    /// abandoned the queue.
+    #[inline]
+    fn serve_next(&mut self) {
+        self.serving += 1;
+        while self.abandoned.remove(&self.serving) {
+            self.serving += 1;
+        }
+    }
+
+    /// Gives up the given ticket following a timeout. If the ticket is presently being served,
+    /// service moves on to the next one; otherwise, the ticket will be skipped when its turn comes.
+    #[inline]
+    fn abandon(&mut self, ticket: u64) {
+        if self.serving == ticket {
+            self.serve_next();
+        } else {
+            self.abandoned.insert(ticket);
+        }
+    }
+
+    /// Determines whether a reader whose ticket is not being served may join the readers
+    /// presently holding the lock.
+    #[inline]
+    fn may_barge(&self) -> bool {
+        self.readers > 0 && !self.writer && self.barged < self.batch
+    }
+}
+
+impl ConfigurableModerator for ArrivalOrdered {
+    /// The batch size. See [`ArrivalOrdered::with_batch`].
+    type Config = u32;
+
+    #[inline]
+    fn new_with(config: Self::Config) -> Self::Sync {
+        ArrivalOrderedSync::new(config)
+    }
 }
 
 impl Moderator for ArrivalOrdered {
@@ -32,62 +132,73 @@ impl Moderator for ArrivalOrdered {
 
     #[inline]
     fn new() -> Self::Sync {
-        Self::Sync {
-            monitor: SpeculativeMonitor::new(ArrivalOrderedState { readers: 0, writer: false, next_ticket: 1, serviced_tickets: 0 }),
-        }
+        ArrivalOrderedSync::new(0)
     }
 
     #[inline]
     fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut ticket = 0;
-        sync.monitor.enter(|state| {
-            if ticket == 0 {
-                ticket = state.take_ticket();
+        let ticket = Cell::new(0);
+        let mut queued = Registration::new(|_| {
+            let mut abandoned = false;
+            sync.monitor.enter_classed(|state| {
+                if !abandoned {
+                    abandoned = true;
+                    state.abandon(ticket.get());
+                }
+                ClassedDirective::Notify(Wake::NONE.all(QUEUED))
+            });
+        });
+        sync.monitor.enter_classed(|state| {
+            if !acquired && !queued.is_raised() {
+                ticket.set(state.take_ticket());
+                queued.raise();
             }
-            if !acquired && !state.writer && state.serviced_tickets >= ticket - 1 {
+            if !acquired && !state.writer && state.serving == ticket.get() {
                 acquired = true;
+                queued.lower();
                 state.readers += 1;
-                state.serviced_tickets += 1;
+                state.serve_next();
+            } else if !acquired && state.may_barge() {
+                // the ticket is given up, to be skipped when its turn comes
+                acquired = true;
+                queued.lower();
+                state.readers += 1;
+                state.barged += 1;
+                state.abandon(ticket.get());
             }
 
             if acquired {
-                Directive::NotifyAll
+                // the next ticket holder may also be a reader
+                ClassedDirective::Notify(Wake::NONE.all(QUEUED))
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(QUEUED, deadline.remaining())
             }
         });
 
-        if !acquired {
-            let mut inc_serviced = false;
-            sync.monitor.enter(|state| {
-                if !inc_serviced {
-                    inc_serviced = true;
-                    state.serviced_tickets += 1;
-                }
-                Directive::NotifyAll
-            });
-        }
-
-        acquired
+        queued.settle(acquired)
     }
 
     #[inline]
     fn read_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
                 released = true;
                 state.readers -= 1;
+                if state.readers == 0 {
+                    state.barged = 0;
+                }
             }
 
             match state.readers {
-                0 | 1 => Directive::NotifyAll,
-                _ => Directive::Return
+                1 => ClassedDirective::Notify(Wake::NONE.all(UPGRADERS)),
+                0 => ClassedDirective::Notify(Wake::NONE.all(QUEUED)),
+                _ => ClassedDirective::Return
             }
         });
     }
@@ -96,42 +207,44 @@ impl Moderator for ArrivalOrdered {
     fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut ticket = 0;
-        sync.monitor.enter(|state| {
-            if ticket == 0 {
-                ticket = state.take_ticket();
+        let ticket = Cell::new(0);
+        let mut queued = Registration::new(|_| {
+            let mut abandoned = false;
+            sync.monitor.enter_classed(|state| {
+                if !abandoned {
+                    abandoned = true;
+                    state.abandon(ticket.get());
+                }
+                ClassedDirective::Notify(Wake::NONE.all(QUEUED))
+            });
+        });
+        sync.monitor.enter_classed(|state| {
+            if !acquired && !queued.is_raised() {
+                ticket.set(state.take_ticket());
+                queued.raise();
             }
-            if !acquired && state.readers == 0 && !state.writer && state.serviced_tickets >= ticket - 1 {
+            if !acquired && state.readers == 0 && !state.writer && state.serving == ticket.get() {
                 acquired = true;
+                queued.lower();
                 state.writer = true;
-                state.serviced_tickets += 1;
+                state.serve_next();
             }
 
             if acquired {
-                Directive::NotifyAll
+                // no one else may proceed while the writer holds the lock
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(QUEUED, deadline.remaining())
             }
         });
 
-        if !acquired {
-            let mut inc_serviced = false;
-            sync.monitor.enter(|state| {
-                if !inc_serviced {
-                    inc_serviced = true;
-                    state.serviced_tickets += 1;
-                }
-                Directive::NotifyAll
-            });
-        }
-
-        acquired
+        queued.settle(acquired)
     }
 
     #[inline]
     fn write_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
@@ -140,13 +253,13 @@ impl Moderator for ArrivalOrdered {
                 state.writer = false;
             }
 
-            Directive::NotifyAll
+            ClassedDirective::Notify(Wake::NONE.all(QUEUED))
         });
     }
 
     fn downgrade(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
@@ -156,30 +269,79 @@ impl Moderator for ArrivalOrdered {
                 state.readers = 1;
             }
 
-            Directive::NotifyAll
+            ClassedDirective::Notify(Wake::NONE.all(QUEUED))
         });
     }
 
     fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        sync.monitor.enter(|state| {
-            if !acquired && state.readers == 1 {
+        sync.monitor.enter_classed(|state| {
+            if !acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
-                acquired = true;
-                state.readers = 0;
-                state.writer = true;
+                if state.readers == 1 {
+                    acquired = true;
+                    state.readers = 0;
+                    state.writer = true;
+                    state.barged = 0;
+                }
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(UPGRADERS, deadline.remaining())
             }
         });
         acquired
     }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter_classed(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                ClassedDirective::Return
+            } else {
+                ClassedDirective::Wait(CLAIMANTS, deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter_classed(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            ClassedDirective::Notify(Wake::NONE.one(CLAIMANTS))
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("ArrivalOrdered").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("ArrivalOrdered")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("next_ticket", &state.next_ticket)
+                .field("serving", &state.serving)
+                .finish(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,12 @@
 use std::cmp::Ordering;
-use std::sync::{Arc, Barrier};
+use std::sync::{Arc, Barrier, Mutex};
+use std::sync::atomic::{self, AtomicBool};
+use std::thread;
 use std::time::Duration;
 use crate::executor::{Executor, Queue, Submitter, ThreadPool};
-use crate::monitor::{Monitor};
-use crate::test_utils::LONG_WAIT;
+use crate::monitor::{Directive, Monitor};
+use crate::remedy::Remedy;
+use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
 use crate::wait;
 use crate::wait::{Wait, WaitResult};
 use crate::zlock::{ArrivalOrdered, ZLock};
@@ -15,7 +18,7 @@ fn readers_do_not_block_without_writer() {
     let _guard_2 = lock.read();
 
     assert_eq!(3, lock.next_ticket());
-    assert_eq!(2, lock.serviced_tickets());
+    assert_eq!(3, lock.serving());
 }
 
 #[test]
@@ -25,7 +28,7 @@ fn interleaving_writer_blocks_reader() {
 
     // after acquiring the lock, both the ticket and the service count should increase
     assert_eq!(2, lock.next_ticket());
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     let t_2 = ThreadPool::new(1, Queue::Unbounded);
 
@@ -39,16 +42,17 @@ fn interleaving_writer_blocks_reader() {
     // t_2 will block trying to acquire a write lock; it should increase the next_ticket count
     lock.wait_for_next_ticket(Ordering::is_ge, 3, LONG_WAIT).unwrap();
 
-    // the serviced_ticket count should remain
-    assert_eq!(1, lock.serviced_tickets());
+    // the ticket being served should remain
+    assert_eq!(2, lock.serving());
 
     // should not be able to read-acquire
     let guard_3 = lock.try_read(Duration::ZERO);
     assert!(guard_3.is_none());
 
-    // but timing in read-acquire should bump the serviced_tickets count
+    // timing out in read-acquire should abandon the ticket without serving it out of turn
     assert_eq!(4, lock.next_ticket());
-    assert_eq!(2, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
+    assert_eq!(1, lock.abandoned());
 
     // read-release
     drop(guard_1);
@@ -56,7 +60,10 @@ fn interleaving_writer_blocks_reader() {
     // t_2 should eventually succeed
     assert!(t_2_write.get().is_success());
     assert_eq!(4, lock.next_ticket());
-    assert_eq!(3, lock.serviced_tickets());
+
+    // the abandoned ticket should have been skipped over
+    assert_eq!(4, lock.serving());
+    assert_eq!(0, lock.abandoned());
 
     // main can now read-acquire
     let guard_4 = lock.try_read(Duration::ZERO);
@@ -71,7 +78,7 @@ fn queuing_order() {
 
     // after acquiring the lock, both the ticket and the service count should increase
     assert_eq!(2, lock.next_ticket());
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     let t_2 = ThreadPool::new(1, Queue::Unbounded);
     let t_3 = ThreadPool::new(1, Queue::Unbounded);
@@ -91,7 +98,7 @@ fn queuing_order() {
 
     // t_2 will block trying to acquire a write lock; it should increase the next_ticket count
     lock.wait_for_next_ticket(Ordering::is_ge, 3, LONG_WAIT).unwrap();
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     let t_3_read = {
         let lock = lock.clone();
@@ -102,7 +109,7 @@ fn queuing_order() {
 
     // t_3 will block trying to acquire a read lock; it should increase the next_ticket count
     lock.wait_for_next_ticket(Ordering::is_ge, 4, LONG_WAIT).unwrap();
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     let t_4_read_release = Arc::new(Barrier::new(2));
     let t_4_read = {
@@ -117,7 +124,7 @@ fn queuing_order() {
 
     // t_4 will block trying to acquire a read lock; it should increase the next_ticket count
     lock.wait_for_next_ticket(Ordering::is_ge, 5, LONG_WAIT).unwrap();
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     let t_5_write = {
         let lock = lock.clone();
@@ -128,7 +135,7 @@ fn queuing_order() {
 
     // t_5 will block trying to acquire a read lock; it should increase the next_ticket count
     lock.wait_for_next_ticket(Ordering::is_ge, 6, LONG_WAIT).unwrap();
-    assert_eq!(1, lock.serviced_tickets());
+    assert_eq!(2, lock.serving());
 
     // t_2-5 are definitely blocked
     assert!(!t_2_write.is_complete());
@@ -159,16 +166,157 @@ fn queuing_order() {
 
     // this unblocks t_5
     assert!(t_5_write.get().is_success());
-    assert_eq!(5, lock.serviced_tickets());
+    assert_eq!(6, lock.serving());
+}
+
+#[test]
+fn arrival_order_survives_spurious_wakeups() {
+    const THREADS: u64 = 5;
+    let lock = Arc::new(ZLock::<_, ArrivalOrdered>::new(()));
+    let guard_1 = lock.write();
+    let acquisitions = Arc::new(Mutex::new(Vec::new()));
+
+    // queue up the threads in a known order, each taking the next ticket
+    let threads = (0..THREADS)
+        .map(|i| {
+            let thread = {
+                let lock = lock.clone();
+                let acquisitions = acquisitions.clone();
+                thread::spawn(move || {
+                    let guard = lock.write();
+                    acquisitions.lock().remedy().push(i);
+                    thread::sleep(SHORT_WAIT);
+                    drop(guard);
+                })
+            };
+            lock.wait_for_next_ticket(Ordering::is_ge, i + 3, LONG_WAIT).unwrap();
+            thread
+        })
+        .collect::<Vec<_>>();
+
+    // wake all waiters continually, irrespective of whether their tickets are being served
+    let running = Arc::new(AtomicBool::new(true));
+    let notifier = {
+        let lock = lock.clone();
+        let running = running.clone();
+        thread::spawn(move || {
+            while running.load(atomic::Ordering::Relaxed) {
+                lock.notify_all();
+                thread::yield_now();
+            }
+        })
+    };
+
+    drop(guard_1);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    running.store(false, atomic::Ordering::Relaxed);
+    notifier.join().unwrap();
+
+    assert_eq!((0..THREADS).collect::<Vec<_>>(), *acquisitions.lock().remedy());
+}
+
+#[test]
+fn abandoned_ticket_does_not_advance_earlier_waiter() {
+    let lock = Arc::new(ZLock::<_, ArrivalOrdered>::new(0));
+    let guard_1 = lock.read();
+
+    // t_2 queues for a write lock behind main's read lock
+    let t_2 = ThreadPool::new(1, Queue::Unbounded);
+    let t_2_write = {
+        let lock = lock.clone();
+        t_2.submitter().submit(move || {
+            *lock.write() += 1;
+        })
+    };
+    lock.wait_for_next_ticket(Ordering::is_ge, 3, LONG_WAIT).unwrap();
+
+    // queue two readers that time out ahead of t_2; their tickets must not be served before t_2's
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert_eq!(2, lock.serving());
+    assert_eq!(2, lock.abandoned());
+
+    // main still cannot write-acquire, since t_2 is ahead of it
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(!t_2_write.is_complete());
+
+    drop(guard_1);
+    assert!(t_2_write.get().is_success());
+    assert_eq!(6, lock.serving());
+    assert_eq!(0, lock.abandoned());
+    assert_eq!(1, *lock.read());
+}
+
+#[test]
+fn batch_admits_readers_ahead_of_writer() {
+    const BATCH: u32 = 2;
+    let lock = Arc::new(ZLock::<_, ArrivalOrdered>::with_sync(ArrivalOrdered::with_batch(BATCH), 0));
+    let guard_1 = lock.read();
+
+    // t_2 queues for a write lock behind main's read lock
+    let t_2 = ThreadPool::new(1, Queue::Unbounded);
+    let t_2_write = {
+        let lock = lock.clone();
+        t_2.submitter().submit(move || {
+            *lock.write() += 1;
+        })
+    };
+    lock.wait_for_next_ticket(Ordering::is_ge, 3, LONG_WAIT).unwrap();
+
+    // readers join main's read lock out of turn, until the batch is exhausted
+    let barged = (0..BATCH).map(|_| lock.try_read(Duration::ZERO).unwrap()).collect::<Vec<_>>();
+    assert_eq!(BATCH, lock.barged());
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert_eq!(2, lock.serving());
+    assert!(!t_2_write.is_complete());
+
+    // once the readers drain, t_2 is admitted and the batch starts over
+    drop(barged);
+    drop(guard_1);
+    assert!(t_2_write.get().is_success());
+    assert_eq!(0, lock.barged());
+    assert_eq!(0, lock.abandoned());
+    assert_eq!(1, *lock.read());
+}
+
+#[test]
+fn zero_batch_is_strict() {
+    let lock = Arc::new(ZLock::<_, ArrivalOrdered>::with_config(0, 0));
+    let guard_1 = lock.read();
+    let t_2 = ThreadPool::new(1, Queue::Unbounded);
+    let t_2_write = {
+        let lock = lock.clone();
+        t_2.submitter().submit(move || {
+            *lock.write() += 1;
+        })
+    };
+    lock.wait_for_next_ticket(Ordering::is_ge, 3, LONG_WAIT).unwrap();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard_1);
+    assert!(t_2_write.get().is_success());
 }
 
 impl<T> ZLock<T, ArrivalOrdered> {
+    fn notify_all(&self) {
+        self.sync.monitor.enter(|_| Directive::NotifyAll);
+    }
+
     fn next_ticket(&self) -> u64 {
         self.sync.monitor.compute(|state| state.next_ticket)
     }
 
-    fn serviced_tickets(&self) -> u64 {
-        self.sync.monitor.compute(|state| state.serviced_tickets)
+    fn serving(&self) -> u64 {
+        self.sync.monitor.compute(|state| state.serving)
+    }
+
+    fn barged(&self) -> u32 {
+        self.sync.monitor.compute(|state| state.barged)
+    }
+
+    fn abandoned(&self) -> usize {
+        self.sync.monitor.compute(|state| state.abandoned.len())
     }
 
     fn wait_for_next_ticket(&self, cmp: impl FnMut(Ordering) -> bool, target: u64, duration: Duration) -> WaitResult {
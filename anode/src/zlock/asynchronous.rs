@@ -0,0 +1,94 @@
+use crate::zlock::{LockReadGuard, LockWriteGuard, Moderator, Polled, ZLock};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+impl<T: ?Sized, M: Moderator> ZLock<T, M> {
+    /// Returns a future that resolves to a read guard once the lock is acquired, without
+    /// blocking the polling thread.
+    ///
+    /// For moderators that support external waiters (see [`Moderator::poll_read`]), a pending
+    /// future is woken when the lock is released. For the remaining moderators, a pending future
+    /// wakes itself immediately, so that the executor retries the lock whenever it
+    /// next gets around to the task. Acquisition thus never blocks a worker thread, but
+    /// degrades to busy-polling under contention.
+    ///
+    /// The returned guard is not [`Send`]; it must be dropped before the task
+    /// yields if the task is to be spawned on a multithreaded executor.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// async fn read_it(lock: &ZLock<u64, ReadBiased>) -> u64 {
+    ///     *lock.read_async().await
+    /// }
+    /// ```
+    #[inline]
+    pub fn read_async(&self) -> ReadFuture<'_, T, M> {
+        ReadFuture { lock: self }
+    }
+
+    /// Returns a future that resolves to a write guard once the lock is acquired, without
+    /// blocking the polling thread. See [`read_async`](Self::read_async) for the
+    /// waking behaviour.
+    #[inline]
+    pub fn write_async(&self) -> WriteFuture<'_, T, M> {
+        WriteFuture { lock: self }
+    }
+}
+
+/// A future returned by [`ZLock::read_async`].
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadFuture<'a, T: ?Sized, M: Moderator> {
+    lock: &'a ZLock<T, M>,
+}
+
+impl<'a, T: ?Sized, M: Moderator> Future for ReadFuture<'a, T, M> {
+    type Output = LockReadGuard<'a, T, M>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let lock = self.lock;
+        match lock.poll_read(cx.waker()) {
+            Polled::Acquired(guard) => Poll::Ready(guard),
+            Polled::Pending => Poll::Pending,
+            Polled::Unsupported => retry_later(lock.try_read(Duration::ZERO), cx),
+        }
+    }
+}
+
+/// A future returned by [`ZLock::write_async`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteFuture<'a, T: ?Sized, M: Moderator> {
+    lock: &'a ZLock<T, M>,
+}
+
+impl<'a, T: ?Sized, M: Moderator> Future for WriteFuture<'a, T, M> {
+    type Output = LockWriteGuard<'a, T, M>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let lock = self.lock;
+        match lock.poll_write(cx.waker()) {
+            Polled::Acquired(guard) => Poll::Ready(guard),
+            Polled::Pending => Poll::Pending,
+            Polled::Unsupported => retry_later(lock.try_write(Duration::ZERO), cx),
+        }
+    }
+}
+
+/// Resolves to the guard if one was acquired; otherwise, schedules the task to be polled again.
+#[inline]
+fn retry_later<G>(guard: Option<G>, cx: &mut Context<'_>) -> Poll<G> {
+    match guard {
+        Some(guard) => Poll::Ready(guard),
+        None => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use crate::test_utils::block_on;
+use crate::zlock::{ArrivalOrdered, LegacyReadBiased, Moderator, ReadBiased, SpinModerator, Stochastic, WriteBiased, ZLock};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn uncontended() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    *block_on(lock.write_async()) = 42;
+    assert_eq!(42, *block_on(lock.read_async()));
+}
+
+// relies on Waker::will_wake to deduplicate the shared waker, which Miri's vtables defeat
+#[test]
+#[cfg_attr(miri, ignore)]
+fn pending_until_released() {
+    let lock = ZLock::<_, WriteBiased>::new(0);
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let mut guard = lock.write();
+    let mut read = pin!(lock.read_async());
+    let mut write = pin!(lock.write_async());
+    assert!(read.as_mut().poll(&mut cx).is_pending());
+    assert!(write.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(0, counter.0.load(Ordering::Relaxed));
+
+    *guard = 42;
+    drop(guard);
+    assert_eq!(1, counter.0.load(Ordering::Relaxed));
+    match read.as_mut().poll(&mut cx) {
+        Poll::Ready(guard) => assert_eq!(42, *guard),
+        Poll::Pending => panic!("read still pending"),
+    };
+    assert!(write.as_mut().poll(&mut cx).is_ready());
+}
+
+#[test]
+fn unsupported_moderator_wakes_itself() {
+    let lock = ZLock::<_, ArrivalOrdered>::new(0);
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    let guard = lock.write();
+    let mut read = pin!(lock.read_async());
+    assert!(read.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(1, counter.0.load(Ordering::Relaxed));
+    drop(guard);
+    assert!(read.as_mut().poll(&mut cx).is_ready());
+}
+
+#[test]
+fn contended_across_threads() {
+    __contended_across_threads::<ReadBiased>();
+    __contended_across_threads::<WriteBiased>();
+    __contended_across_threads::<ArrivalOrdered>();
+    __contended_across_threads::<Stochastic>();
+    __contended_across_threads::<SpinModerator>();
+    __contended_across_threads::<LegacyReadBiased>();
+}
+
+fn __contended_across_threads<M: Moderator + 'static>() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 100;
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    for _ in 0..ITERATIONS {
+                        let val = *lock.read_async().await;
+                        let mut guard = lock.write_async().await;
+                        assert!(*guard >= val);
+                        *guard += 1;
+                    }
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // a blocking writer contends with the asynchronous ones
+    for _ in 0..ITERATIONS {
+        *lock.write() += 1;
+        thread::sleep(Duration::ZERO);
+    }
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!((THREADS + 1) * ITERATIONS, *lock.read());
+}
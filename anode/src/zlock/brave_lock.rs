@@ -0,0 +1,316 @@
+use crate::backoff::spin_until;
+use crate::deadline::Deadline;
+use crate::zlock::{Moderator, RawZLock};
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long the reader bias stays inhibited after a writer revokes it, as a multiple of the
+/// time taken to revoke it. Bounds the slowdown inflicted on writers by revocation to
+/// roughly 1/(1 + `INHIBIT_MULTIPLIER`).
+const INHIBIT_MULTIPLIER: u32 = 9;
+
+/// A reader slot, padded to occupy a cache line of its own.
+#[repr(align(128))]
+#[derive(Default)]
+struct Slot(AtomicBool);
+
+/// A read-mostly lock (of the BRAVO, or "big reader", design), wherein readers avoid
+/// contending on a shared reader count by each occupying a slot of their own.
+///
+/// The lock layers a table of reader slots over an underlying [`RawZLock`]. While the lock is
+/// _reader-biased_, a reader claims the slot assigned to its thread, and that is all; readers
+/// on different threads touch different cache lines. Should the slot be occupied (by another
+/// thread sharing it), the reader falls back to read-locking the underlying lock. A writer
+/// write-locks the underlying lock, revokes the bias, and waits for the occupied slots to
+/// drain. Revocation is costly, scanning every slot, so the bias stays revoked for a time
+/// proportionate to the cost of revoking it, after which the next slow-path reader restores it.
+///
+/// Thus, readers scale with the number of cores when writes are rare, at the expense of slower
+/// writes and a larger footprint (a cache line per slot). The underlying lock's moderator
+/// governs the fairness of the slow path.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{BraveLock, ReadBiased};
+/// let lock = BraveLock::<_, ReadBiased>::new(42);
+/// let guard_1 = lock.read();
+/// let guard_2 = lock.read();
+/// assert_eq!(84, *guard_1 + *guard_2);
+/// drop((guard_1, guard_2));
+/// *lock.write() += 1;
+/// assert_eq!(43, *lock.read());
+/// ```
+pub struct BraveLock<T: ?Sized, M: Moderator> {
+    raw: RawZLock<M>,
+
+    /// Whether readers may take the fast path through their slots.
+    bias: AtomicBool,
+
+    /// The time (in nanoseconds since [`epoch`]) before which the bias may not be restored.
+    inhibit_until: AtomicU64,
+
+    slots: Box<[Slot]>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send, M: Moderator> Send for BraveLock<T, M> {}
+unsafe impl<T: ?Sized + Send + Sync, M: Moderator> Sync for BraveLock<T, M> {}
+
+/// The reference point for [`BraveLock::inhibit_until`].
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// The current time, in nanoseconds since [`epoch`].
+#[inline]
+fn now() -> u64 {
+    epoch().elapsed().as_nanos() as u64
+}
+
+/// A number that is unique to the current thread, from which its slot in every lock is derived.
+#[inline]
+fn thread_index() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    thread_local! {
+        static INDEX: usize = NEXT.fetch_add(1, Ordering::Relaxed);
+    }
+    INDEX.with(|index| *index)
+}
+
+impl<T, M: Moderator> BraveLock<T, M> {
+    /// Creates a lock with twice as many slots as the available parallelism.
+    #[inline]
+    pub fn new(t: T) -> Self {
+        let slots = thread::available_parallelism().map_or(16, |parallelism| parallelism.get() * 2);
+        Self::with_slots(slots, t)
+    }
+
+    /// Creates a lock with the given number of reader slots. Threads are assigned slots
+    /// round-robin, so there should be at least as many slots as concurrent readers for every
+    /// reader to take the fast path.
+    ///
+    /// # Panics
+    /// If `slots` is zero.
+    pub fn with_slots(slots: usize, t: T) -> Self {
+        assert_ne!(0, slots, "no slots");
+        Self {
+            raw: RawZLock::new(),
+            bias: AtomicBool::new(true),
+            inhibit_until: AtomicU64::new(0),
+            slots: (0..slots).map(|_| Slot::default()).collect(),
+            data: UnsafeCell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: ?Sized, M: Moderator> BraveLock<T, M> {
+    /// The number of reader slots.
+    #[inline]
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Determines whether readers are presently biased towards their slots. The result is a
+    /// racy snapshot.
+    #[inline]
+    pub fn is_reader_biased(&self) -> bool {
+        self.bias.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn read(&self) -> BraveReadGuard<'_, T, M> {
+        self.try_read(Duration::MAX).unwrap()
+    }
+
+    pub fn try_read(&self, duration: Duration) -> Option<BraveReadGuard<'_, T, M>> {
+        if let Some(slot) = self.try_read_fast() {
+            return Some(BraveReadGuard {
+                lock: self,
+                slot: Some(slot),
+                __no_send: PhantomData,
+            });
+        }
+
+        if !self.raw.try_lock_read(duration) {
+            return None;
+        }
+        // no writer can be revoking the bias while the read lock is held; the release publishes
+        // the last writer's changes to the fast-path readers that follow
+        if !self.bias.load(Ordering::Relaxed) && now() >= self.inhibit_until.load(Ordering::Relaxed) {
+            self.bias.store(true, Ordering::Release);
+        }
+        Some(BraveReadGuard {
+            lock: self,
+            slot: None,
+            __no_send: PhantomData,
+        })
+    }
+
+    /// Attempts to read-lock through the current thread's slot, returning the slot's index.
+    #[inline]
+    fn try_read_fast(&self) -> Option<usize> {
+        if !self.bias.load(Ordering::Relaxed) {
+            return None;
+        }
+        let index = thread_index() % self.slots.len();
+        let slot = &self.slots[index].0;
+        if slot.load(Ordering::Relaxed) || slot.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        // pairs with the writer's revocation: either the writer sees the slot occupied, or
+        // the reader sees the bias revoked
+        if self.bias.load(Ordering::SeqCst) {
+            Some(index)
+        } else {
+            slot.store(false, Ordering::Release);
+            None
+        }
+    }
+
+    #[inline]
+    pub fn write(&self) -> BraveWriteGuard<'_, T, M> {
+        self.try_write(Duration::MAX).unwrap()
+    }
+
+    /// Attempts to write-lock within the given `duration`, which bounds both the wait for the
+    /// underlying lock and the wait for slot readers to drain.
+    pub fn try_write(&self, duration: Duration) -> Option<BraveWriteGuard<'_, T, M>> {
+        let mut deadline = Deadline::lazy_after(duration);
+        if !self.raw.try_lock_write(duration) {
+            return None;
+        }
+        if self.bias.load(Ordering::Relaxed) && !self.revoke_bias(&mut deadline) {
+            // readers still occupy their slots, so the bias is reinstated for the next writer to
+            // revoke it anew
+            self.bias.store(true, Ordering::Release);
+            unsafe { self.raw.unlock_write() };
+            return None;
+        }
+        Some(BraveWriteGuard {
+            lock: self,
+            __no_send: PhantomData,
+        })
+    }
+
+    /// Revokes the reader bias and waits for the slot readers to drain before `deadline`,
+    /// returning `true` if they did. Called with the underlying lock write-locked.
+    fn revoke_bias(&self, deadline: &mut Deadline) -> bool {
+        let start = Instant::now();
+        self.bias.store(false, Ordering::SeqCst);
+        for slot in self.slots.iter() {
+            if !spin_until(deadline.remaining(), || !slot.0.load(Ordering::SeqCst)) {
+                return false;
+            }
+        }
+        let inhibit = start.elapsed() * INHIBIT_MULTIPLIER;
+        self.inhibit_until.store(now() + inhibit.as_nanos() as u64, Ordering::Relaxed);
+        true
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<T: ?Sized + Debug, M: Moderator> Debug for BraveLock<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("BraveLock");
+        match self.try_read(Duration::ZERO) {
+            Some(guard) => debug.field("data", &&*guard),
+            None => debug.field("data", &format_args!("<locked>")),
+        };
+        debug.field("reader_biased", &self.is_reader_biased())
+            .finish()
+    }
+}
+
+pub struct BraveReadGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    lock: &'a BraveLock<T, M>,
+
+    /// The slot occupied by the reader, if it took the fast path.
+    slot: Option<usize>,
+
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for BraveReadGuard<'_, T, M> {}
+
+impl<T: ?Sized, M: Moderator> Drop for BraveReadGuard<'_, T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        match self.slot {
+            Some(slot) => self.lock.slots[slot].0.store(false, Ordering::Release),
+            None => unsafe { self.lock.raw.unlock_read() },
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for BraveReadGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + Debug, M: Moderator> Debug for BraveReadGuard<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+pub struct BraveWriteGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    lock: &'a BraveLock<T, M>,
+
+    /// Emulates !Send for the struct. (Until issue 68318 -- negative trait bounds -- is resolved.)
+    __no_send: PhantomData<*const ()>,
+}
+
+unsafe impl<T: ?Sized + Sync, M: Moderator> Sync for BraveWriteGuard<'_, T, M> {}
+
+impl<T: ?Sized, M: Moderator> Drop for BraveWriteGuard<'_, T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.lock.raw.unlock_write() }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for BraveWriteGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> DerefMut for BraveWriteGuard<'_, T, M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized + Debug, M: Moderator> Debug for BraveWriteGuard<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,121 @@
+use crate::test_utils::{CHECK_WAIT, SHORT_WAIT};
+use crate::zlock::{BraveLock, Moderator, ReadBiased, WriteBiased};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn read_and_write() {
+    __read_and_write::<ReadBiased>();
+    __read_and_write::<WriteBiased>();
+}
+
+fn __read_and_write<M: Moderator>() {
+    let lock = BraveLock::<_, M>::with_slots(4, 0);
+    assert_eq!(4, lock.slots());
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    drop((guard_1, guard_2));
+
+    let mut guard = lock.write();
+    *guard = 42;
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+    assert_eq!(42, *lock.read());
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn fast_path_occupies_slot() {
+    let lock = BraveLock::<_, ReadBiased>::with_slots(1, ());
+    assert!(lock.is_reader_biased());
+    let fast = lock.read();
+    assert_eq!(Some(0), fast.slot);
+
+    // the slot is taken, so the next reader takes the slow path
+    let slow = lock.read();
+    assert_eq!(None, slow.slot);
+    drop((fast, slow));
+}
+
+#[test]
+fn writer_revokes_bias() {
+    let lock = BraveLock::<_, ReadBiased>::with_slots(2, ());
+    drop(lock.write());
+    assert!(!lock.is_reader_biased());
+    let guard = lock.read();
+    assert_eq!(None, guard.slot);
+    drop(guard);
+
+    // revocation took next to no time, so the bias is soon restored by a slow-path reader
+    thread::sleep(CHECK_WAIT);
+    drop(lock.read());
+    assert!(lock.is_reader_biased());
+    assert!(lock.read().slot.is_some());
+}
+
+#[test]
+fn writer_times_out_on_slot_reader() {
+    let lock = BraveLock::<_, ReadBiased>::with_slots(2, ());
+    let fast = lock.read();
+    assert!(fast.slot.is_some());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+
+    // the underlying lock was released by the failed writer, and the bias reinstated
+    assert!(lock.is_reader_biased());
+    assert_eq!(None, lock.try_read(Duration::ZERO).unwrap().slot);
+    drop(fast);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+#[should_panic(expected = "no slots")]
+fn no_slots() {
+    BraveLock::<(), ReadBiased>::with_slots(0, ());
+}
+
+#[test]
+fn contention() {
+    __contention::<ReadBiased>();
+    __contention::<WriteBiased>();
+}
+
+fn __contention<M: Moderator>() {
+    const READERS: usize = 4;
+    const WRITERS: usize = 2;
+    const ITERATIONS: u64 = 1_000;
+    // fewer slots than readers, exercising both paths
+    let lock = BraveLock::<_, M>::with_slots(3, (0u64, 0u64));
+    thread::scope(|scope| {
+        for _ in 0..WRITERS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let mut guard = lock.write();
+                    guard.0 += 1;
+                    guard.1 += 1;
+                }
+            });
+        }
+        for _ in 0..READERS {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    let guard = lock.read();
+                    assert_eq!(guard.0, guard.1);
+                }
+            });
+        }
+    });
+    assert_eq!((WRITERS as u64 * ITERATIONS, WRITERS as u64 * ITERATIONS), lock.into_inner());
+}
+
+#[test]
+fn debug() {
+    let lock = BraveLock::<_, ReadBiased>::with_slots(1, 42);
+    assert_eq!("BraveLock { data: 42, reader_biased: true }", format!("{:?}", lock));
+    let guard = lock.write();
+    assert_eq!("BraveLock { data: <locked>, reader_biased: false }", format!("{:?}", lock));
+    assert_eq!("42", format!("{:?}", guard));
+}
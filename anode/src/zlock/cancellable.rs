@@ -0,0 +1,108 @@
+use crate::cancellation::{wait_cancellable, CancellationToken, Interruption};
+use crate::instrument::Mode;
+use crate::zlock::{LockReadGuard, LockWriteGuard, Moderator, Timeout, ZLock};
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+impl<T: ?Sized, M: Moderator> ZLock<T, M> {
+    /// Attempts to acquire a read lock within the given `duration`, giving up early should
+    /// `token` be cancelled in the meantime.
+    ///
+    /// For moderators that support external waiters (see [`Moderator::poll_read`]), the
+    /// waiting thread is woken as soon as the token is cancelled. For the remaining moderators,
+    /// the wait is carried out in short slices, between which the token is checked; between
+    /// slices, a waiter may lose its place among other waiters (e.g., under [`ArrivalOrdered`](crate::zlock::ArrivalOrdered)).
+    #[inline]
+    pub fn try_read_cancellable(&self, token: &CancellationToken, duration: Duration) -> Result<LockReadGuard<'_, T, M>, WaitError> {
+        let mut outcome = Ok(());
+        self.acquire(Mode::Read, duration, |duration| {
+            outcome = wait_cancellable(
+                token,
+                duration,
+                |waker| M::poll_read(&self.sync, waker),
+                |slice| M::try_read(&self.sync, slice),
+            );
+            outcome.is_ok()
+        });
+        match outcome {
+            Ok(()) => {
+                Ok(LockReadGuard {
+                    lock: self,
+                    locked: true,
+                    __no_send: PhantomData,
+                })
+            }
+            Err(interruption) => Err(WaitError::new(interruption, duration)),
+        }
+    }
+
+    /// Attempts to acquire a write lock within the given `duration`, giving up early should
+    /// `token` be cancelled in the meantime. See [`try_read_cancellable`](Self::try_read_cancellable)
+    /// for the waking behaviour.
+    #[inline]
+    pub fn try_write_cancellable(&self, token: &CancellationToken, duration: Duration) -> Result<LockWriteGuard<'_, T, M>, WaitError> {
+        let mut outcome = Ok(());
+        self.acquire(Mode::Write, duration, |duration| {
+            outcome = wait_cancellable(
+                token,
+                duration,
+                |waker| M::poll_write(&self.sync, waker),
+                |slice| M::try_write(&self.sync, slice),
+            );
+            outcome.is_ok()
+        });
+        match outcome {
+            Ok(()) => Ok(LockWriteGuard {
+                lock: self,
+                locked: true,
+                __no_send: PhantomData,
+            }),
+            Err(interruption) => Err(WaitError::new(interruption, duration)),
+        }
+    }
+}
+
+/// The error returned when a cancellable wait for a lock is cut short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+    /// The lock could not be acquired within the requested duration.
+    Timeout(Timeout),
+
+    /// The [`CancellationToken`] was cancelled before the lock could be acquired.
+    Cancelled,
+}
+
+impl WaitError {
+    #[inline]
+    fn new(interruption: Interruption, duration: Duration) -> Self {
+        match interruption {
+            Interruption::Timeout => WaitError::Timeout(Timeout::new(duration)),
+            Interruption::Cancelled => WaitError::Cancelled,
+        }
+    }
+
+    #[inline]
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, WaitError::Timeout(_))
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, WaitError::Cancelled)
+    }
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitError::Timeout(timeout) => fmt::Display::fmt(timeout, f),
+            WaitError::Cancelled => f.write_str("lock wait cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+#[cfg(test)]
+mod tests;
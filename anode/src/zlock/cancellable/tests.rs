@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use std::time::Duration;
+use crate::cancellation::CancellationToken;
+use crate::test_utils::{spawn_blocked, SHORT_WAIT};
+use crate::zlock::{ArrivalOrdered, Moderator, ReadBiased, Timeout, WaitError, WriteBiased, ZLock};
+
+#[test]
+fn acquire_uncontended() {
+    __acquire_uncontended::<ReadBiased>();
+    __acquire_uncontended::<WriteBiased>();
+    __acquire_uncontended::<ArrivalOrdered>();
+}
+
+fn __acquire_uncontended<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let token = CancellationToken::new();
+    *lock.try_write_cancellable(&token, Duration::ZERO).unwrap() = 42;
+    assert_eq!(42, *lock.try_read_cancellable(&token, Duration::ZERO).unwrap());
+
+    token.cancel();
+    assert_eq!(WaitError::Cancelled, lock.try_read_cancellable(&token, Duration::ZERO).map(|_| ()).unwrap_err());
+}
+
+#[test]
+fn timeout() {
+    __timeout::<ReadBiased>();
+    __timeout::<WriteBiased>();
+    __timeout::<ArrivalOrdered>();
+}
+
+fn __timeout<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let token = CancellationToken::new();
+    let guard = lock.read();
+    let err = lock.try_write_cancellable(&token, SHORT_WAIT).map(|_| ()).unwrap_err();
+    assert_eq!(WaitError::Timeout(Timeout::new(SHORT_WAIT)), err);
+    assert!(err.is_timeout());
+    assert_eq!("lock not acquired within 1µs", err.to_string());
+
+    // the read lock is unaffected by the failed write attempt
+    assert!(lock.try_read_cancellable(&token, Duration::ZERO).is_ok());
+    drop(guard);
+    assert!(lock.try_write_cancellable(&token, Duration::ZERO).is_ok());
+}
+
+#[test]
+fn cancel_wakes_waiter() {
+    __cancel_wakes_waiter::<ReadBiased>();
+    __cancel_wakes_waiter::<WriteBiased>();
+    __cancel_wakes_waiter::<ArrivalOrdered>();
+}
+
+fn __cancel_wakes_waiter<M: Moderator + 'static>() {
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let token = Arc::new(CancellationToken::new());
+    let guard = lock.write();
+    let waiter = {
+        let lock = lock.clone();
+        let token = token.clone();
+        spawn_blocked(move || lock.try_read_cancellable(&token, Duration::MAX).map(|_| ()))
+    };
+    token.cancel();
+    let err = waiter.join().unwrap().unwrap_err();
+    assert!(err.is_cancelled());
+    assert_eq!("lock wait cancelled", err.to_string());
+    drop(guard);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn release_wakes_waiter() {
+    __release_wakes_waiter::<ReadBiased>();
+    __release_wakes_waiter::<WriteBiased>();
+    __release_wakes_waiter::<ArrivalOrdered>();
+}
+
+fn __release_wakes_waiter<M: Moderator + 'static>() {
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let token = Arc::new(CancellationToken::new());
+    let guard = lock.write();
+    let waiter = {
+        let lock = lock.clone();
+        let token = token.clone();
+        spawn_blocked(move || {
+            *lock.try_write_cancellable(&token, Duration::MAX).unwrap() += 1;
+        })
+    };
+    drop(guard);
+    waiter.join().unwrap();
+    assert_eq!(1, *lock.read());
+}
@@ -0,0 +1,253 @@
+use crate::zlock::{Moderator, ZLock};
+use std::fmt;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of transactions attempted by an elided acquisition before falling back to the
+/// lock.
+const ELISION_ATTEMPTS: u32 = 3;
+
+/// A lock that elides read acquisitions by way of hardware transactional memory, where the
+/// hardware supports it. (Experimental; available with the `htm` feature.)
+///
+/// Each read first runs its closure within a hardware transaction, without acquiring the
+/// underlying [`ZLock`], so that concurrent readers do not contend on the lock's state. After a
+/// few aborted attempts (or straight away, if the abort is not worth retrying), the closure is
+/// run under a read lock instead. Writes are never elided: a transaction that is later aborted
+/// still executes its closure, and handing the same `&mut T` to several such closures at once
+/// would be undefined behaviour, whatever the hardware later discards. Every thread on the slow
+/// path — including every writer — is registered as such for the duration, aborting any
+/// transaction in flight and deferring new ones, so that elided reads never overlap a write.
+///
+/// Elision is presently implemented over Intel's Restricted Transactional Memory (RTM), on
+/// `x86_64`, and is detected at runtime. Elsewhere (including on processors with RTM disabled),
+/// every operation takes the slow path.
+///
+/// Unlike [`ZLock`], the lock is accessed through closures rather than guards: a transaction
+/// that aborts resumes execution at the point at which it began, which must not be a stack
+/// frame that has since returned. A read closure may consequently be run more than once.
+/// Operations that cannot be performed transactionally (system calls, I/O, and so forth) abort
+/// the transaction, forcing the slow path, so read closures should be brief and free of such
+/// operations to benefit from elision.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{ElidedLock, ReadBiased};
+/// let lock = ElidedLock::<_, ReadBiased>::new(vec![0; 4]);
+/// lock.write(|vec| vec[1] = 42);
+/// assert_eq!(42, lock.read(|vec| vec[1]));
+/// ```
+pub struct ElidedLock<T, M: Moderator> {
+    /// The number of threads on the slow path.
+    fallbacks: AtomicUsize,
+    lock: ZLock<T, M>,
+}
+
+impl<T, M: Moderator> ElidedLock<T, M> {
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self {
+            fallbacks: AtomicUsize::new(0),
+            lock: ZLock::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.lock.into_inner()
+    }
+
+    /// Determines whether the processor supports elision. If not, every operation is
+    /// performed under the underlying lock.
+    #[inline]
+    pub fn is_elision_supported() -> bool {
+        rtm::is_supported()
+    }
+
+    /// Applies `f` to the data, in a transaction if possible, or under a read lock
+    /// otherwise.
+    #[inline]
+    pub fn read<U>(&self, f: impl Fn(&T) -> U) -> U {
+        let data = self.lock.data.get();
+        // SAFETY: writers register on the slow path before acquiring the lock, aborting the
+        // transaction before any write to the data
+        if let Some(val) = self.elide(|| f(unsafe { &*data })) {
+            return val;
+        }
+        let _fallback = Fallback::enter(&self.fallbacks);
+        f(&self.lock.read())
+    }
+
+    /// Applies `f` to the data under a write lock, having first aborted any elided reads in
+    /// flight.
+    #[inline]
+    pub fn write<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        let _fallback = Fallback::enter(&self.fallbacks);
+        f(&mut self.lock.write())
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.lock.get_mut()
+    }
+
+    /// The number of threads presently operating on the lock non-transactionally.
+    #[inline]
+    pub fn fallbacks(&self) -> usize {
+        self.fallbacks.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f` in a transaction, returning its result if the transaction committed, or
+    /// `None` if the caller should take the slow path.
+    #[inline]
+    fn elide<U>(&self, mut f: impl FnMut() -> U) -> Option<U> {
+        if !rtm::is_supported() {
+            return None;
+        }
+        for _ in 0..ELISION_ATTEMPTS {
+            if self.fallbacks.load(Ordering::Relaxed) != 0 {
+                // a transaction would abort anyway; queue up on the lock instead
+                return None;
+            }
+            // SAFETY: RTM support was detected above
+            let status = unsafe { rtm::begin() };
+            if status == rtm::STARTED {
+                // reading the counter brings it into the transaction's read set, such that a
+                // thread entering the slow path subsequently aborts the transaction
+                if self.fallbacks.load(Ordering::Relaxed) != 0 {
+                    unsafe { rtm::abort() };
+                }
+                let abort_on_unwind = AbortOnUnwind;
+                let val = f();
+                std::mem::forget(abort_on_unwind);
+                unsafe { rtm::end() };
+                return Some(val);
+            }
+            if status & rtm::RETRY == 0 {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+impl<T: Default, M: Moderator> Default for ElidedLock<T, M> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug, M: Moderator> Debug for ElidedLock<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElidedLock")
+            .field("fallbacks", &self.fallbacks())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+/// Registers a thread on the slow path for as long as it is held.
+struct Fallback<'a>(&'a AtomicUsize);
+
+impl<'a> Fallback<'a> {
+    #[inline]
+    fn enter(fallbacks: &'a AtomicUsize) -> Self {
+        // registered before acquiring the lock, so as to abort any transaction in flight
+        fallbacks.fetch_add(1, Ordering::SeqCst);
+        Self(fallbacks)
+    }
+}
+
+impl Drop for Fallback<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// Aborts the enclosing transaction should the closure run within it panic, rather than
+/// letting the unwind escape the frame in which the transaction began.
+struct AbortOnUnwind;
+
+impl Drop for AbortOnUnwind {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { rtm::abort() };
+    }
+}
+
+#[cfg(all(target_arch = "x86_64", not(miri)))]
+mod rtm {
+    use std::arch::asm;
+
+    /// The status returned by [`begin`] upon starting a transaction.
+    pub const STARTED: u32 = !0;
+
+    /// Set in the abort status if the transaction may succeed on a retry.
+    pub const RETRY: u32 = 1 << 1;
+
+    #[inline]
+    pub fn is_supported() -> bool {
+        std::is_x86_feature_detected!("rtm")
+    }
+
+    /// Begins a transaction, returning [`STARTED`]. Should the transaction abort, execution
+    /// resumes from here, with all registers and memory as they were, and the abort status
+    /// returned instead.
+    ///
+    /// # Safety
+    /// The processor must support RTM.
+    #[inline(always)]
+    pub unsafe fn begin() -> u32 {
+        let status: u32;
+        asm!("mov eax, -1", "xbegin 2f", "2:", out("eax") status, options(nostack));
+        status
+    }
+
+    /// Commits the current transaction.
+    ///
+    /// # Safety
+    /// Must be called within a transaction.
+    #[inline(always)]
+    pub unsafe fn end() {
+        asm!("xend", options(nostack));
+    }
+
+    /// Aborts the current transaction, if there is one. Outside of a transaction, this
+    /// does nothing.
+    ///
+    /// # Safety
+    /// The processor must support RTM.
+    #[inline(always)]
+    pub unsafe fn abort() {
+        asm!("xabort 0xff", options(nostack));
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", not(miri))))]
+mod rtm {
+    pub const STARTED: u32 = !0;
+
+    pub const RETRY: u32 = 1 << 1;
+
+    #[inline]
+    pub fn is_supported() -> bool {
+        false
+    }
+
+    #[inline(always)]
+    pub unsafe fn begin() -> u32 {
+        unreachable!("RTM is not supported")
+    }
+
+    #[inline(always)]
+    pub unsafe fn end() {
+        unreachable!("RTM is not supported")
+    }
+
+    #[inline(always)]
+    pub unsafe fn abort() {}
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,86 @@
+use crate::zlock::{ArrivalOrdered, ElidedLock, Moderator, ReadBiased, WriteBiased};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+#[test]
+fn read_and_write() {
+    __read_and_write::<ReadBiased>();
+    __read_and_write::<WriteBiased>();
+    __read_and_write::<ArrivalOrdered>();
+}
+
+fn __read_and_write<M: Moderator>() {
+    let lock = ElidedLock::<_, M>::new(vec![0; 4]);
+    assert_eq!(0, lock.write(|vec| std::mem::replace(&mut vec[1], 42)));
+    assert_eq!(vec![0, 42, 0, 0], lock.read(Vec::clone));
+    assert_eq!(0, lock.fallbacks());
+    assert_eq!(vec![0, 42, 0, 0], lock.into_inner());
+}
+
+#[test]
+fn elision_support_matches_detection() {
+    #[cfg(all(target_arch = "x86_64", not(miri)))]
+    let detected = std::is_x86_feature_detected!("rtm");
+    #[cfg(not(all(target_arch = "x86_64", not(miri))))]
+    let detected = false;
+    assert_eq!(detected, ElidedLock::<(), ReadBiased>::is_elision_supported());
+}
+
+#[test]
+fn writes_take_the_slow_path() {
+    let lock = ElidedLock::<_, ReadBiased>::new(0);
+    lock.write(|val| {
+        assert_eq!(1, lock.fallbacks());
+        *val = 42;
+    });
+    assert_eq!(0, lock.fallbacks());
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn fallback_released_on_panic() {
+    let lock = ElidedLock::<_, ReadBiased>::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| lock.write(|_| panic!("in the critical section"))));
+    assert!(result.is_err());
+    assert_eq!(0, lock.fallbacks());
+    assert_eq!(42, lock.write(|val| {
+        *val = 42;
+        *val
+    }));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn concurrent_writes_are_not_lost() {
+    const THREADS: usize = 4;
+    const WRITES: u64 = 1_000;
+    let lock = ElidedLock::<_, ReadBiased>::new((0u64, 0u64));
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..WRITES {
+                    lock.write(|(a, b)| {
+                        *a += 1;
+                        *b += 1;
+                    });
+                    lock.read(|(a, b)| assert_eq!(a, b));
+                }
+            });
+        }
+    });
+    assert_eq!(0, lock.fallbacks());
+    assert_eq!((THREADS as u64 * WRITES, THREADS as u64 * WRITES), lock.into_inner());
+}
+
+#[test]
+fn get_mut_and_default() {
+    let mut lock = ElidedLock::<u64, ReadBiased>::default();
+    *lock.get_mut() = 42;
+    assert_eq!(42, lock.read(|&val| val));
+}
+
+#[test]
+fn debug() {
+    let lock = ElidedLock::<_, ReadBiased>::new(42);
+    assert!(format!("{:?}", lock).starts_with("ElidedLock { fallbacks: 0, lock: ZLock"));
+}
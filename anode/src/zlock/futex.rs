@@ -0,0 +1,246 @@
+use std::fmt;
+use std::hint;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::zlock::{ConstModerator, Moderator};
+
+/// A read-biased moderator that waits on a Linux futex, rather than a [`Mutex`](std::sync::Mutex)
+/// and a [`Condvar`](std::sync::Condvar). Available on Linux with the `futex` feature.
+///
+/// The entire lock state is a single [`AtomicU32`], which also serves as the futex word. The most
+/// significant bit denotes the writer, the next bit denotes the upgradable slot, the next records
+/// the presence of parked waiters, and the remaining bits count the readers. An uncontended
+/// acquisition or release is a single atomic operation; the kernel is only entered once a waiter
+/// has spun briefly without acquiring the lock, and a release only wakes waiters if any are
+/// parked. All parked waiters are woken together, to re-contend for the lock.
+///
+/// Readers are admitted whenever there is no writer, so a writer may be starved by a
+/// continuous stream of overlapping readers.
+///
+/// Being a [`ConstModerator`], the lock may be constructed in a `const` context using
+/// [`ZLock::const_new`].
+///
+/// # Examples
+/// ```
+/// # #[cfg(all(feature = "futex", target_os = "linux"))]
+/// # {
+/// use anode::zlock::{Futex, ZLock};
+/// static COUNTER: ZLock<u64, Futex> = ZLock::const_new(0);
+/// *COUNTER.write() += 1;
+/// assert_eq!(1, *COUNTER.read());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Futex;
+
+pub(crate) const WRITER: u32 = 1 << 31;
+
+pub(crate) const UPGRADABLE: u32 = 1 << 30;
+
+/// Set while at least one thread is parked on the futex word.
+pub(crate) const PARKED: u32 = 1 << 29;
+
+pub(crate) const READERS: u32 = PARKED - 1;
+
+/// The number of acquisition attempts made before parking.
+const SPIN_ATTEMPTS: u32 = 100;
+
+/// Blocks the calling thread while the word holds `expected`, for at most `timeout`. May return
+/// spuriously.
+#[inline]
+fn futex_wait(word: &AtomicU32, expected: u32, timeout: Duration) {
+    let timespec = libc::time_t::try_from(timeout.as_secs())
+        .ok()
+        .map(|tv_sec| libc::timespec {
+            tv_sec,
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        });
+    let timespec = timespec.as_ref().map_or(ptr::null(), |timespec| timespec as *const _);
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+            expected,
+            timespec,
+        );
+    }
+}
+
+/// Wakes all threads parked on the word.
+#[inline]
+fn futex_wake_all(word: &AtomicU32) {
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            word.as_ptr(),
+            libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+            i32::MAX,
+        );
+    }
+}
+
+/// Acquires the lock once `admit` yields the next state for the current one, waiting at
+/// most `duration`. The transition performed by `admit` must preserve the [`PARKED`] bit.
+#[inline]
+fn acquire(sync: &AtomicU32, duration: Duration, admit: impl Fn(u32) -> Option<u32>) -> bool {
+    let mut state = sync.load(Ordering::Relaxed);
+    let mut attempts = 0;
+    let mut deadline = Deadline::lazy_after(duration);
+    loop {
+        if let Some(next) = admit(state) {
+            match sync.compare_exchange_weak(state, next, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => {
+                    state = actual;
+                    continue;
+                }
+            }
+        }
+
+        let remaining = deadline.remaining();
+        if remaining.is_zero() {
+            return false;
+        }
+
+        if attempts < SPIN_ATTEMPTS {
+            attempts += 1;
+            hint::spin_loop();
+            state = sync.load(Ordering::Relaxed);
+            continue;
+        }
+
+        if state & PARKED == 0 {
+            if let Err(actual) = sync.compare_exchange_weak(state, state | PARKED, Ordering::Relaxed, Ordering::Relaxed) {
+                state = actual;
+                continue;
+            }
+            state |= PARKED;
+        }
+        futex_wait(sync, state, remaining);
+        state = sync.load(Ordering::Relaxed);
+    }
+}
+
+/// Releases the lock by applying `release` to its state, clearing the [`PARKED`] bit and waking
+/// all parked waiters if `wake` holds for the prior state.
+#[inline]
+fn release(sync: &AtomicU32, release: impl Fn(u32) -> u32, wake: impl Fn(u32) -> bool) -> u32 {
+    let prev = sync
+        .fetch_update(Ordering::Release, Ordering::Relaxed, |state| {
+            let next = release(state);
+            if state & PARKED != 0 && wake(state) {
+                Some(next & !PARKED)
+            } else {
+                Some(next)
+            }
+        })
+        .unwrap();
+    if prev & PARKED != 0 && wake(prev) {
+        futex_wake_all(sync);
+    }
+    prev
+}
+
+impl ConstModerator for Futex {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: AtomicU32 = AtomicU32::new(0);
+}
+
+impl Moderator for Futex {
+    type Sync = AtomicU32;
+
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::INIT
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        acquire(sync, duration, |state| {
+            if state & WRITER == 0 {
+                debug_assert!(state & READERS < READERS, "too many readers");
+                Some(state + 1)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        // only writers (once no readers remain) and upgraders (once one reader remains) are
+        // waiting on the departure of a reader
+        let _prev = release(sync, |state| state - 1, |state| state & READERS <= 2);
+        debug_assert!(_prev & READERS > 0, "readers: {}", _prev & READERS);
+        debug_assert_eq!(0, _prev & WRITER);
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        // the upgradable slot does not exclude writers
+        acquire(sync, duration, |state| {
+            if state & (WRITER | READERS) == 0 {
+                Some(state | WRITER)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        let _prev = release(sync, |state| state & !WRITER, |_| true);
+        debug_assert_eq!(WRITER, _prev & (WRITER | READERS));
+    }
+
+    #[inline]
+    fn downgrade(sync: &Self::Sync) {
+        let _prev = release(sync, |state| state - WRITER + 1, |_| true);
+        debug_assert_eq!(WRITER, _prev & (WRITER | READERS));
+    }
+
+    #[inline]
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        debug_assert!(sync.load(Ordering::Relaxed) & READERS > 0, "readers: {}", sync.load(Ordering::Relaxed) & READERS);
+        acquire(sync, duration, |state| {
+            if state & (WRITER | READERS) == 1 {
+                Some(state - 1 + WRITER)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        acquire(sync, duration, |state| {
+            if state & UPGRADABLE == 0 {
+                Some(state | UPGRADABLE)
+            } else {
+                None
+            }
+        })
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let _prev = release(sync, |state| state & !UPGRADABLE, |_| true);
+        debug_assert_ne!(0, _prev & UPGRADABLE);
+    }
+
+    #[inline]
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = sync.load(Ordering::Relaxed);
+        f.debug_struct("Futex")
+            .field("readers", &(state & READERS))
+            .field("writer", &(state & WRITER != 0))
+            .field("parked", &(state & PARKED != 0))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
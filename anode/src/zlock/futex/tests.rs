@@ -0,0 +1,154 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::wait;
+use crate::wait::Wait;
+use crate::zlock::futex::{PARKED, READERS, UPGRADABLE, WRITER};
+use crate::zlock::{Futex, ZLock};
+
+impl<T> ZLock<T, Futex> {
+    fn readers(&self) -> u32 {
+        self.sync.load(Ordering::Relaxed) & READERS
+    }
+
+    fn writer(&self) -> bool {
+        self.sync.load(Ordering::Relaxed) & WRITER != 0
+    }
+
+    fn upgradable(&self) -> bool {
+        self.sync.load(Ordering::Relaxed) & UPGRADABLE != 0
+    }
+
+    fn parked(&self) -> bool {
+        self.sync.load(Ordering::Relaxed) & PARKED != 0
+    }
+}
+
+static STATIC_LOCK: ZLock<u64, Futex> = ZLock::const_new(0);
+
+#[test]
+fn static_lock() {
+    *STATIC_LOCK.write() = 42;
+    assert_eq!(42, *STATIC_LOCK.read());
+    assert_eq!(0, STATIC_LOCK.readers());
+    assert!(!STATIC_LOCK.writer());
+}
+
+#[test]
+fn state_transitions() {
+    let lock = ZLock::<_, Futex>::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    assert_eq!(2, lock.readers());
+    assert!(!lock.writer());
+
+    // cannot upgrade while another reader is present
+    let guard_1 = guard_1.try_upgrade(Duration::ZERO).unchanged().unwrap();
+    drop(guard_2);
+    assert_eq!(1, lock.readers());
+
+    let mut guard = guard_1.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+    assert_eq!(0, lock.readers());
+    assert!(lock.writer());
+
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert_eq!(1, lock.readers());
+    assert!(!lock.writer());
+    drop(guard);
+    assert_eq!(0, lock.readers());
+
+    let guard = lock.read_upgradable();
+    assert!(lock.upgradable());
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_none());
+    drop(guard);
+    assert!(!lock.upgradable());
+    assert!(!lock.parked());
+}
+
+#[test]
+fn timeout_is_honoured() {
+    let lock = ZLock::<_, Futex>::new(());
+    let guard = lock.write();
+    let start = Instant::now();
+    assert!(lock.try_read(CHECK_WAIT).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert!(start.elapsed() >= CHECK_WAIT + SHORT_WAIT);
+    drop(guard);
+    assert!(!lock.parked());
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn release_wakes_parked_waiters() {
+    let lock = Arc::new(ZLock::<_, Futex>::new(0));
+    let guard = lock.write();
+    let readers = (0..2)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || *lock.read())
+        })
+        .collect::<Vec<_>>();
+    wait::Spin::wait_for(|| lock.parked(), LONG_WAIT).unwrap();
+
+    drop(guard);
+    for reader in readers {
+        assert_eq!(0, reader.join().unwrap());
+    }
+    assert!(!lock.parked());
+}
+
+#[test]
+fn last_reader_wakes_upgrader() {
+    let lock = Arc::new(ZLock::<_, Futex>::new(0));
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    let upgrader = thread::spawn({
+        let lock = lock.clone();
+        move || {
+            let guard = lock.read();
+            *guard.upgrade() = 42;
+        }
+    });
+    wait::Spin::wait_for(|| lock.parked(), LONG_WAIT).unwrap();
+
+    // with two other readers remaining, the upgrader remains parked
+    drop(guard_1);
+    assert!(lock.parked());
+    drop(guard_2);
+    upgrader.join().unwrap();
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn contended_increments() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 1_000;
+    let lock = Arc::new(ZLock::<_, Futex>::new(0));
+    let threads = (0..THREADS)
+        .map(|i| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    if i % 2 == 0 {
+                        *lock.write() += 1;
+                    } else {
+                        *lock.read_upgradable().upgrade() += 1;
+                    }
+                    let guard = lock.read();
+                    assert!(*guard > 0);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS, *lock.read());
+    assert_eq!(0, lock.readers());
+    assert!(!lock.writer());
+    assert!(!lock.upgradable());
+}
@@ -0,0 +1,227 @@
+use crate::zlock::{LockReadGuard, LockWriteGuard, Moderator, ZLock};
+use std::fmt;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+#[cfg(debug_assertions)]
+thread_local! {
+    /// The levels of the hierarchical locks held by the current thread.
+    static HELD: std::cell::RefCell<Vec<u32>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A [`ZLock`] that is assigned a level in a lock hierarchy, for catching lock-order
+/// inversions before they manifest as deadlocks.
+///
+/// Locks must be acquired in strictly descending order of level: in debug builds, acquiring
+/// a lock at a level greater than or equal to that of any hierarchical lock already held by
+/// the current thread panics. Zero-duration attempts ([`try_read`](Self::try_read) and
+/// [`try_write`](Self::try_write) with [`Duration::ZERO`]) cannot deadlock, and are exempt
+/// from the check; a lock so acquired nonetheless counts as held. In release builds, no
+/// bookkeeping is performed.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{HierarchicalLock, ReadBiased};
+/// let accounts = HierarchicalLock::<_, ReadBiased>::new(2, vec![100]);
+/// let audit = HierarchicalLock::<Vec<u32>, ReadBiased>::new(1, vec![]);
+/// let balance = accounts.read()[0];
+/// audit.write().push(balance);
+/// ```
+///
+/// Acquiring in the wrong order panics (in debug builds):
+/// ```should_panic
+/// # if !cfg!(debug_assertions) { panic!() }
+/// use anode::zlock::{HierarchicalLock, ReadBiased};
+/// let accounts = HierarchicalLock::<_, ReadBiased>::new(2, vec![100]);
+/// let audit = HierarchicalLock::<Vec<u32>, ReadBiased>::new(1, vec![]);
+/// let _audit = audit.write();
+/// let _accounts = accounts.read(); // panics
+/// ```
+pub struct HierarchicalLock<T: ?Sized, M: Moderator> {
+    level: u32,
+    lock: ZLock<T, M>,
+}
+
+impl<T, M: Moderator> HierarchicalLock<T, M> {
+    #[inline]
+    pub fn new(level: u32, t: T) -> Self {
+        Self {
+            level,
+            lock: ZLock::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.lock.into_inner()
+    }
+}
+
+impl<T: ?Sized, M: Moderator> HierarchicalLock<T, M> {
+    #[inline]
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    #[inline]
+    pub fn read(&self) -> HierarchicalReadGuard<'_, T, M> {
+        self.try_read(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_read(&self, duration: Duration) -> Option<HierarchicalReadGuard<'_, T, M>> {
+        self.check(duration);
+        self.lock.try_read(duration).map(|inner| HierarchicalReadGuard {
+            inner,
+            held: Held::push(self.level),
+        })
+    }
+
+    #[inline]
+    pub fn write(&self) -> HierarchicalWriteGuard<'_, T, M> {
+        self.try_write(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_write(&self, duration: Duration) -> Option<HierarchicalWriteGuard<'_, T, M>> {
+        self.check(duration);
+        self.lock.try_write(duration).map(|inner| HierarchicalWriteGuard {
+            inner,
+            held: Held::push(self.level),
+        })
+    }
+
+    /// Returns a mutable reference to the underlying data.
+    ///
+    /// Since this call borrows the lock mutably, no actual locking needs to take place, and
+    /// the hierarchy is not consulted.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.lock.get_mut()
+    }
+
+    #[cfg(debug_assertions)]
+    #[inline]
+    fn check(&self, duration: Duration) {
+        if duration.is_zero() {
+            return;
+        }
+        HELD.with_borrow(|held| {
+            if let Some(&lowest) = held.iter().min() {
+                assert!(
+                    self.level < lowest,
+                    "lock order violation: acquiring level {} while holding level {}",
+                    self.level,
+                    lowest
+                );
+            }
+        });
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn check(&self, _duration: Duration) {}
+}
+
+impl<T: ?Sized + Debug, M: Moderator> Debug for HierarchicalLock<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HierarchicalLock")
+            .field("level", &self.level)
+            .field("lock", &&self.lock)
+            .finish()
+    }
+}
+
+/// Records a level as held by the current thread for as long as it lives.
+struct Held {
+    #[cfg(debug_assertions)]
+    level: u32,
+}
+
+impl Held {
+    #[cfg(debug_assertions)]
+    #[inline]
+    fn push(level: u32) -> Self {
+        HELD.with_borrow_mut(|held| held.push(level));
+        Self { level }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline(always)]
+    fn push(_level: u32) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Drop for Held {
+    #[inline]
+    fn drop(&mut self) {
+        HELD.with_borrow_mut(|held| {
+            // guards may be released out of acquisition order
+            if let Some(index) = held.iter().rposition(|&level| level == self.level) {
+                held.remove(index);
+            }
+        });
+    }
+}
+
+pub struct HierarchicalReadGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    inner: LockReadGuard<'a, T, M>,
+    held: Held,
+}
+
+impl<'a, T: ?Sized, M: Moderator> HierarchicalReadGuard<'a, T, M> {
+    /// Upgrades the read lock to a write lock. The lock remains at the same level, so the
+    /// upgrade is not subject to the hierarchy check.
+    #[inline]
+    pub fn upgrade(self) -> HierarchicalWriteGuard<'a, T, M> {
+        HierarchicalWriteGuard {
+            inner: self.inner.upgrade(),
+            held: self.held,
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for HierarchicalReadGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+pub struct HierarchicalWriteGuard<'a, T: ?Sized + 'a, M: Moderator + 'a> {
+    inner: LockWriteGuard<'a, T, M>,
+    held: Held,
+}
+
+impl<'a, T: ?Sized, M: Moderator> HierarchicalWriteGuard<'a, T, M> {
+    #[inline]
+    pub fn downgrade(self) -> HierarchicalReadGuard<'a, T, M> {
+        HierarchicalReadGuard {
+            inner: self.inner.downgrade(),
+            held: self.held,
+        }
+    }
+}
+
+impl<T: ?Sized, M: Moderator> Deref for HierarchicalWriteGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized, M: Moderator> DerefMut for HierarchicalWriteGuard<'_, T, M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests;
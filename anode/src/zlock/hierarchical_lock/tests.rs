@@ -0,0 +1,97 @@
+use crate::zlock::{HierarchicalLock, Moderator, ReadBiased, WriteBiased};
+use std::time::Duration;
+
+#[test]
+fn descending_order() {
+    __descending_order::<ReadBiased>();
+    __descending_order::<WriteBiased>();
+}
+
+fn __descending_order<M: Moderator>() {
+    let high = HierarchicalLock::<_, M>::new(3, 1);
+    let mid = HierarchicalLock::<_, M>::new(2, 2);
+    let low = HierarchicalLock::<_, M>::new(1, 0);
+    let high_guard = high.read();
+    let mid_guard = mid.read();
+    *low.write() = *high_guard + *mid_guard;
+    drop(high_guard);
+    drop(mid_guard);
+    assert_eq!(3, *low.read());
+
+    // with everything released, any order is permitted
+    let low_guard = low.read();
+    drop(low_guard);
+    let _high_guard = high.write();
+}
+
+#[test]
+fn out_of_order_release() {
+    let high = HierarchicalLock::<_, ReadBiased>::new(2, ());
+    let low = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let high_guard = high.write();
+    let low_guard = low.write();
+    drop(high_guard);
+    drop(low_guard);
+    assert!(high.try_write(Duration::MAX).is_some());
+}
+
+#[test]
+fn upgrade_and_downgrade_retain_level() {
+    let high = HierarchicalLock::<_, ReadBiased>::new(2, 0);
+    let low = HierarchicalLock::<_, ReadBiased>::new(1, 0);
+    let mut guard = high.read().upgrade();
+    *guard = 42;
+    let guard = guard.downgrade();
+    *low.write() = *guard;
+    drop(guard);
+    assert_eq!(42, *low.read());
+    assert_eq!(42, high.into_inner());
+}
+
+#[test]
+fn zero_duration_try_is_exempt() {
+    let high = HierarchicalLock::<_, ReadBiased>::new(2, ());
+    let low = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let _low_guard = low.read();
+    assert!(high.try_read(Duration::ZERO).is_some());
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "lock order violation: acquiring level 2 while holding level 1")]
+fn ascending_order_panics() {
+    let high = HierarchicalLock::<_, ReadBiased>::new(2, ());
+    let low = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let _low_guard = low.read();
+    let _high_guard = high.read();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "lock order violation: acquiring level 1 while holding level 1")]
+fn same_level_panics() {
+    let a = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let b = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let _a_guard = a.write();
+    let _b_guard = b.write();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "lock order violation: acquiring level 2 while holding level 1")]
+fn out_of_order_release_retains_lower_level() {
+    let high = HierarchicalLock::<_, ReadBiased>::new(2, ());
+    let low = HierarchicalLock::<_, ReadBiased>::new(1, ());
+    let high_guard = high.write();
+    let _low_guard = low.write();
+    drop(high_guard);
+    let _high_guard = high.write();
+}
+
+#[test]
+fn debug() {
+    let lock = HierarchicalLock::<_, ReadBiased>::new(7, 42);
+    let str = format!("{lock:?}");
+    assert!(str.starts_with("HierarchicalLock { level: 7, lock: ZLock<ReadBiased> {"), "{str}");
+    assert!(str.contains("data: 42"), "{str}");
+}
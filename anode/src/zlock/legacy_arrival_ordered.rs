@@ -1,4 +1,5 @@
 use std::sync::{Condvar, Mutex};
+use std::fmt;
 use std::time::Duration;
 use crate::deadline::Deadline;
 use crate::remedy;
@@ -18,6 +19,7 @@ pub struct LegacyArrivalOrderedSync {
 struct LegacyArrivalOrderedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
     next_ticket: u64,
     serviced_tickets: u64
 }
@@ -37,7 +39,7 @@ impl Moderator for LegacyArrivalOrdered {
     #[inline]
     fn new() -> Self::Sync {
         Self::Sync {
-            state: Mutex::new(LegacyArrivalOrderedState { readers: 0, writer: false, next_ticket: 1, serviced_tickets: 0 }),
+            state: Mutex::new(LegacyArrivalOrderedState { readers: 0, writer: false, upgradable: false, next_ticket: 1, serviced_tickets: 0 }),
             cond: Condvar::new()
         }
     }
@@ -143,4 +145,43 @@ impl Moderator for LegacyArrivalOrdered {
         state.writer = true;
         true
     }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut state = sync.state.lock().remedy();
+        while state.upgradable {
+            let (guard, timed_out) =
+                remedy::cond_wait_remedy(&sync.cond, state, deadline.remaining());
+
+            if timed_out {
+                return false
+            }
+            state = guard;
+        }
+        state.upgradable = true;
+        true
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut state = sync.state.lock().remedy();
+        debug_assert!(state.upgradable);
+        state.upgradable = false;
+        drop(state);
+        sync.cond.notify_all();
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.state.try_lock().remedy() {
+            None => f.debug_struct("LegacyArrivalOrdered").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("LegacyArrivalOrdered")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("next_ticket", &state.next_ticket)
+                .field("serviced_tickets", &state.serviced_tickets)
+                .finish(),
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use std::sync::{Condvar, Mutex};
+use std::fmt;
 use std::time::Duration;
 use crate::deadline::Deadline;
 use crate::remedy;
@@ -18,6 +19,7 @@ pub struct LegacyReadBiasedSync {
 struct LegacyReadBiasedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
 }
 
 impl Moderator for LegacyReadBiased {
@@ -26,7 +28,7 @@ impl Moderator for LegacyReadBiased {
     #[inline]
     fn new() -> Self::Sync {
         Self::Sync {
-            state: Mutex::new(LegacyReadBiasedState { readers: 0, writer: false }),
+            state: Mutex::new(LegacyReadBiasedState { readers: 0, writer: false, upgradable: false }),
             cond: Condvar::new()
         }
     }
@@ -87,7 +89,8 @@ impl Moderator for LegacyReadBiased {
         debug_assert!(state.writer);
         state.writer = false;
         drop(state);
-        sync.cond.notify_one();
+        // all blocked readers may proceed at once
+        sync.cond.notify_all();
     }
 
     fn downgrade(sync: &Self::Sync) {
@@ -120,4 +123,41 @@ impl Moderator for LegacyReadBiased {
         state.writer = true;
         true
     }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut state = sync.state.lock().remedy();
+        while state.upgradable {
+            let (guard, timed_out) =
+                remedy::cond_wait_remedy(&sync.cond, state, deadline.remaining());
+
+            if timed_out {
+                return false
+            }
+            state = guard;
+        }
+        state.upgradable = true;
+        true
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut state = sync.state.lock().remedy();
+        debug_assert!(state.upgradable);
+        state.upgradable = false;
+        drop(state);
+        sync.cond.notify_all();
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.state.try_lock().remedy() {
+            None => f.debug_struct("LegacyReadBiased").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("LegacyReadBiased")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .finish(),
+        }
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use std::sync::{Condvar, Mutex};
+use std::fmt;
 use std::time::Duration;
 use crate::deadline::Deadline;
 use crate::remedy;
@@ -18,6 +19,7 @@ pub struct LegacyWriteBiasedSync {
 struct LegacyWriteBiasedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
     writer_pending: bool,
 }
 
@@ -27,7 +29,7 @@ impl Moderator for LegacyWriteBiased {
     #[inline]
     fn new() -> Self::Sync {
         Self::Sync {
-            state: Mutex::new(LegacyWriteBiasedState { readers: 0, writer: false, writer_pending: false }),
+            state: Mutex::new(LegacyWriteBiasedState { readers: 0, writer: false, upgradable: false, writer_pending: false }),
             cond: Condvar::new()
         }
     }
@@ -149,4 +151,47 @@ impl Moderator for LegacyWriteBiased {
         state.writer = true;
         true
     }
+
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        sync.state.lock().remedy().writer_pending
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut state = sync.state.lock().remedy();
+        while state.upgradable {
+            let (guard, timed_out) =
+                remedy::cond_wait_remedy(&sync.cond, state, deadline.remaining());
+
+            if timed_out {
+                return false
+            }
+            state = guard;
+        }
+        state.upgradable = true;
+        true
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut state = sync.state.lock().remedy();
+        debug_assert!(state.upgradable);
+        state.upgradable = false;
+        drop(state);
+        sync.cond.notify_all();
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.state.try_lock().remedy() {
+            None => f.debug_struct("LegacyWriteBiased").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("LegacyWriteBiased")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("writer_pending", &state.writer_pending)
+                .finish(),
+        }
+    }
 }
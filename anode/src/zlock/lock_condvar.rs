@@ -0,0 +1,172 @@
+use crate::deadline::Deadline;
+use crate::remedy::{Remedy, TimedCondvar};
+use crate::zlock::{LockWriteGuard, Moderator};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A condition variable for use with a [`ZLock`](crate::zlock::ZLock) write guard, allowing
+/// a thread to block on a condition of the guarded data while holding the lock.
+///
+/// Waiting atomically releases the write lock, and reacquires it before returning. Atomicity
+/// holds with respect to notifications: a notification issued by any thread that
+/// subsequently acquires the write lock (and notifies while holding it, or after releasing
+/// it) is guaranteed to be observed by the waiter.
+///
+/// As with [`std::sync::Condvar`], the waiting thread may be woken spuriously;
+/// [`wait_while`](Self::wait_while) and its timed variants re-evaluate the condition
+/// on the caller's behalf.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use anode::zlock::{LockCondvar, ReadBiased, ZLock};
+///
+/// let pair = Arc::new((ZLock::<_, ReadBiased>::new(false), LockCondvar::new()));
+/// let t_2 = {
+///     let pair = pair.clone();
+///     thread::spawn(move || {
+///         *pair.0.write() = true;
+///         pair.1.notify_all();
+///     })
+/// };
+/// let guard = pair.1.wait_while(pair.0.write(), |ready| !*ready);
+/// assert!(*guard);
+/// # drop(guard);
+/// # t_2.join().unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct LockCondvar {
+    /// Incremented on every notification; a waiter returns once it observes a change.
+    notifications: Mutex<u64>,
+    cond: TimedCondvar,
+}
+
+impl LockCondvar {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            notifications: Mutex::new(0),
+            cond: TimedCondvar::new(),
+        }
+    }
+
+    /// Releases the write lock and blocks the current thread until notified, reacquiring
+    /// the write lock before returning.
+    #[inline]
+    pub fn wait<'a, T: ?Sized, M: Moderator>(&self, guard: LockWriteGuard<'a, T, M>) -> LockWriteGuard<'a, T, M> {
+        self.wait_until(guard, Deadline::Forever).0
+    }
+
+    /// Releases the write lock and blocks the current thread until notified or until
+    /// `deadline` elapses, reacquiring the write lock before returning.
+    ///
+    /// Returns the guard, along with a flag that is `true` if the wait timed out. The
+    /// reacquisition of the write lock is not subject to the deadline.
+    #[inline]
+    pub fn wait_until<'a, T: ?Sized, M: Moderator>(
+        &self,
+        guard: LockWriteGuard<'a, T, M>,
+        deadline: impl Into<Deadline>,
+    ) -> (LockWriteGuard<'a, T, M>, bool) {
+        self.wait_deadline(guard, &mut deadline.into())
+    }
+
+    /// A variant of [`wait_until`](Self::wait_until) that waits for at most `duration`.
+    #[inline]
+    pub fn wait_for<'a, T: ?Sized, M: Moderator>(
+        &self,
+        guard: LockWriteGuard<'a, T, M>,
+        duration: Duration,
+    ) -> (LockWriteGuard<'a, T, M>, bool) {
+        self.wait_until(guard, Deadline::lazy_after(duration))
+    }
+
+    /// Blocks the current thread while `condition` holds, releasing the write lock while
+    /// waiting. The condition is always evaluated at least once, and is never evaluated without
+    /// the write lock held.
+    #[inline]
+    pub fn wait_while<'a, T: ?Sized, M: Moderator>(
+        &self,
+        guard: LockWriteGuard<'a, T, M>,
+        condition: impl FnMut(&mut T) -> bool,
+    ) -> LockWriteGuard<'a, T, M> {
+        self.wait_while_until(guard, condition, Deadline::Forever).0
+    }
+
+    /// Blocks the current thread while `condition` holds, until `deadline` elapses.
+    ///
+    /// Returns the guard, along with a flag that is `true` if the wait timed out (i.e., the
+    /// condition still held when the deadline elapsed) or `false` if the condition ceased to
+    /// hold.
+    #[inline]
+    pub fn wait_while_until<'a, T: ?Sized, M: Moderator>(
+        &self,
+        mut guard: LockWriteGuard<'a, T, M>,
+        mut condition: impl FnMut(&mut T) -> bool,
+        deadline: impl Into<Deadline>,
+    ) -> (LockWriteGuard<'a, T, M>, bool) {
+        let mut deadline = deadline.into();
+        while condition(&mut guard) {
+            if deadline.remaining().is_zero() {
+                return (guard, true);
+            }
+            (guard, _) = self.wait_deadline(guard, &mut deadline);
+        }
+        (guard, false)
+    }
+
+    /// A variant of [`wait_while_until`](Self::wait_while_until) that waits for at most
+    /// `duration`.
+    #[inline]
+    pub fn wait_while_for<'a, T: ?Sized, M: Moderator>(
+        &self,
+        guard: LockWriteGuard<'a, T, M>,
+        condition: impl FnMut(&mut T) -> bool,
+        duration: Duration,
+    ) -> (LockWriteGuard<'a, T, M>, bool) {
+        self.wait_while_until(guard, condition, Deadline::lazy_after(duration))
+    }
+
+    /// Wakes up one thread blocked on this condvar.
+    #[inline]
+    pub fn notify_one(&self) {
+        *self.notifications.lock().remedy() += 1;
+        self.cond.notify_one();
+    }
+
+    /// Wakes up all threads blocked on this condvar.
+    #[inline]
+    pub fn notify_all(&self) {
+        *self.notifications.lock().remedy() += 1;
+        self.cond.notify_all();
+    }
+
+    #[inline]
+    fn wait_deadline<'a, T: ?Sized, M: Moderator>(
+        &self,
+        guard: LockWriteGuard<'a, T, M>,
+        deadline: &mut Deadline,
+    ) -> (LockWriteGuard<'a, T, M>, bool) {
+        let lock = guard.lock;
+
+        // the write lock is released only once the notification count has been captured; a
+        // notifier that changes the guarded data must first acquire the write lock, and thus
+        // cannot notify before the waiter has been accounted for
+        let notifications = self.notifications.lock().remedy();
+        let observed = *notifications;
+        drop(guard);
+
+        // initialising the deadline before cloning it ensures that the clone shares its
+        // point in time with the caller's deadline
+        deadline.remaining();
+        let (notifications, timed_out) =
+            self.cond
+                .wait_while_until(notifications, |notifications| *notifications == observed, deadline.clone());
+        drop(notifications);
+        (lock.write(), timed_out)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,93 @@
+use crate::deadline::Deadline;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT};
+use crate::zlock::{ArrivalOrdered, LegacyReadBiased, LockCondvar, Moderator, ReadBiased, SpinModerator, WriteBiased, ZLock};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn producer_consumer() {
+    __producer_consumer::<ReadBiased>();
+    __producer_consumer::<WriteBiased>();
+    __producer_consumer::<ArrivalOrdered>();
+    __producer_consumer::<SpinModerator>();
+    __producer_consumer::<LegacyReadBiased>();
+}
+
+fn __producer_consumer<M: Moderator + 'static>() {
+    const ITEMS: u64 = 100;
+    let pair = Arc::new((ZLock::<_, M>::new(Vec::new()), LockCondvar::new()));
+    let consumer = {
+        let pair = pair.clone();
+        thread::spawn(move || {
+            let mut sum = 0;
+            let mut consumed = 0;
+            while consumed < ITEMS {
+                let mut guard = pair.1.wait_while(pair.0.write(), |items| items.is_empty());
+                consumed += guard.len() as u64;
+                sum += guard.drain(..).sum::<u64>();
+            }
+            sum
+        })
+    };
+
+    for item in 0..ITEMS {
+        pair.0.write().push(item);
+        pair.1.notify_one();
+    }
+    assert_eq!((0..ITEMS).sum::<u64>(), consumer.join().unwrap());
+}
+
+#[test]
+fn notify_all_wakes_every_waiter() {
+    const WAITERS: usize = 4;
+    let pair = Arc::new((ZLock::<_, ReadBiased>::new(0), LockCondvar::new()));
+    let waiters = (0..WAITERS)
+        .map(|_| {
+            let pair = pair.clone();
+            thread::spawn(move || {
+                let mut guard = pair.0.write();
+                *guard += 1;
+                let (guard, timed_out) = pair.1.wait_while_for(guard, |count| *count <= WAITERS, LONG_WAIT);
+                assert!(!timed_out);
+                assert_eq!(WAITERS + 1, *guard);
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // the write lock is released by waiters, so the count can be observed under a read lock
+    while *pair.0.read() < WAITERS {
+        thread::sleep(Duration::from_millis(1));
+    }
+    *pair.0.write() += 1;
+    pair.1.notify_all();
+    for waiter in waiters {
+        waiter.join().unwrap();
+    }
+}
+
+#[test]
+fn wait_timeout_reacquires_lock() {
+    let lock = ZLock::<_, WriteBiased>::new(0);
+    let cond = LockCondvar::new();
+
+    let start = Instant::now();
+    let (mut guard, timed_out) = cond.wait_for(lock.write(), CHECK_WAIT);
+    assert!(timed_out);
+    assert!(start.elapsed() >= CHECK_WAIT);
+    *guard = 42;
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard);
+
+    // an unmet condition with an elapsed deadline returns without waiting
+    let (guard, timed_out) = cond.wait_while_until(lock.write(), |_| true, Deadline::lazy_after(Duration::ZERO));
+    assert!(timed_out);
+    assert_eq!(42, *guard);
+    drop(guard);
+
+    // a met condition returns without waiting, irrespective of the deadline
+    let (guard, timed_out) = cond.wait_while_until(lock.write(), |_| false, Deadline::Forever);
+    assert!(!timed_out);
+    drop(guard);
+}
+
@@ -1,12 +1,41 @@
-use crate::zlock::{ArrivalOrdered, LockReadGuard, LockWriteGuard, Moderator, ReadBiased, Stochastic, UpgradeOutcome, WriteBiased, ZLock};
+use crate::zlock::{AnyGuard, ArrivalOrdered, LockDowngradableGuard, LockReadGuard, LockUpgradableGuard, LockWriteGuard, MappedLockReadGuard, MappedLockWriteGuard, Moderator, Owner, PriorityOrdered, ReadBiased, SpinModerator, Stochastic, UpgradeBiased, UpgradeOutcome, WriteBiased, ZLock};
 use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::thread;
 use std::time::Duration;
-
-pub type LockBox<T> =
-    Box<dyn for<'a> Locklike<'a, T, R = DynLockReadGuard<'a, T>, W = DynLockWriteGuard<'a, T>>>;
+use crate::backoff::ExpBackoff;
+use crate::deadline::Deadline;
+use crate::inf_iterator::{InfIterator, IntoInfIterator};
+use crate::rand::{clock_seed, RandRange, Xorshift, Seeded, FIXED_DURATION};
+
+mod std_rwlock;
+#[cfg(windows)]
+mod srw_lock;
+#[cfg(target_os = "macos")]
+mod unfair_lock;
+#[cfg(feature = "parking_lot")]
+mod pl_locks;
+
+pub type LockBox<T> = Box<
+    dyn for<'a> Locklike<
+        'a,
+        T,
+        R = DynLockReadGuard<'a, T>,
+        W = DynLockWriteGuard<'a, T>,
+        U = DynLockUpgradableGuard<'a, T>,
+        D = DynLockDowngradableGuard<'a, T>,
+    >,
+>;
 
 pub type LockBoxSized<T> = Box<
-    dyn for<'a> LocklikeSized<'a, T, R = DynLockReadGuard<'a, T>, W = DynLockWriteGuard<'a, T>>,
+    dyn for<'a> LocklikeSized<
+        'a,
+        T,
+        R = DynLockReadGuard<'a, T>,
+        W = DynLockWriteGuard<'a, T>,
+        U = DynLockUpgradableGuard<'a, T>,
+        D = DynLockDowngradableGuard<'a, T>,
+    >,
 >;
 
 pub trait LockReadGuardlike<'a, T: ?Sized>: Deref<Target = T> {
@@ -22,7 +51,22 @@ pub trait LockWriteGuardlike<'a, T: ?Sized>: DerefMut<Target = T> {
     fn downgrade(self) -> DynLockReadGuard<'a, T>;
 }
 
-trait LockReadGuardSurrogate<'a, T: ?Sized>: Deref<Target = T> {
+pub trait LockUpgradableGuardlike<'a, T: ?Sized>: Deref<Target = T> {
+    fn upgrade(self) -> DynLockWriteGuard<'a, T>;
+
+    fn try_upgrade(
+        self,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>>;
+
+    fn downgrade(self) -> DynLockReadGuard<'a, T>;
+}
+
+pub trait LockDowngradableGuardlike<'a, T: ?Sized>: DerefMut<Target = T> {
+    fn downgrade(self) -> DynLockUpgradableGuard<'a, T>;
+}
+
+trait LockReadGuardSurrogate<'a, T: ?Sized>: Deref<Target = T> + AnyGuard {
     fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T>;
 
     fn try_upgrade_box(
@@ -31,13 +75,30 @@ trait LockReadGuardSurrogate<'a, T: ?Sized>: Deref<Target = T> {
     ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>>;
 }
 
-trait LockWriteGuardSurrogate<'a, T: ?Sized>: DerefMut<Target = T> {
+trait LockWriteGuardSurrogate<'a, T: ?Sized>: DerefMut<Target = T> + AnyGuard {
     fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T>;
 }
 
+trait LockUpgradableGuardSurrogate<'a, T: ?Sized>: Deref<Target = T> {
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T>;
+
+    fn try_upgrade_box(
+        self: Box<Self>,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>>;
+
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T>;
+}
+
+trait LockDowngradableGuardSurrogate<'a, T: ?Sized>: DerefMut<Target = T> {
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T>;
+}
+
 pub trait Locklike<'a, T: ?Sized>: Sync + Send {
     type R: LockReadGuardlike<'a, T>;
     type W: LockWriteGuardlike<'a, T>;
+    type U: LockUpgradableGuardlike<'a, T>;
+    type D: LockDowngradableGuardlike<'a, T>;
 
     fn read(&'a self) -> Self::R;
 
@@ -47,7 +108,127 @@ pub trait Locklike<'a, T: ?Sized>: Sync + Send {
 
     fn try_write(&'a self, duration: Duration) -> Option<Self::W>;
 
+    /// Acquires an upgradable read lock. See [`ZLock::read_upgradable`].
+    fn read_upgradable(&'a self) -> Self::U;
+
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U>;
+
+    /// Acquires a write lock that may be downgraded to an upgradable read lock. See
+    /// [`ZLock::write_downgradable`].
+    fn write_downgradable(&'a self) -> Self::D;
+
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D>;
+
     fn get_mut(&mut self) -> &mut T;
+
+    /// Repeatedly attempts to read-acquire the lock, as prescribed by the given [`RetryPolicy`],
+    /// returning `None` if the lock could not be acquired within the policy's total budget.
+    #[inline]
+    fn try_read_with_retry(&'a self, policy: RetryPolicy) -> Option<Self::R> {
+        policy.retry(|duration| self.try_read(duration))
+    }
+
+    /// Repeatedly attempts to write-acquire the lock, as prescribed by the given [`RetryPolicy`],
+    /// returning `None` if the lock could not be acquired within the policy's total budget.
+    #[inline]
+    fn try_write_with_retry(&'a self, policy: RetryPolicy) -> Option<Self::W> {
+        policy.retry(|duration| self.try_write(duration))
+    }
+}
+
+/// Closure-based acquisition for all [`Locklike`] implementations, including boxed locks. (These
+/// are generic methods, which cannot reside on `Locklike` without making it unusable as a
+/// trait object.) The lock is held for the duration of the closure and released when it returns.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::{lock_box_read_biased, LocklikeExt};
+/// let lock = lock_box_read_biased(vec![1, 2]);
+/// lock.with_write(|vec| vec.push(3));
+/// assert_eq!(3, lock.with_read(|vec| vec.len()));
+/// ```
+pub trait LocklikeExt<'a, T: ?Sized>: Locklike<'a, T> {
+    #[inline]
+    fn with_read<U>(&'a self, f: impl FnOnce(&T) -> U) -> U {
+        f(&self.read())
+    }
+
+    /// Runs `f` under a read lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    fn try_with_read<U>(&'a self, duration: Duration, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.try_read(duration).map(|guard| f(&guard))
+    }
+
+    #[inline]
+    fn with_write<U>(&'a self, f: impl FnOnce(&mut T) -> U) -> U {
+        f(&mut self.write())
+    }
+
+    /// Runs `f` under a write lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    fn try_with_write<U>(&'a self, duration: Duration, f: impl FnOnce(&mut T) -> U) -> Option<U> {
+        self.try_write(duration).map(|mut guard| f(&mut guard))
+    }
+}
+
+impl<'a, T: ?Sized, L: Locklike<'a, T> + ?Sized> LocklikeExt<'a, T> for L {}
+
+/// Governs the repeated acquisition attempts of [`Locklike::try_read_with_retry`] and
+/// [`Locklike::try_write_with_retry`].
+///
+/// Each attempt waits for at most `attempt` (or whatever remains of `total`, if less).
+/// Between failed attempts, the caller sleeps for a random duration in the range
+/// \[0, `jitter`), which spreads out competing retries. Attempts cease once `total` has elapsed;
+/// at least one attempt is always made.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use anode::zlock::locklike::{Locklike, RetryPolicy};
+/// use anode::zlock::{ReadBiased, ZLock};
+/// let lock = ZLock::<_, ReadBiased>::new(42);
+/// let policy = RetryPolicy {
+///     total: Duration::from_millis(100),
+///     attempt: Duration::from_millis(10),
+///     jitter: Some(Duration::from_millis(1)),
+/// };
+/// let guard = lock.try_read_with_retry(policy).unwrap();
+/// assert_eq!(42, *guard);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The overall time budget across all attempts.
+    pub total: Duration,
+
+    /// The maximum time to wait on any single attempt.
+    pub attempt: Duration,
+
+    /// The upper bound on the random pause between attempts, or `None` to retry immediately.
+    pub jitter: Option<Duration>,
+}
+
+impl RetryPolicy {
+    fn retry<G>(&self, mut attempt: impl FnMut(Duration) -> Option<G>) -> Option<G> {
+        let mut deadline = Deadline::lazy_after(self.total);
+        let mut rng = None;
+        loop {
+            let remaining = deadline.remaining();
+            if let Some(guard) = attempt(self.attempt.min(remaining)) {
+                return Some(guard);
+            }
+
+            let remaining = deadline.remaining();
+            if remaining.is_zero() {
+                return None;
+            }
+            if let Some(jitter) = self.jitter {
+                let rng = rng.get_or_insert_with(|| Xorshift::seed(clock_seed()));
+                thread::sleep(rng.next_range(Duration::ZERO..jitter).min(remaining));
+            }
+        }
+    }
 }
 
 pub trait LocklikeSized<'a, T>: Locklike<'a, T> {
@@ -57,6 +238,8 @@ pub trait LocklikeSized<'a, T>: Locklike<'a, T> {
 impl<'a, T: ?Sized + Sync + Send + 'a, M: Moderator + 'a> Locklike<'a, T> for ZLock<T, M> {
     type R = LockReadGuard<'a, T, M>;
     type W = LockWriteGuard<'a, T, M>;
+    type U = LockUpgradableGuard<'a, T, M>;
+    type D = LockDowngradableGuard<'a, T, M>;
 
     #[inline]
     fn read(&'a self) -> Self::R {
@@ -78,6 +261,26 @@ impl<'a, T: ?Sized + Sync + Send + 'a, M: Moderator + 'a> Locklike<'a, T> for ZL
         self.try_write(duration)
     }
 
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        self.read_upgradable()
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.try_read_upgradable(duration)
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        self.write_downgradable()
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.try_write_downgradable(duration)
+    }
+
     #[inline]
     fn get_mut(&mut self) -> &mut T {
         self.get_mut()
@@ -144,11 +347,69 @@ impl<'a, T: ?Sized, M: Moderator> LockWriteGuardSurrogate<'a, T> for LockWriteGu
     }
 }
 
+impl<'a, T: ?Sized, M: Moderator> LockUpgradableGuardlike<'a, T> for LockUpgradableGuard<'a, T, M> {
+    #[inline]
+    fn upgrade(self) -> DynLockWriteGuard<'a, T> {
+        self.upgrade().into()
+    }
+
+    #[inline]
+    fn try_upgrade(
+        self,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        self.try_upgrade(duration)
+            .map(DynLockWriteGuard::from, DynLockUpgradableGuard::from)
+    }
+
+    #[inline]
+    fn downgrade(self) -> DynLockReadGuard<'a, T> {
+        self.downgrade().into()
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockUpgradableGuardSurrogate<'a, T> for LockUpgradableGuard<'a, T, M> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        self.upgrade().into()
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        self.try_upgrade(duration)
+            .map(DynLockWriteGuard::from, DynLockUpgradableGuard::from)
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        self.downgrade().into()
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockDowngradableGuardlike<'a, T> for LockDowngradableGuard<'a, T, M> {
+    #[inline]
+    fn downgrade(self) -> DynLockUpgradableGuard<'a, T> {
+        self.downgrade().into()
+    }
+}
+
+impl<'a, T: ?Sized, M: Moderator> LockDowngradableGuardSurrogate<'a, T> for LockDowngradableGuard<'a, T, M> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        self.downgrade().into()
+    }
+}
+
 struct PolyLock<T: ?Sized, M: Moderator>(ZLock<T, M>);
 
 impl<'a, T: ?Sized + Sync + Send + 'a, M: Moderator + 'a> Locklike<'a, T> for PolyLock<T, M> {
     type R = DynLockReadGuard<'a, T>;
     type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
 
     #[inline]
     fn read(&'a self) -> Self::R {
@@ -170,6 +431,26 @@ impl<'a, T: ?Sized + Sync + Send + 'a, M: Moderator + 'a> Locklike<'a, T> for Po
         self.0.try_write(duration).map(DynLockWriteGuard::from)
     }
 
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        self.0.read_upgradable().into()
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.0.try_read_upgradable(duration).map(DynLockUpgradableGuard::from)
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        self.0.write_downgradable().into()
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.0.try_write_downgradable(duration).map(DynLockDowngradableGuard::from)
+    }
+
     #[inline]
     fn get_mut(&mut self) -> &mut T {
         self.0.get_mut()
@@ -195,6 +476,25 @@ impl<'a, T: ?Sized> DynLockReadGuard<'a, T> {
     }
 }
 
+impl<'a, T: ?Sized + 'a> DynLockReadGuard<'a, T> {
+    /// Projects this guard onto a part of the guarded data. See [`LockReadGuard::map`].
+    #[inline]
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&T) -> &U) -> MappedLockReadGuard<'a, U> {
+        let data = NonNull::from(f(&self));
+        MappedLockReadGuard::new(data, Owner::Guard(self.0))
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case the original guard is handed back.
+    #[inline]
+    pub fn try_map<U: ?Sized>(self, f: impl FnOnce(&T) -> Option<&U>) -> Result<MappedLockReadGuard<'a, U>, Self> {
+        match f(&self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockReadGuard::new(data, Owner::Guard(self.0))),
+        }
+    }
+}
+
 impl<T: ?Sized> Deref for DynLockReadGuard<'_, T> {
     type Target = T;
 
@@ -207,7 +507,7 @@ impl<T: ?Sized> Deref for DynLockReadGuard<'_, T> {
 impl<'a, T: ?Sized> LockReadGuardlike<'a, T> for DynLockReadGuard<'a, T> {
     #[inline]
     fn upgrade(self) -> DynLockWriteGuard<'a, T> {
-        self.0.upgrade_box().into()
+        self.0.upgrade_box()
     }
 
     #[inline]
@@ -229,6 +529,25 @@ impl<'a, T: ?Sized + 'a, M: Moderator> From<LockReadGuard<'a, T, M>> for DynLock
 
 pub struct DynLockWriteGuard<'a, T: ?Sized>(Box<dyn LockWriteGuardSurrogate<'a, T> + 'a>);
 
+impl<'a, T: ?Sized + 'a> DynLockWriteGuard<'a, T> {
+    /// Projects this guard onto a part of the guarded data. See [`LockWriteGuard::map`].
+    #[inline]
+    pub fn map<U: ?Sized>(mut self, f: impl FnOnce(&mut T) -> &mut U) -> MappedLockWriteGuard<'a, U> {
+        let data = NonNull::from(f(&mut self));
+        MappedLockWriteGuard::new(data, Owner::Guard(self.0))
+    }
+
+    /// A variant of [`map`](Self::map) wherein `f` may decline the projection by returning
+    /// `None`, in which case the original guard is handed back.
+    #[inline]
+    pub fn try_map<U: ?Sized>(mut self, f: impl FnOnce(&mut T) -> Option<&mut U>) -> Result<MappedLockWriteGuard<'a, U>, Self> {
+        match f(&mut self).map(NonNull::from) {
+            None => Err(self),
+            Some(data) => Ok(MappedLockWriteGuard::new(data, Owner::Guard(self.0))),
+        }
+    }
+}
+
 impl<T: ?Sized> Deref for DynLockWriteGuard<'_, T> {
     type Target = T;
 
@@ -259,40 +578,487 @@ impl<'a, T: ?Sized, M: Moderator> From<LockWriteGuard<'a, T, M>> for DynLockWrit
     }
 }
 
-#[derive(Debug)]
+pub struct DynLockUpgradableGuard<'a, T: ?Sized>(Box<dyn LockUpgradableGuardSurrogate<'a, T> + 'a>);
+
+impl<T: ?Sized> Deref for DynLockUpgradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.0.as_ref()
+    }
+}
+
+impl<'a, T: ?Sized> LockUpgradableGuardlike<'a, T> for DynLockUpgradableGuard<'a, T> {
+    #[inline]
+    fn upgrade(self) -> DynLockWriteGuard<'a, T> {
+        self.0.upgrade_box()
+    }
+
+    #[inline]
+    fn try_upgrade(
+        self,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        self.0.try_upgrade_box(duration)
+    }
+
+    #[inline]
+    fn downgrade(self) -> DynLockReadGuard<'a, T> {
+        self.0.downgrade_box()
+    }
+}
+
+impl<'a, T: ?Sized + 'a, M: Moderator> From<LockUpgradableGuard<'a, T, M>> for DynLockUpgradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: LockUpgradableGuard<'a, T, M>) -> Self {
+        DynLockUpgradableGuard(Box::new(guard))
+    }
+}
+
+pub struct DynLockDowngradableGuard<'a, T: ?Sized>(Box<dyn LockDowngradableGuardSurrogate<'a, T> + 'a>);
+
+impl<T: ?Sized> Deref for DynLockDowngradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.0.as_ref()
+    }
+}
+
+impl<T: ?Sized> DerefMut for DynLockDowngradableGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.0.as_mut()
+    }
+}
+
+impl<'a, T: ?Sized> LockDowngradableGuardlike<'a, T> for DynLockDowngradableGuard<'a, T> {
+    #[inline]
+    fn downgrade(self) -> DynLockUpgradableGuard<'a, T> {
+        self.0.downgrade_box()
+    }
+}
+
+impl<'a, T: ?Sized + 'a, M: Moderator> From<LockDowngradableGuard<'a, T, M>> for DynLockDowngradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: LockDowngradableGuard<'a, T, M>) -> Self {
+        DynLockDowngradableGuard(Box::new(guard))
+    }
+}
+
+/// Write-acquires every lock in `locks`, returning the guards in slice order.
+///
+/// Deadlock is avoided by acquiring the locks in a global order -- that of the slice. This
+/// holds provided that every thread that acquires more than one of the locks does so
+/// in the same order (e.g., by also using [`lock_all`] or [`try_lock_all`] on the same slice).
+pub fn lock_all<'a, T>(locks: &'a [LockBoxSized<T>]) -> Vec<DynLockWriteGuard<'a, T>> {
+    locks.iter().map(|lock| lock.write()).collect()
+}
+
+/// Attempts to write-acquire every lock in `locks` within the given `duration`, returning the
+/// guards in slice order, or `None` if the locks could not all be acquired in time.
+///
+/// The locks are attempted in slice order without waiting. Should any lock be unavailable,
+/// all locks acquired thus far are released and the attempt is retried from the start
+/// after backing off. Thus, a partially successful attempt never holds onto some
+/// locks while waiting for others.
+pub fn try_lock_all<'a, T>(locks: &'a [LockBoxSized<T>], duration: Duration) -> Option<Vec<DynLockWriteGuard<'a, T>>> {
+    let mut deadline = Deadline::lazy_after(duration);
+    let mut rng = FIXED_DURATION;
+    let mut backoff = ExpBackoff::sleepy().into_inf_iter();
+    loop {
+        let guards = locks
+            .iter()
+            .map_while(|lock| lock.try_write(Duration::ZERO))
+            .collect::<Vec<_>>();
+        if guards.len() == locks.len() {
+            return Some(guards);
+        }
+        drop(guards);
+
+        if deadline.remaining().is_zero() {
+            return None;
+        }
+        backoff.next().act(|| &mut rng);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ModeratorKind {
     ReadBiased,
     WriteBiased,
     ArrivalOrdered,
     Stochastic,
+    SpinModerator,
+    UpgradeBiased,
+    PriorityOrdered,
 }
 
-pub const MODERATOR_KINDS: [ModeratorKind; 4] = [
+pub const MODERATOR_KINDS: [ModeratorKind; 7] = [
     ModeratorKind::ReadBiased,
     ModeratorKind::WriteBiased,
     ModeratorKind::ArrivalOrdered,
     ModeratorKind::Stochastic,
+    ModeratorKind::SpinModerator,
+    ModeratorKind::UpgradeBiased,
+    ModeratorKind::PriorityOrdered,
 ];
 
 impl ModeratorKind {
-    pub fn make_lock_for_test<T: Sync + Send + 'static>(&self, t: T) -> LockBoxSized<T> {
-        println!("test running with moderator {:?}", self);
+    /// Creates a boxed lock over `t`, moderated as per this kind. See [`lock_box`].
+    #[inline]
+    pub fn make_lock<T: Sync + Send + 'static>(&self, t: T) -> LockBoxSized<T> {
         match self {
-            ModeratorKind::ReadBiased => Box::new(PolyLock(ZLock::<_, ReadBiased>::new(t))),
-            ModeratorKind::WriteBiased => Box::new(PolyLock(ZLock::<_, WriteBiased>::new(t))),
-            ModeratorKind::ArrivalOrdered => Box::new(PolyLock(ZLock::<_, ArrivalOrdered>::new(t))),
-            ModeratorKind::Stochastic => Box::new(PolyLock(ZLock::<_, Stochastic>::new(t))),
+            ModeratorKind::ReadBiased => lock_box_read_biased(t),
+            ModeratorKind::WriteBiased => lock_box_write_biased(t),
+            ModeratorKind::ArrivalOrdered => lock_box_arrival_ordered(t),
+            ModeratorKind::Stochastic => lock_box_stochastic(t),
+            ModeratorKind::SpinModerator => lock_box_spin(t),
+            ModeratorKind::UpgradeBiased => lock_box_upgrade_biased(t),
+            ModeratorKind::PriorityOrdered => lock_box_priority_ordered(t),
         }
     }
+
+    #[cfg(test)]
+    pub(crate) fn make_lock_for_test<T: Sync + Send + 'static>(&self, t: T) -> LockBoxSized<T> {
+        println!("test running with moderator {:?}", self);
+        self.make_lock(t)
+    }
+}
+
+/// Creates a boxed lock over `t`, with the moderator selected at runtime. The lock is
+/// used through the [`Locklike`] trait, whose guards are type-erased, such that locks of
+/// different kinds may be used interchangeably.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::{lock_box, LockReadGuardlike, ModeratorKind};
+/// let lock = lock_box(42, ModeratorKind::WriteBiased);
+/// let mut guard = lock.read().upgrade();
+/// *guard += 1;
+/// drop(guard);
+/// assert_eq!(43, lock.into_inner());
+/// ```
+#[inline]
+pub fn lock_box<T: Sync + Send + 'static>(t: T, kind: ModeratorKind) -> LockBoxSized<T> {
+    kind.make_lock(t)
+}
+
+/// Creates a boxed lock over `t`, moderated by [`ReadBiased`].
+#[inline]
+pub fn lock_box_read_biased<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, ReadBiased>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`WriteBiased`].
+#[inline]
+pub fn lock_box_write_biased<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, WriteBiased>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`ArrivalOrdered`].
+#[inline]
+pub fn lock_box_arrival_ordered<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, ArrivalOrdered>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`Stochastic`].
+#[inline]
+pub fn lock_box_stochastic<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, Stochastic>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`SpinModerator`].
+#[inline]
+pub fn lock_box_spin<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, SpinModerator>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`UpgradeBiased`].
+#[inline]
+pub fn lock_box_upgrade_biased<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, UpgradeBiased>::new(t)))
+}
+
+/// Creates a boxed lock over `t`, moderated by [`PriorityOrdered`].
+#[inline]
+pub fn lock_box_priority_ordered<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(PolyLock(ZLock::<_, PriorityOrdered>::new(t)))
+}
+
+/// Creates a boxed [`std::sync::RwLock`] over `t`, for porting code that uses standard locks
+/// onto [`Locklike`] incrementally. (`RwLock` implements [`Locklike`], so may also be boxed
+/// directly.)
+///
+/// The standard lock has no upgradable mode, nor can it upgrade a read lock atomically.
+/// Upgradable and downgradable guards are emulated with a write lock, which excludes plain
+/// readers but keeps their transitions atomic. A plain read guard cannot be upgraded: its
+/// `upgrade` and `try_upgrade` panic, as the read lock would have to be released before the
+/// write lock is acquired. Timed acquisitions poll with backoff. Poisoning is disregarded.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::{lock_box_std, LockWriteGuardlike};
+/// let lock = lock_box_std(42);
+/// let mut guard = lock.write();
+/// *guard += 1;
+/// let guard = guard.downgrade();
+/// assert_eq!(43, *guard);
+/// ```
+#[inline]
+pub fn lock_box_std<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(std::sync::RwLock::new(t))
+}
+
+/// Creates a boxed Windows slim reader/writer lock (`SRWLOCK`) over `t`. (Available on
+/// Windows.)
+///
+/// Reads and writes map onto the lock's shared and exclusive modes. `SRWLOCK` has neither an
+/// upgradable mode nor atomic transitions, so upgradable and downgradable guards are emulated
+/// with an exclusive lock, which they retain when downgraded; a downgraded guard therefore still
+/// excludes other readers. A plain read guard's `upgrade` and `try_upgrade` panic, as for
+/// [`lock_box_std`]. Timed acquisitions poll with backoff.
+///
+/// # Examples
+/// ```
+/// # #[cfg(windows)] {
+/// use anode::zlock::locklike::{lock_box_srw, LockUpgradableGuardlike};
+/// let lock = lock_box_srw(42);
+/// let mut guard = lock.read_upgradable().upgrade();
+/// *guard += 1;
+/// drop(guard);
+/// assert_eq!(43, lock.into_inner());
+/// # }
+/// ```
+#[cfg(windows)]
+#[inline]
+pub fn lock_box_srw<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(srw_lock::SrwLock::new(t))
+}
+
+/// Creates a boxed macOS `os_unfair_lock` over `t`. (Available on macOS.)
+///
+/// The lock has no shared mode, so it is held exclusively in every mode, as with
+/// `lock_box_parking_lot_mutex`: readers exclude one another, and every upgrade and downgrade
+/// succeeds immediately. Timed acquisitions poll with backoff.
+#[cfg(target_os = "macos")]
+#[inline]
+pub fn lock_box_unfair<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(unfair_lock::UnfairLock::new(t))
+}
+
+/// Creates a boxed lock over `t` using the native primitive of the target platform:
+/// `lock_box_srw` on Windows, `lock_box_unfair` on macOS, and [`lock_box_std`] elsewhere.
+/// The platforms' locks differ in their emulation of upgradable and downgradable guards, so code
+/// that must behave alike everywhere should upgrade only through an upgradable guard.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::{lock_box_native, LockWriteGuardlike};
+/// let lock = lock_box_native(42);
+/// let mut guard = lock.write();
+/// *guard += 1;
+/// let guard = guard.downgrade();
+/// assert_eq!(43, *guard);
+/// ```
+#[inline]
+pub fn lock_box_native<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(NativeLock::new(t))
+}
+
+#[cfg(windows)]
+type NativeLock<T> = srw_lock::SrwLock<T>;
+#[cfg(target_os = "macos")]
+type NativeLock<T> = unfair_lock::UnfairLock<T>;
+#[cfg(not(any(windows, target_os = "macos")))]
+type NativeLock<T> = std::sync::RwLock<T>;
+
+/// Creates a boxed [`parking_lot::RwLock`] over `t`. (Available with the `parking_lot`
+/// feature.)
+///
+/// Reads, writes, upgradable reads and the transitions between them map onto the lock's own
+/// operations. A plain read lock cannot be upgraded atomically, so a plain read guard's
+/// `upgrade` and `try_upgrade` panic; upgrade through an upgradable guard instead.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::{lock_box_parking_lot_rwlock, LockUpgradableGuardlike};
+/// let lock = lock_box_parking_lot_rwlock(42);
+/// let mut guard = lock.read_upgradable().upgrade();
+/// *guard += 1;
+/// drop(guard);
+/// assert_eq!(43, lock.into_inner());
+/// ```
+#[cfg(feature = "parking_lot")]
+#[inline]
+pub fn lock_box_parking_lot_rwlock<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(parking_lot::RwLock::new(t))
+}
+
+/// Creates a boxed [`parking_lot::Mutex`] over `t`. (Available with the `parking_lot` feature.)
+///
+/// The mutex is held exclusively in every mode, so readers exclude one another, and every
+/// upgrade and downgrade succeeds immediately.
+#[cfg(feature = "parking_lot")]
+#[inline]
+pub fn lock_box_parking_lot_mutex<T: Sync + Send + 'static>(t: T) -> LockBoxSized<T> {
+    Box::new(parking_lot::Mutex::new(t))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::zlock::locklike::{LockBoxSized, LockReadGuardlike, LockWriteGuardlike, Locklike, MODERATOR_KINDS};
+    use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
+    use crate::zlock::locklike::{lock_all, lock_box, lock_box_arrival_ordered, lock_box_read_biased, lock_box_spin, lock_box_stochastic, lock_box_upgrade_biased, lock_box_write_biased, try_lock_all, LockBoxSized, LockDowngradableGuardlike, LockReadGuardlike, LockUpgradableGuardlike, LockWriteGuardlike, Locklike, LocklikeExt, RetryPolicy, MODERATOR_KINDS};
     use crate::zlock::{ReadBiased, ZLock};
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
     use std::thread;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn lock_all_concurrently() {
+        const ITERATIONS: u64 = 100;
+        for moderator in MODERATOR_KINDS {
+            let locks = Arc::new((0..4).map(|_| moderator.make_lock_for_test(0)).collect::<Vec<_>>());
+            let threads = (0..2)
+                .map(|_| {
+                    let locks = locks.clone();
+                    thread::spawn(move || {
+                        for _ in 0..ITERATIONS {
+                            let mut guards = lock_all(&locks);
+                            let first = *guards[0];
+                            // a consistent snapshot: no shard is ever updated in isolation
+                            assert!(guards.iter().all(|guard| **guard == first));
+                            guards.iter_mut().for_each(|guard| **guard += 1);
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+
+            let guards = try_lock_all(&locks, Duration::ZERO).unwrap();
+            assert!(guards.iter().all(|guard| **guard == ITERATIONS * 2));
+        }
+    }
+
+    #[test]
+    fn try_lock_all_releases_on_partial_failure() {
+        for moderator in MODERATOR_KINDS {
+            let locks = (0..3).map(|_| moderator.make_lock_for_test(0)).collect::<Vec<_>>();
+            let guard = locks[1].read();
+
+            // the middle lock is unavailable, so none of the locks are retained
+            assert!(try_lock_all(&locks, SHORT_WAIT).is_none());
+            assert!(locks[0].try_write(Duration::ZERO).is_some());
+            assert!(locks[2].try_write(Duration::ZERO).is_some());
+
+            drop(guard);
+            let guards = try_lock_all(&locks, Duration::ZERO).unwrap();
+            assert_eq!(3, guards.len());
+        }
+    }
+
+    #[test]
+    fn retry_until_acquired() {
+        let policy = RetryPolicy {
+            total: LONG_WAIT,
+            attempt: Duration::from_millis(1),
+            jitter: Some(Duration::from_millis(1)),
+        };
+        for moderator in MODERATOR_KINDS {
+            let lock = Arc::new(moderator.make_lock_for_test(0));
+            let released = Arc::new(AtomicBool::new(false));
+            let mut guard = lock.write();
+            let t = thread::spawn({
+                let lock = lock.clone();
+                let released = released.clone();
+                move || {
+                    let guard = lock.try_read_with_retry(policy).unwrap();
+                    assert!(released.load(Ordering::Relaxed));
+                    assert_eq!(42, *guard);
+                    drop(guard);
+
+                    let mut guard = lock.try_write_with_retry(policy).unwrap();
+                    *guard = 69;
+                }
+            });
+            // let the waiter fail a few attempts before releasing
+            thread::sleep(Duration::from_millis(5));
+            *guard = 42;
+            released.store(true, Ordering::Relaxed);
+            drop(guard);
+            t.join().unwrap();
+            assert_eq!(69, *lock.read());
+        }
+    }
+
+    #[test]
+    fn retry_exhausts_budget() {
+        let policy = RetryPolicy {
+            total: Duration::from_millis(10),
+            attempt: Duration::from_millis(2),
+            jitter: None,
+        };
+        for moderator in MODERATOR_KINDS {
+            let lock = moderator.make_lock_for_test(0);
+            let guard = lock.write();
+            let start = Instant::now();
+            assert!(lock.try_read_with_retry(policy).is_none());
+            assert!(lock.try_write_with_retry(policy).is_none());
+            assert!(start.elapsed() >= policy.total * 2);
+            drop(guard);
+
+            // a zero budget still affords a single attempt
+            let policy = RetryPolicy { total: Duration::ZERO, ..policy };
+            assert!(lock.try_write_with_retry(policy).is_some());
+        }
+    }
+
+    #[test]
+    fn with_read_write_dyn() {
+        for moderator in MODERATOR_KINDS {
+            let lock = moderator.make_lock_for_test(vec![1, 2]);
+            lock.with_write(|vec| vec.push(3));
+            assert_eq!(3, lock.with_read(|vec| vec.len()));
+
+            let guard = lock.read();
+            assert_eq!(None, lock.try_with_write(Duration::ZERO, |_| unreachable!()));
+            assert_eq!(Some(3), lock.try_with_read(Duration::ZERO, |vec| vec.len()));
+            drop(guard);
+            assert_eq!(Some(4), lock.try_with_write(SHORT_WAIT, |vec| {
+                vec.push(4);
+                vec.len()
+            }));
+        }
+    }
+
+    #[test]
+    fn map_dyn_guards() {
+        for moderator in MODERATOR_KINDS {
+            let lock = moderator.make_lock_for_test((0, String::from("foo")));
+            let mut name = lock.write().map(|pair| &mut pair.1);
+            name.push_str("bar");
+            assert!(lock.try_read(Duration::ZERO).is_none());
+            drop(name);
+
+            let name = lock.read().map(|pair| &pair.1);
+            assert_eq!("foobar", *name);
+            assert!(lock.try_write(Duration::ZERO).is_none());
+            drop(name);
+
+            let guard = lock.read().try_map(|pair| (pair.0 > 0).then_some(&pair.0)).unwrap_err();
+            assert_eq!(0, (*guard).0);
+            drop(guard);
+
+            let guard = lock.write().try_map(|pair| (pair.0 > 0).then_some(&mut pair.0)).unwrap_err();
+            drop(guard);
+            assert!(lock.try_write(Duration::ZERO).is_some());
+        }
+    }
 
     #[test]
     fn conformance() {
@@ -309,6 +1075,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lock_box_factories() {
+        for moderator in MODERATOR_KINDS {
+            takes_boxed(lock_box(0, moderator));
+            takes_boxed(moderator.make_lock(0));
+        }
+        takes_boxed(lock_box_read_biased(0));
+        takes_boxed(lock_box_write_biased(0));
+        takes_boxed(lock_box_arrival_ordered(0));
+        takes_boxed(lock_box_stochastic(0));
+        takes_boxed(lock_box_spin(0));
+        takes_boxed(lock_box_upgrade_biased(0));
+
+        let lock = lock_box(vec![42], MODERATOR_KINDS[0]);
+        lock.write().push(69);
+        assert_eq!(vec![42, 69], lock.into_inner());
+    }
+
     fn takes_boxed(lock: LockBoxSized<u64>) {
         let guard = lock.read();
         assert_eq!(0, *guard);
@@ -321,6 +1105,44 @@ mod tests {
         assert_eq!(42, *guard);
     }
 
+    #[test]
+    fn upgradable_and_downgradable() {
+        __upgradable_and_downgradable(&ZLock::<_, ReadBiased>::new(0));
+        for moderator in MODERATOR_KINDS {
+            let lock = moderator.make_lock_for_test(0);
+            __upgradable_and_downgradable(&*lock);
+        }
+    }
+
+    fn __upgradable_and_downgradable<'a, L: Locklike<'a, u64> + ?Sized>(lock: &'a L) {
+        // write -> downgrade to upgradable -> downgrade to read
+        let mut guard = lock.write_downgradable();
+        *guard = 42;
+        let guard = guard.downgrade();
+        assert_eq!(42, *guard);
+        assert!(lock.try_read_upgradable(Duration::ZERO).is_none());
+        assert!(lock.try_write_downgradable(Duration::ZERO).is_none());
+        let reader = lock.try_read(Duration::ZERO).unwrap();
+        let guard = guard.downgrade();
+        assert_eq!(42, *guard);
+        drop((guard, reader));
+
+        // upgradable -> upgrade
+        let guard = lock.read_upgradable();
+        let reader = lock.try_read(Duration::ZERO).unwrap();
+        let guard = guard.try_upgrade(Duration::ZERO).unchanged().unwrap();
+        drop(reader);
+        let mut guard = guard.upgrade();
+        *guard = 69;
+        drop(guard);
+
+        let guard = lock.try_write_downgradable(Duration::ZERO).unwrap();
+        assert_eq!(69, *guard);
+        let guard = guard.downgrade().try_upgrade(Duration::ZERO).upgraded().unwrap();
+        drop(guard);
+        assert!(lock.try_write(Duration::ZERO).is_some());
+    }
+
     fn takes_borrowed<'a, L: Locklike<'a, u64>>(lock: &'a L) {
         let guard = lock.try_read(Duration::ZERO).unwrap();
         assert_eq!(0, *guard);
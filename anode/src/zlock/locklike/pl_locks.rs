@@ -0,0 +1,351 @@
+//! [`Locklike`] for [`parking_lot::RwLock`] and [`parking_lot::Mutex`], enabled by the
+//! `parking_lot` feature. See [`lock_box_parking_lot_rwlock`](super::lock_box_parking_lot_rwlock)
+//! and [`lock_box_parking_lot_mutex`](super::lock_box_parking_lot_mutex) for the emulation of the
+//! capabilities that these locks lack.
+
+use crate::zlock::locklike::{
+    DynLockDowngradableGuard, DynLockReadGuard, DynLockUpgradableGuard, DynLockWriteGuard, LockDowngradableGuardSurrogate, LockReadGuardSurrogate, LockUpgradableGuardSurrogate,
+    LockWriteGuardSurrogate, Locklike, LocklikeSized,
+};
+use crate::zlock::UpgradeOutcome;
+use parking_lot::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+struct PlReadGuard<'a, T: ?Sized>(RwLockReadGuard<'a, T>);
+
+struct PlWriteGuard<'a, T: ?Sized>(RwLockWriteGuard<'a, T>);
+
+struct PlUpgradableGuard<'a, T: ?Sized>(RwLockUpgradableReadGuard<'a, T>);
+
+/// A write lock that downgrades to an upgradable read lock.
+struct PlDowngradableGuard<'a, T: ?Sized>(RwLockWriteGuard<'a, T>);
+
+/// A mutex guard, serving in every mode.
+struct PlMutexGuard<'a, T: ?Sized>(MutexGuard<'a, T>);
+
+impl<'a, T: ?Sized + 'a> From<PlReadGuard<'a, T>> for DynLockReadGuard<'a, T> {
+    #[inline]
+    fn from(guard: PlReadGuard<'a, T>) -> Self {
+        DynLockReadGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<PlWriteGuard<'a, T>> for DynLockWriteGuard<'a, T> {
+    #[inline]
+    fn from(guard: PlWriteGuard<'a, T>) -> Self {
+        DynLockWriteGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<PlUpgradableGuard<'a, T>> for DynLockUpgradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: PlUpgradableGuard<'a, T>) -> Self {
+        DynLockUpgradableGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<PlDowngradableGuard<'a, T>> for DynLockDowngradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: PlDowngradableGuard<'a, T>) -> Self {
+        DynLockDowngradableGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + Sync + Send + 'a> Locklike<'a, T> for RwLock<T> {
+    type R = DynLockReadGuard<'a, T>;
+    type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
+
+    #[inline]
+    fn read(&'a self) -> Self::R {
+        PlReadGuard(self.read()).into()
+    }
+
+    #[inline]
+    fn try_read(&'a self, duration: Duration) -> Option<Self::R> {
+        self.try_read_for(duration).map(|guard| PlReadGuard(guard).into())
+    }
+
+    #[inline]
+    fn write(&'a self) -> Self::W {
+        PlWriteGuard(self.write()).into()
+    }
+
+    #[inline]
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        self.try_write_for(duration).map(|guard| PlWriteGuard(guard).into())
+    }
+
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        PlUpgradableGuard(self.upgradable_read()).into()
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.try_upgradable_read_for(duration).map(|guard| PlUpgradableGuard(guard).into())
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        PlDowngradableGuard(self.write()).into()
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.try_write_for(duration).map(|guard| PlDowngradableGuard(guard).into())
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<'a, T: Sync + Send + 'a> LocklikeSized<'a, T> for RwLock<T> {
+    #[inline]
+    fn into_inner(self: Box<Self>) -> T {
+        (*self).into_inner()
+    }
+}
+
+impl<T: ?Sized> Deref for PlReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockReadGuardSurrogate<'a, T> for PlReadGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        unsupported_upgrade()
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>> {
+        unsupported_upgrade()
+    }
+}
+
+/// A plain read lock cannot be upgraded without first releasing it, whereupon another writer
+/// may intervene. As with `std::sync::RwLock`, upgrading a plain read guard is refused; upgrades
+/// are supported through the lock's upgradable guards.
+#[cold]
+fn unsupported_upgrade() -> ! {
+    panic!("parking_lot::RwLock cannot upgrade a plain read lock atomically; use read_upgradable instead")
+}
+
+impl<T: ?Sized> Deref for PlWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for PlWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockWriteGuardSurrogate<'a, T> for PlWriteGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        PlReadGuard(RwLockWriteGuard::downgrade(self.0)).into()
+    }
+}
+
+impl<T: ?Sized> Deref for PlUpgradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockUpgradableGuardSurrogate<'a, T> for PlUpgradableGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        PlWriteGuard(RwLockUpgradableReadGuard::upgrade(self.0)).into()
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        match RwLockUpgradableReadGuard::try_upgrade_for(self.0, duration) {
+            Ok(guard) => UpgradeOutcome::Upgraded(PlWriteGuard(guard).into()),
+            Err(guard) => UpgradeOutcome::Unchanged(PlUpgradableGuard(guard).into()),
+        }
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        PlReadGuard(RwLockUpgradableReadGuard::downgrade(self.0)).into()
+    }
+}
+
+impl<T: ?Sized> Deref for PlDowngradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for PlDowngradableGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockDowngradableGuardSurrogate<'a, T> for PlDowngradableGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        PlUpgradableGuard(RwLockWriteGuard::downgrade_to_upgradable(self.0)).into()
+    }
+}
+
+impl<'a, T: ?Sized + Sync + Send + 'a> Locklike<'a, T> for Mutex<T> {
+    type R = DynLockReadGuard<'a, T>;
+    type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
+
+    #[inline]
+    fn read(&'a self) -> Self::R {
+        DynLockReadGuard(Box::new(PlMutexGuard(self.lock())))
+    }
+
+    #[inline]
+    fn try_read(&'a self, duration: Duration) -> Option<Self::R> {
+        self.try_lock_for(duration).map(|guard| DynLockReadGuard(Box::new(PlMutexGuard(guard))))
+    }
+
+    #[inline]
+    fn write(&'a self) -> Self::W {
+        DynLockWriteGuard(Box::new(PlMutexGuard(self.lock())))
+    }
+
+    #[inline]
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        self.try_lock_for(duration).map(|guard| DynLockWriteGuard(Box::new(PlMutexGuard(guard))))
+    }
+
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        DynLockUpgradableGuard(Box::new(PlMutexGuard(self.lock())))
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.try_lock_for(duration).map(|guard| DynLockUpgradableGuard(Box::new(PlMutexGuard(guard))))
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        DynLockDowngradableGuard(Box::new(PlMutexGuard(self.lock())))
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.try_lock_for(duration).map(|guard| DynLockDowngradableGuard(Box::new(PlMutexGuard(guard))))
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.get_mut()
+    }
+}
+
+impl<'a, T: Sync + Send + 'a> LocklikeSized<'a, T> for Mutex<T> {
+    #[inline]
+    fn into_inner(self: Box<Self>) -> T {
+        (*self).into_inner()
+    }
+}
+
+impl<T: ?Sized> Deref for PlMutexGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for PlMutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+// the mutex is held exclusively in every mode, so every transition is trivially atomic
+
+impl<'a, T: ?Sized + 'a> LockReadGuardSurrogate<'a, T> for PlMutexGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(DynLockWriteGuard(self))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockWriteGuardSurrogate<'a, T> for PlMutexGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockUpgradableGuardSurrogate<'a, T> for PlMutexGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(DynLockWriteGuard(self))
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockDowngradableGuardSurrogate<'a, T> for PlMutexGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        DynLockUpgradableGuard(self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,137 @@
+use crate::test_utils::stress::{self, Ledger, StressConfig};
+use crate::test_utils::SHORT_WAIT;
+use crate::zlock::locklike::{
+    lock_box_parking_lot_mutex, lock_box_parking_lot_rwlock, LockDowngradableGuardlike, LockReadGuardlike, LockUpgradableGuardlike, LockWriteGuardlike, Locklike,
+};
+use parking_lot::{Mutex, RwLock};
+use std::time::Duration;
+
+#[test]
+fn rwlock_read_and_write() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    drop((guard_1, guard_2));
+
+    let mut guard = lock.write();
+    *guard = 42;
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    drop(guard);
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn rwlock_downgrade_write() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let mut guard = lock.write();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_read(Duration::ZERO).is_some());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+}
+
+#[test]
+#[should_panic(expected = "cannot upgrade a plain read lock atomically")]
+fn rwlock_upgrade_read_unsupported() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let _guard = lock.read().upgrade();
+}
+
+#[test]
+#[should_panic(expected = "cannot upgrade a plain read lock atomically")]
+fn rwlock_try_upgrade_read_unsupported() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let _guard = lock.read().try_upgrade(Duration::ZERO);
+}
+
+#[test]
+fn rwlock_upgradable_admits_readers() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let guard = lock.read_upgradable();
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_none());
+    let reader = lock.try_read(Duration::ZERO).unwrap();
+    let guard = guard.try_upgrade(SHORT_WAIT).unchanged().expect("upgraded alongside a reader");
+    drop(reader);
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().expect("upgrade refused to a sole reader");
+    *guard = 42;
+    drop(guard);
+
+    let reader = lock.read_upgradable().downgrade();
+    assert_eq!(42, *reader);
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_some());
+}
+
+#[test]
+fn rwlock_downgradable_write() {
+    let lock = lock_box_parking_lot_rwlock(0);
+    let mut guard = lock.write_downgradable();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_write_downgradable(Duration::ZERO).is_none());
+    assert!(lock.try_read(Duration::ZERO).is_some());
+    let mut guard = guard.upgrade();
+    *guard += 1;
+    drop(guard);
+    assert_eq!(43, *lock.read());
+}
+
+#[test]
+fn rwlock_get_mut() {
+    let mut lock = RwLock::new(0);
+    *Locklike::get_mut(&mut lock) = 42;
+    assert_eq!(42, *Locklike::read(&lock));
+}
+
+#[test]
+fn mutex_exclusive_in_all_modes() {
+    let lock = lock_box_parking_lot_mutex(0);
+    let guard = lock.read();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read_upgradable(SHORT_WAIT).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard);
+
+    let mut guard = lock.write_downgradable();
+    *guard += 1;
+    let guard = guard.downgrade().upgrade();
+    assert!(lock.try_write_downgradable(Duration::ZERO).is_none());
+    drop(guard);
+    assert_eq!(43, lock.into_inner());
+}
+
+#[test]
+fn mutex_get_mut() {
+    let mut lock = Mutex::new(0);
+    *Locklike::get_mut(&mut lock) = 42;
+    assert_eq!(42, *Locklike::read(&lock));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stress_rwlock() {
+    let lock = RwLock::new(Ledger::default());
+    stress::stress(&lock, StressConfig {
+        duration: Duration::from_millis(20),
+        ..StressConfig::default()
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stress_mutex() {
+    let lock = Mutex::new(Ledger::default());
+    stress::stress(&lock, StressConfig {
+        duration: Duration::from_millis(20),
+        ..StressConfig::default()
+    });
+}
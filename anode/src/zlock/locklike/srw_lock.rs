@@ -0,0 +1,239 @@
+//! [`Locklike`] for the Windows slim reader/writer lock (`SRWLOCK`). See
+//! [`lock_box_srw`](super::lock_box_srw) for the emulation of the capabilities that it lacks.
+
+use crate::backoff::spin_until;
+use crate::zlock::locklike::{
+    DynLockDowngradableGuard, DynLockReadGuard, DynLockUpgradableGuard, DynLockWriteGuard, LockDowngradableGuardSurrogate, LockReadGuardSurrogate, LockUpgradableGuardSurrogate,
+    LockWriteGuardSurrogate, Locklike, LocklikeSized,
+};
+use crate::zlock::UpgradeOutcome;
+use std::cell::UnsafeCell;
+use std::ffi::c_void;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::time::Duration;
+
+/// The `SRWLOCK` structure, which is a single pointer-sized word.
+#[repr(C)]
+struct RawSrwLock {
+    ptr: *mut c_void,
+}
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn AcquireSRWLockShared(lock: *mut RawSrwLock);
+    fn AcquireSRWLockExclusive(lock: *mut RawSrwLock);
+    fn TryAcquireSRWLockShared(lock: *mut RawSrwLock) -> u8;
+    fn TryAcquireSRWLockExclusive(lock: *mut RawSrwLock) -> u8;
+    fn ReleaseSRWLockShared(lock: *mut RawSrwLock);
+    fn ReleaseSRWLockExclusive(lock: *mut RawSrwLock);
+}
+
+/// An `SRWLOCK` over the data of type `T`. The lock may be moved while it is not held, which
+/// the borrow of its guards ensures.
+pub(crate) struct SrwLock<T: ?Sized> {
+    raw: UnsafeCell<RawSrwLock>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for SrwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for SrwLock<T> {}
+
+impl<T> SrwLock<T> {
+    #[inline]
+    pub(crate) fn new(t: T) -> Self {
+        Self {
+            // SRWLOCK_INIT
+            raw: UnsafeCell::new(RawSrwLock { ptr: ptr::null_mut() }),
+            data: UnsafeCell::new(t),
+        }
+    }
+}
+
+impl<T: ?Sized> SrwLock<T> {
+    #[inline]
+    fn lock_shared(&self) -> SrwGuard<'_, T> {
+        unsafe { AcquireSRWLockShared(self.raw.get()) };
+        SrwGuard { lock: self, exclusive: false }
+    }
+
+    #[inline]
+    fn try_lock_shared(&self, duration: Duration) -> Option<SrwGuard<'_, T>> {
+        spin_until(duration, || unsafe { TryAcquireSRWLockShared(self.raw.get()) != 0 })
+            .then(|| SrwGuard { lock: self, exclusive: false })
+    }
+
+    #[inline]
+    fn lock_exclusive(&self) -> SrwGuard<'_, T> {
+        unsafe { AcquireSRWLockExclusive(self.raw.get()) };
+        SrwGuard { lock: self, exclusive: true }
+    }
+
+    #[inline]
+    fn try_lock_exclusive(&self, duration: Duration) -> Option<SrwGuard<'_, T>> {
+        spin_until(duration, || unsafe { TryAcquireSRWLockExclusive(self.raw.get()) != 0 })
+            .then(|| SrwGuard { lock: self, exclusive: true })
+    }
+}
+
+/// Holds the lock in shared mode for a plain read guard, and in exclusive mode otherwise. A
+/// guard never changes its mode, so a guard serving as a read guard holds the lock exclusively
+/// only if it was downgraded from a write guard.
+struct SrwGuard<'a, T: ?Sized> {
+    lock: &'a SrwLock<T>,
+    exclusive: bool,
+}
+
+impl<T: ?Sized> Drop for SrwGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.exclusive {
+            unsafe { ReleaseSRWLockExclusive(self.lock.raw.get()) };
+        } else {
+            unsafe { ReleaseSRWLockShared(self.lock.raw.get()) };
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for SrwGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for SrwGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // only exclusive guards serve as write and downgradable guards
+        debug_assert!(self.exclusive);
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized + Sync + Send + 'a> Locklike<'a, T> for SrwLock<T> {
+    type R = DynLockReadGuard<'a, T>;
+    type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
+
+    #[inline]
+    fn read(&'a self) -> Self::R {
+        DynLockReadGuard(Box::new(self.lock_shared()))
+    }
+
+    #[inline]
+    fn try_read(&'a self, duration: Duration) -> Option<Self::R> {
+        self.try_lock_shared(duration).map(|guard| DynLockReadGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn write(&'a self) -> Self::W {
+        DynLockWriteGuard(Box::new(self.lock_exclusive()))
+    }
+
+    #[inline]
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        self.try_lock_exclusive(duration).map(|guard| DynLockWriteGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        DynLockUpgradableGuard(Box::new(self.lock_exclusive()))
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.try_lock_exclusive(duration).map(|guard| DynLockUpgradableGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        DynLockDowngradableGuard(Box::new(self.lock_exclusive()))
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.try_lock_exclusive(duration).map(|guard| DynLockDowngradableGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<'a, T: Sync + Send + 'a> LocklikeSized<'a, T> for SrwLock<T> {
+    #[inline]
+    fn into_inner(self: Box<Self>) -> T {
+        self.data.into_inner()
+    }
+}
+
+// the exclusive guards retain the exclusive lock through every transition, which is thus
+// trivially atomic; only a plain (shared) read guard cannot be upgraded
+
+impl<'a, T: ?Sized + 'a> LockReadGuardSurrogate<'a, T> for SrwGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        if !self.exclusive {
+            unsupported_upgrade();
+        }
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(LockReadGuardSurrogate::upgrade_box(self))
+    }
+}
+
+/// `SRWLOCK` cannot convert a shared lock into an exclusive one without first releasing it,
+/// whereupon another writer may intervene. As with `std::sync::RwLock`, upgrading a plain read
+/// guard is refused.
+#[cold]
+fn unsupported_upgrade() -> ! {
+    panic!("SRWLOCK cannot upgrade a shared lock atomically; use read_upgradable instead")
+}
+
+impl<'a, T: ?Sized + 'a> LockWriteGuardSurrogate<'a, T> for SrwGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockUpgradableGuardSurrogate<'a, T> for SrwGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(DynLockWriteGuard(self))
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockDowngradableGuardSurrogate<'a, T> for SrwGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        DynLockUpgradableGuard(self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
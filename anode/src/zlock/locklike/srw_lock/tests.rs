@@ -0,0 +1,99 @@
+use super::SrwLock;
+use crate::test_utils::stress::{self, Ledger, StressConfig};
+use crate::test_utils::SHORT_WAIT;
+use crate::zlock::locklike::{lock_box_srw, LockDowngradableGuardlike, LockReadGuardlike, LockUpgradableGuardlike, LockWriteGuardlike, Locklike};
+use std::time::Duration;
+
+#[test]
+fn read_and_write() {
+    let lock = lock_box_srw(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    drop((guard_1, guard_2));
+
+    let mut guard = lock.write();
+    *guard = 42;
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    drop(guard);
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn downgrade_write_retains_exclusive_lock() {
+    let lock = lock_box_srw(0);
+    let mut guard = lock.write();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+
+    // having never released the exclusive lock, the downgraded guard may upgrade again
+    let mut guard = guard.upgrade();
+    *guard += 1;
+    drop(guard);
+    assert_eq!(43, *lock.read());
+}
+
+#[test]
+#[should_panic(expected = "cannot upgrade a shared lock atomically")]
+fn upgrade_read_unsupported() {
+    let lock = lock_box_srw(0);
+    let _guard = lock.read().upgrade();
+}
+
+#[test]
+#[should_panic(expected = "cannot upgrade a shared lock atomically")]
+fn try_upgrade_read_unsupported() {
+    let lock = lock_box_srw(0);
+    let _guard = lock.read().try_upgrade(Duration::ZERO);
+}
+
+#[test]
+fn upgradable_excludes_readers() {
+    let lock = lock_box_srw(0);
+    let guard = lock.read_upgradable();
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_none());
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+    drop(guard);
+
+    let reader = lock.read_upgradable().downgrade();
+    assert_eq!(42, *reader);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+}
+
+#[test]
+fn downgradable_write() {
+    let lock = lock_box_srw(0);
+    let mut guard = lock.write_downgradable();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_write_downgradable(Duration::ZERO).is_none());
+    let mut guard = guard.upgrade();
+    *guard += 1;
+    drop(guard);
+    assert_eq!(43, *lock.read());
+}
+
+#[test]
+fn get_mut() {
+    let mut lock = SrwLock::new(0);
+    *Locklike::get_mut(&mut lock) = 42;
+    assert_eq!(42, *Locklike::read(&lock));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stress() {
+    let lock = SrwLock::new(Ledger::default());
+    stress::stress(&lock, StressConfig {
+        duration: Duration::from_millis(20),
+        ..StressConfig::default()
+    });
+}
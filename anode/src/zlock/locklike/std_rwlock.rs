@@ -0,0 +1,252 @@
+//! [`Locklike`] for [`std::sync::RwLock`]. See [`lock_box_std`](super::lock_box_std) for the
+//! emulation of the capabilities that the standard lock lacks.
+
+use crate::backoff::spin_until;
+use crate::remedy::Remedy;
+use crate::zlock::locklike::{
+    DynLockDowngradableGuard, DynLockReadGuard, DynLockUpgradableGuard, DynLockWriteGuard, LockDowngradableGuardSurrogate, LockReadGuardSurrogate, LockUpgradableGuardSurrogate,
+    LockWriteGuardSurrogate, Locklike, LocklikeSized,
+};
+use crate::zlock::UpgradeOutcome;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+#[inline]
+fn try_read<T: ?Sized>(lock: &RwLock<T>, duration: Duration) -> Option<RwLockReadGuard<'_, T>> {
+    let mut guard = None;
+    spin_until(duration, || {
+        guard = lock.try_read().remedy();
+        guard.is_some()
+    });
+    guard
+}
+
+#[inline]
+fn try_write<T: ?Sized>(lock: &RwLock<T>, duration: Duration) -> Option<RwLockWriteGuard<'_, T>> {
+    let mut guard = None;
+    spin_until(duration, || {
+        guard = lock.try_write().remedy();
+        guard.is_some()
+    });
+    guard
+}
+
+struct StdReadGuard<'a, T: ?Sized> {
+    guard: RwLockReadGuard<'a, T>,
+}
+
+struct StdWriteGuard<'a, T: ?Sized> {
+    guard: RwLockWriteGuard<'a, T>,
+}
+
+/// Holds a write lock, presenting it as an upgradable read lock.
+struct StdUpgradableGuard<'a, T: ?Sized> {
+    guard: RwLockWriteGuard<'a, T>,
+}
+
+struct StdDowngradableGuard<'a, T: ?Sized> {
+    guard: RwLockWriteGuard<'a, T>,
+}
+
+impl<'a, T: ?Sized + 'a> From<StdReadGuard<'a, T>> for DynLockReadGuard<'a, T> {
+    #[inline]
+    fn from(guard: StdReadGuard<'a, T>) -> Self {
+        DynLockReadGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<StdWriteGuard<'a, T>> for DynLockWriteGuard<'a, T> {
+    #[inline]
+    fn from(guard: StdWriteGuard<'a, T>) -> Self {
+        DynLockWriteGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<StdUpgradableGuard<'a, T>> for DynLockUpgradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: StdUpgradableGuard<'a, T>) -> Self {
+        DynLockUpgradableGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> From<StdDowngradableGuard<'a, T>> for DynLockDowngradableGuard<'a, T> {
+    #[inline]
+    fn from(guard: StdDowngradableGuard<'a, T>) -> Self {
+        DynLockDowngradableGuard(Box::new(guard))
+    }
+}
+
+impl<'a, T: ?Sized + Sync + Send + 'a> Locklike<'a, T> for RwLock<T> {
+    type R = DynLockReadGuard<'a, T>;
+    type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
+
+    #[inline]
+    fn read(&'a self) -> Self::R {
+        StdReadGuard { guard: self.read().remedy() }.into()
+    }
+
+    #[inline]
+    fn try_read(&'a self, duration: Duration) -> Option<Self::R> {
+        try_read(self, duration).map(|guard| StdReadGuard { guard }.into())
+    }
+
+    #[inline]
+    fn write(&'a self) -> Self::W {
+        StdWriteGuard { guard: self.write().remedy() }.into()
+    }
+
+    #[inline]
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        try_write(self, duration).map(|guard| StdWriteGuard { guard }.into())
+    }
+
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        StdUpgradableGuard { guard: self.write().remedy() }.into()
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        try_write(self, duration).map(|guard| StdUpgradableGuard { guard }.into())
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        StdDowngradableGuard { guard: self.write().remedy() }.into()
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        try_write(self, duration).map(|guard| StdDowngradableGuard { guard }.into())
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.get_mut().remedy()
+    }
+}
+
+impl<'a, T: Sync + Send + 'a> LocklikeSized<'a, T> for RwLock<T> {
+    #[inline]
+    fn into_inner(self: Box<Self>) -> T {
+        (*self).into_inner().remedy()
+    }
+}
+
+impl<T: ?Sized> Deref for StdReadGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockReadGuardSurrogate<'a, T> for StdReadGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        unsupported_upgrade()
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>> {
+        unsupported_upgrade()
+    }
+}
+
+/// The standard lock cannot upgrade a read lock without first releasing it, whereupon another
+/// writer may intervene. Rather than emulate an upgrade that is not atomic, upgrading a plain
+/// read guard is refused; upgrades are supported through the (emulated) upgradable guards.
+#[cold]
+fn unsupported_upgrade() -> ! {
+    panic!("std::sync::RwLock cannot upgrade a read lock atomically; use read_upgradable instead")
+}
+
+impl<T: ?Sized> Deref for StdWriteGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized> DerefMut for StdWriteGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockWriteGuardSurrogate<'a, T> for StdWriteGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        let Self { guard } = *self;
+        StdReadGuard { guard: RwLockWriteGuard::downgrade(guard) }.into()
+    }
+}
+
+impl<T: ?Sized> Deref for StdUpgradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockUpgradableGuardSurrogate<'a, T> for StdUpgradableGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        let Self { guard } = *self;
+        StdWriteGuard { guard }.into()
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        // the write lock is already held
+        UpgradeOutcome::Upgraded(self.upgrade_box())
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        let Self { guard } = *self;
+        StdReadGuard { guard: RwLockWriteGuard::downgrade(guard) }.into()
+    }
+}
+
+impl<T: ?Sized> Deref for StdDowngradableGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: ?Sized> DerefMut for StdDowngradableGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockDowngradableGuardSurrogate<'a, T> for StdDowngradableGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        let Self { guard } = *self;
+        StdUpgradableGuard { guard }.into()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,202 @@
+//! [`Locklike`] for the macOS `os_unfair_lock`. See
+//! [`lock_box_unfair`](super::lock_box_unfair) for the emulation of the capabilities that it
+//! lacks.
+
+use crate::backoff::spin_until;
+use crate::zlock::locklike::{
+    DynLockDowngradableGuard, DynLockReadGuard, DynLockUpgradableGuard, DynLockWriteGuard, LockDowngradableGuardSurrogate, LockReadGuardSurrogate, LockUpgradableGuardSurrogate,
+    LockWriteGuardSurrogate, Locklike, LocklikeSized,
+};
+use crate::zlock::UpgradeOutcome;
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+/// The `os_unfair_lock` structure, which is a single 32-bit word.
+#[repr(C)]
+struct RawUnfairLock {
+    opaque: u32,
+}
+
+extern "C" {
+    fn os_unfair_lock_lock(lock: *mut RawUnfairLock);
+    fn os_unfair_lock_trylock(lock: *mut RawUnfairLock) -> bool;
+    fn os_unfair_lock_unlock(lock: *mut RawUnfairLock);
+}
+
+/// An `os_unfair_lock` over the data of type `T`. The lock may be moved while it is not held,
+/// which the borrow of its guards ensures.
+pub(crate) struct UnfairLock<T: ?Sized> {
+    raw: UnsafeCell<RawUnfairLock>,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for UnfairLock<T> {}
+unsafe impl<T: ?Sized + Send> Sync for UnfairLock<T> {}
+
+impl<T> UnfairLock<T> {
+    #[inline]
+    pub(crate) fn new(t: T) -> Self {
+        Self {
+            // OS_UNFAIR_LOCK_INIT
+            raw: UnsafeCell::new(RawUnfairLock { opaque: 0 }),
+            data: UnsafeCell::new(t),
+        }
+    }
+}
+
+impl<T: ?Sized> UnfairLock<T> {
+    #[inline]
+    fn lock(&self) -> UnfairGuard<'_, T> {
+        unsafe { os_unfair_lock_lock(self.raw.get()) };
+        UnfairGuard { lock: self }
+    }
+
+    #[inline]
+    fn try_lock(&self, duration: Duration) -> Option<UnfairGuard<'_, T>> {
+        spin_until(duration, || unsafe { os_unfair_lock_trylock(self.raw.get()) })
+            .then(|| UnfairGuard { lock: self })
+    }
+}
+
+/// A guard of the lock, serving in every mode. As the guard is not [`Send`], the lock is
+/// always unlocked by its owner, as `os_unfair_lock` requires.
+struct UnfairGuard<'a, T: ?Sized> {
+    lock: &'a UnfairLock<T>,
+}
+
+impl<T: ?Sized> Drop for UnfairGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { os_unfair_lock_unlock(self.lock.raw.get()) };
+    }
+}
+
+impl<T: ?Sized> Deref for UnfairGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for UnfairGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<'a, T: ?Sized + Sync + Send + 'a> Locklike<'a, T> for UnfairLock<T> {
+    type R = DynLockReadGuard<'a, T>;
+    type W = DynLockWriteGuard<'a, T>;
+    type U = DynLockUpgradableGuard<'a, T>;
+    type D = DynLockDowngradableGuard<'a, T>;
+
+    #[inline]
+    fn read(&'a self) -> Self::R {
+        DynLockReadGuard(Box::new(self.lock()))
+    }
+
+    #[inline]
+    fn try_read(&'a self, duration: Duration) -> Option<Self::R> {
+        self.try_lock(duration).map(|guard| DynLockReadGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn write(&'a self) -> Self::W {
+        DynLockWriteGuard(Box::new(self.lock()))
+    }
+
+    #[inline]
+    fn try_write(&'a self, duration: Duration) -> Option<Self::W> {
+        self.try_lock(duration).map(|guard| DynLockWriteGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn read_upgradable(&'a self) -> Self::U {
+        DynLockUpgradableGuard(Box::new(self.lock()))
+    }
+
+    #[inline]
+    fn try_read_upgradable(&'a self, duration: Duration) -> Option<Self::U> {
+        self.try_lock(duration).map(|guard| DynLockUpgradableGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn write_downgradable(&'a self) -> Self::D {
+        DynLockDowngradableGuard(Box::new(self.lock()))
+    }
+
+    #[inline]
+    fn try_write_downgradable(&'a self, duration: Duration) -> Option<Self::D> {
+        self.try_lock(duration).map(|guard| DynLockDowngradableGuard(Box::new(guard)))
+    }
+
+    #[inline]
+    fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+}
+
+impl<'a, T: Sync + Send + 'a> LocklikeSized<'a, T> for UnfairLock<T> {
+    #[inline]
+    fn into_inner(self: Box<Self>) -> T {
+        self.data.into_inner()
+    }
+}
+
+// the lock is held exclusively in every mode, so every transition is trivially atomic
+
+impl<'a, T: ?Sized + 'a> LockReadGuardSurrogate<'a, T> for UnfairGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockReadGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(DynLockWriteGuard(self))
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockWriteGuardSurrogate<'a, T> for UnfairGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockUpgradableGuardSurrogate<'a, T> for UnfairGuard<'a, T> {
+    #[inline]
+    fn upgrade_box(self: Box<Self>) -> DynLockWriteGuard<'a, T> {
+        DynLockWriteGuard(self)
+    }
+
+    #[inline]
+    fn try_upgrade_box(
+        self: Box<Self>,
+        _duration: Duration,
+    ) -> UpgradeOutcome<DynLockWriteGuard<'a, T>, DynLockUpgradableGuard<'a, T>> {
+        UpgradeOutcome::Upgraded(DynLockWriteGuard(self))
+    }
+
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockReadGuard<'a, T> {
+        DynLockReadGuard(self)
+    }
+}
+
+impl<'a, T: ?Sized + 'a> LockDowngradableGuardSurrogate<'a, T> for UnfairGuard<'a, T> {
+    #[inline]
+    fn downgrade_box(self: Box<Self>) -> DynLockUpgradableGuard<'a, T> {
+        DynLockUpgradableGuard(self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
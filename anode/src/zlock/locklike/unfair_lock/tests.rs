@@ -0,0 +1,48 @@
+use super::UnfairLock;
+use crate::test_utils::stress::{self, Ledger, StressConfig};
+use crate::test_utils::SHORT_WAIT;
+use crate::zlock::locklike::{lock_box_unfair, LockDowngradableGuardlike, LockUpgradableGuardlike, LockWriteGuardlike, Locklike};
+use std::time::Duration;
+
+#[test]
+fn exclusive_in_all_modes() {
+    let lock = lock_box_unfair(0);
+    let guard = lock.read();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_read_upgradable(SHORT_WAIT).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard);
+
+    let mut guard = lock.write_downgradable();
+    *guard += 1;
+    let guard = guard.downgrade().upgrade();
+    assert!(lock.try_write_downgradable(Duration::ZERO).is_none());
+    drop(guard);
+
+    let guard = lock.read_upgradable().upgrade().downgrade();
+    assert_eq!(43, *guard);
+    drop(guard);
+    assert_eq!(43, lock.into_inner());
+}
+
+#[test]
+fn get_mut() {
+    let mut lock = UnfairLock::new(0);
+    *Locklike::get_mut(&mut lock) = 42;
+    assert_eq!(42, *Locklike::read(&lock));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn stress() {
+    let lock = UnfairLock::new(Ledger::default());
+    stress::stress(&lock, StressConfig {
+        duration: Duration::from_millis(20),
+        ..StressConfig::default()
+    });
+}
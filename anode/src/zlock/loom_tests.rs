@@ -0,0 +1,161 @@
+//! Model-checks the [`ZLock`] moderators under [loom](https://github.com/tokio-rs/loom),
+//! exercising the interleavings of reads, writes, upgrades and downgrades.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test -p anode --lib --release zlock::loom_tests`.
+//! Loom's condvar never times out, so only untimed acquisitions are modelled.
+
+use loom::cell::UnsafeCell;
+use loom::sync::Arc;
+use loom::thread;
+use crate::zlock::{ArrivalOrdered, Moderator, PriorityOrdered, ReadBiased, SpinModerator, Stochastic, UpgradeBiased, WriteBiased, ZLock};
+
+/// A counter whose accesses are mediated by the lock under test. Loom flags any access that is
+/// not ordered after the preceding conflicting access; i.e., a writer that was admitted
+/// alongside another reader or writer.
+struct Counter(UnsafeCell<u32>);
+
+unsafe impl Sync for Counter {}
+
+impl Counter {
+    fn new() -> Self {
+        Self(UnsafeCell::new(0))
+    }
+
+    fn get(&self) -> u32 {
+        self.0.with(|val| unsafe { *val })
+    }
+
+    fn increment(&self) {
+        self.0.with_mut(|val| unsafe { *val += 1 });
+    }
+}
+
+/// Runs the given scenario for each moderator whose blocking is visible to loom.
+macro_rules! for_each_moderator {
+    ($scenario:ident) => {
+        $scenario::<ReadBiased>();
+        $scenario::<WriteBiased>();
+        $scenario::<ArrivalOrdered>();
+        $scenario::<UpgradeBiased>();
+        $scenario::<PriorityOrdered>();
+        $scenario::<Stochastic>();
+        $scenario::<SpinModerator>();
+    };
+}
+
+fn model(f: impl Fn() + Sync + Send + 'static) {
+    let mut builder = loom::model::Builder::new();
+    if builder.preemption_bound.is_none() {
+        builder.preemption_bound = Some(2);
+    }
+    builder.check(f);
+}
+
+fn write_write<M: Moderator + 'static>() {
+    model(|| {
+        let lock = Arc::new(ZLock::<_, M>::new(Counter::new()));
+        let t_2 = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.write().increment())
+        };
+        lock.write().increment();
+        t_2.join().unwrap();
+        assert_eq!(2, lock.read().get());
+    });
+}
+
+fn read_write<M: Moderator + 'static>() {
+    model(|| {
+        let lock = Arc::new(ZLock::<_, M>::new(Counter::new()));
+        let t_2 = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.write().increment())
+        };
+        let observed = lock.read().get();
+        assert!(observed <= 1, "observed: {observed}");
+        t_2.join().unwrap();
+        assert_eq!(1, lock.read().get());
+    });
+}
+
+fn upgrade_write<M: Moderator + 'static>() {
+    model(|| {
+        let lock = Arc::new(ZLock::<_, M>::new(Counter::new()));
+        let t_2 = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.write().increment())
+        };
+        {
+            let guard = lock.read();
+            let before = guard.get();
+            let guard = guard.upgrade();
+
+            // no writer may intervene between the read and the upgrade
+            assert_eq!(before, guard.get());
+            guard.increment();
+        }
+        t_2.join().unwrap();
+        assert_eq!(2, lock.read().get());
+    });
+}
+
+fn upgradable_upgradable<M: Moderator + 'static>() {
+    model(|| {
+        let lock = Arc::new(ZLock::<_, M>::new(Counter::new()));
+        let t_2 = {
+            let lock = lock.clone();
+            thread::spawn(move || lock.read_upgradable().upgrade().increment())
+        };
+        lock.read_upgradable().upgrade().increment();
+        t_2.join().unwrap();
+        assert_eq!(2, lock.read().get());
+    });
+}
+
+fn downgrade_read<M: Moderator + 'static>() {
+    model(|| {
+        let lock = Arc::new(ZLock::<_, M>::new(Counter::new()));
+        let t_2 = {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                let observed = lock.read().get();
+                assert!(observed <= 1, "observed: {observed}");
+            })
+        };
+        {
+            let guard = lock.write();
+            guard.increment();
+            let guard = guard.downgrade();
+
+            // the downgraded lock still excludes writers, but not readers
+            assert_eq!(1, guard.get());
+        }
+        t_2.join().unwrap();
+        assert_eq!(1, lock.read().get());
+    });
+}
+
+#[test]
+fn write_write_is_exclusive() {
+    for_each_moderator!(write_write);
+}
+
+#[test]
+fn read_write_is_exclusive() {
+    for_each_moderator!(read_write);
+}
+
+#[test]
+fn upgrade_contends_with_writer() {
+    for_each_moderator!(upgrade_write);
+}
+
+#[test]
+fn upgradables_contend() {
+    for_each_moderator!(upgradable_upgradable);
+}
+
+#[test]
+fn downgrade_admits_reader() {
+    for_each_moderator!(downgrade_read);
+}
@@ -0,0 +1,89 @@
+use crate::backoff::ExpBackoff;
+use crate::deadlock;
+use crate::inf_iterator::{InfIterator, IntoInfIterator};
+use crate::rand::FIXED_DURATION;
+use crate::zlock::{LockWriteGuard, Moderator, ZLock};
+
+/// Write-acquires both `a` and `b`, returning their guards in argument order.
+///
+/// The locks are always acquired in the order of their addresses (lowest first), irrespective
+/// of the order of the arguments, as in [`ZLock::swap`]. Thus, two threads locking the same
+/// pair in opposite argument order cannot deadlock.
+///
+/// # Panics
+/// If `a` and `b` are the same lock.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{lock_both, ReadBiased, WriteBiased, ZLock};
+/// let a = ZLock::<_, ReadBiased>::new(42);
+/// let b = ZLock::<_, WriteBiased>::new(String::from("foo"));
+/// let (mut a, mut b) = lock_both(&a, &b);
+/// *a += 1;
+/// b.push_str("bar");
+/// ```
+#[inline]
+pub fn lock_both<'a, A: ?Sized, MA: Moderator, B: ?Sized, MB: Moderator>(
+    a: &'a ZLock<A, MA>,
+    b: &'a ZLock<B, MB>,
+) -> (LockWriteGuard<'a, A, MA>, LockWriteGuard<'a, B, MB>) {
+    let (addr_a, addr_b) = (deadlock::addr_of(a), deadlock::addr_of(b));
+    assert_ne!(addr_a, addr_b, "cannot lock the same lock twice");
+    if addr_a < addr_b {
+        let guard_a = a.write();
+        (guard_a, b.write())
+    } else {
+        let guard_b = b.write();
+        (a.write(), guard_b)
+    }
+}
+
+/// Write-acquires any number of locks, which may be of different types and moderators,
+/// returning a tuple of their guards in argument order.
+///
+/// Rather than ordering the locks, each attempt tries every lock without waiting. Should any
+/// lock be unavailable, the locks acquired thus far are released and the attempt is retried
+/// after backing off, as in [`try_lock_all`](crate::zlock::locklike::try_lock_all). Thus, no
+/// thread holds some of the locks while waiting for others, and the locks may be named in any
+/// order. The lock expressions are evaluated on every attempt, so should be free of side effects.
+///
+/// Naming the same lock twice never succeeds, and so blocks forever.
+///
+/// # Examples
+/// ```
+/// use anode::lock_all;
+/// use anode::zlock::{ReadBiased, SpinModerator, ZLock};
+/// let a = ZLock::<_, ReadBiased>::new(1);
+/// let b = ZLock::<_, SpinModerator>::new(2);
+/// let c = ZLock::<_, ReadBiased>::new(3);
+/// let (mut a, b, c) = lock_all!(a, b, c);
+/// *a += *b + *c;
+/// assert_eq!(6, *a);
+/// ```
+#[macro_export]
+macro_rules! lock_all {
+    ($($lock:expr),+ $(,)?) => {
+        $crate::zlock::__acquire_all(|| {
+            ::core::option::Option::Some(($(
+                $lock.try_write(::core::time::Duration::ZERO)?,
+            )+))
+        })
+    };
+}
+
+/// Repeats `attempt` with backoff until it succeeds. Used by [`lock_all!`].
+#[doc(hidden)]
+#[inline]
+pub fn __acquire_all<G>(mut attempt: impl FnMut() -> Option<G>) -> G {
+    let mut rng = FIXED_DURATION;
+    let mut backoff = ExpBackoff::sleepy().into_inf_iter();
+    loop {
+        if let Some(guards) = attempt() {
+            return guards;
+        }
+        backoff.next().act(|| &mut rng);
+    }
+}
+
+#[cfg(test)]
+mod tests;
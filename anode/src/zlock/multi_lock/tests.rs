@@ -0,0 +1,95 @@
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+use crate::zlock::{lock_both, ReadBiased, SpinModerator, WriteBiased, ZLock};
+
+#[test]
+fn lock_both_in_either_order() {
+    let a = ZLock::<_, ReadBiased>::new(0);
+    let b = ZLock::<_, WriteBiased>::new(String::new());
+    for _ in 0..2 {
+        let (mut guard_a, mut guard_b) = lock_both(&a, &b);
+        *guard_a += 1;
+        guard_b.push('x');
+        assert!(a.try_read(Duration::ZERO).is_none());
+        assert!(b.try_read(Duration::ZERO).is_none());
+        drop((guard_a, guard_b));
+
+        let (mut guard_b, guard_a) = lock_both(&b, &a);
+        guard_b.push('y');
+        drop(guard_a);
+        assert!(a.try_read(Duration::ZERO).is_some());
+        assert!(b.try_read(Duration::ZERO).is_none());
+    }
+    assert_eq!(2, *a.read());
+    assert_eq!("xyxy", *b.read());
+}
+
+#[test]
+#[should_panic(expected = "cannot lock the same lock twice")]
+fn lock_both_same_lock() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    lock_both(&lock, &lock);
+}
+
+#[test]
+fn lock_all_heterogeneous() {
+    let a = ZLock::<_, ReadBiased>::new(1);
+    let b = Arc::new(ZLock::<_, SpinModerator>::new(2));
+    let (mut guard_a, guard_b) = lock_all!(a, b);
+    *guard_a += *guard_b;
+    assert!(b.try_read(Duration::ZERO).is_none());
+    drop((guard_a, guard_b));
+    assert_eq!(3, *a.read());
+
+    let (guard_b,) = lock_all!(&b,);
+    assert_eq!(2, *guard_b);
+}
+
+#[test]
+fn lock_all_waits_for_held_lock() {
+    let a = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let b = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let guard = b.read();
+    let t = {
+        let (a, b) = (a.clone(), b.clone());
+        thread::spawn(move || {
+            let (mut a, mut b) = lock_all!(a, b);
+            *a += 1;
+            *b += 1;
+        })
+    };
+
+    // the partially acquired locks are released while waiting
+    thread::sleep(Duration::from_millis(10));
+    drop(a.write());
+    drop(guard);
+    t.join().unwrap();
+    assert_eq!((1, 1), (*a.read(), *b.read()));
+}
+
+#[test]
+fn opposite_orders_do_not_deadlock() {
+    const ITERATIONS: usize = 1_000;
+    let a = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let b = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let barrier = Arc::new(Barrier::new(4));
+    let threads = (0..4)
+        .map(|i| {
+            let (a, b, barrier) = (a.clone(), b.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ITERATIONS {
+                    match i {
+                        0 => *lock_both(&*a, &*b).0 += 1,
+                        1 => *lock_both(&*b, &*a).1 += 1,
+                        2 => *lock_all!(a, b).0 += 1,
+                        _ => *lock_all!(b, a).1 += 1,
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    threads.into_iter().for_each(|t| t.join().unwrap());
+    assert_eq!(4 * ITERATIONS, *a.read());
+}
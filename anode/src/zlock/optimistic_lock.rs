@@ -0,0 +1,238 @@
+use crate::backoff::spin_hint;
+use crate::snapshot::read_validated;
+use crate::zlock::{LockReadGuard, LockWriteGuard, Moderator, UpgradeOutcome, ZLock};
+use std::fmt;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// The number of optimistic attempts made by [`OptimisticLock::optimistic_read`] before
+/// falling back to a read lock.
+const OPTIMISTIC_ATTEMPTS: u32 = 4;
+
+/// A [`ZLock`] that also admits optimistic reads of [`Copy`] data, in the manner of a
+/// [`SeqLock`](crate::seq_lock::SeqLock).
+///
+/// Writers acquire the underlying lock and advance a write sequence, which is odd while a
+/// writer holds the lock. An optimistic read copies the data out without acquiring anything,
+/// and keeps the copy only if the sequence was even and unchanged throughout. Optimistic
+/// readers therefore neither block writers nor contend with each other.
+/// [`optimistic_read`](Self::optimistic_read) makes a few optimistic attempts before falling
+/// back to a conventional read lock, under the lock's moderator, so that a reader is not
+/// starved by a steady stream of writes.
+///
+/// The data is only mutated through the lock's own [`OptimisticWriteGuard`], whether acquired
+/// by [`write`](Self::write) or by upgrading an [`OptimisticReadGuard`], and the guard advances
+/// the sequence; the underlying lock and its guards are not exposed.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{OptimisticLock, ReadBiased};
+/// let lock = OptimisticLock::<_, ReadBiased>::new((0u64, 0u64));
+/// {
+///     let mut guard = lock.write();
+///     guard.0 += 1;
+///     guard.1 += 2;
+/// }
+/// assert_eq!(3, lock.optimistic_read(|&(a, b)| a + b));
+/// assert_eq!((1, 2), *lock.read());
+/// ```
+pub struct OptimisticLock<T, M: Moderator> {
+    /// Even while no writer holds the lock; odd while one does.
+    seq: AtomicU64,
+    lock: ZLock<T, M>,
+}
+
+impl<T, M: Moderator> OptimisticLock<T, M> {
+    #[inline]
+    pub fn new(t: T) -> Self {
+        Self {
+            seq: AtomicU64::new(0),
+            lock: ZLock::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.lock.into_inner()
+    }
+
+    #[inline]
+    pub fn read(&self) -> OptimisticReadGuard<'_, T, M> {
+        self.try_read(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_read(&self, duration: Duration) -> Option<OptimisticReadGuard<'_, T, M>> {
+        let guard = self.lock.try_read(duration)?;
+        Some(OptimisticReadGuard {
+            guard,
+            seq: &self.seq,
+        })
+    }
+
+    #[inline]
+    pub fn write(&self) -> OptimisticWriteGuard<'_, T, M> {
+        self.try_write(Duration::MAX).unwrap()
+    }
+
+    #[inline]
+    pub fn try_write(&self, duration: Duration) -> Option<OptimisticWriteGuard<'_, T, M>> {
+        let guard = self.lock.try_write(duration)?;
+        Some(OptimisticWriteGuard::begin(guard, &self.seq))
+    }
+
+    /// The current write sequence, which is advanced twice by every write. Intended for
+    /// diagnostics; an odd number indicates that a write is in progress.
+    #[inline]
+    pub fn seq(&self) -> u64 {
+        self.seq.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.lock.get_mut()
+    }
+}
+
+impl<T: Copy, M: Moderator> OptimisticLock<T, M> {
+    /// Makes a single attempt at copying the data optimistically, returning `None` if a
+    /// writer held the lock or intervened.
+    #[inline]
+    pub fn try_optimistic_copy(&self) -> Option<T> {
+        let seq = self.seq.load(Ordering::Acquire);
+        if seq & 1 != 0 {
+            return None;
+        }
+
+        unsafe {
+            read_validated(self.lock.data.get(), || {
+                fence(Ordering::Acquire);
+                self.seq.load(Ordering::Relaxed) == seq
+            })
+        }
+    }
+
+    /// Applies `f` to a consistent snapshot of the data. A few attempts are made at copying
+    /// the data optimistically, after which `f` is applied under a read lock. Either way, `f`
+    /// is invoked exactly once, and never observes a torn copy.
+    #[inline]
+    pub fn optimistic_read<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        for attempt in 0..OPTIMISTIC_ATTEMPTS {
+            if attempt > 0 {
+                spin_hint();
+            }
+            if let Some(val) = self.try_optimistic_copy() {
+                return f(&val);
+            }
+        }
+        f(&*self.lock.read())
+    }
+}
+
+impl<T: Default, M: Moderator> Default for OptimisticLock<T, M> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug, M: Moderator> Debug for OptimisticLock<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OptimisticLock")
+            .field("seq", &self.seq())
+            .field("lock", &self.lock)
+            .finish()
+    }
+}
+
+/// An RAII read guard over the data of an [`OptimisticLock`], obtained from
+/// [`OptimisticLock::read`]. Unlike a [`LockReadGuard`], it can only be upgraded to an
+/// [`OptimisticWriteGuard`], so that every write advances the sequence.
+pub struct OptimisticReadGuard<'a, T, M: Moderator> {
+    guard: LockReadGuard<'a, T, M>,
+    seq: &'a AtomicU64,
+}
+
+impl<'a, T, M: Moderator> OptimisticReadGuard<'a, T, M> {
+    #[inline]
+    pub fn upgrade(self) -> OptimisticWriteGuard<'a, T, M> {
+        OptimisticWriteGuard::begin(self.guard.upgrade(), self.seq)
+    }
+
+    #[inline]
+    pub fn try_upgrade(self, duration: Duration) -> UpgradeOutcome<OptimisticWriteGuard<'a, T, M>, Self> {
+        match self.guard.try_upgrade(duration) {
+            UpgradeOutcome::Upgraded(guard) => UpgradeOutcome::Upgraded(OptimisticWriteGuard::begin(guard, self.seq)),
+            UpgradeOutcome::Unchanged(guard) => UpgradeOutcome::Unchanged(Self { guard, seq: self.seq }),
+        }
+    }
+}
+
+impl<T, M: Moderator> Deref for OptimisticReadGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: Debug, M: Moderator> Debug for OptimisticReadGuard<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+/// An RAII guard over the data of an [`OptimisticLock`], obtained from
+/// [`OptimisticLock::write`] or by upgrading an [`OptimisticReadGuard`]. Optimistic readers are made to retry (or fall back to a read lock)
+/// until the guard is dropped.
+pub struct OptimisticWriteGuard<'a, T, M: Moderator> {
+    guard: LockWriteGuard<'a, T, M>,
+    seq: &'a AtomicU64,
+}
+
+impl<'a, T, M: Moderator> OptimisticWriteGuard<'a, T, M> {
+    /// Advances the sequence on behalf of a writer that has just acquired the underlying lock.
+    #[inline]
+    fn begin(guard: LockWriteGuard<'a, T, M>, seq: &'a AtomicU64) -> Self {
+        seq.fetch_add(1, Ordering::Relaxed);
+        // the odd sequence must be visible before any of the writes to the data, lest an
+        // optimistic reader observe new data alongside the old sequence
+        fence(Ordering::Release);
+        Self { guard, seq }
+    }
+}
+
+impl<T, M: Moderator> Drop for OptimisticWriteGuard<'_, T, M> {
+    #[inline]
+    fn drop(&mut self) {
+        // restores an even sequence before the underlying lock is released
+        self.seq.fetch_add(1, Ordering::Release);
+    }
+}
+
+impl<T, M: Moderator> Deref for OptimisticWriteGuard<'_, T, M> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T, M: Moderator> DerefMut for OptimisticWriteGuard<'_, T, M> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: Debug, M: Moderator> Debug for OptimisticWriteGuard<'_, T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests;
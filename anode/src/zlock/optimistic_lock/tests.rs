@@ -0,0 +1,136 @@
+use crate::test_utils::CHECK_WAIT;
+use crate::zlock::{Moderator, OptimisticLock, ReadBiased, WriteBiased};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn write_advances_seq() {
+    __write_advances_seq::<ReadBiased>();
+    __write_advances_seq::<WriteBiased>();
+}
+
+fn __write_advances_seq<M: Moderator>() {
+    let lock = OptimisticLock::<_, M>::new(0);
+    assert_eq!(0, lock.seq());
+    let mut guard = lock.write();
+    assert_eq!(1, lock.seq());
+    *guard = 42;
+    assert!(lock.try_optimistic_copy().is_none());
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+    assert_eq!(2, lock.seq());
+    assert_eq!(Some(42), lock.try_optimistic_copy());
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn optimistic_read_alongside_reader() {
+    let lock = OptimisticLock::<_, ReadBiased>::new(42);
+    let _guard = lock.read();
+    assert_eq!(42, lock.optimistic_read(|&val| val));
+}
+
+#[test]
+fn optimistic_read_falls_back_to_lock() {
+    let lock = OptimisticLock::<_, ReadBiased>::new(0);
+    let invocations = AtomicUsize::default();
+    thread::scope(|scope| {
+        let mut guard = lock.write();
+        let reader = scope.spawn(|| {
+            lock.optimistic_read(|&val| {
+                invocations.fetch_add(1, Ordering::Relaxed);
+                val
+            })
+        });
+        // the optimistic attempts are exhausted while the writer holds the lock, leaving the
+        // reader blocked on the read lock
+        thread::sleep(CHECK_WAIT);
+        *guard = 42;
+        drop(guard);
+        assert_eq!(42, reader.join().unwrap());
+    });
+    assert_eq!(1, invocations.load(Ordering::Relaxed));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn no_torn_reads() {
+    const WRITES: u64 = 1_000;
+    let lock = OptimisticLock::<_, ReadBiased>::new((0u64, 0u64));
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..WRITES {
+                let mut guard = lock.write();
+                guard.0 += 1;
+                guard.1 += 1;
+            }
+        });
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..WRITES {
+                    lock.optimistic_read(|&(a, b)| assert_eq!(a, b));
+                }
+            });
+        }
+    });
+    assert_eq!((WRITES, WRITES), lock.into_inner());
+}
+
+#[test]
+fn upgrade_advances_seq() {
+    let lock = OptimisticLock::<_, ReadBiased>::new((0u64, 0u64));
+    let mut guard = lock.read().upgrade();
+    assert_eq!(1, lock.seq());
+    guard.0 = 1;
+    assert!(lock.try_optimistic_copy().is_none());
+    drop(guard);
+    assert_eq!(2, lock.seq());
+
+    let mut guard = lock.read().try_upgrade(Duration::ZERO).upgraded().unwrap();
+    assert_eq!(3, lock.seq());
+    guard.1 = 1;
+    drop(guard);
+    assert_eq!(Some((1, 1)), lock.try_optimistic_copy());
+
+    let reader = lock.read();
+    let guard = lock.read().try_upgrade(Duration::ZERO).unchanged().unwrap();
+    assert_eq!(4, lock.seq());
+    drop((reader, guard));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn no_torn_reads_through_upgrades() {
+    const WRITES: u64 = 1_000;
+    let lock = OptimisticLock::<_, ReadBiased>::new((0u64, 0u64));
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..WRITES {
+                let mut guard = lock.read().upgrade();
+                guard.0 += 1;
+                guard.1 += 1;
+            }
+        });
+        for _ in 0..2 {
+            scope.spawn(|| {
+                for _ in 0..WRITES {
+                    lock.optimistic_read(|&(a, b)| assert_eq!(a, b));
+                    if let Some((a, b)) = lock.try_optimistic_copy() {
+                        assert_eq!(a, b);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!((WRITES, WRITES), lock.into_inner());
+}
+
+#[test]
+fn debug() {
+    let lock = OptimisticLock::<_, ReadBiased>::new(42);
+    drop(lock.write());
+    assert!(format!("{:?}", lock).starts_with("OptimisticLock { seq: 2, lock: ZLock"));
+    assert_eq!("42", format!("{:?}", lock.write()));
+}
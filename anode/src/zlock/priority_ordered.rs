@@ -0,0 +1,288 @@
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
+use crate::zlock::Moderator;
+
+thread_local! {
+    static PRIORITY: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Admits waiting readers and writers in order of priority, with higher values taking
+/// precedence. Waiters of equal priority are admitted in the order of their arrival.
+///
+/// A thread's priority is set with [`set_thread_priority`](Self::set_thread_priority), and is
+/// captured when the thread begins waiting for the lock; it defaults to 0. Priorities only
+/// order the queue of waiting threads: an acquired lock is never preempted, nor is the priority
+/// of its holder raised. A low-priority holder may therefore delay a high-priority waiter
+/// (priority inversion), and a steady stream of high-priority waiters may starve lower-priority
+/// ones.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::{PriorityOrdered, ZLock};
+/// PriorityOrdered::set_thread_priority(10);
+/// let lock = ZLock::<_, PriorityOrdered>::new(0);
+/// *lock.write() += 1;
+/// assert_eq!(1, *lock.read());
+/// ```
+#[derive(Debug)]
+pub struct PriorityOrdered;
+
+impl PriorityOrdered {
+    /// Sets the priority of the current thread for all subsequent waits on locks moderated
+    /// by [`PriorityOrdered`].
+    #[inline]
+    pub fn set_thread_priority(priority: u32) {
+        PRIORITY.with(|cell| cell.set(priority));
+    }
+
+    /// The priority of the current thread.
+    #[inline]
+    pub fn thread_priority() -> u32 {
+        PRIORITY.with(Cell::get)
+    }
+}
+
+pub struct PriorityOrderedSync {
+    monitor: SpeculativeMonitor<PriorityOrderedState>,
+}
+
+/// A waiter's position in the queue: by descending priority, then by ascending arrival.
+type Waiter = (Reverse<u32>, u64);
+
+#[derive(Debug)]
+struct PriorityOrderedState {
+    readers: u32,
+    writer: bool,
+    upgradable: bool,
+    next_arrival: u64,
+    waiters: BTreeSet<Waiter>,
+}
+
+impl PriorityOrderedState {
+    #[inline]
+    fn enqueue(&mut self, priority: u32) -> Waiter {
+        let waiter = (Reverse(priority), self.next_arrival);
+        self.next_arrival += 1;
+        self.waiters.insert(waiter);
+        waiter
+    }
+
+    #[inline]
+    fn is_head(&self, waiter: &Waiter) -> bool {
+        self.waiters.first() == Some(waiter)
+    }
+}
+
+/// Acquires the lock on behalf of the current thread once `admissible` holds for the state, and
+/// the thread is at the head of the queue. A waiter that finds the queue empty
+/// and the lock admissible acquires it without queuing.
+#[inline]
+fn acquire(
+    sync: &PriorityOrderedSync,
+    duration: Duration,
+    admissible: impl Fn(&PriorityOrderedState) -> bool,
+    admit: impl Fn(&mut PriorityOrderedState),
+) -> bool {
+    let mut deadline = Deadline::lazy_after(duration);
+    let priority = PriorityOrdered::thread_priority();
+    let mut acquired = false;
+    let mut waiter = None;
+    sync.monitor.enter(|state| {
+        if !acquired {
+            match waiter {
+                None if state.waiters.is_empty() && admissible(state) => {
+                    acquired = true;
+                    admit(state);
+                    return Directive::Return;
+                }
+                None => {
+                    waiter = Some(state.enqueue(priority));
+                }
+                Some(_) => {}
+            }
+
+            let queued = waiter.as_ref().unwrap();
+            if state.is_head(queued) && admissible(state) {
+                state.waiters.remove(queued);
+                acquired = true;
+                admit(state);
+            }
+        }
+
+        if acquired {
+            // the next waiter in line may also be admissible (e.g., when readers are queued)
+            Directive::NotifyAll
+        } else {
+            Directive::Wait(deadline.remaining())
+        }
+    });
+
+    if !acquired {
+        if let Some(waiter) = waiter {
+            let mut abandoned = false;
+            sync.monitor.enter(|state| {
+                if !abandoned {
+                    abandoned = true;
+                    state.waiters.remove(&waiter);
+                }
+                Directive::NotifyAll
+            });
+        }
+    }
+
+    acquired
+}
+
+impl Moderator for PriorityOrdered {
+    type Sync = PriorityOrderedSync;
+
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::Sync {
+            monitor: SpeculativeMonitor::new(PriorityOrderedState {
+                readers: 0,
+                writer: false,
+                upgradable: false,
+                next_arrival: 0,
+                waiters: BTreeSet::new(),
+            }),
+        }
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        acquire(sync, duration, |state| !state.writer, |state| state.readers += 1)
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
+                debug_assert!(!state.writer);
+
+                released = true;
+                state.readers -= 1;
+            }
+
+            match state.readers {
+                0 | 1 => Directive::NotifyAll,
+                _ => Directive::Return
+            }
+        });
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        acquire(sync, duration, |state| state.readers == 0 && !state.writer, |state| state.writer = true)
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers == 0, "readers: {}", state.readers);
+                debug_assert!(state.writer);
+
+                released = true;
+                state.writer = false;
+            }
+
+            Directive::NotifyAll
+        });
+    }
+
+    fn downgrade(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers == 0, "readers: {}", state.readers);
+                debug_assert!(state.writer);
+
+                released = true;
+                state.writer = false;
+                state.readers = 1;
+            }
+
+            Directive::NotifyAll
+        });
+    }
+
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut acquired = false;
+        sync.monitor.enter(|state| {
+            if !acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
+                debug_assert!(!state.writer);
+
+                if state.readers == 1 {
+                    acquired = true;
+                    state.readers = 0;
+                    state.writer = true;
+                }
+            }
+
+            if acquired {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        acquired
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            Directive::NotifyAll
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("PriorityOrdered").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("PriorityOrdered")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("waiting", &state.waiters.len())
+                .finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
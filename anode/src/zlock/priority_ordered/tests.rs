@@ -0,0 +1,137 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use crate::monitor::Monitor;
+use crate::remedy::Remedy;
+use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
+use crate::wait;
+use crate::wait::Wait;
+use crate::zlock::{PriorityOrdered, ZLock};
+
+#[test]
+fn thread_priority() {
+    thread::spawn(|| {
+        assert_eq!(0, PriorityOrdered::thread_priority());
+        PriorityOrdered::set_thread_priority(7);
+        assert_eq!(7, PriorityOrdered::thread_priority());
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn uncontended_acquire_does_not_queue() {
+    let lock = ZLock::<_, PriorityOrdered>::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    assert_eq!(0, lock.waiting());
+    drop(guard_1);
+    drop(guard_2);
+
+    *lock.write() = 42;
+    assert_eq!(0, lock.waiting());
+    assert_eq!(0, lock.next_arrival());
+}
+
+#[test]
+fn timed_out_waiter_leaves_queue() {
+    let lock = ZLock::<_, PriorityOrdered>::new(0);
+    let guard = lock.read();
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert_eq!(0, lock.waiting());
+    assert_eq!(1, lock.next_arrival());
+
+    // with the queue empty again, readers are admitted without queuing
+    assert!(lock.try_read(Duration::ZERO).is_some());
+    drop(guard);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn admits_in_priority_order() {
+    let lock = Arc::new(ZLock::<_, PriorityOrdered>::new(()));
+    let admitted = Arc::new(Mutex::new(Vec::new()));
+    let guard = lock.write();
+
+    // enqueue waiters one at a time, so that arrival order is deterministic
+    let threads = [(1, "low"), (5, "mid_1"), (10, "high"), (5, "mid_2")]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (priority, name))| {
+            let thread = spawn_writer(&lock, &admitted, priority, name);
+            lock.wait_for_waiting(i + 1);
+            thread
+        })
+        .collect::<Vec<_>>();
+
+    drop(guard);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(vec!["high", "mid_1", "mid_2", "low"], *admitted.lock().remedy());
+}
+
+#[test]
+fn queued_writer_blocks_lower_priority_readers_only() {
+    let lock = Arc::new(ZLock::<_, PriorityOrdered>::new(()));
+    let admitted = Arc::new(Mutex::new(Vec::new()));
+    let guard = lock.read();
+    let writer = spawn_writer(&lock, &admitted, 5, "writer");
+    lock.wait_for_waiting(1);
+
+    // a reader of equal priority queues behind the writer...
+    thread::spawn({
+        let lock = lock.clone();
+        move || {
+            PriorityOrdered::set_thread_priority(5);
+            assert!(lock.try_read(Duration::ZERO).is_none());
+        }
+    })
+    .join()
+    .unwrap();
+
+    // ...whereas a reader of higher priority is admitted ahead of it
+    thread::spawn({
+        let lock = lock.clone();
+        move || {
+            PriorityOrdered::set_thread_priority(6);
+            assert!(lock.try_read(Duration::ZERO).is_some());
+        }
+    })
+    .join()
+    .unwrap();
+
+    drop(guard);
+    writer.join().unwrap();
+    assert_eq!(vec!["writer"], *admitted.lock().remedy());
+}
+
+fn spawn_writer(
+    lock: &Arc<ZLock<(), PriorityOrdered>>,
+    admitted: &Arc<Mutex<Vec<&'static str>>>,
+    priority: u32,
+    name: &'static str,
+) -> JoinHandle<()> {
+    let lock = lock.clone();
+    let admitted = admitted.clone();
+    thread::spawn(move || {
+        PriorityOrdered::set_thread_priority(priority);
+        let _guard = lock.write();
+        admitted.lock().remedy().push(name);
+    })
+}
+
+impl<T> ZLock<T, PriorityOrdered> {
+    fn waiting(&self) -> usize {
+        self.sync.monitor.compute(|state| state.waiters.len())
+    }
+
+    fn next_arrival(&self) -> u64 {
+        self.sync.monitor.compute(|state| state.next_arrival)
+    }
+
+    fn wait_for_waiting(&self, waiting: usize) {
+        wait::Spin::wait_for(|| self.waiting() == waiting, LONG_WAIT).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+use crate::deadlock;
+use crate::held;
+use crate::instrument::Mode;
+use crate::zlock::{acquire_tracked, short_type_name, ConfigurableModerator, Moderator};
+use core::fmt;
+use core::fmt::Debug;
+use core::time::Duration;
+use alloc::format;
+
+/// The synchronization state of a [`ZLock`](crate::zlock::ZLock), without the data. A raw
+/// lock applies the same moderator policies, for protecting data that is owned elsewhere
+/// (e.g., disjoint parts of a slice, or a structure shared with foreign code).
+///
+/// Having no data to guard, a raw lock issues no guards either: every acquisition must be
+/// matched by the corresponding (unsafe) release.
+///
+/// # Examples
+/// ```
+/// use std::cell::UnsafeCell;
+/// use anode::zlock::{RawZLock, ReadBiased};
+/// struct Shared {
+///     lock: RawZLock<ReadBiased>,
+///     data: UnsafeCell<[u64; 4]>,
+/// }
+/// let shared = Shared {
+///     lock: RawZLock::new(),
+///     data: UnsafeCell::new([0; 4]),
+/// };
+/// shared.lock.lock_write();
+/// unsafe {
+///     (*shared.data.get())[0] = 42;
+///     shared.lock.unlock_write();
+/// }
+/// shared.lock.lock_read();
+/// unsafe {
+///     assert_eq!(42, (*shared.data.get())[0]);
+///     shared.lock.unlock_read();
+/// }
+/// ```
+pub struct RawZLock<M: Moderator> {
+    sync: M::Sync,
+}
+
+impl<M: Moderator> RawZLock<M> {
+    #[inline]
+    pub fn new() -> Self {
+        Self { sync: M::new() }
+    }
+
+    /// Creates a raw lock using a preconfigured moderator state, for moderators that offer
+    /// configuration beyond their defaults. (E.g., [`ReadBiased::with_writer_grace`](crate::zlock::ReadBiased::with_writer_grace).)
+    #[inline]
+    pub fn with_sync(sync: M::Sync) -> Self {
+        Self { sync }
+    }
+
+    /// Creates a raw lock whose moderator state is derived from `config`. Available for
+    /// moderators implementing [`ConfigurableModerator`].
+    #[inline]
+    pub fn with_config(config: M::Config) -> Self
+    where
+        M: ConfigurableModerator,
+    {
+        Self::with_sync(M::new_with(config))
+    }
+
+    #[inline]
+    pub fn lock_read(&self) {
+        held::assert_not_held(deadlock::addr_of(self), Mode::Read);
+        assert!(self.try_lock_read(Duration::MAX));
+    }
+
+    /// Attempts to acquire a read lock within the given `duration`, returning `true` if the
+    /// lock was acquired.
+    #[inline]
+    pub fn try_lock_read(&self, duration: Duration) -> bool {
+        acquire_tracked(deadlock::addr_of(self), core::any::type_name::<M>(), Mode::Read, duration, || M::try_read(&self.sync, duration))
+    }
+
+    /// Releases a read lock.
+    ///
+    /// # Safety
+    /// The lock must be read-locked by a prior [`lock_read`](Self::lock_read) (or successful
+    /// [`try_lock_read`](Self::try_lock_read)) that has not been matched by a release.
+    #[inline]
+    pub unsafe fn unlock_read(&self) {
+        deadlock::released(deadlock::addr_of(self));
+        held::released(deadlock::addr_of(self));
+        M::read_unlock(&self.sync);
+    }
+
+    #[inline]
+    pub fn lock_write(&self) {
+        held::assert_not_held(deadlock::addr_of(self), Mode::Write);
+        assert!(self.try_lock_write(Duration::MAX));
+    }
+
+    /// Attempts to acquire a write lock within the given `duration`, returning `true` if the
+    /// lock was acquired.
+    #[inline]
+    pub fn try_lock_write(&self, duration: Duration) -> bool {
+        acquire_tracked(deadlock::addr_of(self), core::any::type_name::<M>(), Mode::Write, duration, || M::try_write(&self.sync, duration))
+    }
+
+    /// Releases a write lock.
+    ///
+    /// # Safety
+    /// The lock must be write-locked by a prior [`lock_write`](Self::lock_write) (or successful
+    /// [`try_lock_write`](Self::try_lock_write)) that has not been matched by a release.
+    #[inline]
+    pub unsafe fn unlock_write(&self) {
+        deadlock::released(deadlock::addr_of(self));
+        held::released(deadlock::addr_of(self));
+        M::write_unlock(&self.sync);
+    }
+
+    /// Atomically converts a write lock into a read lock, which must later be released with
+    /// [`unlock_read`](Self::unlock_read).
+    ///
+    /// # Safety
+    /// The lock must be write-locked, as for [`unlock_write`](Self::unlock_write).
+    #[inline]
+    pub unsafe fn downgrade(&self) {
+        M::downgrade(&self.sync);
+        held::converted(deadlock::addr_of(self), Mode::Read);
+    }
+
+    /// Converts a read lock into a write lock, waiting for any other readers to release
+    /// theirs.
+    ///
+    /// # Safety
+    /// The lock must be read-locked, as for [`unlock_read`](Self::unlock_read).
+    #[inline]
+    pub unsafe fn upgrade(&self) {
+        assert!(self.try_upgrade(Duration::MAX));
+    }
+
+    /// Attempts to convert a read lock into a write lock within the given `duration`,
+    /// returning `true` if the lock was upgraded. Otherwise, the read lock is retained.
+    ///
+    /// # Safety
+    /// The lock must be read-locked, as for [`unlock_read`](Self::unlock_read).
+    #[inline]
+    pub unsafe fn try_upgrade(&self, duration: Duration) -> bool {
+        // the upgrading thread already holds the lock, so no further hold is recorded
+        if !duration.is_zero() {
+            deadlock::waiting(deadlock::addr_of(self));
+        }
+        let upgraded = M::try_upgrade(&self.sync, duration);
+        if !duration.is_zero() {
+            deadlock::abandoned();
+        }
+        if upgraded {
+            held::converted(deadlock::addr_of(self), Mode::Write);
+        }
+        upgraded
+    }
+
+    /// Determines whether a writer is presently waiting to acquire this lock. See
+    /// [`Moderator::is_writer_waiting`].
+    #[inline]
+    pub fn is_writer_waiting(&self) -> bool {
+        M::is_writer_waiting(&self.sync)
+    }
+}
+
+impl<M: Moderator> Default for RawZLock<M> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats the raw lock as a single line, naming the moderator type and showing its state;
+/// e.g., `RawZLock<ReadBiased> { moderator: ReadBiased { readers: 2, writer: false, .. } }`.
+impl<M: Moderator> Debug for RawZLock<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct ModeratorState<'a, M: Moderator>(&'a M::Sync);
+        impl<M: Moderator> Debug for ModeratorState<'_, M> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                M::debug_state(self.0, f)
+            }
+        }
+
+        let name = format!("RawZLock<{}>", short_type_name(core::any::type_name::<M>()));
+        f.debug_struct(&name)
+            .field("moderator", &ModeratorState::<M>(&self.sync))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
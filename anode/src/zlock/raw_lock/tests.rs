@@ -0,0 +1,104 @@
+use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
+use crate::zlock::{ArrivalOrdered, LegacyReadBiased, Moderator, PriorityOrdered, RawZLock, ReadBiased, SpinModerator, Stochastic, UpgradeBiased, WriteBiased};
+use std::cell::UnsafeCell;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn lock_cycle() {
+    __lock_cycle::<ReadBiased>();
+    __lock_cycle::<WriteBiased>();
+    __lock_cycle::<ArrivalOrdered>();
+    __lock_cycle::<Stochastic>();
+    __lock_cycle::<SpinModerator>();
+    __lock_cycle::<UpgradeBiased>();
+    __lock_cycle::<PriorityOrdered>();
+    __lock_cycle::<LegacyReadBiased>();
+}
+
+fn __lock_cycle<M: Moderator>() {
+    let lock = RawZLock::<M>::new();
+
+    // readers exclude writers
+    lock.lock_read();
+    assert!(lock.try_lock_read(Duration::ZERO));
+    assert!(!lock.try_lock_write(SHORT_WAIT));
+    unsafe { lock.unlock_read() };
+
+    // the lone reader upgrades and downgrades
+    unsafe {
+        assert!(lock.try_upgrade(Duration::ZERO));
+        assert!(!lock.try_lock_read(Duration::ZERO));
+        lock.downgrade();
+        assert!(!lock.try_lock_write(Duration::ZERO));
+        lock.unlock_read();
+    }
+
+    // writers exclude everyone
+    lock.lock_write();
+    assert!(!lock.try_lock_read(SHORT_WAIT));
+    assert!(!lock.try_lock_write(Duration::ZERO));
+    unsafe { lock.unlock_write() };
+
+    assert!(lock.try_lock_write(Duration::ZERO));
+    unsafe { lock.unlock_write() };
+}
+
+#[test]
+fn configured() {
+    let lock = RawZLock::<ArrivalOrdered>::with_config(4);
+    lock.lock_write();
+    assert!(!lock.try_lock_read(Duration::ZERO));
+    unsafe { lock.unlock_write() };
+    lock.lock_read();
+    unsafe { lock.unlock_read() };
+}
+
+#[test]
+fn protect_disjoint_data() {
+    struct Halves {
+        locks: [RawZLock<ReadBiased>; 2],
+        data: UnsafeCell<[u64; 2]>,
+    }
+    unsafe impl Sync for Halves {}
+
+    const ITERATIONS: u64 = 1_000;
+    let halves = Arc::new(Halves {
+        locks: Default::default(),
+        data: UnsafeCell::new([0; 2]),
+    });
+    let threads = (0..4)
+        .map(|i| {
+            let halves = halves.clone();
+            thread::spawn(move || {
+                let half = i % 2;
+                for _ in 0..ITERATIONS {
+                    halves.locks[half].lock_write();
+                    unsafe {
+                        (*halves.data.get())[half] += 1;
+                        halves.locks[half].unlock_write();
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    for half in 0..2 {
+        assert!(halves.locks[half].try_lock_read(LONG_WAIT));
+        assert_eq!(2 * ITERATIONS, unsafe { (*halves.data.get())[half] });
+        unsafe { halves.locks[half].unlock_read() };
+    }
+}
+
+#[test]
+fn implements_debug() {
+    let lock = RawZLock::<ReadBiased>::new();
+    lock.lock_read();
+    let debug = format!("{:?}", lock);
+    assert!(debug.starts_with("RawZLock<ReadBiased> { moderator: ReadBiased { readers: 1, writer: false"), "{debug}");
+    unsafe { lock.unlock_read() };
+}
@@ -1,19 +1,124 @@
+use std::fmt;
+use std::task::Waker;
 use std::time::Duration;
 use crate::deadline::Deadline;
-use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
-use crate::zlock::Moderator;
+use crate::monitor::{ClassedDirective, ClassedMonitor, Monitor, Wake};
+use crate::zlock::{ConfigurableModerator, Moderator, Polled, Wakers};
+use crate::zlock::registration::Registration;
 
+/// A moderator that admits readers whenever there is no writer. A continuous stream of
+/// overlapping readers can thus starve a waiting writer indefinitely.
+///
+/// Writer starvation may be bounded by constructing the lock with
+/// [`with_writer_grace`](Self::with_writer_grace), such that once a writer has waited through
+/// a given number of reader arrivals, new readers are blocked until the writer is admitted.
+///
+/// Uncontended acquisitions and releases are served by the [`ClassedMonitor`]'s spin lock
+/// alone; the monitor's mutex and condvars are only involved once a thread has to wait.
+/// Readers, writers, upgraders and claimants of the upgradable lock wait on separate condvars,
+/// so that a release only wakes the threads it may admit.
 #[derive(Debug)]
 pub struct ReadBiased;
 
+impl ReadBiased {
+    /// Creates the moderator state for a lock that is read-biased until a writer has waited
+    /// through `grace` reader arrivals, at which point newly arriving readers block until the
+    /// writer acquires the lock (or gives up waiting). This caps writer latency under sustained
+    /// read load, while retaining read-biased behaviour otherwise.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, ZLock};
+    /// let lock = ZLock::<_, ReadBiased>::with_sync(ReadBiased::with_writer_grace(16), 0);
+    /// *lock.write() = 42;
+    /// assert_eq!(42, *lock.read());
+    /// ```
+    #[inline]
+    pub fn with_writer_grace(grace: u32) -> ReadBiasedSync {
+        ReadBiasedSync::new(Some(grace))
+    }
+}
+
+const READERS: usize = 0;
+const WRITERS: usize = 1;
+const UPGRADERS: usize = 2;
+const CLAIMANTS: usize = 3;
+
 pub struct ReadBiasedSync {
-    monitor: SpeculativeMonitor<ReadBiasedState>,
+    monitor: ClassedMonitor<ReadBiasedState, 4>,
 }
 
 #[derive(Debug)]
 struct ReadBiasedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
+    upgraders: u32,
+    wakers: Wakers,
+
+    /// The number of reader arrivals a waiting writer tolerates before readers are blocked,
+    /// or `None` if readers are never blocked on behalf of a waiting writer.
+    grace: Option<u32>,
+    waiting_writers: u32,
+
+    /// Reader arrivals since a writer began waiting.
+    arrivals: u32,
+
+    /// Counts the evaluations of the write-acquire condition that did not result in an
+    /// acquisition, allowing tests to detect needless wakeups of blocked writers.
+    #[cfg(test)]
+    failed_write_attempts: u64,
+}
+
+impl ReadBiasedSync {
+    #[inline]
+    fn new(grace: Option<u32>) -> Self {
+        Self {
+            monitor: ClassedMonitor::new(ReadBiasedState {
+                readers: 0,
+                writer: false,
+                upgradable: false,
+                upgraders: 0,
+                wakers: Wakers::default(),
+                grace,
+                waiting_writers: 0,
+                arrivals: 0,
+                #[cfg(test)]
+                failed_write_attempts: 0,
+            }),
+        }
+    }
+}
+
+impl ReadBiasedState {
+    /// Whether a waiting writer has exhausted its grace, such that new readers must block.
+    #[inline]
+    fn grace_exhausted(&self) -> bool {
+        self.waiting_writers > 0 && self.grace.is_some_and(|grace| self.arrivals >= grace)
+    }
+
+    #[inline]
+    fn admits_reader(&self) -> bool {
+        !self.writer && !self.grace_exhausted()
+    }
+
+    #[inline]
+    fn add_reader(&mut self) {
+        self.readers += 1;
+        if self.waiting_writers > 0 {
+            self.arrivals = self.arrivals.saturating_add(1);
+        }
+    }
+}
+
+impl ConfigurableModerator for ReadBiased {
+    /// The writer grace, if any. See [`ReadBiased::with_writer_grace`].
+    type Config = Option<u32>;
+
+    #[inline]
+    fn new_with(config: Self::Config) -> Self::Sync {
+        Self::Sync::new(config)
+    }
 }
 
 impl Moderator for ReadBiased {
@@ -21,25 +126,23 @@ impl Moderator for ReadBiased {
 
     #[inline]
     fn new() -> Self::Sync {
-        Self::Sync {
-            monitor: SpeculativeMonitor::new(ReadBiasedState { readers: 0, writer: false }),
-        }
+        Self::Sync::new(None)
     }
 
     #[inline]
     fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        sync.monitor.enter(|state| {
-            if !acquired && !state.writer {
+        sync.monitor.enter_classed(|state| {
+            if !acquired && state.admits_reader() {
                 acquired = true;
-                state.readers += 1;
+                state.add_reader();
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(READERS, deadline.remaining())
             }
         });
         acquired
@@ -48,61 +151,109 @@ impl Moderator for ReadBiased {
     #[inline]
     fn read_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
                 released = true;
                 state.readers -= 1;
+                if state.readers == 0 {
+                    wakers = state.wakers.take();
+                }
             }
 
+            // a lone remaining reader is only of interest if it is waiting to upgrade
             match state.readers {
-                1 => Directive::NotifyAll,
-                0 => Directive::NotifyOne,
-                _ => Directive::Return
+                1 if state.upgraders > 0 => ClassedDirective::Notify(Wake::NONE.all(UPGRADERS)),
+                0 => ClassedDirective::Notify(Wake::NONE.one(WRITERS)),
+                _ => ClassedDirective::Return
             }
         });
+        wakers.wake_all();
     }
 
     #[inline]
     fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        sync.monitor.enter(|state| {
+        let mut waiting = Registration::new(|_| {
+            // the writer gave up; readers blocked on its behalf must be released
+            let mut deregistered = false;
+            sync.monitor.enter_classed(|state| {
+                if !deregistered {
+                    deregistered = true;
+                    state.waiting_writers -= 1;
+                    if state.waiting_writers == 0 {
+                        state.arrivals = 0;
+                    }
+                }
+
+                if state.grace.is_some() {
+                    ClassedDirective::Notify(Wake::NONE.all(READERS))
+                } else {
+                    ClassedDirective::Return
+                }
+            });
+        });
+        sync.monitor.enter_classed(|state| {
             if !acquired && state.readers == 0 && !state.writer {
                 acquired = true;
                 state.writer = true;
+                if waiting.is_raised() {
+                    waiting.lower();
+                    state.waiting_writers -= 1;
+                }
+                // the next waiting writer (if any) is afforded a fresh grace
+                state.arrivals = 0;
+            }
+
+            #[cfg(test)]
+            if !acquired {
+                state.failed_write_attempts += 1;
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                let remaining = deadline.remaining();
+                if !waiting.is_raised() && !remaining.is_zero() {
+                    waiting.raise();
+                    state.waiting_writers += 1;
+                }
+                ClassedDirective::Wait(WRITERS, remaining)
             }
         });
-        acquired
+
+        waiting.settle(acquired)
     }
 
     #[inline]
     fn write_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
 
                 released = true;
                 state.writer = false;
+                wakers = state.wakers.take();
             }
 
-            Directive::NotifyOne
+            // all blocked readers may proceed at once, alongside one writer, which either takes
+            // the lock or waits for the readers to drain; upgraders and claimants are unaffected
+            ClassedDirective::Notify(Wake::NONE.all(READERS).one(WRITERS))
         });
+        wakers.wake_all();
     }
 
     fn downgrade(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
@@ -110,30 +261,125 @@ impl Moderator for ReadBiased {
                 released = true;
                 state.writer = false;
                 state.readers = 1;
+                wakers = state.wakers.take();
             }
 
-            Directive::NotifyAll
+            ClassedDirective::Notify(Wake::NONE.all(READERS))
         });
+        wakers.wake_all();
     }
 
     fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        sync.monitor.enter(|state| {
-            if !acquired && state.readers == 1 {
+        let mut upgrading = Registration::new(|_| {
+            sync.monitor.alter(|state| {
+                state.upgraders -= 1;
+            });
+        });
+        sync.monitor.enter_classed(|state| {
+            if !acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
-                acquired = true;
-                state.readers = 0;
-                state.writer = true;
+                if state.readers == 1 {
+                    acquired = true;
+                    state.readers = 0;
+                    state.writer = true;
+                    if upgrading.is_raised() {
+                        upgrading.lower();
+                        state.upgraders -= 1;
+                    }
+                } else if !upgrading.is_raised() {
+                    upgrading.raise();
+                    state.upgraders += 1;
+                }
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(UPGRADERS, deadline.remaining())
             }
         });
-        acquired
+
+        upgrading.settle(acquired)
+    }
+
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        sync.monitor.compute(|state| state.waiting_writers > 0)
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter_classed(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                ClassedDirective::Return
+            } else {
+                ClassedDirective::Wait(CLAIMANTS, deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter_classed(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            ClassedDirective::Notify(Wake::NONE.one(CLAIMANTS))
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("ReadBiased").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("ReadBiased")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("upgraders", &state.upgraders)
+                .field("waiting_writers", &state.waiting_writers)
+                .finish(),
+        }
+    }
+
+    #[inline]
+    fn poll_read(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let mut state = sync.monitor.lock();
+        if state.admits_reader() {
+            state.add_reader();
+            Polled::Acquired(())
+        } else {
+            state.wakers.register(waker);
+            Polled::Pending
+        }
+    }
+
+    #[inline]
+    fn poll_write(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let mut state = sync.monitor.lock();
+        if state.readers == 0 && !state.writer {
+            state.writer = true;
+            Polled::Acquired(())
+        } else {
+            state.wakers.register(waker);
+            Polled::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
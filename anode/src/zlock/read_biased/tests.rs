@@ -0,0 +1,217 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::monitor::Monitor;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::{test_utils, wait};
+use crate::wait::{Wait, WaitResult};
+use crate::zlock::{ReadBiased, ZLock};
+
+#[test]
+fn read_release_to_one_does_not_wake_writer() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    let guard_3 = lock.read();
+
+    let t_4 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_4 blocks because main holds the read lock (thrice)
+            *lock.write() = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    let failed_write_attempts = lock.failed_write_attempts();
+
+    // releasing readers down to one, with no upgrader, should not wake the writer
+    drop(guard_1);
+    drop(guard_2);
+    thread::sleep(CHECK_WAIT);
+    assert_eq!(failed_write_attempts, lock.failed_write_attempts());
+    assert!(!t_4.is_finished());
+
+    // releasing the last reader wakes the writer, which acquires the lock
+    drop(guard_3);
+    t_4.join().unwrap();
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn downgrade_does_not_wake_writer() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let guard_1 = lock.write();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds the write lock
+            *lock.write() = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    let failed_write_attempts = lock.failed_write_attempts();
+
+    // downgrading only admits readers, so the writer is left undisturbed
+    let guard_1 = guard_1.downgrade();
+    thread::sleep(CHECK_WAIT);
+    assert_eq!(failed_write_attempts, lock.failed_write_attempts());
+    assert!(!t_2.is_finished());
+
+    drop(guard_1);
+    t_2.join().unwrap();
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn release_upgradable_does_not_wake_writer() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let guard_1 = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds the read lock
+            *lock.write() = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    let failed_write_attempts = lock.failed_write_attempts();
+
+    // releasing the upgradable lock is only of interest to other claimants
+    drop(lock.read_upgradable());
+    thread::sleep(CHECK_WAIT);
+    assert_eq!(failed_write_attempts, lock.failed_write_attempts());
+    assert!(!t_2.is_finished());
+
+    drop(guard_1);
+    t_2.join().unwrap();
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn read_release_to_one_wakes_upgrader() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let guard_1 = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds the read lock
+            let guard = lock.read();
+            let mut guard = guard.try_upgrade(LONG_WAIT).upgraded().unwrap();
+            *guard = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    assert_eq!(1, lock.upgraders());
+
+    // releasing main's read lock leaves t_2 as the lone reader, which is woken to upgrade
+    drop(guard_1);
+    t_2.join().unwrap();
+    assert_eq!(0, lock.upgraders());
+    assert_eq!(42, *lock.read());
+
+    // an upgrader that times out is deregistered
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    assert!(guard_2.try_upgrade(Duration::ZERO).is_unchanged());
+    assert_eq!(0, lock.upgraders());
+    drop(guard_1);
+}
+
+#[test]
+fn writer_grace_bounds_starvation() {
+    const GRACE: u32 = 4;
+    let lock = Arc::new(ZLock::<_, ReadBiased>::with_sync(ReadBiased::with_writer_grace(GRACE), 0));
+    let mut guard = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds the read lock
+            *lock.write() = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+    assert_eq!(1, lock.waiting_writers());
+
+    // under constant read pressure (overlapping readers), exactly GRACE readers are admitted
+    for _ in 0..GRACE {
+        let next = lock.try_read(Duration::ZERO).unwrap();
+        drop(guard);
+        guard = next;
+    }
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    assert!(!t_2.is_finished());
+
+    // once the last reader departs, the writer is admitted
+    drop(guard);
+    t_2.join().unwrap();
+    assert_eq!(0, lock.waiting_writers());
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn writer_grace_lifted_on_writer_timeout() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::with_sync(ReadBiased::with_writer_grace(0), 0));
+    let guard = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            lock.try_read(LONG_WAIT).is_some()
+        })
+    };
+
+    // a writer waiting with no grace blocks new readers, until it times out
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert_eq!(0, lock.waiting_writers());
+    assert!(t_2.join().unwrap());
+    assert!(lock.try_read(Duration::ZERO).is_some());
+    drop(guard);
+}
+
+#[test]
+fn no_writer_grace_by_default() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let mut guard = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            *lock.write() = 42;
+        })
+    };
+    lock.wait_for_num_waiting(Ordering::is_eq, 1, LONG_WAIT).unwrap();
+
+    // readers are admitted irrespective of the waiting writer
+    for _ in 0..100 {
+        let next = lock.try_read(Duration::ZERO).unwrap();
+        drop(guard);
+        guard = next;
+    }
+    assert!(!t_2.is_finished());
+
+    drop(guard);
+    t_2.join().unwrap();
+}
+
+impl<T> ZLock<T, ReadBiased> {
+    fn waiting_writers(&self) -> u32 {
+        self.sync.monitor.compute(|state| state.waiting_writers)
+    }
+
+    fn failed_write_attempts(&self) -> u64 {
+        self.sync.monitor.compute(|state| state.failed_write_attempts)
+    }
+
+    fn upgraders(&self) -> u32 {
+        self.sync.monitor.compute(|state| state.upgraders)
+    }
+
+    fn wait_for_num_waiting(&self, cmp: impl FnMut(Ordering) -> bool, target: u32, duration: Duration) -> WaitResult {
+        wait::Spin::wait_for_inequality(|| self.sync.monitor.num_waiting(), cmp, &target, duration)
+    }
+}
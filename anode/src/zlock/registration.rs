@@ -0,0 +1,52 @@
+/// Tracks the registration of the current thread as a waiter — e.g., a raised `writer_pending`
+/// flag, a count of waiting writers, or a ticket in a queue. A registration that is still
+/// raised when the tracker is dropped, including during unwinding, is handed back to `clear`, so
+/// that other threads are never left blocked behind a waiter that is no longer waiting. `clear`
+/// is told whether the lock was acquired, in which case there is nobody to wake.
+pub(crate) struct Registration<F: FnMut(bool)> {
+    clear: F,
+    raised: bool,
+    acquired: bool,
+}
+
+impl<F: FnMut(bool)> Registration<F> {
+    #[inline]
+    pub(crate) fn new(clear: F) -> Self {
+        Self { clear, raised: false, acquired: false }
+    }
+
+    /// Records that the current thread has registered as a waiter.
+    #[inline]
+    pub(crate) fn raise(&mut self) {
+        self.raised = true;
+    }
+
+    /// Records that the registration has been withdrawn in the same critical section that
+    /// acquired the lock, leaving nothing for `clear`.
+    #[inline]
+    pub(crate) fn lower(&mut self) {
+        self.raised = false;
+    }
+
+    #[inline]
+    pub(crate) fn is_raised(&self) -> bool {
+        self.raised
+    }
+
+    /// Clears the registration, if raised, once the wait has ended in the given outcome, which
+    /// is passed through.
+    #[inline]
+    pub(crate) fn settle(mut self, acquired: bool) -> bool {
+        self.acquired = acquired;
+        acquired
+    }
+}
+
+impl<F: FnMut(bool)> Drop for Registration<F> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.raised {
+            (self.clear)(self.acquired);
+        }
+    }
+}
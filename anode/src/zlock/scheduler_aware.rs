@@ -0,0 +1,197 @@
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use crate::backoff::{Backoff, ExpBackoff};
+use crate::deadline::Deadline;
+use crate::zlock::{ConfigurableModerator, Moderator};
+
+/// A moderator that waits in cooperation with the OS scheduler: a waiting thread spins
+/// briefly, then yields its time slice, then sleeps for increasing periods, as prescribed by
+/// an [`ExpBackoff`] configuration. No thread is ever parked, so a release wakes nobody;
+/// waiters notice it on their next attempt.
+///
+/// Arriving readers defer to waiting writers (including upgrading readers), such that a
+/// continuous stream of overlapping readers cannot starve a writer.
+///
+/// The moderator is built solely on the crate's public API and serves as an example of
+/// implementing [`Moderator`] outside the crate. Its state is configured through
+/// [`ConfigurableModerator`], and it is checked by the `moderator_conformance!` suite
+/// (available with the `test_utils` feature).
+///
+/// # Examples
+/// ```
+/// use anode::backoff::ExpBackoff;
+/// use anode::zlock::{SchedulerAware, ZLock};
+/// // yield straight away, never spinning or sleeping
+/// let lock = ZLock::<_, SchedulerAware>::with_config(ExpBackoff::yieldy(), 0);
+/// *lock.write() += 1;
+/// assert_eq!(1, *lock.read());
+/// ```
+#[derive(Debug)]
+pub struct SchedulerAware;
+
+const WRITER: usize = 1 << (usize::BITS - 1);
+
+const UPGRADABLE: usize = 1 << (usize::BITS - 2);
+
+const READERS: usize = !(WRITER | UPGRADABLE);
+
+pub struct SchedulerAwareSync {
+    /// The writer, the upgradable slot and the readers, laid out as per [`SpinModerator`](crate::zlock::SpinModerator).
+    state: AtomicUsize,
+
+    /// The number of writers (and upgrading readers) waiting to be admitted.
+    waiting_writers: AtomicUsize,
+
+    backoff: ExpBackoff,
+}
+
+impl SchedulerAwareSync {
+    /// Repeatedly invokes `attempt`, backing off as per the configuration, until it succeeds
+    /// or `duration` elapses.
+    #[inline]
+    fn wait(&self, duration: Duration, mut attempt: impl FnMut() -> bool) -> bool {
+        if attempt() {
+            return true;
+        }
+
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut backoff = Backoff::with_config(self.backoff.clone());
+        while !deadline.remaining().is_zero() {
+            backoff.snooze();
+            if attempt() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// As per [`wait`](Self::wait), registering a waiting writer for the duration, so that
+    /// arriving readers hold back.
+    #[inline]
+    fn wait_as_writer(&self, duration: Duration, mut attempt: impl FnMut() -> bool) -> bool {
+        if attempt() {
+            return true;
+        }
+        if duration.is_zero() {
+            return false;
+        }
+
+        self.waiting_writers.fetch_add(1, Ordering::Relaxed);
+        let acquired = self.wait(duration, attempt);
+        self.waiting_writers.fetch_sub(1, Ordering::Relaxed);
+        acquired
+    }
+}
+
+impl ConfigurableModerator for SchedulerAware {
+    type Config = ExpBackoff;
+
+    #[inline]
+    fn new_with(config: Self::Config) -> Self::Sync {
+        SchedulerAwareSync {
+            state: AtomicUsize::new(0),
+            waiting_writers: AtomicUsize::new(0),
+            backoff: config,
+        }
+    }
+}
+
+impl Moderator for SchedulerAware {
+    type Sync = SchedulerAwareSync;
+
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::new_with(ExpBackoff::balanced())
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        sync.wait(duration, || {
+            sync.state.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & WRITER == 0 && sync.waiting_writers.load(Ordering::Relaxed) == 0 {
+                    Some(state + 1)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        let _prev = sync.state.fetch_sub(1, Ordering::Release);
+        debug_assert!(_prev & READERS > 0, "readers: {}", _prev & READERS);
+        debug_assert_eq!(0, _prev & WRITER);
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        sync.wait_as_writer(duration, || {
+            // the upgradable slot does not exclude writers
+            sync.state.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & !UPGRADABLE == 0 {
+                    Some(state | WRITER)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        let _prev = sync.state.fetch_and(UPGRADABLE, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev & !UPGRADABLE);
+    }
+
+    #[inline]
+    fn downgrade(sync: &Self::Sync) {
+        let _prev = sync.state.fetch_sub(WRITER - 1, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev & !UPGRADABLE);
+    }
+
+    #[inline]
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        sync.wait_as_writer(duration, || {
+            sync.state.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & !UPGRADABLE == 1 {
+                    Some(state - 1 + WRITER)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        sync.wait(duration, || {
+            sync.state.fetch_or(UPGRADABLE, Ordering::Acquire) & UPGRADABLE == 0
+        })
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let _prev = sync.state.fetch_and(!UPGRADABLE, Ordering::Release);
+        debug_assert_ne!(0, _prev & UPGRADABLE);
+    }
+
+    #[inline]
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = sync.state.load(Ordering::Relaxed);
+        f.debug_struct("SchedulerAware")
+            .field("readers", &(state & READERS))
+            .field("writer", &(state & WRITER != 0))
+            .field("waiting_writers", &sync.waiting_writers.load(Ordering::Relaxed))
+            .finish()
+    }
+
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        sync.waiting_writers.load(Ordering::Relaxed) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::backoff::ExpBackoff;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::test_utils;
+use crate::zlock::{SchedulerAware, ZLock};
+
+crate::moderator_conformance!(conformance, SchedulerAware);
+
+#[test]
+fn readers_defer_to_waiting_writer() {
+    let lock = Arc::new(ZLock::<_, SchedulerAware>::with_config(ExpBackoff::yieldy(), 0));
+    let guard = lock.read();
+    assert!(!lock.is_writer_waiting());
+    let writer = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            *lock.try_write(LONG_WAIT).unwrap() = 42;
+        })
+    };
+    thread::sleep(CHECK_WAIT);
+    assert!(lock.is_writer_waiting());
+
+    // an arriving reader holds back while the writer waits, though the lock is read-locked
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    drop(guard);
+    writer.join().unwrap();
+    assert!(!lock.is_writer_waiting());
+    assert_eq!(42, *lock.try_read(Duration::ZERO).unwrap());
+}
+
+#[test]
+fn abandoned_wait_deregisters_writer() {
+    let lock = ZLock::<_, SchedulerAware>::with_config(ExpBackoff::sleepy(), 0);
+    let guard = lock.read();
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert!(!lock.is_writer_waiting());
+    assert!(lock.try_read(Duration::ZERO).is_some());
+    drop(guard);
+}
@@ -0,0 +1,94 @@
+use crate::zlock::locklike::{LockBoxSized, LocklikeExt, ModeratorKind};
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+/// A reader-writer cell that hides its lock entirely, exposing the guarded value only to
+/// closures. The lock is a [`LockBox`](crate::zlock::locklike::LockBox), moderated as per the
+/// [`ModeratorKind`] given at construction.
+///
+/// As no guard outlives the closure it is passed to, a lock can never be held inadvertently
+/// (for instance, across an `await` or in a forgotten local). Applications needing guards,
+/// upgrades or a statically chosen moderator should use [`ZLock`](crate::zlock::ZLock) directly.
+///
+/// # Examples
+/// ```
+/// use anode::zlock::locklike::ModeratorKind;
+/// use anode::zlock::shared_cell::SharedCell;
+/// let cell = SharedCell::new(ModeratorKind::ReadBiased, vec![1, 2]);
+/// cell.write_with(|vec| vec.push(3));
+/// assert_eq!(3, cell.read_with(|vec| vec.len()));
+/// ```
+pub struct SharedCell<T> {
+    kind: ModeratorKind,
+    lock: LockBoxSized<T>,
+}
+
+impl<T: Sync + Send + 'static> SharedCell<T> {
+    #[inline]
+    pub fn new(kind: ModeratorKind, t: T) -> Self {
+        Self {
+            kind,
+            lock: kind.make_lock(t),
+        }
+    }
+}
+
+impl<T> SharedCell<T> {
+    #[inline]
+    pub fn kind(&self) -> ModeratorKind {
+        self.kind
+    }
+
+    /// Runs `f` under a read lock, returning its result.
+    #[inline]
+    pub fn read_with<U>(&self, f: impl FnOnce(&T) -> U) -> U {
+        self.lock.with_read(f)
+    }
+
+    /// Runs `f` under a read lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    pub fn try_read_with<U>(&self, duration: Duration, f: impl FnOnce(&T) -> U) -> Option<U> {
+        self.lock.try_with_read(duration, f)
+    }
+
+    /// Runs `f` under a write lock, returning its result.
+    #[inline]
+    pub fn write_with<U>(&self, f: impl FnOnce(&mut T) -> U) -> U {
+        self.lock.with_write(f)
+    }
+
+    /// Runs `f` under a write lock acquired within the given `duration`, returning `None` if
+    /// the lock could not be acquired in time.
+    #[inline]
+    pub fn try_write_with<U>(&self, duration: Duration, f: impl FnOnce(&mut T) -> U) -> Option<U> {
+        self.lock.try_with_write(duration, f)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.lock.get_mut()
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.lock.into_inner()
+    }
+}
+
+impl<T: Debug> Debug for SharedCell<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("SharedCell");
+        d.field("kind", &self.kind);
+        let read = self.try_read_with(Duration::ZERO, |data| {
+            d.field("data", data);
+        });
+        if read.is_none() {
+            d.field("data", &format_args!("<locked>"));
+        }
+        d.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+use crate::zlock::locklike::MODERATOR_KINDS;
+use crate::zlock::shared_cell::SharedCell;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn kind() {
+    for kind in MODERATOR_KINDS {
+        assert_eq!(kind, SharedCell::new(kind, ()).kind());
+    }
+}
+
+#[test]
+fn read_write_with() {
+    for kind in MODERATOR_KINDS {
+        let mut cell = SharedCell::new(kind, vec![1, 2]);
+        assert_eq!(3, cell.write_with(|vec| {
+            vec.push(3);
+            vec.len()
+        }));
+        assert_eq!(Some(6), cell.try_read_with(Duration::ZERO, |vec| vec.iter().sum::<i32>()));
+
+        // nested acquisitions from within a closure observe the held lock
+        cell.read_with(|_| {
+            assert_eq!(Some(()), cell.try_read_with(Duration::ZERO, |_| ()));
+            assert_eq!(None, cell.try_write_with(Duration::ZERO, |_| unreachable!()));
+        });
+        cell.write_with(|_| {
+            assert_eq!(None, cell.try_read_with(Duration::ZERO, |_| unreachable!()));
+        });
+
+        cell.get_mut().push(4);
+        assert_eq!(vec![1, 2, 3, 4], cell.into_inner());
+    }
+}
+
+#[test]
+fn concurrent_writes() {
+    const ITERATIONS: usize = 1_000;
+    for kind in MODERATOR_KINDS {
+        let cell = Arc::new(SharedCell::new(kind, 0));
+        let threads = (0..4)
+            .map(|_| {
+                let cell = cell.clone();
+                thread::spawn(move || {
+                    for _ in 0..ITERATIONS {
+                        cell.write_with(|count| *count += 1);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+        assert_eq!(4 * ITERATIONS, cell.read_with(|count| *count));
+    }
+}
+
+#[test]
+fn debug() {
+    let cell = SharedCell::new(MODERATOR_KINDS[0], 42);
+    assert_eq!(format!("SharedCell {{ kind: {:?}, data: 42 }}", MODERATOR_KINDS[0]), format!("{:?}", cell));
+    cell.write_with(|_| {
+        assert!(format!("{:?}", cell).contains("<locked>"));
+    });
+}
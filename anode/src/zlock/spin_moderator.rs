@@ -0,0 +1,143 @@
+use core::fmt;
+use core::sync::atomic::Ordering;
+#[cfg(not(loom))]
+use core::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+use loom::sync::atomic::AtomicUsize;
+use core::time::Duration;
+use crate::backoff::spin_until;
+#[cfg(not(loom))]
+use crate::zlock::ConstModerator;
+use crate::zlock::Moderator;
+
+/// A moderator that spins (with backoff) instead of blocking, for locks guarding very short
+/// critical sections, where the cost of parking and waking a thread would dominate.
+///
+/// The entire lock state is a single [`AtomicUsize`], wherein the most significant bit denotes
+/// the writer, the next bit denotes the upgradable slot and the remaining bits count the readers. Readers are admitted whenever there is
+/// no writer; i.e., the moderator is read-biased and a writer may be starved by a continuous
+/// stream of overlapping readers.
+///
+/// Being free of a [`Mutex`](std::sync::Mutex), it is a [`ConstModerator`]: the lock may be
+/// constructed in a `const` context using [`ZLock::const_new`].
+///
+/// It is also the one moderator available without the (default) `std` feature, for `no_std`
+/// targets. There being no clock in that configuration, a finite duration is converted into a
+/// budget of attempts, each nominally taking 100 ns: a zero duration makes a single attempt,
+/// while [`Duration::MAX`] (as used by [`ZLock::read`] and [`ZLock::write`]) waits
+/// indefinitely.
+#[derive(Debug)]
+pub struct SpinModerator;
+
+const WRITER: usize = 1 << (usize::BITS - 1);
+
+const UPGRADABLE: usize = 1 << (usize::BITS - 2);
+
+const READERS: usize = !(WRITER | UPGRADABLE);
+
+#[cfg(not(loom))]
+impl ConstModerator for SpinModerator {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: AtomicUsize = AtomicUsize::new(0);
+}
+
+impl Moderator for SpinModerator {
+    type Sync = AtomicUsize;
+
+    #[cfg(not(loom))]
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::INIT
+    }
+
+    #[cfg(loom)]
+    #[inline]
+    fn new() -> Self::Sync {
+        AtomicUsize::new(0)
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        spin_until(duration, || {
+            sync.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & WRITER == 0 {
+                    Some(state + 1)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        let _prev = sync.fetch_sub(1, Ordering::Release);
+        debug_assert!(_prev & READERS > 0, "readers: {}", _prev & READERS);
+        debug_assert_eq!(0, _prev & WRITER);
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        spin_until(duration, || {
+            // the upgradable slot does not exclude writers
+            sync.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & !UPGRADABLE == 0 {
+                    Some(state | WRITER)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        let _prev = sync.fetch_and(UPGRADABLE, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev & !UPGRADABLE);
+    }
+
+    #[inline]
+    fn downgrade(sync: &Self::Sync) {
+        let _prev = sync.fetch_sub(WRITER - 1, Ordering::Release);
+        debug_assert_eq!(WRITER, _prev & !UPGRADABLE);
+    }
+
+    #[inline]
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        debug_assert!(sync.load(Ordering::Relaxed) & READERS > 0, "readers: {}", sync.load(Ordering::Relaxed) & READERS);
+        spin_until(duration, || {
+            sync.fetch_update(Ordering::Acquire, Ordering::Relaxed, |state| {
+                if state & !UPGRADABLE == 1 {
+                    Some(state - 1 + WRITER)
+                } else {
+                    None
+                }
+            }).is_ok()
+        })
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        spin_until(duration, || {
+            sync.fetch_or(UPGRADABLE, Ordering::Acquire) & UPGRADABLE == 0
+        })
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let _prev = sync.fetch_and(!UPGRADABLE, Ordering::Release);
+        debug_assert_ne!(0, _prev & UPGRADABLE);
+    }
+
+    #[inline]
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = sync.load(Ordering::Relaxed);
+        f.debug_struct("SpinModerator")
+            .field("readers", &(state & READERS))
+            .field("writer", &(state & WRITER != 0))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
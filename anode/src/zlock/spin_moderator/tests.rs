@@ -0,0 +1,98 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::test_utils::SHORT_WAIT;
+use crate::zlock::spin_moderator::{READERS, WRITER};
+use crate::zlock::{SpinModerator, ZLock};
+
+impl<T> ZLock<T, SpinModerator> {
+    fn readers(&self) -> usize {
+        self.sync.load(Ordering::Relaxed) & READERS
+    }
+
+    fn writer(&self) -> bool {
+        self.sync.load(Ordering::Relaxed) & WRITER != 0
+    }
+}
+
+#[cfg(not(loom))]
+static STATIC_LOCK: ZLock<u64, SpinModerator> = ZLock::const_new(0);
+
+#[cfg(not(loom))]
+#[test]
+fn static_lock() {
+    *STATIC_LOCK.write() = 42;
+    assert_eq!(42, *STATIC_LOCK.read());
+    assert_eq!(0, STATIC_LOCK.readers());
+    assert!(!STATIC_LOCK.writer());
+}
+
+#[test]
+fn state_transitions() {
+    let lock = ZLock::<_, SpinModerator>::new(0);
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    assert_eq!(2, lock.readers());
+    assert!(!lock.writer());
+
+    // cannot upgrade while another reader is present
+    let guard_1 = guard_1.try_upgrade(Duration::ZERO).unchanged().unwrap();
+    drop(guard_2);
+    assert_eq!(1, lock.readers());
+
+    let mut guard = guard_1.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+    assert_eq!(0, lock.readers());
+    assert!(lock.writer());
+
+    let guard = guard.downgrade();
+    assert_eq!(42, *guard);
+    assert_eq!(1, lock.readers());
+    assert!(!lock.writer());
+    drop(guard);
+    assert_eq!(0, lock.readers());
+}
+
+#[test]
+fn timeout_is_honoured() {
+    let lock = ZLock::<_, SpinModerator>::new(());
+    let guard = lock.write();
+    let start = Instant::now();
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    assert!(start.elapsed() >= SHORT_WAIT * 2);
+    drop(guard);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn contended_increments() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 1_000;
+    let lock = Arc::new(ZLock::<_, SpinModerator>::new(0));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                for _ in 0..ITERATIONS {
+                    *lock.write() += 1;
+                    let guard = lock.read();
+                    assert!(*guard > 0);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS, *lock.read());
+}
+
+#[test]
+fn debug_state() {
+    let lock = ZLock::<_, SpinModerator>::new(42);
+    let _guard = lock.read();
+    let formatted = format!("{lock:?}");
+    assert!(formatted.contains("SpinModerator { readers: 1, writer: false }"), "{formatted}");
+}
@@ -0,0 +1,285 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+use crate::zlock::{ConfigurableModerator, Moderator, Polled, ZLock};
+
+/// Wraps another moderator `M`, counting the acquisitions, timeouts and waits of the lock, as
+/// well as the longest time for which it was write-locked. The counters are read with
+/// [`ZLock::stats`] and cleared with [`ZLock::reset_stats`].
+///
+/// Locking behaviour is entirely that of `M`; the counters add a handful of relaxed atomic
+/// operations to every acquisition and release. Locks that are not wrapped bear no such cost.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use anode::zlock::{ReadBiased, Stats, ZLock};
+/// let lock = ZLock::<_, Stats<ReadBiased>>::new(0);
+/// *lock.write() += 1;
+/// assert_eq!(1, *lock.read());
+/// let guard = lock.read();
+/// assert!(lock.try_write(Duration::ZERO).is_none());
+/// drop(guard);
+///
+/// let stats = lock.stats();
+/// assert_eq!(2, stats.reads);
+/// assert_eq!(1, stats.writes);
+/// assert_eq!(1, stats.timeouts);
+///
+/// lock.reset_stats();
+/// assert_eq!(0, lock.stats().reads);
+/// ```
+pub struct Stats<M: Moderator>(PhantomData<M>);
+
+impl<M: Moderator> Stats<M> {
+    /// Creates the moderator state for a lock whose inner moderator state is preconfigured,
+    /// such that a configured lock may also be counted.
+    ///
+    /// # Examples
+    /// ```
+    /// use anode::zlock::{ReadBiased, Stats, ZLock};
+    /// let sync = Stats::<ReadBiased>::wrap(ReadBiased::with_writer_grace(16));
+    /// let lock = ZLock::<_, Stats<ReadBiased>>::with_sync(sync, 0);
+    /// *lock.write() += 1;
+    /// assert_eq!(1, lock.stats().writes);
+    /// ```
+    #[inline]
+    pub fn wrap(inner: M::Sync) -> StatsSync<M::Sync> {
+        StatsSync {
+            inner,
+            reads: AtomicU64::default(),
+            writes: AtomicU64::default(),
+            timeouts: AtomicU64::default(),
+            wait_nanos: AtomicU64::default(),
+            max_hold_nanos: AtomicU64::default(),
+            epoch: Instant::now(),
+            write_acquired: AtomicU64::default(),
+        }
+    }
+}
+
+impl<M: Moderator> fmt::Debug for Stats<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Stats")
+    }
+}
+
+pub struct StatsSync<S> {
+    inner: S,
+    reads: AtomicU64,
+    writes: AtomicU64,
+    timeouts: AtomicU64,
+    wait_nanos: AtomicU64,
+    max_hold_nanos: AtomicU64,
+
+    /// The reference point for `write_acquired`.
+    epoch: Instant,
+
+    /// The time of the last write acquisition, in nanoseconds since `epoch`.
+    write_acquired: AtomicU64,
+}
+
+/// A snapshot of the counters of a lock moderated by [`Stats`].
+///
+/// The counters are updated independently of one another, so a snapshot taken while the lock
+/// is in use may be momentarily inconsistent (e.g., a wait may be reflected in `wait` before the
+/// corresponding acquisition is counted).
+///
+/// Attempts are counted as they reach the moderator. A wait that is carried out in several
+/// attempts (such as a cancellable wait under a moderator that does not support polling)
+/// counts a timeout for each attempt that fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LockStats {
+    /// The number of read acquisitions.
+    pub reads: u64,
+
+    /// The number of write acquisitions, including upgrades.
+    pub writes: u64,
+
+    /// The number of acquisition attempts (read, write or upgrade) that timed out.
+    pub timeouts: u64,
+
+    /// The cumulative time spent in acquisition attempts, whether successful or not.
+    pub wait: Duration,
+
+    /// The longest time for which the lock was write-locked. Read locks are not timed, as
+    /// they may be held by several threads at once.
+    pub max_hold: Duration,
+}
+
+impl<S> StatsSync<S> {
+    #[inline]
+    fn nanos_since_epoch(&self, instant: Instant) -> u64 {
+        saturating_nanos(instant - self.epoch)
+    }
+
+    /// Records the wait of an acquisition attempt that began at `start`, counting a timeout
+    /// if the lock was not `acquired`. Returns `acquired`.
+    #[inline]
+    fn attempted(&self, start: Instant, acquired: bool) -> bool {
+        self.wait_nanos.fetch_add(saturating_nanos(start.elapsed()), Ordering::Relaxed);
+        if !acquired {
+            self.timeouts.fetch_add(1, Ordering::Relaxed);
+        }
+        acquired
+    }
+
+    #[inline]
+    fn write_acquired(&self) {
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        self.write_acquired.store(self.nanos_since_epoch(Instant::now()), Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn write_released(&self) {
+        let released = self.nanos_since_epoch(Instant::now());
+        let held = released.saturating_sub(self.write_acquired.load(Ordering::Relaxed));
+        self.max_hold_nanos.fetch_max(held, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn snapshot(&self) -> LockStats {
+        LockStats {
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            wait: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+            max_hold: Duration::from_nanos(self.max_hold_nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    #[inline]
+    fn reset(&self) {
+        for counter in [&self.reads, &self.writes, &self.timeouts, &self.wait_nanos, &self.max_hold_nanos] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[inline]
+fn saturating_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl<M: Moderator> Moderator for Stats<M> {
+    type Sync = StatsSync<M::Sync>;
+
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::wrap(M::new())
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        let start = Instant::now();
+        let acquired = M::try_read(&sync.inner, duration);
+        if sync.attempted(start, acquired) {
+            sync.reads.fetch_add(1, Ordering::Relaxed);
+        }
+        acquired
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        M::read_unlock(&sync.inner);
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        let start = Instant::now();
+        let acquired = M::try_write(&sync.inner, duration);
+        if sync.attempted(start, acquired) {
+            sync.write_acquired();
+        }
+        acquired
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        sync.write_released();
+        M::write_unlock(&sync.inner);
+    }
+
+    #[inline]
+    fn downgrade(sync: &Self::Sync) {
+        sync.write_released();
+        M::downgrade(&sync.inner);
+    }
+
+    #[inline]
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        let start = Instant::now();
+        let upgraded = M::try_upgrade(&sync.inner, duration);
+        if sync.attempted(start, upgraded) {
+            sync.write_acquired();
+        }
+        upgraded
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        M::try_claim_upgradable(&sync.inner, duration)
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        M::release_upgradable(&sync.inner);
+    }
+
+    #[inline]
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        M::debug_state(&sync.inner, f)
+    }
+
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        M::is_writer_waiting(&sync.inner)
+    }
+
+    #[inline]
+    fn poll_read(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let polled = M::poll_read(&sync.inner, waker);
+        if polled.is_acquired() {
+            sync.reads.fetch_add(1, Ordering::Relaxed);
+        }
+        polled
+    }
+
+    #[inline]
+    fn poll_write(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let polled = M::poll_write(&sync.inner, waker);
+        if polled.is_acquired() {
+            sync.write_acquired();
+        }
+        polled
+    }
+}
+
+/// Configures the inner moderator, as per [`Stats::wrap`].
+impl<M: ConfigurableModerator> ConfigurableModerator for Stats<M> {
+    type Config = M::Config;
+
+    #[inline]
+    fn new_with(config: Self::Config) -> Self::Sync {
+        Self::wrap(M::new_with(config))
+    }
+}
+
+impl<T: ?Sized, M: Moderator> ZLock<T, Stats<M>> {
+    /// A snapshot of the lock's counters.
+    #[inline]
+    pub fn stats(&self) -> LockStats {
+        self.sync.snapshot()
+    }
+
+    /// Clears the lock's counters.
+    #[inline]
+    pub fn reset_stats(&self) {
+        self.sync.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests;
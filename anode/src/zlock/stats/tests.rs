@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use crate::test_utils::{CHECK_WAIT, SHORT_WAIT};
+use crate::zlock::{ArrivalOrdered, LockStats, Moderator, ReadBiased, Stats, WriteBiased, ZLock};
+
+#[test]
+fn counts_acquisitions() {
+    __counts_acquisitions::<ReadBiased>();
+    __counts_acquisitions::<WriteBiased>();
+    __counts_acquisitions::<ArrivalOrdered>();
+}
+
+fn __counts_acquisitions<M: Moderator>() {
+    let lock = ZLock::<_, Stats<M>>::new(0);
+    assert_eq!(LockStats::default(), lock.stats());
+
+    drop(lock.read());
+    drop(lock.read());
+    *lock.write() += 1;
+    let stats = lock.stats();
+    assert_eq!(2, stats.reads);
+    assert_eq!(1, stats.writes);
+    assert_eq!(0, stats.timeouts);
+
+    // upgrades count as writes
+    drop(lock.read().upgrade());
+    let stats = lock.stats();
+    assert_eq!(3, stats.reads);
+    assert_eq!(2, stats.writes);
+}
+
+#[test]
+fn counts_timeouts() {
+    let lock = ZLock::<_, Stats<ReadBiased>>::new(0);
+    let guard = lock.write();
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+
+    let stats = lock.stats();
+    assert_eq!(0, stats.reads);
+    assert_eq!(1, stats.writes);
+    assert_eq!(2, stats.timeouts);
+    assert!(stats.wait >= SHORT_WAIT, "{stats:?}");
+}
+
+#[test]
+fn measures_wait_and_hold() {
+    let lock = Arc::new(ZLock::<_, Stats<ReadBiased>>::new(0));
+    let guard = lock.write();
+    let waiter = thread::spawn({
+        let lock = lock.clone();
+        move || drop(lock.read())
+    });
+    thread::sleep(CHECK_WAIT);
+    drop(guard);
+    waiter.join().unwrap();
+
+    let stats = lock.stats();
+    assert_eq!(1, stats.reads);
+    assert!(stats.max_hold >= CHECK_WAIT, "{stats:?}");
+    assert!(stats.wait > Duration::ZERO, "{stats:?}");
+
+    // shorter holds leave the maximum unchanged
+    drop(lock.write());
+    assert_eq!(stats.max_hold, lock.stats().max_hold);
+
+    // as do downgrades, which end the write hold
+    drop(lock.write().downgrade());
+    assert_eq!(stats.max_hold, lock.stats().max_hold);
+}
+
+#[test]
+fn reset() {
+    let lock = ZLock::<_, Stats<ReadBiased>>::new(0);
+    let guard = lock.write();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard);
+    assert_ne!(LockStats::default(), lock.stats());
+
+    lock.reset_stats();
+    assert_eq!(LockStats::default(), lock.stats());
+
+    drop(lock.read());
+    assert_eq!(1, lock.stats().reads);
+}
+
+#[test]
+fn debug_delegates_to_inner() {
+    let lock = ZLock::<_, Stats<ReadBiased>>::new(42);
+    let debug = format!("{lock:?}");
+    assert!(debug.starts_with("ZLock<Stats<ReadBiased>> { moderator: ReadBiased { readers: 0, writer: false"), "{debug}");
+}
+
+#[test]
+fn wraps_configured_moderator() {
+    let lock = Arc::new(ZLock::<_, Stats<ReadBiased>>::with_config(Some(0), 0));
+    let guard = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        thread::spawn(move || lock.try_read(CHECK_WAIT * 10).is_some())
+    };
+
+    // the inner moderator's grace holds back new readers while the writer waits
+    assert!(lock.try_write(CHECK_WAIT).is_none());
+    assert!(t_2.join().unwrap());
+    drop(guard);
+    assert_eq!(1, lock.stats().timeouts);
+}
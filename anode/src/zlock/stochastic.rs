@@ -1,9 +1,11 @@
+use std::fmt;
 use std::time::Duration;
 use crate::deadline::Deadline;
 use crate::inf_iterator::{InfIterator};
 use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
 use crate::rand::{Rand, Seeded, Xorshift, CyclicSeed, Probability};
 use crate::zlock::{Moderator};
+use crate::zlock::registration::Registration;
 
 #[derive(Debug)]
 pub struct Stochastic;
@@ -16,6 +18,7 @@ pub struct StochasticSync {
 struct StochasticState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
     writer_pending: bool,
     queued: u32,
     seed: CyclicSeed,
@@ -30,6 +33,26 @@ impl StochasticState {
     }
 }
 
+/// Clears the `writer_pending` flag on behalf of a writer that has stopped waiting.
+#[inline]
+fn pending_writer(monitor: &SpeculativeMonitor<StochasticState>) -> Registration<impl FnMut(bool) + '_> {
+    Registration::new(move |acquired| {
+        let mut cleared_writer_pending = false;
+        monitor.enter(|state| {
+            if !cleared_writer_pending {
+                cleared_writer_pending = true;
+                state.writer_pending = false;
+            }
+
+            if acquired {
+                Directive::Return
+            } else {
+                Directive::NotifyAll
+            }
+        });
+    })
+}
+
 impl Moderator for Stochastic {
     type Sync = StochasticSync;
 
@@ -39,6 +62,7 @@ impl Moderator for Stochastic {
             monitor: SpeculativeMonitor::new(StochasticState {
                 readers: 0,
                 writer: false,
+                upgradable: false,
                 writer_pending: false,
                 queued: 0,
                 seed: CyclicSeed::default()
@@ -124,14 +148,14 @@ impl Moderator for Stochastic {
     fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut self_writer_pending = false;
+        let mut pending = pending_writer(&sync.monitor);
         sync.monitor.enter(|state| {
             if !acquired {
                 if state.readers == 0 && !state.writer {
                     state.writer = true;
                     acquired = true;
                 } else if !state.writer_pending {
-                    self_writer_pending = true;
+                    pending.raise();
                     state.writer_pending = true;
                 }
             }
@@ -143,23 +167,7 @@ impl Moderator for Stochastic {
             }
         });
 
-        if self_writer_pending {
-            let mut cleared_writer_pending = false;
-            sync.monitor.enter(|state| {
-                if !cleared_writer_pending {
-                    cleared_writer_pending = true;
-                    state.writer_pending = false;
-                }
-
-                if acquired {
-                    Directive::Return
-                } else {
-                    Directive::NotifyAll
-                }
-            });
-        }
-
-        acquired
+        pending.settle(acquired)
     }
 
     #[inline]
@@ -197,9 +205,10 @@ impl Moderator for Stochastic {
     fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut self_writer_pending = false;
+        let mut pending = pending_writer(&sync.monitor);
         sync.monitor.enter(|state| {
             if !acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
                 if state.readers == 1 {
@@ -207,7 +216,7 @@ impl Moderator for Stochastic {
                     state.readers = 0;
                     state.writer = true;
                 } else if !state.writer_pending {
-                    self_writer_pending = true;
+                    pending.raise();
                     state.writer_pending = true;
                 }
             }
@@ -219,22 +228,56 @@ impl Moderator for Stochastic {
             }
         });
 
-        if self_writer_pending {
-            let mut cleared_writer_pending = false;
-            sync.monitor.enter(|state| {
-                if !cleared_writer_pending {
-                    cleared_writer_pending = true;
-                    state.writer_pending = false;
-                }
+        pending.settle(acquired)
+    }
 
-                if acquired {
-                    Directive::Return
-                } else {
-                    Directive::NotifyAll
-                }
-            });
-        }
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        sync.monitor.compute(|state| state.writer_pending)
+    }
 
-        acquired
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            Directive::NotifyAll
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("Stochastic").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("Stochastic")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("writer_pending", &state.writer_pending)
+                .field("queued", &state.queued)
+                .finish(),
+        }
     }
 }
@@ -0,0 +1,121 @@
+use crate::zlock::{LockReadGuard, LockWriteGuard, Moderator, ZLock};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::time::Duration;
+
+/// A fixed number of [`ZLock`] stripes, with keys mapped to stripes by their hash. Suits
+/// sharded structures (e.g., a map split into per-stripe maps), where operations on keys in
+/// different stripes proceed without contending.
+///
+/// Distinct keys may share a stripe, so a thread holding a stripe must not acquire another
+/// key's stripe, lest it self-deadlock. [`write_all`](Self::write_all) acquires every stripe,
+/// in index order, for operations spanning all keys.
+///
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use anode::zlock::{ReadBiased, StripedLock};
+/// let map = StripedLock::<HashMap<&str, u32>, ReadBiased>::new(8, |_| HashMap::new());
+/// map.write(&"foo").insert("foo", 42);
+/// assert_eq!(Some(&42), map.read(&"foo").get("foo"));
+///
+/// let len: usize = map.write_all().iter().map(|stripe| stripe.len()).sum();
+/// assert_eq!(1, len);
+/// ```
+pub struct StripedLock<T, M: Moderator, S = RandomState> {
+    stripes: Box<[ZLock<T, M>]>,
+    hasher: S,
+}
+
+impl<T, M: Moderator> StripedLock<T, M> {
+    /// Creates `stripes` stripes, initializing each from its index with `f`.
+    ///
+    /// # Panics
+    /// If `stripes` is zero.
+    #[inline]
+    pub fn new(stripes: usize, f: impl FnMut(usize) -> T) -> Self {
+        Self::with_hasher(stripes, RandomState::new(), f)
+    }
+}
+
+impl<T, M: Moderator, S: BuildHasher> StripedLock<T, M, S> {
+    /// Creates `stripes` stripes, initializing each from its index with `f`, and mapping keys
+    /// to stripes with `hasher`.
+    ///
+    /// # Panics
+    /// If `stripes` is zero.
+    pub fn with_hasher(stripes: usize, hasher: S, f: impl FnMut(usize) -> T) -> Self {
+        assert_ne!(0, stripes, "no stripes");
+        Self {
+            stripes: (0..stripes).map(f).map(ZLock::new).collect(),
+            hasher,
+        }
+    }
+
+    /// The index of the stripe that `key` maps to.
+    #[inline]
+    pub fn stripe_of<K: Hash + ?Sized>(&self, key: &K) -> usize {
+        (self.hasher.hash_one(key) % self.stripes.len() as u64) as usize
+    }
+
+    #[inline]
+    pub fn read<K: Hash + ?Sized>(&self, key: &K) -> LockReadGuard<'_, T, M> {
+        self.stripes[self.stripe_of(key)].read()
+    }
+
+    #[inline]
+    pub fn try_read<K: Hash + ?Sized>(&self, key: &K, duration: Duration) -> Option<LockReadGuard<'_, T, M>> {
+        self.stripes[self.stripe_of(key)].try_read(duration)
+    }
+
+    #[inline]
+    pub fn write<K: Hash + ?Sized>(&self, key: &K) -> LockWriteGuard<'_, T, M> {
+        self.stripes[self.stripe_of(key)].write()
+    }
+
+    #[inline]
+    pub fn try_write<K: Hash + ?Sized>(&self, key: &K, duration: Duration) -> Option<LockWriteGuard<'_, T, M>> {
+        self.stripes[self.stripe_of(key)].try_write(duration)
+    }
+}
+
+impl<T, M: Moderator, S> StripedLock<T, M, S> {
+    /// The number of stripes.
+    #[inline]
+    pub fn stripes(&self) -> usize {
+        self.stripes.len()
+    }
+
+    /// The stripe at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    #[inline]
+    pub fn stripe(&self, index: usize) -> &ZLock<T, M> {
+        &self.stripes[index]
+    }
+
+    /// Write-acquires every stripe, in index order, returning their guards in the same order.
+    /// As every caller acquires the stripes in the same order, concurrent callers cannot
+    /// deadlock one another.
+    pub fn write_all(&self) -> Vec<LockWriteGuard<'_, T, M>> {
+        self.stripes.iter().map(ZLock::write).collect()
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.stripes.into_vec().into_iter().map(ZLock::into_inner).collect()
+    }
+}
+
+impl<T: Debug, M: Moderator, S> Debug for StripedLock<T, M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StripedLock")
+            .field("stripes", &self.stripes)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests;
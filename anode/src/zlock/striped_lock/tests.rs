@@ -0,0 +1,95 @@
+use crate::zlock::{Moderator, ReadBiased, StripedLock, WriteBiased};
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn keys_map_to_stripes() {
+    __keys_map_to_stripes::<ReadBiased>();
+    __keys_map_to_stripes::<WriteBiased>();
+}
+
+fn __keys_map_to_stripes<M: Moderator>() {
+    let lock = StripedLock::<_, M>::new(4, |index| index);
+    assert_eq!(4, lock.stripes());
+    for key in 0..100 {
+        let stripe = lock.stripe_of(&key);
+        assert!(stripe < 4);
+        assert_eq!(stripe, lock.stripe_of(&key));
+        assert_eq!(stripe, *lock.read(&key));
+    }
+}
+
+#[test]
+fn stripe_excludes_its_keys() {
+    __stripe_excludes_its_keys::<ReadBiased>();
+    __stripe_excludes_its_keys::<WriteBiased>();
+}
+
+fn __stripe_excludes_its_keys<M: Moderator>() {
+    let lock = StripedLock::<_, M>::new(2, |_| ());
+    let (same, other) = {
+        let stripe = lock.stripe_of(&0);
+        let same = (1..).find(|key| lock.stripe_of(key) == stripe).unwrap();
+        let other = (1..).find(|key| lock.stripe_of(key) != stripe).unwrap();
+        (same, other)
+    };
+
+    let guard = lock.write(&0);
+    assert!(lock.try_read(&same, Duration::ZERO).is_none());
+    assert!(lock.try_write(&same, Duration::ZERO).is_none());
+    assert!(lock.try_write(&other, Duration::ZERO).is_some());
+    drop(guard);
+
+    let guard = lock.read(&0);
+    assert!(lock.try_read(&same, Duration::ZERO).is_some());
+    assert!(lock.try_write(&same, Duration::ZERO).is_none());
+    drop(guard);
+}
+
+#[test]
+fn write_all() {
+    let lock = StripedLock::<_, ReadBiased>::new(3, |index| index);
+    let guards = lock.write_all();
+    assert_eq!(vec![0, 1, 2], guards.iter().map(|guard| **guard).collect::<Vec<_>>());
+    for key in 0..10 {
+        assert!(lock.try_read(&key, Duration::ZERO).is_none());
+    }
+    drop(guards);
+    assert!(lock.try_write(&0, Duration::ZERO).is_some());
+}
+
+#[test]
+#[should_panic(expected = "no stripes")]
+fn no_stripes() {
+    StripedLock::<(), ReadBiased>::new(0, |_| ());
+}
+
+#[test]
+fn sharded_map() {
+    const THREADS: u32 = 4;
+    const KEYS: u32 = 100;
+    let map = StripedLock::<HashMap<u32, u32>, ReadBiased>::new(8, |_| HashMap::new());
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for key in 0..KEYS {
+                    *map.write(&key).entry(key).or_default() += 1;
+                }
+                let total: u32 = map.write_all().iter().flat_map(|stripe| stripe.values().copied().collect::<Vec<_>>()).sum();
+                assert!(total >= KEYS);
+            });
+        }
+    });
+
+    let stripes = map.into_inner();
+    assert_eq!(8, stripes.len());
+    assert_eq!(KEYS as usize, stripes.iter().map(HashMap::len).sum::<usize>());
+    assert!(stripes.iter().flat_map(HashMap::values).all(|&count| count == THREADS));
+}
+
+#[test]
+fn debug() {
+    let lock = StripedLock::<_, ReadBiased>::new(2, |index| index);
+    assert!(format!("{:?}", lock).starts_with("StripedLock { stripes: ["));
+}
@@ -1,5 +1,27 @@
-use std::time::{Duration};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Barrier};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Wake, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::deadline::Deadline;
+use crate::test_utils::{CHECK_WAIT, LONG_WAIT, SHORT_WAIT};
+use crate::{monitor, test_utils, wait};
+use crate::wait::Wait;
 use crate::zlock::locklike::{LockBoxSized, LockReadGuardlike, LockWriteGuardlike, MODERATOR_KINDS};
+use crate::zlock::{ArrivalOrdered, LegacyArrivalOrdered, LegacyReadBiased, LegacyWriteBiased, Moderator, Polled, PriorityOrdered, ReadBiased, SchedulerAware, SpinModerator, Stats, Stochastic, UpgradeBiased, WriteBiased, ZLock};
+
+crate::moderator_conformance!(read_biased_conformance, ReadBiased);
+crate::moderator_conformance!(write_biased_conformance, WriteBiased);
+crate::moderator_conformance!(arrival_ordered_conformance, ArrivalOrdered);
+crate::moderator_conformance!(stochastic_conformance, Stochastic);
+crate::moderator_conformance!(spin_moderator_conformance, SpinModerator);
+crate::moderator_conformance!(upgrade_biased_conformance, UpgradeBiased);
+crate::moderator_conformance!(priority_ordered_conformance, PriorityOrdered);
+crate::moderator_conformance!(legacy_read_biased_conformance, LegacyReadBiased);
+crate::moderator_conformance!(legacy_write_biased_conformance, LegacyWriteBiased);
+crate::moderator_conformance!(legacy_arrival_ordered_conformance, LegacyArrivalOrdered);
+crate::moderator_conformance!(stats_conformance, Stats<ReadBiased>);
 
 #[test]
 fn box_cycle() {
@@ -77,3 +99,872 @@ fn __box_cycle(boxed: LockBoxSized<i32>) {
         assert_eq!(1983, *guard);
     }
 }
+
+#[test]
+fn read_or_write_with_read_biased() {
+    __read_or_write_with::<ReadBiased>();
+}
+
+#[test]
+fn read_or_write_with_write_biased() {
+    __read_or_write_with::<WriteBiased>();
+}
+
+#[test]
+fn read_or_write_with_arrival_ordered() {
+    __read_or_write_with::<ArrivalOrdered>();
+}
+
+#[test]
+fn read_or_write_with_stochastic() {
+    __read_or_write_with::<Stochastic>();
+}
+
+fn __read_or_write_with<M: Moderator + 'static>() {
+    const THREADS: usize = 8;
+    let lock = Arc::new(ZLock::<_, M>::new(None));
+    let inits = Arc::new(AtomicUsize::default());
+    let start = Arc::new(Barrier::new(THREADS));
+
+    let threads = (0..THREADS)
+        .map(|i| {
+            let lock = lock.clone();
+            let inits = inits.clone();
+            let start = start.clone();
+            thread::spawn(move || {
+                start.wait();
+                let guard = lock.read_or_write_with(Option::is_some, |val| {
+                    inits.fetch_add(1, Ordering::Relaxed);
+                    *val = Some(i);
+                });
+                assert!(guard.is_some());
+                guard.unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // every thread observes the value written by the single initializer
+    let observed = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(1, inits.load(Ordering::Relaxed));
+    assert!(observed.iter().all(|&val| val == observed[0]), "observed: {observed:?}");
+
+    // the check passes on an initialized lock, so init is not invoked
+    let guard = lock.read_or_write_with(Option::is_some, |_| unreachable!());
+    assert_eq!(Some(observed[0]), *guard);
+}
+
+#[test]
+fn debug_moderator_state() {
+    __debug_moderator_state::<ReadBiased>("ReadBiased");
+    __debug_moderator_state::<WriteBiased>("WriteBiased");
+    __debug_moderator_state::<ArrivalOrdered>("ArrivalOrdered");
+    __debug_moderator_state::<Stochastic>("Stochastic");
+    __debug_moderator_state::<SpinModerator>("SpinModerator");
+    __debug_moderator_state::<UpgradeBiased>("UpgradeBiased");
+    __debug_moderator_state::<PriorityOrdered>("PriorityOrdered");
+    __debug_moderator_state::<SchedulerAware>("SchedulerAware");
+    __debug_moderator_state::<LegacyReadBiased>("LegacyReadBiased");
+    __debug_moderator_state::<LegacyWriteBiased>("LegacyWriteBiased");
+    __debug_moderator_state::<LegacyArrivalOrdered>("LegacyArrivalOrdered");
+    #[cfg(all(feature = "futex", target_os = "linux"))]
+    __debug_moderator_state::<crate::zlock::Futex>("Futex");
+}
+
+fn __debug_moderator_state<M: Moderator>(name: &str) {
+    let lock = ZLock::<_, M>::new("foobar");
+    let debug = format!("{:?}", lock);
+    assert!(debug.starts_with(&format!("ZLock<{name}> {{ moderator: {name} {{ readers: 0, writer: false")), "{debug}");
+    assert!(debug.contains("foobar"), "{debug}");
+
+    let guard_1 = lock.read();
+    let guard_2 = lock.read();
+    let debug = format!("{:?}", lock);
+    assert!(debug.contains("readers: 2, writer: false"), "{debug}");
+    drop(guard_1);
+    drop(guard_2);
+
+    let guard = lock.write();
+    let debug = format!("{:?}", lock);
+    assert!(debug.contains("readers: 0, writer: true"), "{debug}");
+    assert!(debug.contains("<locked>"), "{debug}");
+    drop(guard);
+}
+
+#[test]
+fn debug_generic_moderator_name() {
+    let lock = ZLock::<_, Stats<ReadBiased>>::new(42);
+    let debug = format!("{:?}", lock);
+    assert!(debug.starts_with("ZLock<Stats<ReadBiased>> { "), "{debug}");
+}
+
+#[test]
+fn panic_in_upgrade_wait_releases_read_lock() {
+    __panic_in_upgrade_wait_releases_read_lock::<ReadBiased>();
+    __panic_in_upgrade_wait_releases_read_lock::<WriteBiased>();
+    __panic_in_upgrade_wait_releases_read_lock::<ArrivalOrdered>();
+    __panic_in_upgrade_wait_releases_read_lock::<Stochastic>();
+}
+
+fn __panic_in_upgrade_wait_releases_read_lock<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let guard_1 = lock.read();
+
+    // the upgrades and the write cannot succeed while main holds a read lock, so each will
+    // wait, and panic from within the wait
+    thread::scope(|scope| {
+        scope.spawn(|| monitor::with_wait_hook(|| panic!("injected panic in wait"), || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                lock.read().upgrade();
+            }));
+            assert!(result.is_err());
+            assert!(!lock.is_writer_waiting());
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                lock.read().try_upgrade(Duration::MAX);
+            }));
+            assert!(result.is_err());
+            assert!(!lock.is_writer_waiting());
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                lock.try_write(Duration::MAX);
+            }));
+            assert!(result.is_err());
+            assert!(!lock.is_writer_waiting());
+        }));
+    });
+
+    // readers are not blocked behind a writer that is no longer waiting
+    drop(lock.try_read(Duration::ZERO).unwrap());
+
+    // the panicking threads' read locks have been released, leaving only main's
+    let guard_1 = guard_1.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    drop(guard_1);
+
+    // the lock remains fully usable
+    *lock.try_write(Duration::ZERO).unwrap() = 42;
+    let guard_2 = lock.try_read(Duration::ZERO).unwrap();
+    assert_eq!(42, *guard_2);
+    let mut guard_2 = guard_2.upgrade();
+    *guard_2 = 69;
+    drop(guard_2);
+    assert_eq!(69, lock.into_inner());
+}
+
+#[test]
+fn budgeted_acquire() {
+    let lock = Arc::new(ZLock::<_, WriteBiased>::new(0));
+
+    // acquiring without contention leaves (nearly) the entire budget
+    let (guard, remaining) = lock.try_read_budgeted(LONG_WAIT).unwrap();
+    assert!(remaining > LONG_WAIT / 2, "remaining: {remaining:?}");
+
+    // a writer cannot acquire while read-locked; the budget is exhausted
+    assert!(lock.try_write_budgeted(SHORT_WAIT).is_none());
+
+    // a writer waiting for the read lock to be released has its budget consumed
+    let t_2 = {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let (guard, remaining) = lock.try_write_budgeted(LONG_WAIT).unwrap();
+            drop(guard);
+            remaining
+        })
+    };
+    thread::sleep(CHECK_WAIT);
+    drop(guard);
+    let remaining = t_2.join().unwrap();
+    assert!(remaining < LONG_WAIT, "remaining: {remaining:?}");
+    assert!(remaining > Duration::ZERO, "remaining: {remaining:?}");
+
+    let (guard, remaining) = lock.try_write_budgeted(Duration::MAX).unwrap();
+    assert_eq!(Duration::MAX, remaining);
+    drop(guard);
+}
+
+#[test]
+fn replace() {
+    let lock = ZLock::<_, WriteBiased>::new(42);
+    assert_eq!(42, lock.replace(69));
+    assert_eq!(69, lock.replace(1911));
+    assert_eq!(1911, lock.into_inner());
+}
+
+#[test]
+fn swap_in_opposite_order() {
+    const ITERATIONS: usize = 1_000;
+    let a = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    let b = Arc::new(ZLock::<_, ReadBiased>::new(1));
+    let barrier = Arc::new(Barrier::new(2));
+    let threads = [(a.clone(), b.clone()), (b.clone(), a.clone())]
+        .into_iter()
+        .map(|(x, y)| {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ITERATIONS {
+                    // each thread swaps the same pair, but with the arguments reversed
+                    x.swap(&y);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    // an even number of swaps in total restores the original assignment
+    assert_eq!(0, *a.read());
+    assert_eq!(1, *b.read());
+
+    a.swap(&b);
+    assert_eq!(1, *a.read());
+    assert_eq!(0, *b.read());
+
+    // swapping with self is a no-op and does not deadlock
+    a.swap(&a);
+    assert_eq!(1, *a.read());
+}
+
+#[test]
+fn map_split() {
+    struct Pair {
+        left: Vec<u64>,
+        right: String,
+    }
+
+    let lock = ZLock::<_, WriteBiased>::new(Pair { left: vec![], right: String::new() });
+    let (mut left, mut right) = lock.write().map_split(|pair| (&mut pair.left, &mut pair.right));
+
+    // both halves are borrowed mutably at once
+    let (left_ref, right_ref) = (&mut *left, &mut *right);
+    left_ref.push(42);
+    right_ref.push_str("foo");
+    assert_eq!(vec![42], *left);
+    assert_eq!("foo", *right);
+
+    // the write lock is held until the last of the halves is dropped
+    drop(left);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(right);
+
+    let guard = lock.try_read(Duration::ZERO).unwrap();
+    assert_eq!(vec![42], guard.left);
+    assert_eq!("foo", guard.right);
+}
+
+#[test]
+fn map_read_and_write() {
+    struct Record {
+        id: u64,
+        tags: Vec<String>,
+    }
+
+    let lock = ZLock::<_, ReadBiased>::new(Record { id: 42, tags: vec![] });
+    let mut tags = lock.write().map(|record| &mut record.tags);
+    tags.push("foo".into());
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(tags);
+
+    // mapped read guards hold the read lock, coexisting with other readers
+    let id = lock.read().map(|record| &record.id);
+    let first_tag = lock.read().map(|record| &record.tags).map(|tags| tags[0].as_str());
+    assert_eq!(42, *id);
+    assert_eq!("foo", &*first_tag);
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(id);
+    drop(first_tag);
+
+    // a mapped guard may be shared with other threads
+    let id = lock.write().map(|record| &mut record.id);
+    thread::scope(|scope| {
+        scope.spawn(|| assert_eq!(42, *id));
+    });
+    drop(id);
+    assert_eq!(42, lock.read().id);
+}
+
+#[test]
+fn try_map_declined() {
+    let lock = ZLock::<_, WriteBiased>::new(vec![1, 2, 3]);
+    let guard = lock.read().try_map(|vec| vec.get(3)).unwrap_err();
+    assert_eq!(3, guard.len());
+    let second = guard.try_map(|vec| vec.get(1)).unwrap_or_else(|_| unreachable!());
+    assert_eq!(2, *second);
+    drop(second);
+
+    let mut guard = lock.write().try_map(|vec| vec.first_mut()).unwrap_or_else(|_| unreachable!());
+    *guard = 0;
+    let guard = guard.try_map(|_| None::<&mut u64>).unwrap_err();
+    assert_eq!(0, *guard);
+    drop(guard);
+    assert_eq!(vec![0, 2, 3], *lock.read());
+}
+
+#[test]
+fn map_split_panic_releases_write_lock() {
+    let lock = ZLock::<_, ReadBiased>::new((0, 0));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        lock.write().map_split::<i32, i32>(|_| panic!("boom"));
+    }));
+    assert!(result.is_err());
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn update_if_concurrently() {
+    const THREADS: usize = 4;
+    const TARGET: usize = 1_000;
+    let lock = Arc::new(ZLock::<_, Stochastic>::new(0));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            thread::spawn(move || {
+                let mut applied = 0;
+                // increment only while below the target; should the check and the update not be
+                // atomic, concurrent increments would overshoot the target
+                while lock.update_if(|val| *val < TARGET, |val| *val += 1) {
+                    applied += 1;
+                }
+                applied
+            })
+        })
+        .collect::<Vec<_>>();
+    let applied = threads.into_iter().map(|thread| thread.join().unwrap()).sum::<usize>();
+    assert_eq!(TARGET, applied);
+    assert_eq!(TARGET, *lock.read());
+}
+
+#[test]
+fn try_update_if_timeout() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    let guard = lock.read();
+    assert_eq!(None, lock.try_update_if(SHORT_WAIT, |_| unreachable!(), |_| unreachable!()));
+    drop(guard);
+
+    assert_eq!(Some(false), lock.try_update_if(Duration::ZERO, |val| *val > 0, |_| unreachable!()));
+    assert_eq!(Some(true), lock.try_update_if(Duration::ZERO, |val| *val == 0, |val| *val = 42));
+    assert_eq!(42, lock.into_inner());
+}
+
+#[test]
+fn is_writer_waiting() {
+    __is_writer_waiting::<ReadBiased>();
+    __is_writer_waiting::<WriteBiased>();
+    __is_writer_waiting::<Stochastic>();
+    __is_writer_waiting::<LegacyWriteBiased>();
+    __is_writer_waiting::<Stats<WriteBiased>>();
+}
+
+fn __is_writer_waiting<M: Moderator + 'static>() {
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let guard = lock.read();
+    assert!(!lock.is_writer_waiting());
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds the read lock
+            *lock.write() = 42;
+        })
+    };
+    wait::Spin::wait_for(|| lock.is_writer_waiting(), LONG_WAIT).unwrap();
+
+    // once the writer is let through, it is no longer waiting
+    drop(guard);
+    t_2.join().unwrap();
+    assert!(!lock.is_writer_waiting());
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn is_writer_waiting_untracked() {
+    let lock = ZLock::<_, ArrivalOrdered>::new(0);
+    let _guard = lock.write();
+    assert!(!lock.is_writer_waiting());
+}
+
+#[test]
+fn acquire_with_timeout_error() {
+    fn increment(lock: &ZLock<u64, WriteBiased>) -> Result<u64, Box<dyn std::error::Error>> {
+        let mut guard = lock.write_timeout(SHORT_WAIT)?;
+        *guard += 1;
+        Ok(*guard)
+    }
+
+    let lock = ZLock::<_, WriteBiased>::new(0);
+    assert_eq!(1, increment(&lock).unwrap());
+    assert_eq!(1, *lock.read_timeout(Duration::ZERO).unwrap());
+
+    let guard = lock.read();
+    let err = increment(&lock).unwrap_err();
+    assert_eq!(format!("lock not acquired within {SHORT_WAIT:?}"), err.to_string());
+    drop(guard);
+
+    let guard = lock.write();
+    let err = lock.read_timeout(Duration::ZERO).map(|_| ()).unwrap_err();
+    assert_eq!(Duration::ZERO, err.duration());
+    drop(guard);
+}
+
+#[test]
+fn timed_with_read_and_write() {
+    const SLEEP: Duration = Duration::from_millis(10);
+    let lock = Arc::new(ZLock::<_, WriteBiased>::new(0));
+
+    let (val, timing) = lock.timed_with_write(|val| {
+        thread::sleep(SLEEP);
+        *val = 42;
+        *val
+    });
+    assert_eq!(42, val);
+    assert!(timing.hold >= SLEEP, "hold: {:?}", timing.hold);
+    assert!(timing.acquire < LONG_WAIT, "acquire: {:?}", timing.acquire);
+
+    let (val, timing) = lock.timed_with_read(|val| {
+        thread::sleep(SLEEP);
+        *val
+    });
+    assert_eq!(42, val);
+    assert!(timing.hold >= SLEEP, "hold: {:?}", timing.hold);
+
+    // acquisition spans the time spent waiting for a contending writer
+    let guard = lock.write();
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || lock.timed_with_read(|val| *val))
+    };
+    thread::sleep(CHECK_WAIT);
+    drop(guard);
+    let (val, timing) = t_2.join().unwrap();
+    assert_eq!(42, val);
+    assert!(timing.acquire > Duration::ZERO);
+}
+
+#[test]
+fn write_unlock_wakes_all_waiters() {
+    __write_unlock_wakes_all_waiters::<ReadBiased>();
+    __write_unlock_wakes_all_waiters::<LegacyReadBiased>();
+}
+
+fn __write_unlock_wakes_all_waiters<M: Moderator + 'static>() {
+    const READERS: usize = 4;
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let guard = lock.write();
+    let concurrent_readers = Arc::new(AtomicUsize::default());
+
+    let reader_threads = (0..READERS)
+        .map(|_| {
+            let lock = lock.clone();
+            let concurrent_readers = concurrent_readers.clone();
+            test_utils::spawn_blocked(move || {
+                // each reader holds onto its read lock until all readers have acquired theirs,
+                // which requires all blocked readers to be woken when the write lock is released
+                let guard = lock.try_read(LONG_WAIT).unwrap();
+                concurrent_readers.fetch_add(1, Ordering::Relaxed);
+                wait::Spin::wait_for(|| concurrent_readers.load(Ordering::Relaxed) == READERS, LONG_WAIT).unwrap();
+                *guard
+            })
+        })
+        .collect::<Vec<_>>();
+    let writer_thread = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            *lock.try_write(LONG_WAIT).unwrap() += 1;
+        })
+    };
+    thread::sleep(CHECK_WAIT);
+
+    drop(guard);
+    for thread in reader_threads {
+        thread.join().unwrap();
+    }
+    writer_thread.join().unwrap();
+    assert_eq!(1, *lock.read());
+}
+
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl CountingWaker {
+    fn wakes(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn poll_external_waiter_read_biased() {
+    __poll_external_waiter::<ReadBiased>();
+}
+
+#[test]
+fn poll_external_waiter_write_biased() {
+    __poll_external_waiter::<WriteBiased>();
+}
+
+#[test]
+fn poll_external_waiter_stats() {
+    __poll_external_waiter::<Stats<ReadBiased>>();
+}
+
+fn __poll_external_waiter<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    let counter = Arc::new(CountingWaker::default());
+    let waker = Waker::from(counter.clone());
+
+    // uncontended polls acquire immediately and register nothing
+    let mut guard = lock.poll_write(&waker).acquired().unwrap();
+    *guard = 42;
+
+    // read and write polls are pending while write-locked; the waker is registered once
+    assert!(lock.poll_read(&waker).is_pending());
+    assert!(lock.poll_write(&waker).is_pending());
+    assert_eq!(0, counter.wakes());
+
+    // downgrading the lock wakes the waiter; a read poll will now succeed
+    let guard = guard.downgrade();
+    assert_eq!(1, counter.wakes());
+    let guard_2 = lock.poll_read(&waker).acquired().unwrap();
+    assert_eq!(42, *guard_2);
+
+    // a write poll is pending while read-locked, woken only when the last reader releases
+    assert!(lock.poll_write(&waker).is_pending());
+    drop(guard);
+    assert_eq!(1, counter.wakes());
+    drop(guard_2);
+    assert_eq!(2, counter.wakes());
+
+    // once woken, the waker is deregistered
+    let guard = lock.poll_write(&waker).acquired().unwrap();
+    drop(guard);
+    assert_eq!(2, counter.wakes());
+}
+
+#[test]
+fn poll_external_waiter_unsupported() {
+    let lock = ZLock::<_, ArrivalOrdered>::new(0);
+    let waker = Waker::from(Arc::new(CountingWaker::default()));
+    assert_eq!(Polled::Unsupported, lock.poll_read(&waker).map(|_| ()));
+    assert_eq!(Polled::Unsupported, lock.poll_write(&waker).map(|_| ()));
+}
+
+#[test]
+fn upgradable_cycle() {
+    __upgradable_cycle::<ReadBiased>();
+    __upgradable_cycle::<WriteBiased>();
+    __upgradable_cycle::<ArrivalOrdered>();
+    __upgradable_cycle::<Stochastic>();
+    __upgradable_cycle::<SpinModerator>();
+    __upgradable_cycle::<UpgradeBiased>();
+    __upgradable_cycle::<PriorityOrdered>();
+    __upgradable_cycle::<LegacyReadBiased>();
+    __upgradable_cycle::<LegacyWriteBiased>();
+    __upgradable_cycle::<LegacyArrivalOrdered>();
+    __upgradable_cycle::<Stats<ReadBiased>>();
+    #[cfg(all(feature = "futex", target_os = "linux"))]
+    __upgradable_cycle::<crate::zlock::Futex>();
+}
+
+fn __upgradable_cycle<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+
+    // the upgradable guard coexists with plain readers, but not with another upgradable guard
+    let guard = lock.read_upgradable();
+    let reader = lock.try_read(Duration::ZERO).unwrap();
+    assert!(lock.try_read_upgradable(Duration::ZERO).is_none());
+    assert!(lock.try_read_upgradable(SHORT_WAIT).is_none());
+    assert_eq!(0, *guard);
+
+    // the upgrade waits for the plain reader
+    let guard = guard.try_upgrade(SHORT_WAIT).unchanged().unwrap();
+    drop(reader);
+    let mut guard = guard.try_upgrade(Duration::ZERO).upgraded().unwrap();
+    *guard = 42;
+
+    // the slot is released by the upgrade
+    let guard = guard.downgrade();
+    let upgradable = lock.try_read_upgradable(Duration::ZERO).unwrap();
+    assert_eq!(42, *upgradable);
+    drop(guard);
+
+    // downgrading releases the slot, retaining the read lock
+    let reader = upgradable.downgrade();
+    let upgradable = lock.try_read_upgradable(Duration::ZERO).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(reader);
+    drop(upgradable);
+
+    // dropping releases both the slot and the read lock
+    *lock.try_write(Duration::ZERO).unwrap() = 69;
+    assert_eq!(69, *lock.read_upgradable().upgrade());
+}
+
+#[test]
+fn upgradable_blocks_writer() {
+    let lock = ZLock::<_, ReadBiased>::new(0);
+    let guard = lock.read_upgradable();
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+    drop(guard);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn upgradable_upgrades_concurrently() {
+    __upgradable_upgrades_concurrently::<ReadBiased>();
+    __upgradable_upgrades_concurrently::<WriteBiased>();
+    __upgradable_upgrades_concurrently::<ArrivalOrdered>();
+    __upgradable_upgrades_concurrently::<Stochastic>();
+    __upgradable_upgrades_concurrently::<SpinModerator>();
+    __upgradable_upgrades_concurrently::<UpgradeBiased>();
+    __upgradable_upgrades_concurrently::<PriorityOrdered>();
+    __upgradable_upgrades_concurrently::<LegacyReadBiased>();
+    __upgradable_upgrades_concurrently::<LegacyWriteBiased>();
+    __upgradable_upgrades_concurrently::<LegacyArrivalOrdered>();
+    #[cfg(all(feature = "futex", target_os = "linux"))]
+    __upgradable_upgrades_concurrently::<crate::zlock::Futex>();
+}
+
+fn __upgradable_upgrades_concurrently<M: Moderator + 'static>() where M::Sync: Send + Sync {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 100;
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+    let barrier = Arc::new(Barrier::new(THREADS));
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..ITERATIONS {
+                    // were two upgradable guards to coexist, their upgrades would deadlock
+                    let guard = lock.read_upgradable();
+                    let val = *guard;
+                    let mut guard = guard.upgrade();
+                    assert_eq!(val, *guard);
+                    *guard += 1;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(THREADS * ITERATIONS, *lock.read());
+}
+
+#[test]
+fn forget_and_force_unlock() {
+    __forget_and_force_unlock::<ReadBiased>();
+    __forget_and_force_unlock::<WriteBiased>();
+    __forget_and_force_unlock::<ArrivalOrdered>();
+    __forget_and_force_unlock::<Stochastic>();
+    __forget_and_force_unlock::<SpinModerator>();
+    __forget_and_force_unlock::<UpgradeBiased>();
+    __forget_and_force_unlock::<PriorityOrdered>();
+    __forget_and_force_unlock::<LegacyReadBiased>();
+    __forget_and_force_unlock::<LegacyWriteBiased>();
+    __forget_and_force_unlock::<LegacyArrivalOrdered>();
+    __forget_and_force_unlock::<Stats<ReadBiased>>();
+    #[cfg(all(feature = "futex", target_os = "linux"))]
+    __forget_and_force_unlock::<crate::zlock::Futex>();
+}
+
+fn __forget_and_force_unlock<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+
+    // a forgotten read lock is held until forcibly released
+    lock.read().forget();
+    lock.read().forget();
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    unsafe { lock.force_unlock_read() };
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    unsafe { lock.force_unlock_read() };
+
+    // likewise, a forgotten write lock
+    let mut guard = lock.try_write(Duration::ZERO).unwrap();
+    *guard = 42;
+    guard.forget();
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    unsafe { lock.force_unlock_write() };
+    assert_eq!(42, *lock.try_read(Duration::ZERO).unwrap());
+}
+
+#[test]
+fn force_unlock_write_on_other_thread() {
+    let lock = Arc::new(ZLock::<_, ReadBiased>::new(0));
+    *lock.write() = 42;
+    let mut guard = lock.write();
+    *guard += 1;
+    guard.forget();
+
+    let t_2 = {
+        let lock = lock.clone();
+        thread::spawn(move || unsafe { lock.force_unlock_write() })
+    };
+    t_2.join().unwrap();
+    assert_eq!(43, *lock.try_read(Duration::ZERO).unwrap());
+}
+
+#[test]
+fn try_until_deadline() {
+    __try_until_deadline::<ReadBiased>();
+    __try_until_deadline::<WriteBiased>();
+    __try_until_deadline::<SpinModerator>();
+}
+
+fn __try_until_deadline<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(0);
+    *lock.try_write_until(Deadline::lazy_after(Duration::ZERO)).unwrap() = 42;
+    assert_eq!(42, *lock.try_read_until(Deadline::Forever).unwrap());
+
+    // an elapsed deadline makes a single attempt
+    let guard = lock.read();
+    assert!(lock.try_read_until(Deadline::Elapsed).is_some());
+    assert!(lock.try_write_until(Deadline::Elapsed).is_none());
+
+    // a future deadline is waited out
+    let start = Instant::now();
+    assert!(lock.try_write_until(Deadline::after(CHECK_WAIT)).is_none());
+    assert!(start.elapsed() >= CHECK_WAIT);
+
+    // as is a deadline that has already passed, by not waiting at all
+    assert!(lock.try_write_until(Deadline::Point(start)).is_none());
+    drop(guard);
+    assert!(lock.try_write_until(Deadline::Point(start)).is_some());
+}
+
+#[test]
+fn unlocked() {
+    __unlocked::<ReadBiased>();
+    __unlocked::<WriteBiased>();
+    __unlocked::<SpinModerator>();
+    __unlocked::<Stats<ReadBiased>>();
+}
+
+fn __unlocked<M: Moderator + 'static>() {
+    let lock = Arc::new(ZLock::<_, M>::new(0));
+
+    // another thread may write while the write guard is unlocked
+    let mut guard = lock.write();
+    *guard = 1;
+    let observed = guard.unlocked(|| {
+        let lock = lock.clone();
+        thread::spawn(move || {
+            let mut guard = lock.write();
+            *guard += 1;
+            *guard
+        }).join().unwrap()
+    });
+    assert_eq!(2, observed);
+    assert_eq!(2, *guard);
+    assert!(lock.try_read(Duration::ZERO).is_none());
+    drop(guard);
+
+    // likewise while the read guard is unlocked
+    let mut guard = lock.read();
+    guard.unlocked(|| *lock.try_write(Duration::ZERO).unwrap() = 3);
+    assert_eq!(3, *guard);
+    assert!(lock.try_write(Duration::ZERO).is_none());
+    drop(guard);
+
+    // a panic in the closure leaves the lock released, and it is not released twice
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = lock.write();
+        guard.unlocked(|| panic!("boom"));
+    }));
+    assert!(result.is_err());
+    assert_eq!(3, *lock.try_write(Duration::ZERO).unwrap());
+    let reader = lock.read();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut guard = lock.read();
+        guard.unlocked(|| panic!("boom"));
+    }));
+    assert!(result.is_err());
+    drop(reader);
+    assert!(lock.try_write(Duration::ZERO).is_some());
+}
+
+#[test]
+fn with_read_write() {
+    __with_read_write::<ReadBiased>();
+    __with_read_write::<WriteBiased>();
+    __with_read_write::<SpinModerator>();
+}
+
+fn __with_read_write<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(vec![1, 2]);
+    lock.with_write(|vec| vec.push(3));
+    assert_eq!(3, lock.with_read(|vec| {
+        // the read lock is held within the closure
+        assert!(lock.try_write(Duration::ZERO).is_none());
+        vec.len()
+    }));
+    assert!(lock.try_write(Duration::ZERO).is_some());
+
+    assert_eq!(Some(4), lock.try_with_write(Duration::ZERO, |vec| {
+        vec.push(4);
+        vec.len()
+    }));
+    let guard = lock.write();
+    assert_eq!(None, lock.try_with_read(Duration::ZERO, |_| unreachable!()));
+    assert_eq!(None, lock.try_with_write(SHORT_WAIT, |_| unreachable!()));
+    drop(guard);
+    assert_eq!(Some(vec![1, 2, 3, 4]), lock.try_with_read(Duration::ZERO, Vec::clone));
+}
+
+#[test]
+fn default_from_clone() {
+    __default_from_clone::<ReadBiased>();
+    __default_from_clone::<WriteBiased>();
+    __default_from_clone::<SpinModerator>();
+    __default_from_clone::<Stats<ReadBiased>>();
+}
+
+fn __default_from_clone<M: Moderator>() {
+    let lock = ZLock::<Vec<i32>, M>::default();
+    assert!(lock.read().is_empty());
+
+    let lock = ZLock::<_, M>::from(vec![1, 2, 3]);
+    assert_eq!(vec![1, 2, 3], *lock.read());
+
+    // cloning takes a read lock, so coexists with other readers; the clone is independent
+    let guard = lock.read();
+    let clone = lock.clone();
+    drop(guard);
+    clone.write().push(4);
+    assert_eq!(vec![1, 2, 3, 4], *clone.read());
+    assert_eq!(vec![1, 2, 3], *lock.read());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    __serde_round_trip::<ReadBiased>();
+    __serde_round_trip::<WriteBiased>();
+    __serde_round_trip::<SpinModerator>();
+    __serde_round_trip::<Stats<ReadBiased>>();
+}
+
+#[cfg(feature = "serde")]
+fn __serde_round_trip<M: Moderator>() {
+    let lock = ZLock::<_, M>::new(vec![1, 2, 3]);
+    let json = serde_json::to_string(&lock).unwrap();
+    assert_eq!("[1,2,3]", json);
+
+    // serialization takes a read lock, so coexists with other readers
+    {
+        let _guard = lock.read();
+        assert_eq!(json, serde_json::to_string(&lock).unwrap());
+    }
+
+    let lock = serde_json::from_str::<ZLock<Vec<i32>, M>>(&json).unwrap();
+    assert!(lock.try_write(Duration::ZERO).is_some());
+    assert_eq!(vec![1, 2, 3], *lock.read());
+    assert!(serde_json::from_str::<ZLock<Vec<i32>, M>>("{}").is_err());
+}
@@ -149,6 +149,51 @@ fn timeout_on_upgrade_while_read_locked() {
     }
 }
 
+#[test]
+fn contended_timeout_on_upgrade_retains_read_lock() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 50;
+    for moderator in MODERATOR_KINDS {
+        let lock = Arc::new(moderator.make_lock_for_test(0));
+        let start = Arc::new(Barrier::new(THREADS));
+
+        let threads = (0..THREADS)
+            .map(|_| {
+                let lock = lock.clone();
+                let start = start.clone();
+                thread::spawn(move || {
+                    start.wait();
+                    let mut upgrades = 0;
+                    for _ in 0..ITERATIONS {
+                        let guard = lock.read();
+                        let guard_res = guard.try_upgrade(SHORT_WAIT);
+                        if guard_res.is_upgraded() {
+                            *guard_res.upgraded().unwrap() += 1;
+                            upgrades += 1;
+                        } else {
+                            // still read-locked, so no writer can get in
+                            let guard = guard_res.unchanged().unwrap();
+                            assert!(lock.try_write(Duration::ZERO).is_none());
+                            drop(guard);
+                        }
+                    }
+                    upgrades
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let upgrades = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .sum::<i32>();
+
+        // all read locks have been released, so the lock can be write-acquired without waiting
+        let guard = lock.try_write(Duration::ZERO);
+        assert!(guard.is_some());
+        assert_eq!(upgrades, *guard.unwrap());
+    }
+}
+
 #[test]
 fn timeout_on_write_acquire_while_write_locked() {
     for moderator in MODERATOR_KINDS {
@@ -0,0 +1,242 @@
+use std::fmt;
+use std::time::Duration;
+use crate::deadline::Deadline;
+use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
+use crate::zlock::Moderator;
+
+/// A moderator that prioritises pending upgrades over both new readers and new writers.
+/// Once a reader begins waiting to upgrade, arriving readers and writers are held back until
+/// the upgrade completes (or the upgrader gives up waiting), such that an upgrade can only be
+/// delayed by the readers that were already present. This prevents upgrade starvation under
+/// a continuous stream of overlapping readers.
+///
+/// Absent upgraders, the moderator admits readers whenever there is no writer, as
+/// [`ReadBiased`](crate::zlock::ReadBiased) does.
+///
+/// As with any moderator, two readers that concurrently attempt to upgrade without a timeout
+/// will deadlock, as each waits for the other to release its read lock.
+#[derive(Debug)]
+pub struct UpgradeBiased;
+
+pub struct UpgradeBiasedSync {
+    monitor: SpeculativeMonitor<UpgradeBiasedState>,
+}
+
+#[derive(Debug)]
+struct UpgradeBiasedState {
+    readers: u32,
+    writer: bool,
+    upgradable: bool,
+    upgraders: u32,
+}
+
+/// Tracks the registration of the current thread as an upgrader while it waits. The
+/// registration is withdrawn when the tracker is dropped without having acquired the lock,
+/// including during unwinding, so that readers and writers are never left blocked behind
+/// an upgrader that is no longer waiting.
+struct PendingUpgrader<'a> {
+    monitor: &'a SpeculativeMonitor<UpgradeBiasedState>,
+    registered: bool,
+    acquired: bool,
+}
+
+impl Drop for PendingUpgrader<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.registered && !self.acquired {
+            let mut withdrawn = false;
+            self.monitor.enter(|state| {
+                if !withdrawn {
+                    withdrawn = true;
+                    state.upgraders -= 1;
+                }
+                Directive::NotifyAll
+            });
+        }
+    }
+}
+
+impl Moderator for UpgradeBiased {
+    type Sync = UpgradeBiasedSync;
+
+    #[inline]
+    fn new() -> Self::Sync {
+        Self::Sync {
+            monitor: SpeculativeMonitor::new(UpgradeBiasedState {
+                readers: 0,
+                writer: false,
+                upgradable: false,
+                upgraders: 0,
+            }),
+        }
+    }
+
+    #[inline]
+    fn try_read(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut acquired = false;
+        sync.monitor.enter(|state| {
+            if !acquired && !state.writer && state.upgraders == 0 {
+                acquired = true;
+                state.readers += 1;
+            }
+
+            if acquired {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        acquired
+    }
+
+    #[inline]
+    fn read_unlock(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
+                debug_assert!(!state.writer);
+
+                released = true;
+                state.readers -= 1;
+            }
+
+            match state.readers {
+                0 => Directive::NotifyAll,
+                1 if state.upgraders > 0 => Directive::NotifyAll,
+                _ => Directive::Return,
+            }
+        });
+    }
+
+    #[inline]
+    fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut acquired = false;
+        sync.monitor.enter(|state| {
+            if !acquired && state.readers == 0 && !state.writer && state.upgraders == 0 {
+                acquired = true;
+                state.writer = true;
+            }
+
+            if acquired {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        acquired
+    }
+
+    #[inline]
+    fn write_unlock(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers == 0, "readers: {}", state.readers);
+                debug_assert!(state.writer);
+
+                released = true;
+                state.writer = false;
+            }
+
+            Directive::NotifyAll
+        });
+    }
+
+    #[inline]
+    fn downgrade(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.readers == 0, "readers: {}", state.readers);
+                debug_assert!(state.writer);
+
+                released = true;
+                state.writer = false;
+                state.readers = 1;
+            }
+
+            Directive::NotifyAll
+        });
+    }
+
+    #[inline]
+    fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut pending = PendingUpgrader { monitor: &sync.monitor, registered: false, acquired: false };
+        sync.monitor.enter(|state| {
+            if !pending.acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
+                debug_assert!(!state.writer);
+
+                if state.readers == 1 {
+                    pending.acquired = true;
+                    state.readers = 0;
+                    state.writer = true;
+                    if pending.registered {
+                        state.upgraders -= 1;
+                    }
+                } else if !pending.registered {
+                    pending.registered = true;
+                    state.upgraders += 1;
+                }
+            }
+
+            if pending.acquired {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        pending.acquired
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                Directive::Return
+            } else {
+                Directive::Wait(deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            Directive::NotifyAll
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("UpgradeBiased").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("UpgradeBiased")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("upgraders", &state.upgraders)
+                .finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
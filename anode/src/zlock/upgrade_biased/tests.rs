@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use crate::monitor::Monitor;
+use crate::test_utils::{LONG_WAIT, SHORT_WAIT};
+use crate::{test_utils, wait};
+use crate::wait::Wait;
+use crate::zlock::{UpgradeBiased, ZLock};
+
+#[test]
+fn pending_upgrade_blocks_readers_and_writers() {
+    let lock = Arc::new(ZLock::<_, UpgradeBiased>::new(0));
+    let guard_1 = lock.read();
+
+    let t_2 = {
+        let lock = lock.clone();
+        test_utils::spawn_blocked(move || {
+            // t_2 blocks because main holds a read lock
+            let guard = lock.read();
+            let mut guard = guard.try_upgrade(LONG_WAIT).upgraded().unwrap();
+            *guard = 42;
+        })
+    };
+    wait::Spin::wait_for(|| lock.upgraders() == 1, LONG_WAIT).unwrap();
+
+    // new readers and writers defer to the pending upgrade
+    assert!(lock.try_read(SHORT_WAIT).is_none());
+    assert!(lock.try_write(SHORT_WAIT).is_none());
+
+    // releasing main's read lock admits the upgrader
+    drop(guard_1);
+    t_2.join().unwrap();
+    assert_eq!(0, lock.upgraders());
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn timeout_in_upgrade_unblocks_readers() {
+    let lock = ZLock::<_, UpgradeBiased>::new(0);
+    let guard_1 = lock.read();
+
+    // cannot upgrade while another reader is present
+    let guard_2 = lock.read().try_upgrade(SHORT_WAIT).unchanged().unwrap();
+
+    // the timeout should have withdrawn the upgrader
+    assert_eq!(0, lock.upgraders());
+    let guard_3 = lock.read();
+    drop(guard_1);
+    drop(guard_3);
+
+    // once the remaining reader is alone, it upgrades immediately
+    let mut guard = guard_2.try_upgrade(SHORT_WAIT).upgraded().unwrap();
+    *guard = 42;
+    drop(guard);
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn upgrade_not_starved_by_overlapping_readers() {
+    const READERS: usize = 4;
+    let lock = Arc::new(ZLock::<_, UpgradeBiased>::new(0));
+    let running = Arc::new(AtomicBool::new(true));
+
+    // the readers overlap, such that there is almost always at least one reader present
+    let readers = (0..READERS)
+        .map(|_| {
+            let lock = lock.clone();
+            let running = running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    let _guard = lock.read();
+                    thread::yield_now();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..10 {
+        let guard = lock.read();
+        let mut guard = guard.try_upgrade(LONG_WAIT).upgraded().unwrap();
+        *guard = i;
+    }
+    running.store(false, Ordering::Relaxed);
+    for reader in readers {
+        reader.join().unwrap();
+    }
+    assert_eq!(9, *lock.read());
+}
+
+impl<T> ZLock<T, UpgradeBiased> {
+    fn upgraders(&self) -> u32 {
+        self.sync.monitor.compute(|state| state.upgraders)
+    }
+}
@@ -1,20 +1,64 @@
+use std::fmt;
+use std::task::Waker;
 use std::time::Duration;
 use crate::deadline::Deadline;
-use crate::monitor::{Directive, Monitor, SpeculativeMonitor};
-use crate::zlock::{Moderator};
+use crate::monitor::{ClassedDirective, ClassedMonitor, Monitor, Wake};
+use crate::zlock::{Moderator, Polled, Wakers};
+use crate::zlock::registration::Registration;
 
+/// A moderator that blocks arriving readers while a writer is waiting, such that a writer
+/// can only be delayed by the readers that were already present.
+///
+/// Uncontended acquisitions and releases are served by the [`ClassedMonitor`]'s spin lock
+/// alone; the monitor's mutex and condvars are only involved once a thread has to wait.
+/// Readers, writers, upgraders and claimants of the upgradable lock wait on separate condvars,
+/// so that a release only wakes the threads it may admit.
 #[derive(Debug)]
 pub struct WriteBiased;
 
+const READERS: usize = 0;
+const WRITERS: usize = 1;
+const UPGRADERS: usize = 2;
+const CLAIMANTS: usize = 3;
+
 pub struct WriteBiasedSync {
-    monitor: SpeculativeMonitor<WriteBiasedState>,
+    monitor: ClassedMonitor<WriteBiasedState, 4>,
 }
 
 #[derive(Debug)]
 struct WriteBiasedState {
     readers: u32,
     writer: bool,
+    upgradable: bool,
     writer_pending: bool,
+    wakers: Wakers,
+}
+
+/// Clears the `writer_pending` flag on behalf of a writer that has stopped waiting.
+#[inline]
+fn pending_writer(monitor: &ClassedMonitor<WriteBiasedState, 4>) -> Registration<impl FnMut(bool) + '_> {
+    Registration::new(move |acquired| {
+        let mut cleared_writer_pending = false;
+        let mut wakers = Wakers::default();
+        monitor.enter_classed(|state| {
+            if !cleared_writer_pending {
+                cleared_writer_pending = true;
+                state.writer_pending = false;
+                if !acquired {
+                    wakers = state.wakers.take();
+                }
+            }
+
+            if acquired {
+                ClassedDirective::Return
+            } else {
+                // readers blocked behind the flag may proceed, while another waiting writer
+                // (if any) is woken so that it may raise the flag in turn
+                ClassedDirective::Notify(Wake::NONE.all(READERS).one(WRITERS))
+            }
+        });
+        wakers.wake_all();
+    })
 }
 
 impl Moderator for WriteBiased {
@@ -23,7 +67,13 @@ impl Moderator for WriteBiased {
     #[inline]
     fn new() -> Self::Sync {
         Self::Sync {
-            monitor: SpeculativeMonitor::new(WriteBiasedState { readers: 0, writer: false, writer_pending: false }),
+            monitor: ClassedMonitor::new(WriteBiasedState {
+                readers: 0,
+                writer: false,
+                upgradable: false,
+                writer_pending: false,
+                wakers: Wakers::default(),
+            }),
         }
     }
 
@@ -32,7 +82,7 @@ impl Moderator for WriteBiased {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
         let mut saw_no_pending_writer = false;
-        sync.monitor.enter(|state| {
+        sync.monitor.enter_classed(|state| {
             if !state.writer_pending {
                 saw_no_pending_writer = true;
             }
@@ -43,9 +93,9 @@ impl Moderator for WriteBiased {
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(READERS, deadline.remaining())
             }
         });
         acquired
@@ -54,83 +104,78 @@ impl Moderator for WriteBiased {
     #[inline]
     fn read_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
                 released = true;
                 state.readers -= 1;
+                if state.readers == 0 {
+                    wakers = state.wakers.take();
+                }
             }
 
+            // a lone remaining reader is only of interest if it is waiting to upgrade
             match state.readers {
-                0 | 1 => Directive::NotifyAll,
-                _ => Directive::Return
+                1 => ClassedDirective::Notify(Wake::NONE.all(UPGRADERS)),
+                0 => ClassedDirective::Notify(Wake::NONE.one(WRITERS)),
+                _ => ClassedDirective::Return
             }
         });
+        wakers.wake_all();
     }
 
     #[inline]
     fn try_write(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut self_writer_pending = false;
-        sync.monitor.enter(|state| {
+        let mut pending = pending_writer(&sync.monitor);
+        sync.monitor.enter_classed(|state| {
             if !acquired {
                 if state.readers == 0 && !state.writer {
                     state.writer = true;
                     acquired = true;
                 } else if !state.writer_pending {
-                    self_writer_pending = true;
+                    pending.raise();
                     state.writer_pending = true;
                 }
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(WRITERS, deadline.remaining())
             }
         });
 
-        if self_writer_pending {
-            let mut cleared_writer_pending = false;
-            sync.monitor.enter(|state| {
-                if !cleared_writer_pending {
-                    cleared_writer_pending = true;
-                    state.writer_pending = false;
-                }
-
-                if acquired {
-                    Directive::Return
-                } else {
-                    Directive::NotifyAll
-                }
-            });
-        }
-
-        acquired
+        pending.settle(acquired)
     }
 
     #[inline]
     fn write_unlock(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
 
                 released = true;
                 state.writer = false;
+                wakers = state.wakers.take();
             }
 
-            Directive::NotifyAll
+            ClassedDirective::Notify(Wake::NONE.all(READERS).one(WRITERS))
         });
+        wakers.wake_all();
     }
 
     fn downgrade(sync: &Self::Sync) {
         let mut released = false;
-        sync.monitor.enter(|state| {
+        let mut wakers = Wakers::default();
+        sync.monitor.enter_classed(|state| {
             if !released {
                 debug_assert!(state.readers == 0, "readers: {}", state.readers);
                 debug_assert!(state.writer);
@@ -138,18 +183,21 @@ impl Moderator for WriteBiased {
                 released = true;
                 state.writer = false;
                 state.readers = 1;
+                wakers = state.wakers.take();
             }
 
-            Directive::NotifyAll
+            ClassedDirective::Notify(Wake::NONE.all(READERS))
         });
+        wakers.wake_all();
     }
 
     fn try_upgrade(sync: &Self::Sync, duration: Duration) -> bool {
         let mut deadline = Deadline::lazy_after(duration);
         let mut acquired = false;
-        let mut self_writer_pending = false;
-        sync.monitor.enter(|state| {
+        let mut pending = pending_writer(&sync.monitor);
+        sync.monitor.enter_classed(|state| {
             if !acquired {
+                debug_assert!(state.readers > 0, "readers: {}", state.readers);
                 debug_assert!(!state.writer);
 
                 if state.readers == 1 {
@@ -157,35 +205,95 @@ impl Moderator for WriteBiased {
                     state.readers = 0;
                     state.writer = true;
                 } else if !state.writer_pending {
-                    self_writer_pending = true;
+                    pending.raise();
                     state.writer_pending = true;
                 }
             }
 
             if acquired {
-                Directive::Return
+                ClassedDirective::Return
             } else {
-                Directive::Wait(deadline.remaining())
+                ClassedDirective::Wait(UPGRADERS, deadline.remaining())
             }
         });
 
-        if self_writer_pending {
-            let mut cleared_writer_pending = false;
-            sync.monitor.enter(|state| {
-                if !cleared_writer_pending {
-                    cleared_writer_pending = true;
-                    state.writer_pending = false;
-                }
+        pending.settle(acquired)
+    }
 
-                if acquired {
-                    Directive::Return
-                } else {
-                    Directive::NotifyAll
-                }
-            });
+    #[inline]
+    fn is_writer_waiting(sync: &Self::Sync) -> bool {
+        sync.monitor.compute(|state| state.writer_pending)
+    }
+
+    #[inline]
+    fn try_claim_upgradable(sync: &Self::Sync, duration: Duration) -> bool {
+        let mut deadline = Deadline::lazy_after(duration);
+        let mut claimed = false;
+        sync.monitor.enter_classed(|state| {
+            if !claimed && !state.upgradable {
+                claimed = true;
+                state.upgradable = true;
+            }
+
+            if claimed {
+                ClassedDirective::Return
+            } else {
+                ClassedDirective::Wait(CLAIMANTS, deadline.remaining())
+            }
+        });
+        claimed
+    }
+
+    #[inline]
+    fn release_upgradable(sync: &Self::Sync) {
+        let mut released = false;
+        sync.monitor.enter_classed(|state| {
+            if !released {
+                debug_assert!(state.upgradable);
+                released = true;
+                state.upgradable = false;
+            }
+            ClassedDirective::Notify(Wake::NONE.one(CLAIMANTS))
+        });
+    }
+
+    fn debug_state(sync: &Self::Sync, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match sync.monitor.try_lock() {
+            None => f.debug_struct("WriteBiased").finish_non_exhaustive(),
+            Some(state) => f
+                .debug_struct("WriteBiased")
+                .field("readers", &state.readers)
+                .field("writer", &state.writer)
+                .field("writer_pending", &state.writer_pending)
+                .finish(),
         }
+    }
 
-        acquired
+    /// Readers polling on behalf of an external waiter defer to a pending writer, as
+    /// blocking readers do. However, external waiters never raise the `writer_pending`
+    /// flag themselves.
+    #[inline]
+    fn poll_read(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let mut state = sync.monitor.lock();
+        if !state.writer && !state.writer_pending {
+            state.readers += 1;
+            Polled::Acquired(())
+        } else {
+            state.wakers.register(waker);
+            Polled::Pending
+        }
+    }
+
+    #[inline]
+    fn poll_write(sync: &Self::Sync, waker: &Waker) -> Polled<()> {
+        let mut state = sync.monitor.lock();
+        if state.readers == 0 && !state.writer {
+            state.writer = true;
+            Polled::Acquired(())
+        } else {
+            state.wakers.register(waker);
+            Polled::Pending
+        }
     }
 }
 
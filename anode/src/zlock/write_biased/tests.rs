@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
-use std::sync::Arc;
+use std::sync::{Arc, Barrier};
+use std::thread;
 use std::time::Duration;
 use test_utils::SHORT_WAIT;
 use crate::executor::{Executor, Queue, Submitter, ThreadPool};
@@ -7,7 +8,7 @@ use crate::{test_utils, wait};
 use crate::monitor::{Monitor};
 use crate::test_utils::LONG_WAIT;
 use crate::wait::{Wait, WaitResult};
-use crate::zlock::{WriteBiased, ZLock};
+use crate::zlock::{UpgradeOutcome, WriteBiased, ZLock};
 
 #[test]
 fn timeout_in_write_unblocks_readers() {
@@ -45,6 +46,52 @@ fn timeout_in_upgrade_unblocks_readers() {
     drop(guard_4);
 }
 
+#[test]
+fn timeout_in_upgrade_retains_reader_under_contention() {
+    const THREADS: usize = 4;
+    const ITERATIONS: usize = 100;
+    let lock = Arc::new(ZLock::<_, WriteBiased>::new(0));
+    let start = Arc::new(Barrier::new(THREADS));
+
+    let threads = (0..THREADS)
+        .map(|_| {
+            let lock = lock.clone();
+            let start = start.clone();
+            thread::spawn(move || {
+                start.wait();
+                let mut upgrades = 0;
+                for _ in 0..ITERATIONS {
+                    let guard = lock.read();
+                    match guard.try_upgrade(SHORT_WAIT) {
+                        UpgradeOutcome::Upgraded(mut guard) => {
+                            *guard += 1;
+                            upgrades += 1;
+                        }
+                        UpgradeOutcome::Unchanged(guard) => {
+                            // the failed upgrade must leave us holding the original read lock
+                            assert!(lock.readers() > 0);
+                            assert!(lock.try_write(Duration::ZERO).is_none());
+                            drop(guard);
+                        }
+                    }
+                }
+                upgrades
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let upgrades = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .sum::<i32>();
+
+    // every read lock was released exactly once
+    assert_eq!(0, lock.readers());
+    assert!(!lock.is_writer_pending());
+    let guard = lock.try_write(Duration::ZERO).unwrap();
+    assert_eq!(upgrades, *guard);
+}
+
 #[test]
 fn await_pending_writer() {
     let lock = Arc::new(ZLock::<_, WriteBiased>::new(0));
@@ -118,6 +165,10 @@ fn await_pending_writer_timeout() {
 }
 
 impl<T> ZLock<T, WriteBiased> {
+    fn readers(&self) -> u32 {
+        self.sync.monitor.compute(|state| state.readers)
+    }
+
     fn is_writer_pending(&self) -> bool {
         self.sync.monitor.compute(|state| state.writer_pending)
     }
@@ -0,0 +1,40 @@
+//! Exercises the crate without the `std` feature. Run with
+//! `cargo test -p anode --no-default-features --test no_std`.
+
+#![cfg(not(feature = "std"))]
+
+use anode::shm::{ShmRwLock, ShmSpinLock};
+use anode::zlock::{SpinModerator, ZLock};
+use core::time::Duration;
+
+#[test]
+fn spin_moderator_timed_waits() {
+    let lock = ZLock::<_, SpinModerator>::new(0);
+    let guard = lock.write();
+    assert!(lock.try_read(Duration::from_micros(10)).is_none());
+    assert!(lock.try_write(Duration::from_micros(10)).is_none());
+    assert!(lock.try_read_upgradable(Duration::from_micros(10)).is_none());
+    drop(guard);
+
+    let guard = lock.try_read(Duration::from_micros(10)).unwrap();
+    assert!(lock.try_write(Duration::from_micros(10)).is_none());
+    drop(guard);
+    *lock.try_write(Duration::from_millis(1)).unwrap() = 42;
+    assert_eq!(42, *lock.read());
+}
+
+#[test]
+fn shm_timed_waits() {
+    let spin_lock = ShmSpinLock::new();
+    let guard = spin_lock.lock();
+    assert!(spin_lock.try_lock_for(Duration::from_micros(10)).is_none());
+    drop(guard);
+    assert!(spin_lock.try_lock_for(Duration::from_micros(10)).is_some());
+
+    let rw_lock = ShmRwLock::new();
+    let guard = rw_lock.write();
+    assert!(rw_lock.try_read(Duration::from_micros(10)).is_none());
+    assert!(rw_lock.try_write(Duration::from_micros(10)).is_none());
+    drop(guard);
+    assert!(rw_lock.try_read(Duration::from_micros(10)).is_some());
+}
@@ -0,0 +1,6 @@
+#!/bin/sh
+set -e
+
+# run the library tests under Miri with tree borrows; timing-dependent tests are ignored under cfg(miri)
+rustup +nightly component add miri
+MIRIFLAGS="-Zmiri-tree-borrows -Zmiri-disable-isolation" cargo +nightly miri test -p anode --lib "$@"